@@ -0,0 +1,139 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppResult;
+
+/// One write to the mailbox or its settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AuditWrite {
+    /// API method, e.g. `messages.modify` (see `timings::operation_name`).
+    pub operation: String,
+    /// Messages, addresses, or other resources it changed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added_labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_labels: Vec<String>,
+    /// Gmail refused the request or never answered it; the change may not
+    /// have happened.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub failed: bool,
+}
+
+/// One line of the audit log: a command run and the writes it made.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AuditEntry {
+    /// RFC 3339, UTC.
+    pub time: String,
+    pub profile: String,
+    /// Command path, e.g. `label add`.
+    pub command: String,
+    /// The arguments after `gmail`, as given.
+    pub args: Vec<String>,
+    pub writes: Vec<AuditWrite>,
+    /// Why the command failed, when it did; the writes before it still happened.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Collects the writes a client (and its clones) makes, until drained into
+/// the log.
+#[derive(Debug, Clone, Default)]
+pub struct AuditTrail {
+    writes: Arc<Mutex<Vec<AuditWrite>>>,
+}
+
+impl AuditTrail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, write: AuditWrite) {
+        self.writes
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(write);
+    }
+
+    /// Take the writes recorded so far.
+    pub fn drain(&self) -> Vec<AuditWrite> {
+        std::mem::take(&mut *self.writes.lock().unwrap_or_else(|err| err.into_inner()))
+    }
+}
+
+/// Append one entry to the JSONL log, creating it on first use. The command
+/// lines it holds can carry addresses and message text, so a new log is
+/// readable by the user alone.
+pub fn append(path: &Path, entry: &AuditEntry) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Every entry in the log, oldest first. Lines that don't parse (a write cut
+/// short, say) are skipped.
+pub fn read(path: &Path) -> AppResult<Vec<AuditEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_reads_back_entries() {
+        let dir = std::env::temp_dir().join(format!("gmail-audit-{}", std::process::id()));
+        let path = dir.join("audit.jsonl");
+        let trail = AuditTrail::new();
+        trail.record(AuditWrite {
+            operation: "messages.modify".to_string(),
+            ids: vec!["m1".to_string()],
+            added_labels: vec!["Label_1".to_string()],
+            ..AuditWrite::default()
+        });
+        let entry = AuditEntry {
+            time: "2026-10-17T09:00:00Z".to_string(),
+            profile: "work".to_string(),
+            command: "label add".to_string(),
+            args: vec!["label".to_string(), "add".to_string()],
+            writes: trail.drain(),
+            error: None,
+        };
+        assert!(trail.drain().is_empty());
+
+        append(&path, &entry).unwrap();
+        fs::write(
+            &path,
+            format!("{}{{\"truncated\n", fs::read_to_string(&path).unwrap()),
+        )
+        .unwrap();
+        append(&path, &entry).unwrap();
+        assert_eq!(read(&path).unwrap(), vec![entry.clone(), entry]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,38 +1,122 @@
 use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
-use reqwest::{Client, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
 use serde::Deserialize;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use tokio::task::JoinSet;
+use tracing::Instrument;
 use url::Url;
 
-use crate::error::{AppError, AppResult};
+use crate::error::{ApiError, AppError, AppResult};
+use crate::interrupt::Interrupt;
+use crate::mail::address;
 
+use super::audit::{AuditTrail, AuditWrite};
+use super::drafts;
+use super::forwarding;
+use super::history;
 use super::labels;
+use super::legacy_access;
 use super::messages;
 use super::models::{
-    AttachmentList, AttachmentMeta, LabelMutationResult, LabelView, MessageView, SendAsView,
-    SendResult,
+    AttachmentList, AttachmentMeta, AutoForwarding, DraftResult, DraftView, ForwardingAddress,
+    HistoryPage, ImapSettings, LabelMutationResult, LabelView, MailboxEvent, MailboxEventKind,
+    MailboxProfile, MessageView, NewSendAs, PersonMatch, PersonSource, PopSettings, SendAsUpdate,
+    SendAsView, SendResult, ThreadView, WatchResult,
 };
+use super::people;
 use super::send_as;
+use super::threads;
+use super::timings::{self, Timings};
+use super::watch;
 
 const GMAIL_API_BASE_URL: &str = "https://gmail.googleapis.com";
 
+/// Message fetches kept in flight at once when hydrating a listing.
+const HYDRATE_CONCURRENCY: usize = 10;
+
 #[derive(Debug, Clone)]
 pub struct GmailClient {
     http: Client,
     base_url: String,
+    /// Sent on every request, after the HTTP client's own defaults.
+    headers: HeaderMap,
+    /// Labels from the last `labels.list`, so name resolution costs one
+    /// fetch per invocation rather than one per lookup.
+    labels: Arc<Mutex<Option<Vec<LabelView>>>>,
+    /// Where `--timings` collects per-request latency, when enabled.
+    timings: Option<Timings>,
+    /// Once set, paged listings stop fetching further pages and messages.
+    interrupt: Option<Interrupt>,
+    /// Refuse every write request (`--dry-run`).
+    read_only: bool,
+    /// Where successful writes are noted for the audit log, when enabled.
+    audit: Option<AuditTrail>,
 }
 
 impl GmailClient {
     /// Construct a client targeting the public Gmail API base URL.
     pub fn new() -> Self {
+        Self::with_http(Client::new())
+    }
+
+    /// Construct a client that sends its requests through an existing,
+    /// already configured HTTP client (see `api::http::build_client`).
+    pub fn with_http(http: Client) -> Self {
         Self {
-            http: Client::new(),
+            http,
             base_url: GMAIL_API_BASE_URL.to_string(),
+            headers: HeaderMap::new(),
+            labels: Arc::default(),
+            timings: None,
+            interrupt: None,
+            read_only: false,
+            audit: None,
         }
     }
 
+    /// Stop paged listings and hydration early once `interrupt` is set,
+    /// returning what was already fetched.
+    pub fn with_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.interrupt = Some(interrupt);
+        self
+    }
+
+    /// With `dry_run`, fail any POST, PUT, PATCH, or DELETE instead of sending
+    /// it: a backstop for commands that cannot preview their changes.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.read_only = dry_run;
+        self
+    }
+
+    /// Note every successful write this client (and its clones) makes in `audit`.
+    pub fn with_audit(mut self, audit: AuditTrail) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Start configuring a client: base URL (for emulators and proxies), extra
+    /// headers, user agent, and the HTTP client to send through.
+    pub fn builder() -> GmailClientBuilder {
+        GmailClientBuilder::default()
+    }
+
+    /// The base URL that relative Gmail endpoints are joined onto.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Record every request this client (and its clones) sends into `timings`.
+    pub fn with_timings(mut self, timings: Timings) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
     /// Fetch a single message with `format=metadata` and project it into a `MessageView`.
     pub async fn get_msg(&self, id: &str, access_token: &str) -> AppResult<MessageView> {
         let endpoint = messages::message_endpoint(id);
@@ -52,6 +136,19 @@ impl GmailClient {
         Ok(resource.into_view())
     }
 
+    /// Fetch a message as Gmail stored it (`format=raw`): the complete RFC 822
+    /// bytes, headers and all, suitable for writing out as an `.eml` file.
+    pub async fn get_msg_raw(&self, id: &str, access_token: &str) -> AppResult<Vec<u8>> {
+        let endpoint = messages::message_endpoint(id);
+        let query = messages::raw_query();
+        let resource: GmailRawMessageResource =
+            self.get_json(&endpoint, access_token, Some(&query)).await?;
+        let raw = resource.raw.ok_or_else(|| {
+            AppError::api(format!("gmail returned no raw content for message {id}"))
+        })?;
+        decode_base64url(&raw)
+    }
+
     /// Fetch a message with `format=full` and walk its MIME tree, returning
     /// metadata for every part that carries a downloadable `attachmentId`.
     pub async fn list_attachments(
@@ -88,12 +185,40 @@ impl GmailClient {
             self.get_json(&endpoint, access_token, None).await?;
 
         let data = resource.data.ok_or_else(|| {
-            AppError::Api("gmail attachment response contained no data".to_string())
+            AppError::api("gmail attachment response contained no data".to_string())
         })?;
 
         decode_base64url(&data)
     }
 
+    /// Fetch a message's calendar invitation (a `text/calendar` part, or an
+    /// `.ics` attachment) as text, downloading it when Gmail stores it apart.
+    pub async fn get_calendar_part(
+        &self,
+        id: &str,
+        access_token: &str,
+    ) -> AppResult<Option<String>> {
+        let endpoint = messages::message_endpoint(id);
+        let query = messages::full_query();
+        let resource: GmailMessageResource =
+            self.get_json(&endpoint, access_token, Some(&query)).await?;
+        let Some(part) = resource.payload.as_ref().and_then(calendar_part) else {
+            return Ok(None);
+        };
+
+        let Some(body) = &part.body else {
+            return Ok(None);
+        };
+        let bytes = match (&body.data, &body.attachment_id) {
+            (Some(data), _) => decode_base64url(data)?,
+            (None, Some(attachment_id)) => {
+                self.get_attachment(id, attachment_id, access_token).await?
+            }
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
     /// List messages matching `query` (up to `limit`), fetching each one's metadata.
     pub async fn list(
         &self,
@@ -101,19 +226,205 @@ impl GmailClient {
         limit: u32,
         query: Option<&str>,
     ) -> AppResult<Vec<MessageView>> {
-        let endpoint = messages::list_endpoint();
-        let query_params = messages::list_query(limit, query);
-        let list_resource: GmailMessageListResource = self
-            .get_json(endpoint, access_token, Some(&query_params))
-            .await?;
+        let ids = self.list_ids(access_token, limit, query).await?;
+        self.get_msgs(&ids, access_token).await
+    }
 
-        let mut results = Vec::new();
-        for entry in list_resource.messages.unwrap_or_default() {
-            let message = self.get_msg(&entry.id, access_token).await?;
-            results.push(message);
+    /// Fetch metadata for several messages, keeping up to
+    /// `HYDRATE_CONCURRENCY` requests in flight; results keep the order of `ids`.
+    /// After an interrupt no new fetches start, and only the finished ones
+    /// are returned.
+    pub async fn get_msgs(
+        &self,
+        ids: &[String],
+        access_token: &str,
+    ) -> AppResult<Vec<MessageView>> {
+        self.hydrate(
+            "messages",
+            ids,
+            access_token,
+            |client, id, access_token| async move { client.get_msg(&id, &access_token).await },
+        )
+        .await
+    }
+
+    /// Fetch several messages with `format=full`, concurrently like
+    /// [`Self::get_msgs`], keeping the order of `ids`.
+    pub async fn get_msgs_full(
+        &self,
+        ids: &[String],
+        access_token: &str,
+    ) -> AppResult<Vec<MessageView>> {
+        self.hydrate(
+            "messages",
+            ids,
+            access_token,
+            |client, id, access_token| async move { client.get_msg_full(&id, &access_token).await },
+        )
+        .await
+    }
+
+    /// List the ids of messages matching `query` (up to `limit`), newest first,
+    /// without hydrating any message metadata. Limits above one page
+    /// (`messages::MAX_PAGE_SIZE`) follow `nextPageToken` until the limit or
+    /// an interrupt.
+    pub async fn list_ids(
+        &self,
+        access_token: &str,
+        limit: u32,
+        query: Option<&str>,
+    ) -> AppResult<Vec<String>> {
+        self.page_ids(messages::list_endpoint(), access_token, limit, query)
+            .await
+    }
+
+    /// Fetch a conversation with every message's metadata, oldest first.
+    pub async fn get_thread(&self, id: &str, access_token: &str) -> AppResult<ThreadView> {
+        self.fetch_thread(id, &threads::get_query(), access_token)
+            .await
+    }
+
+    /// Fetch a conversation with every message's decoded body, oldest first.
+    pub async fn get_thread_full(&self, id: &str, access_token: &str) -> AppResult<ThreadView> {
+        self.fetch_thread(id, &threads::full_query(), access_token)
+            .await
+    }
+
+    async fn fetch_thread(
+        &self,
+        id: &str,
+        query: &[(String, String)],
+        access_token: &str,
+    ) -> AppResult<ThreadView> {
+        let endpoint = threads::thread_endpoint(id);
+        let resource: GmailThreadResource =
+            self.get_json(&endpoint, access_token, Some(query)).await?;
+        Ok(ThreadView {
+            id: resource.id,
+            history_id: resource.history_id,
+            messages: resource
+                .messages
+                .unwrap_or_default()
+                .into_iter()
+                .map(GmailMessageResource::into_view)
+                .collect(),
+        })
+    }
+
+    /// List conversations matching `query` (up to `limit`), most recently
+    /// active first, fetching each one's messages like [`Self::get_msgs`].
+    pub async fn list_threads(
+        &self,
+        access_token: &str,
+        limit: u32,
+        query: Option<&str>,
+    ) -> AppResult<Vec<ThreadView>> {
+        let ids = self.list_thread_ids(access_token, limit, query).await?;
+        self.hydrate(
+            "threads",
+            &ids,
+            access_token,
+            |client, id, access_token| async move { client.get_thread(&id, &access_token).await },
+        )
+        .await
+    }
+
+    /// List the ids of conversations matching `query` (up to `limit`), most
+    /// recently active first, paging like [`Self::list_ids`].
+    pub async fn list_thread_ids(
+        &self,
+        access_token: &str,
+        limit: u32,
+        query: Option<&str>,
+    ) -> AppResult<Vec<String>> {
+        self.page_ids(threads::list_endpoint(), access_token, limit, query)
+            .await
+    }
+
+    /// Run `fetch` for every id with up to `HYDRATE_CONCURRENCY` in flight,
+    /// keeping the order of `ids` and stopping early on an interrupt.
+    async fn hydrate<T, F, Fut>(
+        &self,
+        what: &str,
+        ids: &[String],
+        access_token: &str,
+        fetch: F,
+    ) -> AppResult<Vec<T>>
+    where
+        T: Send + 'static,
+        F: Fn(GmailClient, String, String) -> Fut,
+        Fut: Future<Output = AppResult<T>> + Send + 'static,
+    {
+        let started = Instant::now();
+        let mut pending = ids.iter().cloned().enumerate();
+        let mut tasks = JoinSet::new();
+        let mut results = ids.iter().map(|_| None).collect::<Vec<_>>();
+
+        loop {
+            while tasks.len() < HYDRATE_CONCURRENCY
+                && !self.interrupted()
+                && let Some((index, id)) = pending.next()
+            {
+                let fetched = fetch(self.clone(), id, access_token.to_string());
+                tasks.spawn(async move { (index, fetched.await) }.in_current_span());
+            }
+
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            let (index, item) =
+                joined.map_err(|err| AppError::api(format!("{what} fetch task failed: {err}")))?;
+            results[index] = Some(item?);
+        }
+
+        tracing::debug!(
+            count = ids.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "hydrated {what}"
+        );
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Collect ids from a list endpoint (`messages`, `threads`, or `drafts`), following
+    /// `nextPageToken` until `limit` or an interrupt.
+    async fn page_ids(
+        &self,
+        endpoint: &str,
+        access_token: &str,
+        limit: u32,
+        query: Option<&str>,
+    ) -> AppResult<Vec<String>> {
+        let mut ids = Vec::new();
+        let mut page_token = None;
+
+        loop {
+            let remaining = limit.saturating_sub(ids.len() as u32);
+            let mut query_params =
+                messages::list_query(remaining.min(messages::MAX_PAGE_SIZE), query);
+            if let Some(token) = page_token.take() {
+                query_params.push(("pageToken".to_string(), token));
+            }
+            let list_resource: GmailMessageListResource = self
+                .get_json(endpoint, access_token, Some(&query_params))
+                .await?;
+
+            ids.extend(
+                list_resource
+                    .messages
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|entry| entry.id),
+            );
+            match list_resource.next_page_token {
+                Some(token) if (ids.len() as u32) < limit && !self.interrupted() => {
+                    page_token = Some(token)
+                }
+                _ => break,
+            }
         }
 
-        Ok(results)
+        ids.truncate(limit as usize);
+        Ok(ids)
     }
 
     /// Submit a base64url-encoded raw RFC 822 message, optionally into an existing thread.
@@ -129,6 +440,7 @@ impl GmailClient {
             thread_id: thread_id.map(ToOwned::to_owned),
         };
         let response: GmailSendResponse = self.post_json(endpoint, access_token, &request).await?;
+        self.audited("POST", endpoint, [&response.id]);
 
         Ok(SendResult {
             id: response.id,
@@ -137,6 +449,186 @@ impl GmailClient {
         })
     }
 
+    /// Add a base64url-encoded RFC 822 message to the mailbox with `label_ids`
+    /// and without sending it: through `messages.import`, which scans it like
+    /// incoming mail, or with `insert` through `messages.insert`, which stores
+    /// it as is.
+    pub async fn import_message(
+        &self,
+        raw_message: &str,
+        label_ids: &[String],
+        insert: bool,
+        access_token: &str,
+    ) -> AppResult<SendResult> {
+        let endpoint = if insert {
+            messages::list_endpoint()
+        } else {
+            messages::import_endpoint()
+        };
+        let request = GmailImportRequest {
+            raw: raw_message.to_string(),
+            label_ids: label_ids.to_vec(),
+        };
+        let url = self.endpoint_url(endpoint)?;
+        let builder = self
+            .request(Method::POST, url)
+            .bearer_auth(access_token)
+            .query(&messages::import_query())
+            .json(&request);
+        let response = self.execute("POST", endpoint, builder).await?;
+        let response: GmailSendResponse =
+            self.parse_json_response("POST", endpoint, response).await?;
+        self.audit_labels(endpoint, std::slice::from_ref(&response.id), label_ids, &[]);
+
+        Ok(SendResult {
+            id: response.id,
+            thread_id: response.thread_id,
+            note: if insert {
+                "message inserted".to_string()
+            } else {
+                "message imported".to_string()
+            },
+        })
+    }
+
+    /// Save a base64url-encoded RFC 822 message as a draft, optionally in a thread.
+    pub async fn create_draft(
+        &self,
+        raw_message: &str,
+        thread_id: Option<&str>,
+        access_token: &str,
+    ) -> AppResult<DraftResult> {
+        let endpoint = drafts::list_endpoint();
+        let request = GmailDraftRequest {
+            id: None,
+            message: GmailSendRequest {
+                raw: raw_message.to_string(),
+                thread_id: thread_id.map(ToOwned::to_owned),
+            },
+        };
+        let response: GmailDraftResponse = self.post_json(endpoint, access_token, &request).await?;
+        self.audited("POST", endpoint, [&response.id]);
+
+        Ok(DraftResult {
+            id: response.id,
+            message_id: response.message.id,
+            thread_id: response.message.thread_id,
+        })
+    }
+
+    /// Replace a draft's message with a new base64url-encoded RFC 822 message
+    /// (`drafts.update`); the draft keeps its id.
+    pub async fn update_draft(
+        &self,
+        id: &str,
+        raw_message: &str,
+        thread_id: Option<&str>,
+        access_token: &str,
+    ) -> AppResult<DraftResult> {
+        let endpoint = drafts::draft_endpoint(id);
+        let request = GmailDraftRequest {
+            id: Some(id.to_string()),
+            message: GmailSendRequest {
+                raw: raw_message.to_string(),
+                thread_id: thread_id.map(ToOwned::to_owned),
+            },
+        };
+        let response: GmailDraftResponse = self
+            .send_json(Method::PUT, &endpoint, access_token, &request)
+            .await?;
+        self.audited("PUT", &endpoint, [&response.id]);
+
+        Ok(DraftResult {
+            id: response.id,
+            message_id: response.message.id,
+            thread_id: response.message.thread_id,
+        })
+    }
+
+    /// Fetch a draft with its message's metadata, or with `full` the decoded
+    /// body too.
+    pub async fn get_draft(
+        &self,
+        id: &str,
+        full: bool,
+        access_token: &str,
+    ) -> AppResult<DraftView> {
+        let endpoint = drafts::draft_endpoint(id);
+        let query = drafts::get_query(full);
+        let resource: GmailDraftResource =
+            self.get_json(&endpoint, access_token, Some(&query)).await?;
+        Ok(DraftView {
+            id: resource.id,
+            message: resource.message.into_view(),
+        })
+    }
+
+    /// List drafts matching `query` (up to `limit`), newest first, fetching
+    /// each one's metadata like [`Self::get_msgs`].
+    pub async fn list_drafts(
+        &self,
+        access_token: &str,
+        limit: u32,
+        query: Option<&str>,
+    ) -> AppResult<Vec<DraftView>> {
+        let ids = self
+            .page_ids(drafts::list_endpoint(), access_token, limit, query)
+            .await?;
+        self.hydrate(
+            "drafts",
+            &ids,
+            access_token,
+            |client, id, access_token| async move {
+                client.get_draft(&id, false, &access_token).await
+            },
+        )
+        .await
+    }
+
+    /// Send an existing draft (`drafts.send`), which removes it from drafts.
+    pub async fn send_draft(&self, id: &str, access_token: &str) -> AppResult<SendResult> {
+        let endpoint = drafts::send_endpoint();
+        let request = GmailDraftIdRequest { id };
+        let response: GmailSendResponse = self.post_json(endpoint, access_token, &request).await?;
+        self.audited("POST", endpoint, [id, &response.id]);
+
+        Ok(SendResult {
+            id: response.id,
+            thread_id: response.thread_id,
+            note: "draft sent".to_string(),
+        })
+    }
+
+    /// Delete a draft for good (`drafts.delete`); it does not go to the trash.
+    pub async fn delete_draft(&self, id: &str, access_token: &str) -> AppResult<()> {
+        let endpoint = drafts::draft_endpoint(id);
+        self.delete_no_content(&endpoint, access_token).await?;
+        self.audited("DELETE", &endpoint, [id]);
+        Ok(())
+    }
+
+    /// Fetch just the named headers of a message (all of them when `names` is
+    /// empty) as `(name, value)` pairs, in message order. Headers the message
+    /// lacks are simply absent.
+    pub async fn get_headers(
+        &self,
+        id: &str,
+        names: &[&str],
+        access_token: &str,
+    ) -> AppResult<Vec<(String, String)>> {
+        let endpoint = messages::message_endpoint(id);
+        let query = messages::headers_query(names);
+        let resource: GmailMessageResource =
+            self.get_json(&endpoint, access_token, Some(&query)).await?;
+        Ok(resource
+            .payload
+            .and_then(|payload| payload.headers)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|header| (header.name, header.value))
+            .collect())
+    }
+
     /// Fetch the account's send-as aliases, primary first then alphabetical by email.
     pub async fn list_send_as(&self, access_token: &str) -> AppResult<Vec<SendAsView>> {
         let endpoint = send_as::list_send_as_endpoint();
@@ -151,10 +643,358 @@ impl GmailClient {
         Ok(aliases)
     }
 
-    /// Fetch all labels on the account, sorted alphabetically by name.
-    pub async fn list_labels(&self, _access_token: &str) -> AppResult<Vec<LabelView>> {
+    /// Add a send-as alias. Gmail emails a verification link to addresses
+    /// outside the account's domain, leaving the alias `pending` until used.
+    pub async fn create_send_as(
+        &self,
+        alias: &NewSendAs,
+        access_token: &str,
+    ) -> AppResult<SendAsView> {
+        let body = serde_json::json!({
+            "sendAsEmail": alias.email,
+            "displayName": alias.display_name,
+            "replyToAddress": alias.reply_to,
+            "signature": alias.signature,
+            "treatAsAlias": true,
+        });
+        let resource: GmailSendAsResource = self
+            .post_json(send_as::list_send_as_endpoint(), access_token, &body)
+            .await?;
+        self.audited("POST", send_as::list_send_as_endpoint(), [&alias.email]);
+        Ok(resource.into_view())
+    }
+
+    /// Resend the verification email for a pending send-as alias.
+    pub async fn verify_send_as(&self, email: &str, access_token: &str) -> AppResult<()> {
+        let endpoint = send_as::verify_send_as_endpoint(email);
+        self.post_no_content(&endpoint, access_token).await?;
+        self.audited("POST", &endpoint, [email]);
+        Ok(())
+    }
+
+    /// Change a send-as alias's display name, reply-to, signature, or default flag.
+    pub async fn update_send_as(
+        &self,
+        email: &str,
+        update: &SendAsUpdate,
+        access_token: &str,
+    ) -> AppResult<SendAsView> {
+        let endpoint = send_as::send_as_endpoint(email);
+        let resource: GmailSendAsResource = self
+            .send_json(Method::PATCH, &endpoint, access_token, update)
+            .await?;
+        self.audited("PATCH", &endpoint, [email]);
+        Ok(resource.into_view())
+    }
+
+    /// List the addresses mail may be forwarded to, confirmed or not.
+    pub async fn list_forwarding_addresses(
+        &self,
+        access_token: &str,
+    ) -> AppResult<Vec<ForwardingAddress>> {
+        let endpoint = forwarding::forwarding_addresses_endpoint();
+        let response: GmailForwardingListResponse =
+            self.get_json(endpoint, access_token, None).await?;
+        Ok(response
+            .forwarding_addresses
+            .into_iter()
+            .map(GmailForwardingAddressResource::into_view)
+            .collect())
+    }
+
+    /// Add a forwarding address. Gmail emails it a confirmation link and
+    /// keeps it `pending` until that is followed.
+    pub async fn create_forwarding_address(
+        &self,
+        email: &str,
+        access_token: &str,
+    ) -> AppResult<ForwardingAddress> {
+        let endpoint = forwarding::forwarding_addresses_endpoint();
+        let body = serde_json::json!({ "forwardingEmail": email });
+        let resource: GmailForwardingAddressResource =
+            self.post_json(endpoint, access_token, &body).await?;
+        self.audited("POST", endpoint, [email]);
+        Ok(resource.into_view())
+    }
+
+    /// Remove a forwarding address (and auto-forwarding to it).
+    pub async fn delete_forwarding_address(
+        &self,
+        email: &str,
+        access_token: &str,
+    ) -> AppResult<()> {
+        let endpoint = forwarding::forwarding_address_endpoint(email);
+        self.delete_no_content(&endpoint, access_token).await?;
+        self.audited("DELETE", &endpoint, [email]);
+        Ok(())
+    }
+
+    pub async fn get_auto_forwarding(&self, access_token: &str) -> AppResult<AutoForwarding> {
+        let endpoint = forwarding::auto_forwarding_endpoint();
+        let resource: GmailAutoForwardingResource =
+            self.get_json(endpoint, access_token, None).await?;
+        Ok(resource.into_view())
+    }
+
+    /// Replace the auto-forwarding setting.
+    pub async fn update_auto_forwarding(
+        &self,
+        setting: &AutoForwarding,
+        access_token: &str,
+    ) -> AppResult<AutoForwarding> {
+        let endpoint = forwarding::auto_forwarding_endpoint();
+        let body = serde_json::json!({
+            "enabled": setting.enabled,
+            "emailAddress": setting.email,
+            "disposition": setting.disposition,
+        });
+        let resource: GmailAutoForwardingResource = self
+            .send_json(Method::PUT, endpoint, access_token, &body)
+            .await?;
+        self.audited("PUT", endpoint, setting.email.as_ref());
+        Ok(resource.into_view())
+    }
+
+    pub async fn get_imap(&self, access_token: &str) -> AppResult<ImapSettings> {
+        let resource: GmailImapResource = self
+            .get_json(legacy_access::imap_endpoint(), access_token, None)
+            .await?;
+        Ok(resource.into_view())
+    }
+
+    /// Turn IMAP access on or off, keeping the other IMAP options.
+    pub async fn set_imap(&self, enabled: bool, access_token: &str) -> AppResult<ImapSettings> {
+        let endpoint = legacy_access::imap_endpoint();
+        let mut resource: GmailImapResource = self.get_json(endpoint, access_token, None).await?;
+        resource.enabled = enabled;
+        let resource: GmailImapResource = self
+            .send_json(Method::PUT, endpoint, access_token, &resource)
+            .await?;
+        self.audited("PUT", endpoint, None::<&str>);
+        Ok(resource.into_view())
+    }
+
+    pub async fn get_pop(&self, access_token: &str) -> AppResult<PopSettings> {
+        let resource: GmailPopResource = self
+            .get_json(legacy_access::pop_endpoint(), access_token, None)
+            .await?;
+        Ok(resource.into_view())
+    }
+
+    /// Set which mail POP clients may download (`disabled` turns POP off),
+    /// keeping what happens to fetched mail.
+    pub async fn set_pop(&self, access_window: &str, access_token: &str) -> AppResult<PopSettings> {
+        let endpoint = legacy_access::pop_endpoint();
+        let mut resource: GmailPopResource = self.get_json(endpoint, access_token, None).await?;
+        resource.access_window = Some(access_window.to_string());
+        let resource: GmailPopResource = self
+            .send_json(Method::PUT, endpoint, access_token, &resource)
+            .await?;
+        self.audited("PUT", endpoint, None::<&str>);
+        Ok(resource.into_view())
+    }
+
+    /// Search saved contacts, the Workspace directory, and other contacts (in
+    /// that order) for `query`, one match per address, at most `limit`. The
+    /// directory is skipped when the account has none, as personal accounts don't.
+    pub async fn search_people(
+        &self,
+        query: &str,
+        limit: usize,
+        access_token: &str,
+    ) -> AppResult<Vec<PersonMatch>> {
+        let page_size = limit.clamp(1, 30).to_string();
+        let search = |read_mask: &str| {
+            vec![
+                ("query".to_string(), query.to_string()),
+                ("readMask".to_string(), read_mask.to_string()),
+                ("pageSize".to_string(), page_size.clone()),
+            ]
+        };
+        // The contact searches answer from a cache that an empty query warms.
+        let warmup = |read_mask: &str| {
+            vec![
+                ("query".to_string(), String::new()),
+                ("readMask".to_string(), read_mask.to_string()),
+            ]
+        };
+
+        let contacts_endpoint = people::search_contacts_endpoint();
+        let _: PeopleSearchResponse = self
+            .get_json(
+                contacts_endpoint,
+                access_token,
+                Some(&warmup(people::PERSON_READ_MASK)),
+            )
+            .await?;
+        let contacts: PeopleSearchResponse = self
+            .get_json(
+                contacts_endpoint,
+                access_token,
+                Some(&search(people::PERSON_READ_MASK)),
+            )
+            .await?;
+
+        let mut directory_query = search(people::PERSON_READ_MASK);
+        directory_query.push((
+            "sources".to_string(),
+            "DIRECTORY_SOURCE_TYPE_DOMAIN_PROFILE".to_string(),
+        ));
+        let directory = match self
+            .get_json::<PeopleDirectoryResponse>(
+                people::search_directory_endpoint(),
+                access_token,
+                Some(&directory_query),
+            )
+            .await
+        {
+            Ok(response) => response.people,
+            Err(err) => {
+                tracing::debug!(error = %err, "directory search unavailable; skipping");
+                Vec::new()
+            }
+        };
+
+        let other_endpoint = people::search_other_contacts_endpoint();
+        let _: PeopleSearchResponse = self
+            .get_json(
+                other_endpoint,
+                access_token,
+                Some(&warmup(people::OTHER_CONTACT_READ_MASK)),
+            )
+            .await?;
+        let other: PeopleSearchResponse = self
+            .get_json(
+                other_endpoint,
+                access_token,
+                Some(&search(people::OTHER_CONTACT_READ_MASK)),
+            )
+            .await?;
+
+        let sources = [
+            (PersonSource::Contacts, contacts.people()),
+            (PersonSource::Directory, directory),
+            (PersonSource::OtherContacts, other.people()),
+        ];
+        let mut matches = Vec::<PersonMatch>::new();
+        for (source, people) in sources {
+            for person in people {
+                for found in person.into_matches(source) {
+                    if !matches
+                        .iter()
+                        .any(|known| known.email.eq_ignore_ascii_case(&found.email))
+                    {
+                        matches.push(found);
+                    }
+                }
+            }
+        }
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    /// Fetch the authenticated mailbox's address, totals, and current history id.
+    pub async fn get_profile(&self, access_token: &str) -> AppResult<MailboxProfile> {
+        let endpoint = history::profile_endpoint();
+        let response: GmailProfileResponse = self.get_json(endpoint, access_token, None).await?;
+        Ok(MailboxProfile {
+            email: response.email_address,
+            messages_total: response.messages_total.unwrap_or_default(),
+            threads_total: response.threads_total.unwrap_or_default(),
+            history_id: response.history_id,
+        })
+    }
+
+    /// Page through `users.history.list` from `start_history_id`, flattening
+    /// every record into per-message events in history order. The returned
+    /// `history_id` is the mailbox's latest, i.e. the next call's start point.
+    /// `label_id` and `history_types` narrow the records Gmail returns.
+    pub async fn list_history(
+        &self,
+        start_history_id: &str,
+        label_id: Option<&str>,
+        history_types: &[&str],
+        access_token: &str,
+    ) -> AppResult<HistoryPage> {
+        let endpoint = history::list_history_endpoint();
+        let mut events = Vec::new();
+        let mut latest = start_history_id.to_string();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let query = history::list_history_query(
+                start_history_id,
+                label_id,
+                history_types,
+                page_token.as_deref(),
+            );
+            let response: GmailHistoryListResponse =
+                self.get_json(endpoint, access_token, Some(&query)).await?;
+
+            for record in response.history.unwrap_or_default() {
+                record.into_events(&mut events);
+            }
+            if let Some(history_id) = response.history_id {
+                latest = history_id;
+            }
+
+            match response.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+
+        Ok(HistoryPage {
+            start_history_id: start_history_id.to_string(),
+            history_id: latest,
+            events,
+        })
+    }
+
+    /// Register a Cloud Pub/Sub push watch on the mailbox, optionally limited to
+    /// the given label names or ids. Gmail expires the registration after 7 days.
+    pub async fn watch(
+        &self,
+        topic: &str,
+        labels: &[String],
+        access_token: &str,
+    ) -> AppResult<WatchResult> {
+        let [label_ids] = self.resolve_label_ids([labels], access_token).await?;
+        let endpoint = watch::watch_endpoint();
+        let request = GmailWatchRequest {
+            topic_name: topic.to_string(),
+            label_filter_behavior: (!label_ids.is_empty()).then(|| "include".to_string()),
+            label_ids,
+        };
+        let response: GmailWatchResponse = self.post_json(endpoint, access_token, &request).await?;
+        self.audited("POST", endpoint, None::<&str>);
+
+        let expiration_unix_ms = response.expiration.parse::<u64>().map_err(|err| {
+            AppError::api(format!(
+                "gmail watch response had an invalid expiration `{}`: {err}",
+                response.expiration
+            ))
+        })?;
+
+        Ok(WatchResult {
+            history_id: response.history_id,
+            expiration_unix_ms,
+        })
+    }
+
+    /// Stop push notifications for the mailbox.
+    pub async fn stop_watch(&self, access_token: &str) -> AppResult<()> {
+        let endpoint = watch::stop_endpoint();
+        self.post_no_content(endpoint, access_token).await?;
+        self.audited("POST", endpoint, None::<&str>);
+        Ok(())
+    }
+
+    /// Fetch all labels on the account, sorted alphabetically by name, and
+    /// remember them for later name resolution.
+    pub async fn list_labels(&self, access_token: &str) -> AppResult<Vec<LabelView>> {
         let endpoint = labels::list_labels_endpoint();
-        let response: GmailLabelListResponse = self.get_json(endpoint, _access_token, None).await?;
+        let response: GmailLabelListResponse = self.get_json(endpoint, access_token, None).await?;
         let mut labels_out = response
             .labels
             .unwrap_or_default()
@@ -166,9 +1006,119 @@ impl GmailClient {
             })
             .collect::<Vec<_>>();
         labels_out.sort_by(|a, b| a.name.cmp(&b.name));
+        *self.labels.lock().unwrap_or_else(|err| err.into_inner()) = Some(labels_out.clone());
         Ok(labels_out)
     }
 
+    /// Create a user label (`labels.create`). The visibilities are Gmail's
+    /// values (`labelShow`, `show`, ...); `None` leaves Gmail's default. The
+    /// new label joins the name cache.
+    pub async fn create_label(
+        &self,
+        name: &str,
+        label_list_visibility: Option<&str>,
+        message_list_visibility: Option<&str>,
+        access_token: &str,
+    ) -> AppResult<LabelView> {
+        let endpoint = labels::list_labels_endpoint();
+        let body = GmailCreateLabelRequest {
+            name,
+            label_list_visibility,
+            message_list_visibility,
+        };
+        let label: GmailLabelResource = self.post_json(endpoint, access_token, &body).await?;
+        self.audited("POST", endpoint, [&label.id]);
+        let label = LabelView {
+            id: label.id,
+            name: label.name,
+            kind: label.kind,
+        };
+        if let Some(labels) = self
+            .labels
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .as_mut()
+        {
+            labels.push(label.clone());
+            labels.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Ok(label)
+    }
+
+    /// Labels from the cache, fetching them on first use.
+    async fn known_labels(&self, access_token: &str) -> AppResult<Vec<LabelView>> {
+        let cached = self
+            .labels
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        match cached {
+            Some(labels) => Ok(labels),
+            None => self.list_labels(access_token).await,
+        }
+    }
+
+    /// Move a message to the trash (`messages.trash`), where Gmail deletes it
+    /// for good after 30 days.
+    pub async fn trash(&self, id: &str, access_token: &str) -> AppResult<()> {
+        let endpoint = messages::trash_endpoint(id);
+        self.post_no_content(&endpoint, access_token).await?;
+        self.audited("POST", &endpoint, [id]);
+        Ok(())
+    }
+
+    /// Take a message back out of the trash (`messages.untrash`).
+    pub async fn untrash(&self, id: &str, access_token: &str) -> AppResult<()> {
+        let endpoint = messages::untrash_endpoint(id);
+        self.post_no_content(&endpoint, access_token).await?;
+        self.audited("POST", &endpoint, [id]);
+        Ok(())
+    }
+
+    /// Move every message of a thread to the trash (`threads.trash`).
+    pub async fn trash_thread(&self, id: &str, access_token: &str) -> AppResult<()> {
+        let endpoint = threads::trash_endpoint(id);
+        self.post_no_content(&endpoint, access_token).await?;
+        self.audited("POST", &endpoint, [id]);
+        Ok(())
+    }
+
+    /// Take every message of a thread back out of the trash (`threads.untrash`).
+    pub async fn untrash_thread(&self, id: &str, access_token: &str) -> AppResult<()> {
+        let endpoint = threads::untrash_endpoint(id);
+        self.post_no_content(&endpoint, access_token).await?;
+        self.audited("POST", &endpoint, [id]);
+        Ok(())
+    }
+
+    /// Delete a message immediately and for good (`messages.delete`),
+    /// bypassing the trash. Needs [`messages::DELETE_SCOPES`].
+    pub async fn delete_msg(&self, id: &str, access_token: &str) -> AppResult<()> {
+        let endpoint = messages::message_endpoint(id);
+        self.delete_no_content(&endpoint, access_token).await?;
+        self.audited("DELETE", &endpoint, [id]);
+        Ok(())
+    }
+
+    /// Delete many messages for good with `messages.batchDelete`,
+    /// [`messages::BATCH_SIZE`] ids per request. Needs
+    /// [`messages::DELETE_SCOPES`]. After an interrupt no new batch starts;
+    /// returns how many ids were deleted.
+    pub async fn batch_delete(&self, ids: &[String], access_token: &str) -> AppResult<usize> {
+        let mut deleted = 0;
+        for chunk in ids.chunks(messages::BATCH_SIZE) {
+            if self.interrupted() {
+                break;
+            }
+            let body = GmailBatchDeleteRequest { ids: chunk };
+            self.post_json_no_content(messages::batch_delete_endpoint(), access_token, &body)
+                .await?;
+            self.audited("POST", messages::batch_delete_endpoint(), chunk);
+            deleted += chunk.len();
+        }
+        Ok(deleted)
+    }
+
     /// Add the given labels to a message.
     pub async fn add_labels(
         &self,
@@ -189,16 +1139,51 @@ impl GmailClient {
         self.modify_labels(id, &[], labels, access_token).await
     }
 
+    /// Add and remove labels (names or ids) on many messages with
+    /// `messages.batchModify`, [`messages::BATCH_SIZE`] ids per request.
+    /// After an interrupt no new batch starts; returns how many ids were sent.
+    pub async fn batch_modify(
+        &self,
+        ids: &[String],
+        add: &[String],
+        rm: &[String],
+        access_token: &str,
+    ) -> AppResult<usize> {
+        let [add_label_ids, remove_label_ids] =
+            self.resolve_label_ids([add, rm], access_token).await?;
+
+        let mut modified = 0;
+        for chunk in ids.chunks(messages::BATCH_SIZE) {
+            if self.interrupted() {
+                break;
+            }
+            let body = GmailBatchModifyRequest {
+                ids: chunk,
+                add_label_ids: &add_label_ids,
+                remove_label_ids: &remove_label_ids,
+            };
+            self.post_json_no_content(messages::batch_modify_endpoint(), access_token, &body)
+                .await?;
+            self.audit_labels(
+                messages::batch_modify_endpoint(),
+                chunk,
+                &add_label_ids,
+                &remove_label_ids,
+            );
+            modified += chunk.len();
+        }
+        Ok(modified)
+    }
+
     /// Resolve label names/ids, then issue a single `messages.modify` adding and removing them.
-    async fn modify_labels(
+    pub async fn modify_labels(
         &self,
         id: &str,
         add: &[String],
         rm: &[String],
         access_token: &str,
     ) -> AppResult<LabelMutationResult> {
-        let resolved_add = self.resolve_label_ids(add, access_token).await?;
-        let resolved_rm = self.resolve_label_ids(rm, access_token).await?;
+        let [resolved_add, resolved_rm] = self.resolve_label_ids([add, rm], access_token).await?;
 
         let endpoint = labels::modify_labels_endpoint(id);
         let body = GmailModifyLabelsRequest {
@@ -207,101 +1192,297 @@ impl GmailClient {
         };
 
         let _: GmailModifyLabelsResponse = self.post_json(&endpoint, access_token, &body).await?;
+        self.audit_labels(&endpoint, &[id.to_string()], &resolved_add, &resolved_rm);
         Ok(LabelMutationResult {
             id: id.to_string(),
             added: resolved_add,
             removed: resolved_rm,
             note: "message labels updated".to_string(),
+            error: None,
         })
     }
 
-    /// Map requested label names or ids to canonical label ids, erroring on any unknown label.
-    async fn resolve_label_ids(
+    /// Resolve label names/ids, then add and remove them on every message of
+    /// a thread with one `threads.modify`.
+    pub async fn modify_thread(
         &self,
-        requested: &[String],
+        id: &str,
+        add: &[String],
+        rm: &[String],
         access_token: &str,
-    ) -> AppResult<Vec<String>> {
-        if requested.is_empty() {
-            return Ok(Vec::new());
+    ) -> AppResult<LabelMutationResult> {
+        let [resolved_add, resolved_rm] = self.resolve_label_ids([add, rm], access_token).await?;
+
+        let endpoint = threads::modify_endpoint(id);
+        let body = GmailModifyLabelsRequest {
+            add_label_ids: resolved_add.clone(),
+            remove_label_ids: resolved_rm.clone(),
+        };
+
+        let _: GmailModifyLabelsResponse = self.post_json(&endpoint, access_token, &body).await?;
+        self.audit_labels(&endpoint, &[id.to_string()], &resolved_add, &resolved_rm);
+        Ok(LabelMutationResult {
+            id: id.to_string(),
+            added: resolved_add,
+            removed: resolved_rm,
+            note: "thread labels updated".to_string(),
+            error: None,
+        })
+    }
+
+    /// Map each group of requested label names or ids to canonical label ids
+    /// against one label listing, erroring on any unknown label. A cached
+    /// listing that misses a label is refetched once in case it is new.
+    pub async fn resolve_label_ids<const N: usize>(
+        &self,
+        groups: [&[String]; N],
+        access_token: &str,
+    ) -> AppResult<[Vec<String>; N]> {
+        if groups.iter().all(|group| group.is_empty()) {
+            return Ok(std::array::from_fn(|_| Vec::new()));
         }
 
-        let known = self.list_labels(access_token).await?;
-        let mut out = Vec::new();
+        let was_cached = self
+            .labels
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .is_some();
+        let known = self.known_labels(access_token).await?;
+        let mut resolved = groups.map(|group| match_label_ids(&known, group));
+        if was_cached && resolved.iter().any(Result::is_err) {
+            let known = self.list_labels(access_token).await?;
+            resolved = groups.map(|group| match_label_ids(&known, group));
+        }
 
-        for raw in requested {
-            let needle = raw.trim();
-            if needle.is_empty() {
-                continue;
-            }
+        let mut out = std::array::from_fn(|_| Vec::new());
+        for (slot, ids) in out.iter_mut().zip(resolved) {
+            *slot = ids?;
+        }
+        Ok(out)
+    }
 
-            let mut matched = None;
-            for label in &known {
-                if label.id == needle || label.name.eq_ignore_ascii_case(needle) {
-                    matched = Some(label.id.clone());
-                    break;
-                }
-            }
+    /// Issue a bearer-authenticated GET with optional query params and deserialize the JSON body.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        access_token: &str,
+        query: Option<&[(String, String)]>,
+    ) -> AppResult<T> {
+        let url = self.endpoint_url(endpoint)?;
+        let mut request = self.request(Method::GET, url).bearer_auth(access_token);
+        if let Some(query) = query {
+            request = request.query(query);
+        }
+
+        let response = self.execute("GET", endpoint, request).await?;
+        self.parse_json_response("GET", endpoint, response).await
+    }
+
+    /// Issue a bearer-authenticated POST with a JSON body and deserialize the JSON response.
+    async fn post_json<T: DeserializeOwned, B: Serialize>(
+        &self,
+        endpoint: &str,
+        access_token: &str,
+        body: &B,
+    ) -> AppResult<T> {
+        self.send_json(Method::POST, endpoint, access_token, body)
+            .await
+    }
+
+    /// Issue a bearer-authenticated request with a JSON body and deserialize the JSON response.
+    async fn send_json<T: DeserializeOwned, B: Serialize>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        access_token: &str,
+        body: &B,
+    ) -> AppResult<T> {
+        let name = match method {
+            Method::PATCH => "PATCH",
+            Method::PUT => "PUT",
+            _ => "POST",
+        };
+        let url = self.endpoint_url(endpoint)?;
+        let request = self
+            .request(method, url)
+            .bearer_auth(access_token)
+            .json(body);
+        let response = self.execute(name, endpoint, request).await?;
+
+        self.parse_json_response(name, endpoint, response).await
+    }
+
+    /// Issue a bearer-authenticated POST without a body, discarding any response body.
+    async fn post_no_content(&self, endpoint: &str, access_token: &str) -> AppResult<()> {
+        let url = self.endpoint_url(endpoint)?;
+        let request = self
+            .request(Method::POST, url)
+            .bearer_auth(access_token)
+            .header(reqwest::header::CONTENT_LENGTH, 0);
+        let response = self.execute("POST", endpoint, request).await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err(map_api_error(status, &body).at_endpoint("POST", endpoint))
+    }
+
+    /// Issue a bearer-authenticated POST with a JSON body, discarding any response body.
+    async fn post_json_no_content<B: Serialize>(
+        &self,
+        endpoint: &str,
+        access_token: &str,
+        body: &B,
+    ) -> AppResult<()> {
+        let url = self.endpoint_url(endpoint)?;
+        let request = self
+            .request(Method::POST, url)
+            .bearer_auth(access_token)
+            .json(body);
+        let response = self.execute("POST", endpoint, request).await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
 
-            let Some(label_id) = matched else {
-                return Err(AppError::InvalidInput(format!(
-                    "unknown label `{needle}`; run `gmail label ls` to inspect labels"
-                )));
-            };
+        let body = response.text().await.unwrap_or_default();
+        Err(map_api_error(status, &body).at_endpoint("POST", endpoint))
+    }
 
-            if !out.contains(&label_id) {
-                out.push(label_id);
-            }
+    /// Issue a bearer-authenticated DELETE, discarding any response body.
+    async fn delete_no_content(&self, endpoint: &str, access_token: &str) -> AppResult<()> {
+        let url = self.endpoint_url(endpoint)?;
+        let request = self.request(Method::DELETE, url).bearer_auth(access_token);
+        let response = self.execute("DELETE", endpoint, request).await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
         }
 
-        Ok(out)
+        let body = response.text().await.unwrap_or_default();
+        Err(map_api_error(status, &body).at_endpoint("DELETE", endpoint))
     }
 
-    /// Issue a bearer-authenticated GET with optional query params and deserialize the JSON body.
-    async fn get_json<T: DeserializeOwned>(
+    /// Note a successful write on `ids` for the audit log.
+    fn audited<I: AsRef<str>>(
         &self,
+        method: &str,
         endpoint: &str,
-        access_token: &str,
-        query: Option<&[(String, String)]>,
-    ) -> AppResult<T> {
-        let url = self.endpoint_url(endpoint)?;
-        let mut request = self.http.get(url).bearer_auth(access_token);
-        if let Some(query) = query {
-            request = request.query(query);
+        ids: impl IntoIterator<Item = I>,
+    ) {
+        if let Some(audit) = &self.audit {
+            audit.record(AuditWrite {
+                operation: timings::operation_name(method, endpoint),
+                ids: ids.into_iter().map(|id| id.as_ref().to_string()).collect(),
+                ..AuditWrite::default()
+            });
         }
+    }
 
-        let response = request.send().await?;
-        self.parse_json_response(response).await
+    /// Note a successful label change (a POST to `endpoint`) for the audit log.
+    fn audit_labels(&self, endpoint: &str, ids: &[String], added: &[String], removed: &[String]) {
+        if let Some(audit) = &self.audit {
+            audit.record(AuditWrite {
+                operation: timings::operation_name("POST", endpoint),
+                ids: ids.to_vec(),
+                added_labels: added.to_vec(),
+                removed_labels: removed.to_vec(),
+                ..AuditWrite::default()
+            });
+        }
     }
 
-    /// Issue a bearer-authenticated POST with a JSON body and deserialize the JSON response.
-    async fn post_json<T: DeserializeOwned, B: Serialize>(
+    fn interrupted(&self) -> bool {
+        self.interrupt.as_ref().is_some_and(Interrupt::is_set)
+    }
+
+    /// Start a request carrying the client's default headers.
+    fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        self.http.request(method, url).headers(self.headers.clone())
+    }
+
+    /// Send a request inside a `gmail_api` span, logging status and latency at
+    /// debug level. A write that fails is noted for the audit log.
+    async fn execute(
         &self,
+        method: &'static str,
         endpoint: &str,
-        access_token: &str,
-        body: &B,
-    ) -> AppResult<T> {
-        let url = self.endpoint_url(endpoint)?;
-        let response = self
-            .http
-            .post(url)
-            .bearer_auth(access_token)
-            .json(body)
-            .send()
-            .await?;
+        request: RequestBuilder,
+    ) -> AppResult<reqwest::Response> {
+        if self.read_only && method != "GET" {
+            return Err(AppError::InvalidInput(format!(
+                "--dry-run: skipped {method} {endpoint}; this command cannot preview its changes"
+            )));
+        }
+        let span = tracing::debug_span!("gmail_api", method, endpoint);
+        let started = Instant::now();
+        let result = request.send().instrument(span.clone()).await;
+        let latency = started.elapsed();
+        let latency_ms = latency.as_millis() as u64;
+        if let Some(timings) = &self.timings {
+            let status = result
+                .as_ref()
+                .ok()
+                .map(|response| response.status().as_u16());
+            timings.record(method, endpoint, status, latency);
+        }
+
+        let failed = result
+            .as_ref()
+            .map_or(true, |response| !response.status().is_success());
+        if method != "GET"
+            && failed
+            && let Some(audit) = &self.audit
+        {
+            audit.record(AuditWrite {
+                operation: timings::operation_name(method, endpoint),
+                failed: true,
+                ..AuditWrite::default()
+            });
+        }
+
+        match &result {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                if response.status().is_success() {
+                    tracing::debug!(parent: &span, status, latency_ms, "request completed");
+                } else {
+                    tracing::info!(parent: &span, status, latency_ms, "request failed");
+                }
+            }
+            Err(err) => tracing::info!(parent: &span, latency_ms, error = %err, "request errored"),
+        }
 
-        self.parse_json_response(response).await
+        Ok(result?)
     }
 
-    /// Join an endpoint path onto the client's base URL.
+    /// Join an endpoint path onto the client's base URL, keeping any path
+    /// prefix the base URL has. Absolute endpoints (other Google APIs, such as
+    /// People) are used as given.
     fn endpoint_url(&self, endpoint: &str) -> AppResult<Url> {
+        if endpoint.starts_with("https://") {
+            return Ok(Url::parse(endpoint)?);
+        }
         let mut url = Url::parse(&self.base_url)?;
-        url.set_path(endpoint.trim_start_matches('/'));
+        let path = format!(
+            "{}/{}",
+            url.path().trim_end_matches('/'),
+            endpoint.trim_start_matches('/')
+        );
+        url.set_path(&path);
         Ok(url)
     }
 
     /// Deserialize a successful response, or convert an error status + body into an `AppError`.
     async fn parse_json_response<T: DeserializeOwned>(
         &self,
+        method: &str,
+        endpoint: &str,
         response: reqwest::Response,
     ) -> AppResult<T> {
         let status = response.status();
@@ -310,7 +1491,7 @@ impl GmailClient {
         }
 
         let body = response.text().await.unwrap_or_default();
-        Err(map_api_error(status, &body))
+        Err(map_api_error(status, &body).at_endpoint(method, endpoint))
     }
 }
 
@@ -320,13 +1501,120 @@ impl Default for GmailClient {
     }
 }
 
+/// Configures a [`GmailClient`]; see [`GmailClient::builder`]. Anything left
+/// unset keeps the defaults of [`GmailClient::new`].
+#[derive(Debug, Clone, Default)]
+pub struct GmailClientBuilder {
+    http: Option<Client>,
+    base_url: Option<String>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+}
+
+impl GmailClientBuilder {
+    /// Send requests through `http` (connection pool, proxy, timeouts).
+    pub fn http_client(mut self, http: Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Join Gmail endpoints onto `base_url` instead of
+    /// `https://gmail.googleapis.com`, e.g. an emulator at
+    /// `http://127.0.0.1:8085` or a proxy path prefix.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Override the HTTP client's `User-Agent` on Gmail requests.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Add a header to every request; repeated names send every value.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Validate the base URL and headers and build the client.
+    pub fn build(self) -> AppResult<GmailClient> {
+        let mut client = GmailClient::with_http(self.http.unwrap_or_default());
+
+        if let Some(base_url) = self.base_url {
+            let parsed = Url::parse(&base_url)
+                .map_err(|err| AppError::Config(format!("invalid base_url `{base_url}`: {err}")))?;
+            if !matches!(parsed.scheme(), "http" | "https") || parsed.cannot_be_a_base() {
+                return Err(AppError::Config(format!(
+                    "invalid base_url `{base_url}`: expected an http(s) URL"
+                )));
+            }
+            client.base_url = base_url;
+        }
+
+        for (name, value) in self.headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| AppError::InvalidInput(format!("invalid header name `{name}`")))?;
+            let header_value = HeaderValue::from_str(&value).map_err(|_| {
+                AppError::InvalidInput(format!("invalid value for header `{name}`"))
+            })?;
+            client.headers.append(header_name, header_value);
+        }
+
+        if let Some(user_agent) = self.user_agent {
+            let value = HeaderValue::from_str(&user_agent).map_err(|_| {
+                AppError::InvalidInput(format!("invalid user agent `{user_agent}`"))
+            })?;
+            client.headers.insert(USER_AGENT, value);
+        }
+
+        Ok(client)
+    }
+}
+
+/// Map requested label names (case-insensitive) or ids to label ids from
+/// `known`, dropping blanks and duplicates.
+fn match_label_ids(known: &[LabelView], requested: &[String]) -> AppResult<Vec<String>> {
+    let mut out = Vec::new();
+
+    for raw in requested {
+        let needle = raw.trim();
+        if needle.is_empty() {
+            continue;
+        }
+
+        let Some(label) = known
+            .iter()
+            .find(|label| label.id == needle || label.name.eq_ignore_ascii_case(needle))
+        else {
+            return Err(AppError::InvalidInput(format!(
+                "unknown label `{needle}`; run `gmail label ls` to inspect labels"
+            )));
+        };
+
+        if !out.contains(&label.id) {
+            out.push(label.id.clone());
+        }
+    }
+
+    Ok(out)
+}
+
 #[derive(Debug, Deserialize)]
 struct GmailMessageResource {
     id: String,
     #[serde(rename = "threadId")]
     thread_id: Option<String>,
+    #[serde(rename = "labelIds")]
+    label_ids: Option<Vec<String>>,
     snippet: Option<String>,
     payload: Option<GmailMessagePayload>,
+    #[serde(rename = "sizeEstimate")]
+    size_estimate: Option<u64>,
+    /// Milliseconds since the epoch, as a decimal string.
+    #[serde(rename = "internalDate")]
+    internal_date: Option<String>,
 }
 
 impl GmailMessageResource {
@@ -336,8 +1624,11 @@ impl GmailMessageResource {
         let GmailMessageResource {
             id,
             thread_id,
+            label_ids,
             snippet,
             payload,
+            size_estimate,
+            internal_date,
         } = self;
 
         let headers = payload
@@ -350,25 +1641,38 @@ impl GmailMessageResource {
             collect_attachments(payload, &mut attachments);
         }
 
+        let (to, cc) = (header_value(headers, "To"), header_value(headers, "Cc"));
         MessageView {
             id,
             thread_id,
+            label_ids: label_ids.unwrap_or_default(),
             snippet,
             subject: header_value(headers, "Subject"),
             from: header_value(headers, "From"),
+            to_addresses: addresses(to.as_deref()),
+            cc_addresses: addresses(cc.as_deref()),
+            to,
+            cc,
             reply_to: header_value(headers, "Reply-To"),
             date: header_value(headers, "Date"),
+            internal_date: internal_date
+                .and_then(|millis| millis.parse().ok())
+                .and_then(chrono::DateTime::from_timestamp_millis)
+                .map(|at| at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
             message_id: header_value(headers, "Message-ID"),
             in_reply_to: header_value(headers, "In-Reply-To"),
             references: header_value(headers, "References"),
             body,
             attachments,
+            size_estimate,
         }
     }
 }
 
 #[derive(Debug, Deserialize)]
 struct GmailMessagePayload {
+    #[serde(rename = "partId")]
+    part_id: Option<String>,
     headers: Option<Vec<GmailMessageHeader>>,
     #[serde(rename = "mimeType")]
     mime_type: Option<String>,
@@ -420,6 +1724,38 @@ fn part_text(part: &GmailMessagePayload, want_mime: &str) -> Option<String> {
     None
 }
 
+/// The first part holding an iCalendar object: `text/calendar` preferred,
+/// then `application/ics` or any `.ics` attachment.
+fn calendar_part(part: &GmailMessagePayload) -> Option<&GmailMessagePayload> {
+    fn find<'a>(
+        part: &'a GmailMessagePayload,
+        matches: &dyn Fn(&GmailMessagePayload) -> bool,
+    ) -> Option<&'a GmailMessagePayload> {
+        if matches(part) {
+            return Some(part);
+        }
+        part.parts
+            .iter()
+            .flatten()
+            .find_map(|nested| find(nested, matches))
+    }
+
+    let mime = |part: &GmailMessagePayload, want: &str| {
+        part.mime_type
+            .as_deref()
+            .is_some_and(|mime| mime.eq_ignore_ascii_case(want))
+    };
+    find(part, &|part| mime(part, "text/calendar")).or_else(|| {
+        find(part, &|part| {
+            mime(part, "application/ics")
+                || part
+                    .filename
+                    .as_deref()
+                    .is_some_and(|name| name.to_ascii_lowercase().ends_with(".ics"))
+        })
+    })
+}
+
 /// Crudely reduce an HTML fragment to plain text: drop tags, decode entities,
 /// and collapse trailing whitespace. Good enough for reading an email in a terminal.
 fn strip_html(html: &str) -> String {
@@ -451,6 +1787,7 @@ fn collect_attachments(part: &GmailMessagePayload, out: &mut Vec<AttachmentMeta>
         let filename = part.filename.clone().unwrap_or_default();
         if !filename.is_empty() {
             out.push(AttachmentMeta {
+                part_id: part.part_id.clone().filter(|id| !id.is_empty()),
                 attachment_id: attachment_id.clone(),
                 filename,
                 mime_type: part
@@ -474,12 +1811,17 @@ fn decode_base64url(data: &str) -> AppResult<Vec<u8>> {
     let trimmed = data.trim_end_matches('=');
     URL_SAFE_NO_PAD
         .decode(trimmed)
-        .map_err(|err| AppError::Api(format!("failed to decode attachment data: {err}")))
+        .map_err(|err| AppError::api(format!("failed to decode base64url data: {err}")))
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct GmailMessageListResource {
+    /// `threads` in a `threads.list` page and `drafts` in a `drafts.list`
+    /// page, whose entries also carry an `id`.
+    #[serde(alias = "threads", alias = "drafts")]
     messages: Option<Vec<GmailMessageListEntry>>,
+    next_page_token: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -494,6 +1836,51 @@ struct GmailSendRequest {
     thread_id: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct GmailImportRequest {
+    raw: String,
+    #[serde(rename = "labelIds", skip_serializing_if = "Vec::is_empty")]
+    label_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct GmailDraftRequest {
+    /// Set on `drafts.update`, naming the draft being replaced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    message: GmailSendRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct GmailDraftResponse {
+    id: String,
+    message: GmailSendResponse,
+}
+
+#[derive(Debug, Serialize)]
+struct GmailDraftIdRequest<'a> {
+    id: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct GmailDraftResource {
+    id: String,
+    message: GmailMessageResource,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailThreadResource {
+    id: String,
+    history_id: Option<String>,
+    messages: Option<Vec<GmailMessageResource>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GmailRawMessageResource {
+    raw: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GmailSendResponse {
     id: String,
@@ -517,6 +1904,8 @@ struct GmailSendAsResource {
     #[serde(default)]
     is_default: bool,
     verification_status: Option<String>,
+    reply_to_address: Option<String>,
+    signature: Option<String>,
 }
 
 impl GmailSendAsResource {
@@ -531,10 +1920,315 @@ impl GmailSendAsResource {
             is_primary: self.is_primary,
             is_default: self.is_default,
             verification_status: self.verification_status,
+            reply_to: self.reply_to_address.filter(|address| !address.is_empty()),
+            signature: self
+                .signature
+                .filter(|signature| !signature.trim().is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailForwardingListResponse {
+    #[serde(default)]
+    forwarding_addresses: Vec<GmailForwardingAddressResource>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailForwardingAddressResource {
+    forwarding_email: String,
+    verification_status: Option<String>,
+}
+
+impl GmailForwardingAddressResource {
+    fn into_view(self) -> ForwardingAddress {
+        ForwardingAddress {
+            email: self.forwarding_email,
+            verification_status: self.verification_status,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailAutoForwardingResource {
+    #[serde(default)]
+    enabled: bool,
+    email_address: Option<String>,
+    disposition: Option<String>,
+}
+
+impl GmailAutoForwardingResource {
+    /// Drop the placeholder values Gmail reports while forwarding is off.
+    fn into_view(self) -> AutoForwarding {
+        AutoForwarding {
+            enabled: self.enabled,
+            email: self.email_address.filter(|email| !email.is_empty()),
+            disposition: self
+                .disposition
+                .filter(|disposition| disposition != "dispositionUnspecified"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailImapResource {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    auto_expunge: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expunge_behavior: Option<String>,
+    #[serde(default)]
+    max_folder_size: u32,
+}
+
+impl GmailImapResource {
+    fn into_view(self) -> ImapSettings {
+        ImapSettings {
+            enabled: self.enabled,
+            auto_expunge: self.auto_expunge,
+            expunge_behavior: self
+                .expunge_behavior
+                .filter(|behavior| behavior != "expungeBehaviorUnspecified"),
+            max_folder_size: self.max_folder_size,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailPopResource {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    access_window: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disposition: Option<String>,
+}
+
+impl GmailPopResource {
+    fn into_view(self) -> PopSettings {
+        let access_window = self
+            .access_window
+            .filter(|window| window != "accessWindowUnspecified")
+            .unwrap_or_else(|| "disabled".to_string());
+        PopSettings {
+            enabled: access_window != "disabled",
+            access_window,
+            disposition: self
+                .disposition
+                .filter(|disposition| disposition != "dispositionUnspecified"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PeopleSearchResponse {
+    #[serde(default)]
+    results: Vec<PeopleSearchResult>,
+}
+
+impl PeopleSearchResponse {
+    fn people(self) -> Vec<PeopleResource> {
+        self.results
+            .into_iter()
+            .map(|result| result.person)
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PeopleSearchResult {
+    person: PeopleResource,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PeopleDirectoryResponse {
+    #[serde(default)]
+    people: Vec<PeopleResource>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PeopleResource {
+    #[serde(default)]
+    names: Vec<PeopleName>,
+    #[serde(default)]
+    email_addresses: Vec<PeopleEmailAddress>,
+    #[serde(default)]
+    organizations: Vec<PeopleOrganization>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PeopleName {
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PeopleEmailAddress {
+    value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PeopleOrganization {
+    name: Option<String>,
+    department: Option<String>,
+    title: Option<String>,
+}
+
+impl PeopleResource {
+    /// One match per email address on the person, sharing their name and
+    /// primary organization (`department, title, name`).
+    fn into_matches(self, source: PersonSource) -> Vec<PersonMatch> {
+        let name = self
+            .names
+            .into_iter()
+            .filter_map(|name| name.display_name)
+            .map(|name| name.trim().to_string())
+            .find(|name| !name.is_empty());
+        let organization = self.organizations.into_iter().next().and_then(|org| {
+            let parts = [org.department, org.title, org.name]
+                .into_iter()
+                .flatten()
+                .map(|part| part.trim().to_string())
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>();
+            (!parts.is_empty()).then(|| parts.join(", "))
+        });
+
+        self.email_addresses
+            .into_iter()
+            .filter_map(|email| email.value)
+            .map(|email| email.trim().to_string())
+            .filter(|email| email.contains('@'))
+            .map(|email| PersonMatch {
+                name: name.clone(),
+                address: address::display(name.as_deref(), &email),
+                email,
+                organization: organization.clone(),
+                source,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailProfileResponse {
+    email_address: String,
+    messages_total: Option<u64>,
+    threads_total: Option<u64>,
+    history_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailHistoryListResponse {
+    history: Option<Vec<GmailHistoryRecord>>,
+    next_page_token: Option<String>,
+    history_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailHistoryRecord {
+    id: String,
+    #[serde(default)]
+    messages_added: Vec<GmailHistoryMessageChange>,
+    #[serde(default)]
+    messages_deleted: Vec<GmailHistoryMessageChange>,
+    #[serde(default)]
+    labels_added: Vec<GmailHistoryLabelChange>,
+    #[serde(default)]
+    labels_removed: Vec<GmailHistoryLabelChange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GmailHistoryMessageChange {
+    message: GmailHistoryMessage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailHistoryLabelChange {
+    message: GmailHistoryMessage,
+    #[serde(default)]
+    label_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailHistoryMessage {
+    id: String,
+    thread_id: Option<String>,
+    #[serde(default)]
+    label_ids: Vec<String>,
+}
+
+impl GmailHistoryRecord {
+    /// Flatten one history record into per-message events, appending to `out`.
+    fn into_events(self, out: &mut Vec<MailboxEvent>) {
+        let history_id = self.id;
+        let event = |kind, message: GmailHistoryMessage, label_ids| MailboxEvent {
+            kind,
+            history_id: history_id.clone(),
+            message_id: message.id,
+            thread_id: message.thread_id,
+            label_ids,
+        };
+
+        for change in self.messages_added {
+            let labels = change.message.label_ids.clone();
+            out.push(event(
+                MailboxEventKind::MessageAdded,
+                change.message,
+                labels,
+            ));
+        }
+        for change in self.messages_deleted {
+            out.push(event(
+                MailboxEventKind::MessageDeleted,
+                change.message,
+                Vec::new(),
+            ));
+        }
+        for change in self.labels_added {
+            out.push(event(
+                MailboxEventKind::LabelsAdded,
+                change.message,
+                change.label_ids,
+            ));
+        }
+        for change in self.labels_removed {
+            out.push(event(
+                MailboxEventKind::LabelsRemoved,
+                change.message,
+                change.label_ids,
+            ));
         }
     }
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailWatchRequest {
+    topic_name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    label_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label_filter_behavior: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailWatchResponse {
+    history_id: String,
+    expiration: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct GmailLabelListResponse {
     labels: Option<Vec<GmailLabelResource>>,
@@ -548,6 +2242,16 @@ struct GmailLabelResource {
     kind: String,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GmailCreateLabelRequest<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label_list_visibility: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_list_visibility: Option<&'a str>,
+}
+
 #[derive(Debug, Serialize)]
 struct GmailModifyLabelsRequest {
     #[serde(rename = "addLabelIds")]
@@ -559,6 +2263,20 @@ struct GmailModifyLabelsRequest {
 #[derive(Debug, Deserialize)]
 struct GmailModifyLabelsResponse {}
 
+#[derive(Debug, Serialize)]
+struct GmailBatchModifyRequest<'a> {
+    ids: &'a [String],
+    #[serde(rename = "addLabelIds")]
+    add_label_ids: &'a [String],
+    #[serde(rename = "removeLabelIds")]
+    remove_label_ids: &'a [String],
+}
+
+#[derive(Debug, Serialize)]
+struct GmailBatchDeleteRequest<'a> {
+    ids: &'a [String],
+}
+
 #[derive(Debug, Deserialize)]
 struct GmailMessageHeader {
     name: String,
@@ -583,6 +2301,16 @@ struct GmailApiErrorDetail {
     reason: Option<String>,
 }
 
+/// The bare addresses in an address-list header, in order.
+fn addresses(header: Option<&str>) -> Vec<String> {
+    header
+        .map(address::split_addresses)
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| address::email_of(entry))
+        .collect()
+}
+
 /// Find a header by case-insensitive name, returning its trimmed value if non-empty.
 fn header_value(headers: &[GmailMessageHeader], target: &str) -> Option<String> {
     headers
@@ -592,9 +2320,19 @@ fn header_value(headers: &[GmailMessageHeader], target: &str) -> Option<String>
         .filter(|value| !value.is_empty())
 }
 
-/// Map an HTTP error status and body into an `AppError`, routing 401/403 to an auth error.
+/// Map an HTTP error status and body into an `AppError` carrying the status
+/// and Gmail reason. 401, and 403 other than a rate limit, are auth errors.
 fn map_api_error(status: StatusCode, body: &str) -> AppError {
-    let message = parse_api_error_message(body).unwrap_or_else(|| {
+    let envelope = serde_json::from_str::<GmailApiErrorEnvelope>(body).ok();
+    let reason = envelope.as_ref().and_then(|envelope| {
+        envelope
+            .error
+            .errors
+            .iter()
+            .flatten()
+            .find_map(|detail| detail.reason.clone())
+    });
+    let message = envelope.and_then(api_error_message).unwrap_or_else(|| {
         let body = body.trim();
         if body.is_empty() {
             "no error details in response body".to_string()
@@ -603,18 +2341,26 @@ fn map_api_error(status: StatusCode, body: &str) -> AppError {
         }
     });
 
-    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
-        return AppError::Auth(format!(
-            "gmail api authorization failed ({status}): {message}. run `gmail auth login`"
-        ));
+    let detail = ApiError::http(status.as_u16(), String::new()).with_reason(reason);
+    if status == StatusCode::UNAUTHORIZED
+        || (status == StatusCode::FORBIDDEN && !detail.retryable())
+    {
+        return AppError::Auth(ApiError {
+            message: format!(
+                "gmail api authorization failed ({status}): {message}. run `gmail auth login`"
+            ),
+            ..detail
+        });
     }
 
-    AppError::Api(format!("gmail api request failed ({status}): {message}"))
+    AppError::Api(ApiError {
+        message: format!("gmail api request failed ({status}): {message}"),
+        ..detail
+    })
 }
 
-/// Parse Gmail's JSON error envelope into a compact `message, status, code, reason` string.
-fn parse_api_error_message(body: &str) -> Option<String> {
-    let envelope = serde_json::from_str::<GmailApiErrorEnvelope>(body).ok()?;
+/// Compact Gmail's JSON error envelope into a `message, status, code, reason` string.
+fn api_error_message(envelope: GmailApiErrorEnvelope) -> Option<String> {
     let mut parts = Vec::new();
 
     if let Some(message) = envelope.error.message {
@@ -0,0 +1,32 @@
+use super::messages;
+
+/// Endpoint path for listing and creating drafts.
+pub fn list_endpoint() -> &'static str {
+    "/gmail/v1/users/me/drafts"
+}
+
+/// Endpoint path for a single draft by id.
+pub fn draft_endpoint(id: &str) -> String {
+    format!("/gmail/v1/users/me/drafts/{id}")
+}
+
+/// Endpoint path for sending an existing draft.
+pub fn send_endpoint() -> &'static str {
+    "/gmail/v1/users/me/drafts/send"
+}
+
+/// Query params fetching a draft: its message with `format=full` (decoded
+/// body included) or `format=metadata` trimmed to
+/// [`messages::METADATA_FIELDS`].
+pub fn get_query(full: bool) -> Vec<(String, String)> {
+    if full {
+        return messages::full_query();
+    }
+    vec![
+        ("format".to_string(), "metadata".to_string()),
+        (
+            "fields".to_string(),
+            format!("id,message({})", messages::METADATA_FIELDS),
+        ),
+    ]
+}
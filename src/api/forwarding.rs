@@ -0,0 +1,14 @@
+/// Endpoint path for listing and adding forwarding addresses.
+pub fn forwarding_addresses_endpoint() -> &'static str {
+    "/gmail/v1/users/me/settings/forwardingAddresses"
+}
+
+/// Endpoint path for one forwarding address (delete).
+pub fn forwarding_address_endpoint(email: &str) -> String {
+    format!("/gmail/v1/users/me/settings/forwardingAddresses/{email}")
+}
+
+/// Endpoint path for the auto-forwarding setting (get, update).
+pub fn auto_forwarding_endpoint() -> &'static str {
+    "/gmail/v1/users/me/settings/autoForwarding"
+}
@@ -0,0 +1,36 @@
+/// Endpoint path for the mailbox's change history.
+pub fn list_history_endpoint() -> &'static str {
+    "/gmail/v1/users/me/history"
+}
+
+/// Endpoint path for the authenticated user's mailbox profile.
+pub fn profile_endpoint() -> &'static str {
+    "/gmail/v1/users/me/profile"
+}
+
+/// Query params for a history page starting after `start_history_id`,
+/// optionally only for one label and only the given `historyTypes`
+/// (`messageAdded`, `labelRemoved`, ...).
+pub fn list_history_query(
+    start_history_id: &str,
+    label_id: Option<&str>,
+    history_types: &[&str],
+    page_token: Option<&str>,
+) -> Vec<(String, String)> {
+    let mut params = vec![
+        ("startHistoryId".to_string(), start_history_id.to_string()),
+        ("maxResults".to_string(), "500".to_string()),
+    ];
+    if let Some(label_id) = label_id {
+        params.push(("labelId".to_string(), label_id.to_string()));
+    }
+    params.extend(
+        history_types
+            .iter()
+            .map(|kind| ("historyTypes".to_string(), kind.to_string())),
+    );
+    if let Some(page_token) = page_token {
+        params.push(("pageToken".to_string(), page_token.to_string()));
+    }
+    params
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use reqwest::{Client, Proxy};
+
+use crate::config::Settings;
+use crate::error::{AppError, AppResult};
+
+/// `User-Agent` sent on every request, e.g. `gmail/0.5.0`.
+pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Build the one HTTP client shared by OAuth, Gmail API, and webhook calls so
+/// they reuse connections and follow the same policy: `timeout_secs`, the
+/// `proxy` setting (falling back to the usual `HTTPS_PROXY` variables), the
+/// connection pool and TCP keepalive settings, our user agent, and
+/// gzip/brotli response compression.
+pub fn build_client(settings: &Settings) -> AppResult<Client> {
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .gzip(true)
+        .brotli(true);
+    if let Some(secs) = settings.timeout_secs {
+        builder = builder.timeout(Duration::from_secs(secs));
+    }
+    if let Some(secs) = settings.pool_idle_timeout_secs {
+        builder = builder.pool_idle_timeout(Duration::from_secs(secs));
+    }
+    if let Some(max) = settings.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max);
+    }
+    if let Some(secs) = settings.tcp_keepalive_secs {
+        builder = builder.tcp_keepalive(Duration::from_secs(secs));
+    }
+    if let Some(proxy) = settings
+        .proxy
+        .as_deref()
+        .map(str::trim)
+        .filter(|proxy| !proxy.is_empty())
+    {
+        let proxy = Proxy::all(proxy)
+            .map_err(|err| AppError::Config(format!("invalid proxy `{proxy}`: {err}")))?;
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn advertises_compression_and_user_agent() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap().to_ascii_lowercase()
+        });
+
+        let client = build_client(&Settings::default()).unwrap();
+        client.get(&url).send().await.unwrap();
+        let request = server.await.unwrap();
+
+        let accept = request
+            .lines()
+            .find_map(|line| line.strip_prefix("accept-encoding:"))
+            .expect("accept-encoding header");
+        assert!(accept.contains("gzip") && accept.contains("br"), "{accept}");
+        assert!(request.contains(&format!("user-agent: {USER_AGENT}")));
+    }
+
+    #[test]
+    fn rejects_malformed_proxy() {
+        let settings = Settings {
+            proxy: Some("http://[::1".to_string()),
+            ..Settings::default()
+        };
+        assert!(build_client(&settings).is_err());
+
+        let settings = Settings {
+            proxy: Some("http://127.0.0.1:3128".to_string()),
+            timeout_secs: Some(5),
+            pool_idle_timeout_secs: Some(30),
+            pool_max_idle_per_host: Some(4),
+            tcp_keepalive_secs: Some(60),
+            ..Settings::default()
+        };
+        assert!(build_client(&settings).is_ok());
+    }
+}
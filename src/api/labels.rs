@@ -1,4 +1,4 @@
-/// Endpoint path for listing the account's labels.
+/// Endpoint path for listing the account's labels; a POST creates one.
 pub fn list_labels_endpoint() -> &'static str {
     "/gmail/v1/users/me/labels"
 }
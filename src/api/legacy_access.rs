@@ -0,0 +1,9 @@
+/// Endpoint path for the IMAP setting (get, update).
+pub fn imap_endpoint() -> &'static str {
+    "/gmail/v1/users/me/settings/imap"
+}
+
+/// Endpoint path for the POP setting (get, update).
+pub fn pop_endpoint() -> &'static str {
+    "/gmail/v1/users/me/settings/pop"
+}
@@ -8,32 +8,99 @@ pub fn attachment_endpoint(message_id: &str, attachment_id: &str) -> String {
     format!("/gmail/v1/users/me/messages/{message_id}/attachments/{attachment_id}")
 }
 
+/// OAuth scope `messages.delete` needs on top of the login scopes: full mail
+/// access, since `gmail.modify` can trash but not permanently delete.
+pub const DELETE_SCOPES: [&str; 1] = ["https://mail.google.com/"];
+
+/// Endpoint path for moving a message to the trash.
+pub fn trash_endpoint(id: &str) -> String {
+    format!("/gmail/v1/users/me/messages/{id}/trash")
+}
+
+/// Endpoint path for taking a message back out of the trash.
+pub fn untrash_endpoint(id: &str) -> String {
+    format!("/gmail/v1/users/me/messages/{id}/untrash")
+}
+
 /// Endpoint path for listing messages.
 pub fn list_endpoint() -> &'static str {
     "/gmail/v1/users/me/messages"
 }
 
+/// Endpoint path for changing labels on many messages at once.
+pub fn batch_modify_endpoint() -> &'static str {
+    "/gmail/v1/users/me/messages/batchModify"
+}
+
+/// Endpoint path for permanently deleting many messages at once.
+pub fn batch_delete_endpoint() -> &'static str {
+    "/gmail/v1/users/me/messages/batchDelete"
+}
+
+/// Most message ids `messages.batchModify` and `messages.batchDelete` accept
+/// in one request.
+pub const BATCH_SIZE: usize = 1000;
+
 /// Endpoint path for sending a message.
 pub fn send_endpoint() -> &'static str {
     "/gmail/v1/users/me/messages/send"
 }
 
-/// Query params requesting `format=metadata` with the common envelope headers.
+/// Endpoint path for importing a message as if delivered (`messages.import`).
+pub fn import_endpoint() -> &'static str {
+    "/gmail/v1/users/me/messages/import"
+}
+
+/// Query params for `messages.import` and `messages.insert`: date the message
+/// by its Date header rather than the time of upload.
+pub fn import_query() -> Vec<(String, String)> {
+    vec![("internalDateSource".to_string(), "dateHeader".to_string())]
+}
+
+/// Partial-response mask for metadata fetches: only what `MessageView` reads.
+pub const METADATA_FIELDS: &str = "id,threadId,labelIds,snippet,sizeEstimate,internalDate,payload(mimeType,filename,headers,body(attachmentId,size),parts)";
+
+/// The envelope headers metadata fetches ask for.
+pub const METADATA_HEADERS: [&str; 9] = [
+    "Subject",
+    "From",
+    "To",
+    "Cc",
+    "Reply-To",
+    "Date",
+    "Message-ID",
+    "In-Reply-To",
+    "References",
+];
+
+/// Query params requesting `format=metadata` with the common envelope headers,
+/// trimmed by [`METADATA_FIELDS`].
 pub fn get_query() -> Vec<(String, String)> {
-    let mut query = vec![("format".to_string(), "metadata".to_string())];
-
-    for header in [
-        "Subject",
-        "From",
-        "Reply-To",
-        "Date",
-        "Message-ID",
-        "In-Reply-To",
-        "References",
-    ] {
-        query.push(("metadataHeaders".to_string(), header.to_string()));
-    }
+    let mut query = vec![
+        ("format".to_string(), "metadata".to_string()),
+        ("fields".to_string(), METADATA_FIELDS.to_string()),
+    ];
 
+    query.extend(
+        METADATA_HEADERS
+            .iter()
+            .map(|header| ("metadataHeaders".to_string(), header.to_string())),
+    );
+    query
+}
+
+/// Query params requesting `format=metadata` with just the named headers, or
+/// every header when `names` is empty.
+pub fn headers_query(names: &[&str]) -> Vec<(String, String)> {
+    let mut query = vec![
+        ("format".to_string(), "metadata".to_string()),
+        ("fields".to_string(), "id,payload/headers".to_string()),
+    ];
+    query.extend(
+        names
+            .iter()
+            .map(|name| ("metadataHeaders".to_string(), name.to_string())),
+    );
     query
 }
 
@@ -42,6 +109,17 @@ pub fn full_query() -> Vec<(String, String)> {
     vec![("format".to_string(), "full".to_string())]
 }
 
+/// Query params requesting `format=raw` (the whole RFC 822 message, base64url).
+pub fn raw_query() -> Vec<(String, String)> {
+    vec![
+        ("format".to_string(), "raw".to_string()),
+        ("fields".to_string(), "raw".to_string()),
+    ]
+}
+
+/// Largest `maxResults` the list endpoint honours in one page.
+pub const MAX_PAGE_SIZE: u32 = 500;
+
 /// Query params for a list request: `maxResults` and an optional Gmail search `q`.
 pub fn list_query(limit: u32, query: Option<&str>) -> Vec<(String, String)> {
     let mut params = vec![("maxResults".to_string(), limit.to_string())];
@@ -1,5 +1,16 @@
+pub mod audit;
 pub mod client;
+pub mod drafts;
+pub mod forwarding;
+pub mod history;
+pub mod http;
 pub mod labels;
+pub mod legacy_access;
 pub mod messages;
 pub mod models;
+pub mod people;
+pub mod quota;
 pub mod send_as;
+pub mod threads;
+pub mod timings;
+pub mod watch;
@@ -1,19 +1,99 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+use crate::mail::address;
+
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
 pub struct MessageView {
     pub id: String,
     pub thread_id: Option<String>,
+    pub label_ids: Vec<String>,
     pub snippet: Option<String>,
     pub subject: Option<String>,
     pub from: Option<String>,
+    pub to: Option<String>,
+    pub cc: Option<String>,
+    /// Each `To` recipient's bare, lowercased address.
+    pub to_addresses: Vec<String>,
+    /// Each `Cc` recipient's bare, lowercased address.
+    pub cc_addresses: Vec<String>,
     pub date: Option<String>,
+    /// When Gmail received the message (`internalDate`), RFC 3339 in UTC;
+    /// unlike `date`, set by Gmail rather than the sender.
+    pub internal_date: Option<String>,
     pub message_id: Option<String>,
     pub in_reply_to: Option<String>,
     pub references: Option<String>,
     pub reply_to: Option<String>,
     pub body: Option<String>,
     pub attachments: Vec<AttachmentMeta>,
+    /// Gmail's estimate of the message's size in bytes.
+    pub size_estimate: Option<u64>,
+}
+
+/// A conversation and its messages, oldest first.
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct ThreadView {
+    pub id: String,
+    pub history_id: Option<String>,
+    pub messages: Vec<MessageView>,
+}
+
+/// A conversation at a glance, as `thread ls` prints it.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ThreadSummary {
+    pub id: String,
+    /// Subject of the first message.
+    pub subject: Option<String>,
+    /// Each sender once, by name when the From header has one, in the order
+    /// they first wrote.
+    pub participants: Vec<String>,
+    pub message_count: usize,
+    /// Date header of the latest message that has one.
+    pub date: Option<String>,
+    /// Snippet of the latest message.
+    pub snippet: Option<String>,
+    /// Whether any message is still unread.
+    pub unread: bool,
+}
+
+impl ThreadView {
+    pub fn summary(&self) -> ThreadSummary {
+        let mut seen = Vec::new();
+        let mut participants = Vec::new();
+        for from in self
+            .messages
+            .iter()
+            .filter_map(|message| message.from.as_deref())
+        {
+            let email = address::email_of(from);
+            if !seen.contains(&email) {
+                participants.push(address::name_of(from).unwrap_or_else(|| email.clone()));
+                seen.push(email);
+            }
+        }
+        let latest = self.messages.last();
+
+        ThreadSummary {
+            id: self.id.clone(),
+            subject: self
+                .messages
+                .iter()
+                .find_map(|message| message.subject.clone()),
+            participants,
+            message_count: self.messages.len(),
+            date: self
+                .messages
+                .iter()
+                .rev()
+                .find_map(|message| message.date.clone()),
+            snippet: latest.and_then(|message| message.snippet.clone()),
+            unread: self
+                .messages
+                .iter()
+                .any(|message| message.label_ids.iter().any(|label| label == "UNREAD")),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,41 +117,98 @@ pub struct Attachment {
     pub data: Vec<u8>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SendResult {
     pub id: String,
     pub thread_id: Option<String>,
     pub note: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DraftResult {
+    pub id: String,
+    pub message_id: String,
+    pub thread_id: Option<String>,
+}
+
+/// A saved draft: its id (what `draft edit|send|rm` take) and the message
+/// it holds.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DraftView {
+    pub id: String,
+    pub message: MessageView,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct AttachmentMeta {
+    /// The MIME part it is, e.g. `1` or `0.2`; `attachments get <id> <part>`
+    /// takes it.
+    pub part_id: Option<String>,
     pub attachment_id: String,
     pub filename: String,
     pub mime_type: String,
     pub size: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct AttachmentList {
     pub message_id: String,
     pub attachments: Vec<AttachmentMeta>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// An attachment found by `attachments ls --q`, with the message it is on.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AttachmentRow {
+    pub message_id: String,
+    pub date: Option<String>,
+    pub from: Option<String>,
+    pub filename: String,
+    pub mime_type: String,
+    pub size: Option<u64>,
+    pub part_id: Option<String>,
+    pub attachment_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SavedAttachment {
     pub filename: String,
     pub path: String,
     pub bytes: u64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SendAsView {
     pub email: String,
     pub display_name: Option<String>,
     pub is_primary: bool,
     pub is_default: bool,
     pub verification_status: Option<String>,
+    pub reply_to: Option<String>,
+    /// HTML signature Gmail appends in its own clients.
+    pub signature: Option<String>,
+}
+
+/// A new send-as address for `GmailClient::create_send_as`.
+#[derive(Debug, Clone, Default)]
+pub struct NewSendAs {
+    pub email: String,
+    pub display_name: Option<String>,
+    pub reply_to: Option<String>,
+    pub signature: Option<String>,
+}
+
+/// Fields to change on a send-as address; `None` leaves a field as it is.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendAsUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_default: Option<bool>,
 }
 
 impl SendAsView {
@@ -83,17 +220,121 @@ impl SendAsView {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// An address mail can be forwarded to, once it has confirmed.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ForwardingAddress {
+    pub email: String,
+    /// `accepted` once the address has confirmed, otherwise `pending`.
+    pub verification_status: Option<String>,
+}
+
+/// The account's auto-forwarding setting.
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct AutoForwarding {
+    pub enabled: bool,
+    pub email: Option<String>,
+    /// `leaveInInbox`, `markRead`, `archive`, or `trash`.
+    pub disposition: Option<String>,
+}
+
+/// The account's IMAP setting.
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct ImapSettings {
+    pub enabled: bool,
+    pub auto_expunge: bool,
+    /// `archive`, `trash`, or `deleteForever`: what an IMAP expunge does.
+    pub expunge_behavior: Option<String>,
+    /// Largest folder shown over IMAP, in messages; 0 means no limit.
+    pub max_folder_size: u32,
+}
+
+/// The account's POP setting.
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct PopSettings {
+    pub enabled: bool,
+    /// `disabled`, `allMail`, or `fromNowOn`.
+    pub access_window: String,
+    /// `leaveInInbox`, `markRead`, `archive`, or `trash` after a POP fetch.
+    pub disposition: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct LabelView {
     pub id: String,
     pub name: String,
     pub kind: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct LabelMutationResult {
     pub id: String,
     pub added: Vec<String>,
     pub removed: Vec<String>,
     pub note: String,
+    /// Why the change failed for this message, when it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WatchResult {
+    pub history_id: String,
+    pub expiration_unix_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MailboxProfile {
+    pub email: String,
+    pub messages_total: u64,
+    pub threads_total: u64,
+    pub history_id: String,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MailboxEventKind {
+    MessageAdded,
+    MessageDeleted,
+    LabelsAdded,
+    LabelsRemoved,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MailboxEvent {
+    #[serde(rename = "type")]
+    pub kind: MailboxEventKind,
+    pub history_id: String,
+    pub message_id: String,
+    pub thread_id: Option<String>,
+    pub label_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HistoryPage {
+    pub start_history_id: String,
+    pub history_id: String,
+    pub events: Vec<MailboxEvent>,
+}
+
+/// Where a `contacts search` match came from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonSource {
+    /// The user's saved contacts.
+    Contacts,
+    /// The Workspace domain directory.
+    Directory,
+    /// People the user has corresponded with but not saved.
+    OtherContacts,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PersonMatch {
+    pub name: Option<String>,
+    pub email: String,
+    /// `Name <email>`, ready for `send --to`.
+    pub address: String,
+    /// Department, title, and organization name, when known.
+    pub organization: Option<String>,
+    pub source: PersonSource,
 }
@@ -0,0 +1,29 @@
+/// OAuth scopes `contacts search` needs on top of the login scopes: saved
+/// contacts, "other contacts" (people you've emailed), and the Workspace
+/// directory.
+pub const CONTACTS_SCOPES: [&str; 3] = [
+    "https://www.googleapis.com/auth/contacts.readonly",
+    "https://www.googleapis.com/auth/contacts.other.readonly",
+    "https://www.googleapis.com/auth/directory.readonly",
+];
+
+/// Fields requested for saved contacts and directory people.
+pub const PERSON_READ_MASK: &str = "names,emailAddresses,organizations";
+
+/// Fields requested for other contacts, which don't support `organizations`.
+pub const OTHER_CONTACT_READ_MASK: &str = "names,emailAddresses";
+
+/// Endpoint URL for `people.searchContacts` (saved contacts).
+pub fn search_contacts_endpoint() -> &'static str {
+    "https://people.googleapis.com/v1/people:searchContacts"
+}
+
+/// Endpoint URL for `otherContacts.search` (auto-saved correspondents).
+pub fn search_other_contacts_endpoint() -> &'static str {
+    "https://people.googleapis.com/v1/otherContacts:search"
+}
+
+/// Endpoint URL for `people.searchDirectoryPeople` (Workspace directory).
+pub fn search_directory_endpoint() -> &'static str {
+    "https://people.googleapis.com/v1/people:searchDirectoryPeople"
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::timings::TimingsReport;
+use crate::error::AppResult;
+
+/// Gmail's per-user rate limit, in quota units per minute.
+pub const PER_USER_UNITS_PER_MINUTE: u32 = 15_000;
+
+/// Days of usage kept in a profile's ledger.
+pub const KEEP_DAYS: usize = 30;
+
+/// Estimated quota use for one local calendar day.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DayUsage {
+    /// `YYYY-MM-DD`, local time.
+    pub date: String,
+    pub units: u32,
+    pub requests: usize,
+    /// Units by command path (`list`, `label add`).
+    pub commands: BTreeMap<String, u32>,
+    /// Units by API method (`messages.get`).
+    pub operations: BTreeMap<String, u32>,
+}
+
+/// A profile's daily quota use, persisted under the data directory so
+/// `gmail quota` can show it across runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaLedger {
+    pub days: Vec<DayUsage>,
+}
+
+impl QuotaLedger {
+    pub fn load(path: &Path) -> AppResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> AppResult<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Add one command's requests to `date`, dropping days beyond [`KEEP_DAYS`].
+    pub fn record(&mut self, date: &str, command: &str, report: &TimingsReport) {
+        let index = match self
+            .days
+            .binary_search_by(|day| day.date.as_str().cmp(date))
+        {
+            Ok(index) => index,
+            Err(index) => {
+                let day = DayUsage {
+                    date: date.to_string(),
+                    ..DayUsage::default()
+                };
+                self.days.insert(index, day);
+                index
+            }
+        };
+        let day = &mut self.days[index];
+        day.units += report.quota_units;
+        day.requests += report.requests;
+        *day.commands.entry(command.to_string()).or_default() += report.quota_units;
+        for operation in &report.operations {
+            *day.operations
+                .entry(operation.operation.clone())
+                .or_default() += operation.quota_units;
+        }
+
+        let excess = self.days.len().saturating_sub(KEEP_DAYS);
+        self.days.drain(..excess);
+    }
+
+    /// The most recent `count` days, oldest first.
+    pub fn recent(&self, count: usize) -> &[DayUsage] {
+        &self.days[self.days.len().saturating_sub(count)..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::timings::Timings;
+    use std::time::Duration;
+
+    fn report() -> TimingsReport {
+        let timings = Timings::new();
+        timings.record(
+            "GET",
+            "/gmail/v1/users/me/messages",
+            Some(200),
+            Duration::ZERO,
+        );
+        timings.record(
+            "GET",
+            "/gmail/v1/users/me/messages/1",
+            Some(200),
+            Duration::ZERO,
+        );
+        timings.report()
+    }
+
+    #[test]
+    fn accumulates_by_day_command_and_operation() {
+        let mut ledger = QuotaLedger::default();
+        ledger.record("2026-10-17", "list", &report());
+        ledger.record("2026-10-17", "get", &report());
+        ledger.record("2026-10-16", "list", &report());
+
+        assert_eq!(ledger.days.len(), 2);
+        assert_eq!(ledger.days[0].date, "2026-10-16");
+        let today = &ledger.days[1];
+        assert_eq!((today.units, today.requests), (20, 4));
+        assert_eq!(today.commands["get"], 10);
+        assert_eq!(today.operations["messages.list"], 10);
+        assert_eq!(ledger.recent(1), &ledger.days[1..]);
+    }
+
+    #[test]
+    fn keeps_only_recent_days() {
+        let mut ledger = QuotaLedger::default();
+        for day in 1..=KEEP_DAYS + 5 {
+            ledger.record(
+                &format!("2026-{:02}-{:02}", 1 + day / 28, 1 + day % 28),
+                "list",
+                &report(),
+            );
+        }
+        assert_eq!(ledger.days.len(), KEEP_DAYS);
+        assert_eq!(ledger.recent(3).len(), 3);
+    }
+}
@@ -2,3 +2,13 @@
 pub fn list_send_as_endpoint() -> &'static str {
     "/gmail/v1/users/me/settings/sendAs"
 }
+
+/// Endpoint path for one send-as alias (get, patch).
+pub fn send_as_endpoint(email: &str) -> String {
+    format!("/gmail/v1/users/me/settings/sendAs/{email}")
+}
+
+/// Endpoint path for resending an alias's verification email.
+pub fn verify_send_as_endpoint(email: &str) -> String {
+    format!("/gmail/v1/users/me/settings/sendAs/{email}/verify")
+}
@@ -0,0 +1,49 @@
+use super::messages;
+
+/// Endpoint path for a single thread by id.
+pub fn thread_endpoint(id: &str) -> String {
+    format!("/gmail/v1/users/me/threads/{id}")
+}
+
+/// Endpoint path for changing the labels on every message of a thread.
+pub fn modify_endpoint(id: &str) -> String {
+    format!("/gmail/v1/users/me/threads/{id}/modify")
+}
+
+/// Endpoint path for moving every message of a thread to the trash.
+pub fn trash_endpoint(id: &str) -> String {
+    format!("/gmail/v1/users/me/threads/{id}/trash")
+}
+
+/// Endpoint path for taking every message of a thread back out of the trash.
+pub fn untrash_endpoint(id: &str) -> String {
+    format!("/gmail/v1/users/me/threads/{id}/untrash")
+}
+
+/// Endpoint path for listing threads.
+pub fn list_endpoint() -> &'static str {
+    "/gmail/v1/users/me/threads"
+}
+
+/// Query params fetching a thread's messages with `format=full`, bodies and all.
+pub fn full_query() -> Vec<(String, String)> {
+    messages::full_query()
+}
+
+/// Query params fetching a thread's messages with `format=metadata`, each
+/// trimmed to [`messages::METADATA_FIELDS`] and the common envelope headers.
+pub fn get_query() -> Vec<(String, String)> {
+    let mut query = vec![
+        ("format".to_string(), "metadata".to_string()),
+        (
+            "fields".to_string(),
+            format!("id,historyId,messages({})", messages::METADATA_FIELDS),
+        ),
+    ];
+    query.extend(
+        messages::METADATA_HEADERS
+            .iter()
+            .map(|header| ("metadataHeaders".to_string(), header.to_string())),
+    );
+    query
+}
@@ -0,0 +1,338 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// One Gmail API request as seen by `--timings`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RequestTiming {
+    /// API method, e.g. `messages.get`.
+    pub operation: String,
+    pub method: &'static str,
+    pub endpoint: String,
+    /// HTTP status, absent when the request never got a response.
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub quota_units: u32,
+}
+
+/// Per-operation totals for the end-of-command report.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OperationSummary {
+    pub operation: String,
+    pub requests: usize,
+    pub errors: usize,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    pub quota_units: u32,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TimingsReport {
+    pub wall_ms: u64,
+    pub requests: usize,
+    pub quota_units: u32,
+    pub operations: Vec<OperationSummary>,
+    pub calls: Vec<RequestTiming>,
+}
+
+/// Shared recorder for `--timings`; clones append to the same list.
+#[derive(Debug, Clone)]
+pub struct Timings {
+    started: Instant,
+    calls: Arc<Mutex<Vec<RequestTiming>>>,
+}
+
+impl Timings {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            calls: Arc::default(),
+        }
+    }
+
+    pub fn record(
+        &self,
+        method: &'static str,
+        endpoint: &str,
+        status: Option<u16>,
+        latency: Duration,
+    ) {
+        let operation = operation_name(method, endpoint);
+        let quota_units = quota_units(&operation);
+        self.calls
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(RequestTiming {
+                operation,
+                method,
+                endpoint: endpoint.to_string(),
+                status,
+                latency_ms: latency.as_millis() as u64,
+                quota_units,
+            });
+    }
+
+    /// Totals so far, with wall time measured from when recording began.
+    pub fn report(&self) -> TimingsReport {
+        Self::combined(std::slice::from_ref(self))
+    }
+
+    /// Totals of the requests recorded since the last drain, forgetting them,
+    /// so long-running commands can hand usage to the quota ledger as they go.
+    pub fn drain(&self) -> TimingsReport {
+        let drained = Timings {
+            started: self.started,
+            calls: Arc::new(Mutex::new(std::mem::take(
+                &mut *self.calls.lock().unwrap_or_else(|err| err.into_inner()),
+            ))),
+        };
+        drained.report()
+    }
+
+    /// Totals across several recorders (one per `--profiles` account), with
+    /// wall time from the earliest start.
+    pub fn combined(recorders: &[Timings]) -> TimingsReport {
+        let wall_ms = recorders
+            .iter()
+            .map(|timings| timings.started.elapsed().as_millis() as u64)
+            .max()
+            .unwrap_or_default();
+        let calls = recorders
+            .iter()
+            .flat_map(|timings| {
+                timings
+                    .calls
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+
+        let mut operations = BTreeMap::<&str, OperationSummary>::new();
+        for call in &calls {
+            let summary = operations
+                .entry(&call.operation)
+                .or_insert_with(|| OperationSummary {
+                    operation: call.operation.clone(),
+                    requests: 0,
+                    errors: 0,
+                    total_ms: 0,
+                    max_ms: 0,
+                    quota_units: 0,
+                });
+            summary.requests += 1;
+            summary.errors += usize::from(call.status.is_none_or(|status| status >= 400));
+            summary.total_ms += call.latency_ms;
+            summary.max_ms = summary.max_ms.max(call.latency_ms);
+            summary.quota_units += call.quota_units;
+        }
+        let operations = operations.into_values().collect::<Vec<_>>();
+
+        TimingsReport {
+            wall_ms,
+            requests: calls.len(),
+            quota_units: operations.iter().map(|summary| summary.quota_units).sum(),
+            operations,
+            calls,
+        }
+    }
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimingsReport {
+    /// A fixed-width table of per-operation totals followed by the overall line.
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![format!(
+            "{:<28} {:>5} {:>6} {:>9} {:>8} {:>8} {:>6}",
+            "operation", "calls", "errors", "total ms", "avg ms", "max ms", "quota"
+        )];
+        for summary in &self.operations {
+            lines.push(format!(
+                "{:<28} {:>5} {:>6} {:>9} {:>8} {:>8} {:>6}",
+                summary.operation,
+                summary.requests,
+                summary.errors,
+                summary.total_ms,
+                summary.total_ms / summary.requests.max(1) as u64,
+                summary.max_ms,
+                summary.quota_units
+            ));
+        }
+        lines.push(format!(
+            "{} requests, ~{} quota units, {} ms wall time",
+            self.requests, self.quota_units, self.wall_ms
+        ));
+        lines.join("\n")
+    }
+}
+
+/// Path segments that name a resource or action rather than an id.
+const VOCABULARY: [&str; 27] = [
+    "attachments",
+    "autoForwarding",
+    "batchDelete",
+    "batchModify",
+    "delegates",
+    "drafts",
+    "filters",
+    "forwardingAddresses",
+    "history",
+    "imap",
+    "import",
+    "labels",
+    "language",
+    "messages",
+    "modify",
+    "pop",
+    "profile",
+    "send",
+    "sendAs",
+    "settings",
+    "stop",
+    "threads",
+    "trash",
+    "untrash",
+    "vacation",
+    "verify",
+    "watch",
+];
+
+/// Collections whose bare path lists on GET and creates on POST.
+const COLLECTIONS: [&str; 9] = [
+    "attachments",
+    "drafts",
+    "filters",
+    "forwardingAddresses",
+    "history",
+    "labels",
+    "messages",
+    "sendAs",
+    "threads",
+];
+
+/// Name the API method an endpoint path calls, following Google's naming:
+/// `/gmail/v1/users/me/messages/abc` with GET is `messages.get`.
+pub fn operation_name(method: &str, endpoint: &str) -> String {
+    let path = endpoint
+        .trim_start_matches('/')
+        .trim_start_matches("gmail/v1/users/me")
+        .trim_start_matches('/');
+    if path == "profile" {
+        return "getProfile".to_string();
+    }
+
+    let mut names = Vec::new();
+    let mut ends_with_id = false;
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if VOCABULARY.contains(&segment) {
+            names.push(segment);
+            ends_with_id = false;
+        } else {
+            ends_with_id = true;
+        }
+    }
+
+    let last = names.last().copied().unwrap_or_default();
+    let verb = match (method, ends_with_id) {
+        ("GET", true) => Some("get"),
+        ("DELETE", true) => Some("delete"),
+        ("PUT" | "PATCH", true) => Some("update"),
+        ("GET", false) if COLLECTIONS.contains(&last) => Some("list"),
+        ("POST", false) if last == "messages" => Some("insert"),
+        ("POST", false) if COLLECTIONS.contains(&last) => Some("create"),
+        ("PUT" | "PATCH", false) => Some("update"),
+        ("GET", false) if names.first() == Some(&"settings") => Some("get"),
+        _ => None,
+    };
+    names.extend(verb);
+    if names.is_empty() {
+        return format!("{method} {endpoint}");
+    }
+    names.join(".")
+}
+
+/// Approximate per-call quota cost from Google's published usage limits;
+/// methods not listed count 5 units.
+pub fn quota_units(operation: &str) -> u32 {
+    match operation {
+        "getProfile" | "labels.list" | "labels.get" => 1,
+        "history.list" => 2,
+        "drafts.delete" | "messages.delete" | "threads.get" | "threads.list" | "threads.modify"
+        | "threads.trash" | "threads.untrash" | "drafts.create" => 10,
+        "drafts.update" => 15,
+        "threads.delete" => 20,
+        "messages.import" | "messages.insert" => 25,
+        "messages.batchModify" | "messages.batchDelete" | "stop" => 50,
+        "messages.send" | "drafts.send" | "watch" => 100,
+        operation if operation.starts_with("settings.") && operation.ends_with(".list") => 1,
+        operation if operation.starts_with("settings.") && operation.ends_with(".get") => 1,
+        _ => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_operations_from_endpoints() {
+        let name = |method, endpoint| operation_name(method, endpoint);
+        assert_eq!(name("GET", "/gmail/v1/users/me/messages"), "messages.list");
+        assert_eq!(
+            name("GET", "/gmail/v1/users/me/messages/18c"),
+            "messages.get"
+        );
+        assert_eq!(
+            name("POST", "/gmail/v1/users/me/messages/send"),
+            "messages.send"
+        );
+        assert_eq!(
+            name("POST", "/gmail/v1/users/me/messages/18c/modify"),
+            "messages.modify"
+        );
+        assert_eq!(
+            name("GET", "/gmail/v1/users/me/messages/18c/attachments/ANGj"),
+            "messages.attachments.get"
+        );
+        assert_eq!(
+            name("GET", "/gmail/v1/users/me/settings/sendAs"),
+            "settings.sendAs.list"
+        );
+        assert_eq!(name("GET", "/gmail/v1/users/me/profile"), "getProfile");
+        assert_eq!(name("POST", "/gmail/v1/users/me/watch"), "watch");
+    }
+
+    #[test]
+    fn totals_requests_errors_and_quota() {
+        let timings = Timings::new();
+        for status in [Some(200), Some(200), Some(404), None] {
+            timings.record(
+                "GET",
+                "/gmail/v1/users/me/messages/1",
+                status,
+                Duration::from_millis(40),
+            );
+        }
+        timings.record(
+            "POST",
+            "/gmail/v1/users/me/messages/send",
+            Some(200),
+            Duration::from_millis(300),
+        );
+
+        let report = timings.report();
+        assert_eq!(report.requests, 5);
+        assert_eq!(report.quota_units, 4 * 5 + 100);
+        let get = &report.operations[0];
+        assert_eq!((get.operation.as_str(), get.errors), ("messages.get", 2));
+        assert!(report.to_text().contains("messages.send"));
+    }
+}
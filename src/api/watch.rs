@@ -0,0 +1,9 @@
+/// Endpoint path for registering a Cloud Pub/Sub push watch on the mailbox.
+pub fn watch_endpoint() -> &'static str {
+    "/gmail/v1/users/me/watch"
+}
+
+/// Endpoint path for stopping push notifications for the mailbox.
+pub fn stop_endpoint() -> &'static str {
+    "/gmail/v1/users/me/stop"
+}
@@ -1,28 +1,553 @@
-use crate::cli::{Cli, Command};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{Local, SecondsFormat, Utc};
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, CommandFactory, Parser};
+use serde_json::Value;
+
+use crate::api::audit::{self, AuditEntry, AuditTrail};
+use crate::api::quota::QuotaLedger;
+use crate::api::timings::{Timings, TimingsReport};
+use crate::cli::{AttachmentsCommand, Cli, Command};
 use crate::commands;
+use crate::commands::triage::{Folder, Triage};
+use crate::config::{self, AppPaths};
 use crate::context::AppContext;
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::output::OutputMode;
+use crate::{interrupt, logging};
+
+/// Options that decide where settings come from, so settings cannot default them.
+const FIXED_OPTIONS: [&str; 4] = ["profile", "profiles", "config_dir", "data_dir"];
 
 /// Bootstrap the app context and dispatch the parsed CLI command to its handler.
-pub async fn run(cli: Cli) -> AppResult<()> {
+/// `args` is the argv `cli` was parsed from, re-parsed when the profile sets
+/// per-command defaults.
+pub async fn run(cli: Cli, args: Vec<OsString>) -> AppResult<()> {
+    let paths = AppPaths::discover(cli.config_dir.clone(), cli.data_dir.clone())?;
+    // With `--profiles`, the first one supplies settings and output defaults.
+    let loaded = config::load_profile(
+        &paths,
+        cli.profiles
+            .first()
+            .or(cli.profile.as_ref())
+            .map(String::as_str),
+    )?;
+    let cli = apply_command_defaults(cli, &args, &loaded.settings.commands)?;
+
     let Cli {
-        profile,
+        profile: _,
+        profiles,
+        output,
         json,
+        color,
+        no_pager,
+        out,
         verbose,
-        command,
+        log_file,
+        timings,
+        dry_run,
+        config_dir: _,
+        data_dir: _,
+        mut command,
     } = cli;
 
-    let ctx = AppContext::bootstrap(profile, json, verbose)?;
+    let output = if json { Some(OutputMode::Json) } else { output };
+    let mut accounts = profiles
+        .iter()
+        .map(|name| {
+            AppContext::bootstrap(
+                paths.clone(),
+                config::load_profile(&paths, Some(name))?,
+                output,
+                color,
+                true,
+                None,
+                verbose,
+            )
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    let mut ctx = AppContext::bootstrap(
+        paths,
+        loaded,
+        output,
+        color,
+        no_pager,
+        route_out(&mut command, out),
+        verbose,
+    )?;
+    logging::init(verbose, log_file.as_deref(), ctx.color)?;
+
+    // Requests are always counted for the quota ledger; `--timings` also
+    // prints them.
+    let show_timings = timings;
+    let command_name = command_path(&args);
+    let recorders = std::iter::once(&mut ctx)
+        .chain(&mut accounts)
+        .map(|context| {
+            let timings = Timings::new();
+            context.gmail_client = context.gmail_client.clone().with_timings(timings.clone());
+            timings
+        })
+        .collect::<Vec<_>>();
+    for context in std::iter::once(&mut ctx).chain(&mut accounts) {
+        context.dry_run = dry_run;
+        context.gmail_client = context.gmail_client.clone().with_dry_run(dry_run);
+    }
+    for account in &mut accounts {
+        account.interrupt = ctx.interrupt.clone();
+        account.gmail_client = account
+            .gmail_client
+            .clone()
+            .with_interrupt(ctx.interrupt.clone());
+    }
+    let usage = profile_usage(&ctx, &accounts, &recorders);
+    let audit_file = ctx.paths.audit_file();
+    let audit_args = args
+        .iter()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    let trails = std::iter::once(&ctx)
+        .chain(&accounts)
+        .map(|context| {
+            let profile = context.profile().unwrap_or_default().to_string();
+            (profile, context.audit.clone())
+        })
+        .collect::<Vec<_>>();
+    let record_writes = |error: Option<&AppError>| {
+        record_audit(&audit_file, &command_name, &audit_args, &trails, error);
+    };
+
+    ctx.interrupt.listen();
+    let work = async {
+        if accounts.is_empty() {
+            dispatch(&ctx, command).await
+        } else {
+            commands::multi::run(&ctx, accounts, command).await
+        }
+    };
+    let flush = async {
+        if show_timings {
+            return std::future::pending().await;
+        }
+        loop {
+            tokio::time::sleep(LEDGER_FLUSH).await;
+            record_usage(&command_name, &usage);
+            record_writes(None);
+        }
+    };
+    let result = tokio::select! {
+        result = work => result,
+        _ = ctx.interrupt.forced(interrupt::GRACE) => Err(AppError::Interrupted),
+        () = flush => unreachable!("the ledger flush loop never ends"),
+    };
+    if show_timings {
+        print_timings(&ctx, &Timings::combined(&recorders));
+    }
+    record_usage(&command_name, &usage);
+    record_writes(result.as_ref().err());
+    result
+}
+
+/// How often a long-running command adds its quota use to the ledger.
+const LEDGER_FLUSH: Duration = Duration::from_secs(300);
+
+/// Each profile taking part in the run, paired with its request recorder.
+fn profile_usage(
+    ctx: &AppContext,
+    accounts: &[AppContext],
+    recorders: &[Timings],
+) -> Vec<(Option<PathBuf>, Timings)> {
+    let contexts = if accounts.is_empty() {
+        vec![ctx]
+    } else {
+        accounts.iter().collect()
+    };
+    let offset = usize::from(!accounts.is_empty());
+    contexts
+        .into_iter()
+        .zip(&recorders[offset..])
+        .map(|(context, timings)| {
+            let file = context
+                .profile()
+                .ok()
+                .map(|profile| context.paths.quota_file(profile));
+            (file, timings.clone())
+        })
+        .collect()
+}
+
+/// Move the requests recorded so far into each profile's quota ledger and
+/// log the estimate at `-v`. Ledger trouble is logged, never fatal.
+fn record_usage(command: &str, usage: &[(Option<PathBuf>, Timings)]) {
+    let today = Local::now().date_naive().to_string();
+    for (file, timings) in usage {
+        let report = timings.drain();
+        let Some(file) = file.as_ref().filter(|_| report.requests > 0) else {
+            continue;
+        };
+        tracing::info!(
+            command,
+            requests = report.requests,
+            quota_units = report.quota_units,
+            "estimated Gmail API quota use"
+        );
+        let recorded = QuotaLedger::load(file).and_then(|mut ledger| {
+            ledger.record(&today, command, &report);
+            ledger.save(file)
+        });
+        if let Err(err) = recorded {
+            tracing::warn!(error = %err, file = %file.display(), "could not update the quota ledger");
+        }
+    }
+}
 
+/// Append each profile's writes since the last call to the audit log, noting
+/// `error` when the command failed. Failed writes count, so a send or delete
+/// Gmail refused is still logged; runs that attempted no write leave no
+/// entry. Log trouble is logged, never fatal.
+fn record_audit(
+    file: &Path,
+    command: &str,
+    args: &[String],
+    trails: &[(String, AuditTrail)],
+    error: Option<&AppError>,
+) {
+    let time = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    for (profile, trail) in trails {
+        let writes = trail.drain();
+        if writes.is_empty() {
+            continue;
+        }
+        let entry = AuditEntry {
+            time: time.clone(),
+            profile: profile.clone(),
+            command: command.to_string(),
+            args: args.to_vec(),
+            writes,
+            error: error.map(ToString::to_string),
+        };
+        if let Err(err) = audit::append(file, &entry) {
+            tracing::warn!(error = %err, file = %file.display(), "could not append to the audit log");
+        }
+    }
+}
+
+/// The invoked command path (`label add`) for the quota ledger and audit log.
+fn command_path(args: &[OsString]) -> String {
+    let Ok(matches) = Cli::command().try_get_matches_from(args) else {
+        return String::new();
+    };
+    let mut names = Vec::new();
+    let mut scope = &matches;
+    while let Some((name, sub)) = scope.subcommand() {
+        names.push(name);
+        scope = sub;
+    }
+    names.join(" ")
+}
+
+async fn dispatch(ctx: &AppContext, command: Command) -> AppResult<()> {
+    match command {
+        Command::Auth(args) => commands::auth::run(ctx, args.command).await,
+        Command::Profile(args) => commands::profile::run(ctx, args.command).await,
+        Command::Signature(args) => commands::signature::run(ctx, args.command).await,
+        Command::List(args) => commands::list::run(ctx, args).await,
+        Command::Search(args) => commands::search::run(ctx, args).await,
+        Command::Send(args) => commands::send::run(ctx, *args).await,
+        Command::Draft(args) => commands::draft::run(ctx, args.command).await,
+        Command::SendPatch(args) => commands::send_patch::run(ctx, args).await,
+        Command::Sendmail(args) => commands::sendmail::run(ctx, args).await,
+        Command::Import(args) => commands::import::run(ctx, args).await,
+        Command::Export(args) => commands::export::run(ctx, args).await,
+        Command::Queue(args) => commands::queue::run(ctx, args.command).await,
+        Command::Snooze(args) => commands::snooze::run(ctx, args).await,
+        Command::Mute(args) => commands::mute::run(ctx, args).await,
+        Command::Unmute(args) => commands::mute::unmute(ctx, args).await,
+        Command::Forward(args) => commands::forward::run(ctx, args).await,
+        Command::Rsvp(args) => commands::rsvp::run(ctx, args).await,
+        Command::Unsubscribe(args) => commands::unsubscribe::run(ctx, args).await,
+        Command::Stats(args) => commands::stats::run(ctx, args).await,
+        Command::Quota(args) => commands::quota::run(ctx, args).await,
+        Command::Usage(args) => commands::usage::run(ctx, args).await,
+        Command::Log(args) => commands::log::run(ctx, args).await,
+        Command::Cleanup(args) => commands::cleanup::run(ctx, args).await,
+        Command::Schema(args) => commands::schema::run(ctx, args).await,
+        Command::Mcp => commands::mcp::run(ctx).await,
+        Command::Get(args) => commands::get::run(ctx, args).await,
+        Command::Open(args) => commands::open::run(ctx, args),
+        Command::Thread(args) => commands::thread::run(ctx, args.command).await,
+        Command::Label(args) => commands::label::run(ctx, args.command).await,
+        Command::Archive(args) => commands::triage::run(ctx, Triage::Archive, args).await,
+        Command::Read(args) => commands::triage::run(ctx, Triage::Read, args).await,
+        Command::Unread(args) => commands::triage::run(ctx, Triage::Unread, args).await,
+        Command::Spam(args) => commands::triage::run(ctx, Triage::Spam, args).await,
+        Command::NotSpam(args) => commands::triage::run(ctx, Triage::NotSpam, args).await,
+        Command::Star(args) => commands::triage::run(ctx, Triage::Star, args).await,
+        Command::Unstar(args) => commands::triage::run(ctx, Triage::Unstar, args).await,
+        Command::Trash(args) => commands::triage::trash(ctx, args, true).await,
+        Command::Untrash(args) => commands::triage::trash(ctx, args, false).await,
+        Command::Delete(args) => commands::triage::delete(ctx, args).await,
+        Command::EmptyTrash(args) => commands::triage::empty(ctx, Folder::Trash, args).await,
+        Command::EmptySpam(args) => commands::triage::empty(ctx, Folder::Spam, args).await,
+        Command::Attachments(args) => commands::attachments::run(ctx, args.command).await,
+        Command::Contacts(args) => commands::contacts::run(ctx, args.command).await,
+        Command::Aliases(args) => commands::aliases::run(ctx, args.command).await,
+        Command::Sendas(args) => commands::sendas::run(ctx, args.command).await,
+        Command::Forwarding(args) => commands::forwarding::run(ctx, args.command).await,
+        Command::LegacyAccess(args) => commands::legacy_access::run(ctx, args.command).await,
+        Command::Alias(args) => commands::alias::run(ctx, args.command).await,
+        Command::Watch(args) => commands::watch::run(ctx, args.command).await,
+        Command::Daemon(args) => commands::daemon::run(ctx, args).await,
+        Command::Rules(args) => commands::rules::run(ctx, args.command).await,
+        Command::Notify(args) => commands::notify::run(ctx, args).await,
+        Command::Stream(args) => commands::stream::run(ctx, args).await,
+        Command::History(args) => commands::history::run(ctx, args).await,
+        Command::Man(args) => commands::man::run(ctx, args).await,
+        Command::Tui(args) => commands::tui::run(ctx, args).await,
+        Command::Doctor => commands::doctor::run(ctx).await,
+    }
+}
+
+/// Write the `--timings` report to stderr: a table, or JSON when a structured
+/// output format is selected.
+fn print_timings(ctx: &AppContext, report: &TimingsReport) {
+    if ctx.output.mode() == OutputMode::Text {
+        eprintln!("{}", report.to_text());
+    } else if let Ok(json) = serde_json::to_string(&report) {
+        eprintln!("{json}");
+    }
+}
+
+/// Hand `--out` to commands that write files into a directory, returning it
+/// as the output file for every other command.
+fn route_out(command: &mut Command, out: Option<PathBuf>) -> Option<PathBuf> {
     match command {
-        Command::Auth(args) => commands::auth::run(&ctx, args.command).await,
-        Command::Profile(args) => commands::profile::run(&ctx, args.command).await,
-        Command::Signature(args) => commands::signature::run(&ctx, args.command).await,
-        Command::List(args) => commands::list::run(&ctx, args).await,
-        Command::Send(args) => commands::send::run(&ctx, args).await,
-        Command::Get(args) => commands::get::run(&ctx, args).await,
-        Command::Label(args) => commands::label::run(&ctx, args.command).await,
-        Command::Attachments(args) => commands::attachments::run(&ctx, args.command).await,
-        Command::Aliases(args) => commands::aliases::run(&ctx, args.command).await,
+        Command::Man(args) => {
+            args.out = out;
+            None
+        }
+        Command::Attachments(args) => match &mut args.command {
+            AttachmentsCommand::Get(args) => {
+                if let Some(out) = out {
+                    args.out = out;
+                }
+                None
+            }
+            AttachmentsCommand::Ls(_) => out,
+        },
+        _ => out,
+    }
+}
+
+/// Re-parse `args` with the `commands` defaults for the invoked (sub)command
+/// inserted for every option the user did not pass, so they behave exactly as
+/// if typed and clap validates them.
+fn apply_command_defaults(
+    cli: Cli,
+    args: &[OsString],
+    defaults: &BTreeMap<String, Value>,
+) -> AppResult<Cli> {
+    if defaults.is_empty() {
+        return Ok(cli);
+    }
+
+    let extra = default_args(args, defaults)?;
+    if extra.is_empty() {
+        return Ok(cli);
+    }
+
+    let mut merged = args.to_vec();
+    let at = merged
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(merged.len());
+    merged.splice(at..at, extra.into_iter().map(OsString::from));
+    Cli::try_parse_from(merged).map_err(|err| {
+        let message = err.to_string();
+        let first = message.lines().next().unwrap_or_default();
+        AppError::Config(format!(
+            "invalid command defaults: {}",
+            first.trim_start_matches("error: ")
+        ))
+    })
+}
+
+/// The extra `--option=value` arguments the defaults contribute.
+fn default_args(args: &[OsString], defaults: &BTreeMap<String, Value>) -> AppResult<Vec<String>> {
+    let mut command = Cli::command();
+    command.build();
+    let matches = command
+        .clone()
+        .try_get_matches_from(args)
+        .map_err(|err| AppError::InvalidInput(err.to_string()))?;
+
+    let root = Value::Object(defaults.clone().into_iter().collect());
+    let mut node = &root;
+    let mut scope = &command;
+    let mut scope_matches = &matches;
+    let mut path: Vec<&str> = Vec::new();
+    let mut extra = Vec::new();
+
+    loop {
+        let Some(table) = node.as_object() else {
+            return Err(AppError::Config(format!(
+                "commands.{} must be a table of option defaults",
+                path.join(".")
+            )));
+        };
+
+        for (key, value) in table {
+            if scope.find_subcommand(key).is_some() {
+                continue;
+            }
+            if path.is_empty() {
+                return Err(AppError::Config(format!(
+                    "unknown command `{key}` in commands defaults"
+                )));
+            }
+            extra.extend(option_args(scope, scope_matches, &path, key, value)?);
+        }
+
+        let Some((name, sub_matches)) = scope_matches.subcommand() else {
+            break;
+        };
+        let (Some(next), Some(sub)) = (table.get(name), scope.find_subcommand(name)) else {
+            break;
+        };
+        node = next;
+        scope = sub;
+        scope_matches = sub_matches;
+        path.push(name);
+    }
+
+    Ok(extra)
+}
+
+/// Arguments for one `key = value` default, or none when the user passed the option.
+fn option_args(
+    scope: &clap::Command,
+    matches: &ArgMatches,
+    path: &[&str],
+    key: &str,
+    value: &Value,
+) -> AppResult<Vec<String>> {
+    let label = format!("commands.{}.{key}", path.join("."));
+    let arg = scope
+        .get_arguments()
+        .find(|arg| arg.get_id() == key || arg.get_long() == Some(key))
+        .ok_or_else(|| {
+            AppError::Config(format!(
+                "unknown default `{label}`: `gmail {}` has no --{} option",
+                path.join(" "),
+                key.replace('_', "-")
+            ))
+        })?;
+    let id = arg.get_id().as_str();
+    if FIXED_OPTIONS.contains(&id) {
+        return Err(AppError::Config(format!(
+            "`{label}` cannot be set per command"
+        )));
+    }
+    let Some(long) = arg.get_long() else {
+        return Err(AppError::Config(format!(
+            "`{label}` is a positional argument; only options take defaults"
+        )));
+    };
+    if matches.value_source(id) == Some(ValueSource::CommandLine) {
+        return Ok(Vec::new());
+    }
+
+    let flag = format!("--{long}");
+    match (arg.get_action(), value) {
+        (ArgAction::SetTrue, Value::Bool(true)) => Ok(vec![flag]),
+        (ArgAction::SetTrue, Value::Bool(false)) => Ok(Vec::new()),
+        (ArgAction::Count, Value::Number(count)) => {
+            let count = count.as_u64().unwrap_or_default() as usize;
+            Ok(vec![flag; count])
+        }
+        (ArgAction::SetTrue | ArgAction::Count, _) => Err(AppError::Config(format!(
+            "`{label}` is a flag; use true or false"
+        ))),
+        (_, Value::Array(items)) => items
+            .iter()
+            .map(|item| Ok(format!("{flag}={}", scalar(&label, item)?)))
+            .collect(),
+        (_, value) => Ok(vec![format!("{flag}={}", scalar(&label, value)?)]),
+    }
+}
+
+fn scalar(label: &str, value: &Value) -> AppResult<String> {
+    match value {
+        Value::String(text) => Ok(text.clone()),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Bool(flag) => Ok(flag.to_string()),
+        _ => Err(AppError::Config(format!(
+            "`{label}` must be a string, number, boolean, or a list of them"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    fn defaults(json: &str) -> BTreeMap<String, Value> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn fills_unset_options_and_keeps_explicit_ones() {
+        let args = argv(&["gmail", "list", "--limit", "5"]);
+        let cli = Cli::parse_from(&args);
+        let cli = apply_command_defaults(
+            cli,
+            &args,
+            &defaults(r#"{ "list": { "limit": 25, "inbox": true, "output": "table" } }"#),
+        )
+        .unwrap();
+
+        let Command::List(list) = cli.command else {
+            panic!("expected list");
+        };
+        assert_eq!(list.limit, 5);
+        assert!(list.inbox);
+        assert_eq!(cli.output, Some(OutputMode::Table));
+    }
+
+    #[test]
+    fn reaches_nested_subcommands_and_rejects_unknown_options() {
+        let args = argv(&["gmail", "attachments", "get", "m1"]);
+        let cli = Cli::parse_from(&args);
+        let cli = apply_command_defaults(
+            cli,
+            &args,
+            &defaults(r#"{ "attachments": { "get": { "index": 2 } }, "send": { "bogus": 1 } }"#),
+        )
+        .unwrap();
+        let Command::Attachments(attachments) = cli.command else {
+            panic!("expected attachments");
+        };
+        let AttachmentsCommand::Get(get) = attachments.command else {
+            panic!("expected attachments get");
+        };
+        assert_eq!(get.index, Some(2));
+
+        let args = argv(&["gmail", "list"]);
+        let err = apply_command_defaults(
+            Cli::parse_from(&args),
+            &args,
+            &defaults(r#"{ "list": { "lmit": 3 } }"#),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("commands.list.lmit"), "{err}");
     }
 }
@@ -1,8 +1,12 @@
 pub mod keyring_store;
 pub mod oauth;
+pub mod pass_store;
+pub mod provider;
 pub mod token;
 pub mod token_store;
 
 pub use oauth::{AuthLoginResult, AuthService, AuthStatus};
+pub use pass_store::PassTokenStore;
+pub use provider::TokenProvider;
 pub use token::TokenSet;
-pub use token_store::{FileTokenStore, TokenStore};
+pub use token_store::{ConfiguredTokenStore, FileTokenStore, TokenStore};
@@ -4,6 +4,7 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use rand::Rng;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -12,7 +13,8 @@ use tokio::time;
 use url::Url;
 
 use crate::config::Settings;
-use crate::error::{AppError, AppResult};
+use crate::desktop::open_browser;
+use crate::error::{ApiError, AppError, AppResult};
 
 use super::token::TokenSet;
 use super::token_store::TokenStore;
@@ -24,7 +26,7 @@ const GOOGLE_USERINFO_ENDPOINT: &str = "https://openidconnect.googleapis.com/v1/
 const OAUTH_CALLBACK_TIMEOUT_SECS: u64 = 180;
 const OAUTH_SCOPES: &str = "https://www.googleapis.com/auth/gmail.modify https://www.googleapis.com/auth/gmail.send openid email profile";
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct AuthLoginResult {
     pub profile: String,
     pub started: bool,
@@ -34,7 +36,7 @@ pub struct AuthLoginResult {
     pub note: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct AuthStatus {
     pub profile: String,
     pub logged_in: bool,
@@ -45,18 +47,39 @@ pub struct AuthStatus {
     pub note: Option<String>,
 }
 
-#[derive(Debug, Default)]
-pub struct AuthService;
+/// OAuth entrypoint; token, userinfo, and revoke calls go through the shared
+/// HTTP client it was built with.
+#[derive(Debug, Clone, Default)]
+pub struct AuthService {
+    http: reqwest::Client,
+}
 
 impl AuthService {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+
     /// Run the full PKCE authorization-code flow, then persist the resulting token set.
     pub async fn login<S: TokenStore>(
+        &self,
         profile: &str,
         settings: &Settings,
         store: &S,
+    ) -> AppResult<AuthLoginResult> {
+        self.login_with_scopes(profile, settings, store, &[]).await
+    }
+
+    /// [`login`](Self::login), also asking for `extra_scopes`. Scopes granted
+    /// earlier carry over, so features can request theirs when first used.
+    pub async fn login_with_scopes<S: TokenStore>(
+        &self,
+        profile: &str,
+        settings: &Settings,
+        store: &S,
+        extra_scopes: &[&str],
     ) -> AppResult<AuthLoginResult> {
         let oauth = OAuthConfig::from_settings(settings)?;
-        let flow = LoginFlow::new(&oauth)?;
+        let flow = LoginFlow::new(&oauth, extra_scopes)?;
         let opened_browser = open_browser(&flow.authorization_url);
 
         if !opened_browser {
@@ -73,8 +96,8 @@ impl AuthService {
         )
         .await?;
 
-        let mut token = exchange_auth_code(&oauth, &code, &flow.code_verifier).await?;
-        if let Ok(profile) = fetch_user_profile(&token.access_token).await {
+        let mut token = exchange_auth_code(&self.http, &oauth, &code, &flow.code_verifier).await?;
+        if let Ok(profile) = fetch_user_profile(&self.http, &token.access_token).await {
             token.email = profile.email;
             token.name = profile.name;
         }
@@ -92,6 +115,7 @@ impl AuthService {
 
     /// Return the stored token if still valid, otherwise exchange the refresh token and re-store it.
     pub async fn refresh<S: TokenStore>(
+        &self,
         profile: &str,
         settings: &Settings,
         store: &S,
@@ -106,11 +130,40 @@ impl AuthService {
             return Ok(current);
         }
 
+        self.exchange_and_store(profile, &oauth, current, store)
+            .await
+    }
+
+    /// Exchange the stored refresh token for a new access token even if the
+    /// current one is still valid, then re-store it.
+    pub async fn force_refresh<S: TokenStore>(
+        &self,
+        profile: &str,
+        settings: &Settings,
+        store: &S,
+    ) -> AppResult<TokenSet> {
+        let oauth = OAuthConfig::from_settings(settings)?;
+        let current = store.load(profile)?.ok_or_else(|| {
+            AppError::InvalidInput("not logged in. run `gmail auth login`".to_string())
+        })?;
+        self.exchange_and_store(profile, &oauth, current, store)
+            .await
+    }
+
+    /// Redeem `current`'s refresh token, carrying over fields the token
+    /// endpoint omits, and persist the result.
+    async fn exchange_and_store<S: TokenStore>(
+        &self,
+        profile: &str,
+        oauth: &OAuthConfig,
+        current: TokenSet,
+        store: &S,
+    ) -> AppResult<TokenSet> {
         let refresh_token = current.refresh_token.clone().ok_or_else(|| {
-            AppError::Auth("access token expired and no refresh token is stored".to_string())
+            AppError::auth("access token expired and no refresh token is stored".to_string())
         })?;
 
-        let mut refreshed = exchange_refresh_token(&oauth, &refresh_token).await?;
+        let mut refreshed = exchange_refresh_token(&self.http, oauth, &refresh_token).await?;
         if refreshed.refresh_token.is_none() {
             refreshed.refresh_token = Some(refresh_token);
         }
@@ -121,13 +174,16 @@ impl AuthService {
         if refreshed.name.is_none() {
             refreshed.name = current.name;
         }
+        if refreshed.scope.is_none() {
+            refreshed.scope = current.scope;
+        }
 
         store.save(profile, &refreshed)?;
         Ok(refreshed)
     }
 
     /// Report login state for a profile, including expiry and refresh-token availability.
-    pub async fn status<S: TokenStore>(profile: &str, store: &S) -> AppResult<AuthStatus> {
+    pub async fn status<S: TokenStore>(&self, profile: &str, store: &S) -> AppResult<AuthStatus> {
         let Some(token) = store.load(profile)? else {
             return Ok(AuthStatus {
                 profile: profile.to_string(),
@@ -156,7 +212,7 @@ impl AuthService {
     }
 
     /// Revoke the stored token with Google (best-effort) and clear local credentials.
-    pub async fn logout<S: TokenStore>(profile: &str, store: &S) -> AppResult<AuthStatus> {
+    pub async fn logout<S: TokenStore>(&self, profile: &str, store: &S) -> AppResult<AuthStatus> {
         let token = store.load(profile)?;
         let note = if let Some(token) = token {
             let token_to_revoke = token
@@ -164,7 +220,7 @@ impl AuthService {
                 .as_deref()
                 .unwrap_or(token.access_token.as_str());
 
-            match revoke_token(token_to_revoke).await {
+            match revoke_token(&self.http, token_to_revoke).await {
                 Ok(()) => "remote token revoked and local credentials removed".to_string(),
                 Err(err) => format!("local credentials removed (revoke failed: {err})"),
             }
@@ -198,7 +254,7 @@ impl OAuthConfig {
     fn from_settings(settings: &Settings) -> AppResult<Self> {
         Ok(Self {
             client_id: settings.client_id()?.to_string(),
-            client_secret: settings.client_secret().map(ToOwned::to_owned),
+            client_secret: settings.client_secret()?,
             redirect_uri: settings.redirect_uri(),
         })
     }
@@ -212,18 +268,24 @@ struct LoginFlow {
 }
 
 impl LoginFlow {
-    /// Generate PKCE verifier/challenge and state, then build the Google authorization URL.
-    fn new(config: &OAuthConfig) -> AppResult<Self> {
+    /// Generate PKCE verifier/challenge and state, then build the Google
+    /// authorization URL for the login scopes plus `extra_scopes`.
+    fn new(config: &OAuthConfig, extra_scopes: &[&str]) -> AppResult<Self> {
         let state = random_token(32);
         let code_verifier = random_token(96);
         let code_challenge = pkce_challenge(&code_verifier);
+        let scopes = std::iter::once(OAUTH_SCOPES)
+            .chain(extra_scopes.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
 
         let mut url = Url::parse(GOOGLE_AUTH_ENDPOINT)?;
         url.query_pairs_mut()
             .append_pair("response_type", "code")
             .append_pair("client_id", &config.client_id)
             .append_pair("redirect_uri", &config.redirect_uri)
-            .append_pair("scope", OAUTH_SCOPES)
+            .append_pair("scope", &scopes)
+            .append_pair("include_granted_scopes", "true")
             .append_pair("access_type", "offline")
             .append_pair("prompt", "consent")
             .append_pair("state", &state)
@@ -261,6 +323,7 @@ struct UserInfoResponse {
 
 /// Exchange an authorization code (with PKCE verifier) for a token set at the token endpoint.
 async fn exchange_auth_code(
+    http: &reqwest::Client,
     config: &OAuthConfig,
     code: &str,
     code_verifier: &str,
@@ -277,17 +340,17 @@ async fn exchange_auth_code(
         form.insert("client_secret", client_secret.clone());
     }
 
-    let response = reqwest::Client::new()
-        .post(GOOGLE_TOKEN_ENDPOINT)
-        .form(&form)
-        .send()
-        .await?;
+    let response = http.post(GOOGLE_TOKEN_ENDPOINT).form(&form).send().await?;
 
     parse_token_response(response).await
 }
 
 /// Exchange a refresh token for a fresh token set, backfilling refresh token and profile fields.
-async fn exchange_refresh_token(config: &OAuthConfig, refresh_token: &str) -> AppResult<TokenSet> {
+async fn exchange_refresh_token(
+    http: &reqwest::Client,
+    config: &OAuthConfig,
+    refresh_token: &str,
+) -> AppResult<TokenSet> {
     let mut form = HashMap::from([
         ("grant_type", "refresh_token".to_string()),
         ("refresh_token", refresh_token.to_string()),
@@ -298,18 +361,14 @@ async fn exchange_refresh_token(config: &OAuthConfig, refresh_token: &str) -> Ap
         form.insert("client_secret", client_secret.clone());
     }
 
-    let response = reqwest::Client::new()
-        .post(GOOGLE_TOKEN_ENDPOINT)
-        .form(&form)
-        .send()
-        .await?;
+    let response = http.post(GOOGLE_TOKEN_ENDPOINT).form(&form).send().await?;
 
     let mut token = parse_token_response(response).await?;
     if token.refresh_token.is_none() {
         token.refresh_token = Some(refresh_token.to_string());
     }
     if (token.email.is_none() || token.name.is_none())
-        && let Ok(profile) = fetch_user_profile(&token.access_token).await
+        && let Ok(profile) = fetch_user_profile(http, &token.access_token).await
     {
         if token.email.is_none() {
             token.email = profile.email;
@@ -346,13 +405,18 @@ async fn parse_token_response(response: reqwest::Response) -> AppResult<TokenSet
         let description = err_payload
             .error_description
             .unwrap_or_else(|| "no description".to_string());
-        return Err(AppError::Auth(format!(
-            "oauth token exchange failed ({status}): {error} ({description})"
-        )));
+        return Err(AppError::Auth(
+            ApiError::http(
+                status.as_u16(),
+                format!("oauth token exchange failed ({status}): {error} ({description})"),
+            )
+            .with_reason(Some(error)),
+        ));
     }
 
-    Err(AppError::Auth(format!(
-        "oauth token exchange failed ({status}): {body}"
+    Err(AppError::Auth(ApiError::http(
+        status.as_u16(),
+        format!("oauth token exchange failed ({status}): {body}"),
     )))
 }
 
@@ -364,8 +428,11 @@ fn expires_at_unix(expires_in: Option<u64>) -> Option<u64> {
 }
 
 /// Fetch the user's email and name from the OpenID userinfo endpoint (empty on failure).
-async fn fetch_user_profile(access_token: &str) -> AppResult<UserInfoResponse> {
-    let response = reqwest::Client::new()
+async fn fetch_user_profile(
+    http: &reqwest::Client,
+    access_token: &str,
+) -> AppResult<UserInfoResponse> {
+    let response = http
         .get(GOOGLE_USERINFO_ENDPOINT)
         .bearer_auth(access_token)
         .send()
@@ -383,20 +450,21 @@ async fn fetch_user_profile(access_token: &str) -> AppResult<UserInfoResponse> {
 }
 
 /// Revoke a token at Google's revoke endpoint.
-async fn revoke_token(token: &str) -> AppResult<()> {
-    let response = reqwest::Client::new()
+async fn revoke_token(http: &reqwest::Client, token: &str) -> AppResult<()> {
+    let response = http
         .post(GOOGLE_REVOKE_ENDPOINT)
         .form(&HashMap::from([("token", token.to_string())]))
         .send()
         .await?;
 
-    if response.status().is_success() {
+    let status = response.status();
+    if status.is_success() {
         return Ok(());
     }
 
-    Err(AppError::Auth(format!(
-        "revoke endpoint returned {}",
-        response.status()
+    Err(AppError::Auth(ApiError::http(
+        status.as_u16(),
+        format!("revoke endpoint returned {status}"),
     )))
 }
 
@@ -422,7 +490,7 @@ async fn wait_for_auth_callback(
     let path = redirect.path().to_string();
 
     let listener = TcpListener::bind((host, port)).await.map_err(|err| {
-        AppError::Auth(format!(
+        AppError::auth(format!(
             "failed to bind oauth callback listener on {host}:{port}: {err}"
         ))
     })?;
@@ -433,14 +501,14 @@ async fn wait_for_auth_callback(
         let mut buf = vec![0_u8; 8192];
         let size = stream.read(&mut buf).await?;
         if size == 0 {
-            return Err(AppError::Auth("empty oauth callback request".to_string()));
+            return Err(AppError::auth("empty oauth callback request".to_string()));
         }
 
         let request = String::from_utf8_lossy(&buf[..size]);
         let request_line = request
             .lines()
             .next()
-            .ok_or_else(|| AppError::Auth("malformed oauth callback request".to_string()))?;
+            .ok_or_else(|| AppError::auth("malformed oauth callback request".to_string()))?;
 
         let mut parts = request_line.split_whitespace();
         let method = parts.next().unwrap_or_default();
@@ -453,7 +521,7 @@ async fn wait_for_auth_callback(
                 "oauth callback only accepts GET requests",
             )
             .await?;
-            return Err(AppError::Auth(
+            return Err(AppError::auth(
                 "oauth callback received non-GET request".to_string(),
             ));
         }
@@ -482,7 +550,7 @@ async fn wait_for_auth_callback(
         Ok(code)
     })
     .await
-    .map_err(|_| AppError::Auth("timed out waiting for oauth callback".to_string()))??;
+    .map_err(|_| AppError::auth("timed out waiting for oauth callback".to_string()))??;
 
     Ok(callback)
 }
@@ -495,7 +563,7 @@ fn extract_callback_code(
 ) -> AppResult<String> {
     let callback_url = Url::parse(&format!("http://localhost{target}"))?;
     if callback_url.path() != expected_path {
-        return Err(AppError::Auth(format!(
+        return Err(AppError::auth(format!(
             "oauth callback path mismatch: expected {expected_path}, got {}",
             callback_url.path()
         )));
@@ -518,20 +586,20 @@ fn extract_callback_code(
 
     if let Some(error) = oauth_error {
         let description = oauth_error_description.unwrap_or_else(|| "no description".to_string());
-        return Err(AppError::Auth(format!(
+        return Err(AppError::auth(format!(
             "oauth authorization failed: {error} ({description})"
         )));
     }
 
     let received_state = state
-        .ok_or_else(|| AppError::Auth("oauth callback missing state parameter".to_string()))?;
+        .ok_or_else(|| AppError::auth("oauth callback missing state parameter".to_string()))?;
     if received_state != expected_state {
-        return Err(AppError::Auth(
+        return Err(AppError::auth(
             "oauth state mismatch; aborting login".to_string(),
         ));
     }
 
-    code.ok_or_else(|| AppError::Auth("oauth callback missing code parameter".to_string()))
+    code.ok_or_else(|| AppError::auth("oauth callback missing code parameter".to_string()))
 }
 
 /// Write a minimal HTML response to the callback socket and close it.
@@ -568,36 +636,6 @@ fn pkce_challenge(verifier: &str) -> String {
     URL_SAFE_NO_PAD.encode(digest)
 }
 
-/// Open a URL in the platform's default browser, returning whether the launch succeeded.
-fn open_browser(url: &str) -> bool {
-    #[cfg(target_os = "macos")]
-    {
-        return std::process::Command::new("open")
-            .arg(url)
-            .status()
-            .is_ok_and(|status| status.success());
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        return std::process::Command::new("xdg-open")
-            .arg(url)
-            .status()
-            .is_ok_and(|status| status.success());
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        return std::process::Command::new("cmd")
-            .args(["/C", "start", "", url])
-            .status()
-            .is_ok_and(|status| status.success());
-    }
-
-    #[allow(unreachable_code)]
-    false
-}
-
 /// Escape HTML metacharacters for safe inclusion in the callback response page.
 fn escape_html(input: &str) -> String {
     input
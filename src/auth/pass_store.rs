@@ -0,0 +1,63 @@
+use crate::config::pass::PassCli;
+use crate::error::{AppError, AppResult};
+
+use super::TokenSet;
+use super::token_store::TokenStore;
+
+/// Entry prefix used when the profile sets no `pass_prefix`.
+pub const DEFAULT_PASS_PREFIX: &str = "gmail";
+
+/// Keeps each profile's token set as JSON in a `pass` or `gopass` entry at
+/// `<prefix>/<profile>`, so tokens live alongside the user's other secrets.
+#[derive(Debug, Clone)]
+pub struct PassTokenStore {
+    cli: PassCli,
+    prefix: String,
+}
+
+impl PassTokenStore {
+    /// Create a store driving `binary` (`pass` or `gopass`) with entries under `prefix`.
+    pub fn new(binary: impl Into<String>, prefix: &str) -> Self {
+        Self {
+            cli: PassCli::new(binary),
+            prefix: prefix.trim_matches('/').to_string(),
+        }
+    }
+
+    /// The store entry holding `profile`'s token set.
+    pub fn entry(&self, profile: &str) -> String {
+        if self.prefix.is_empty() {
+            profile.to_string()
+        } else {
+            format!("{}/{profile}", self.prefix)
+        }
+    }
+
+    pub fn binary(&self) -> &str {
+        self.cli.binary()
+    }
+}
+
+impl TokenStore for PassTokenStore {
+    fn load(&self, profile: &str) -> AppResult<Option<TokenSet>> {
+        let entry = self.entry(profile);
+        let Some(contents) = self.cli.show(&entry)? else {
+            return Ok(None);
+        };
+        serde_json::from_str(&contents).map(Some).map_err(|err| {
+            AppError::Config(format!(
+                "{} entry `{entry}` is not a token set: {err}",
+                self.binary()
+            ))
+        })
+    }
+
+    fn save(&self, profile: &str, token: &TokenSet) -> AppResult<()> {
+        let payload = serde_json::to_string_pretty(token)?;
+        self.cli.insert(&self.entry(profile), &payload)
+    }
+
+    fn clear(&self, profile: &str) -> AppResult<()> {
+        self.cli.remove(&self.entry(profile))
+    }
+}
@@ -0,0 +1,179 @@
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::Mutex;
+
+use crate::config::Settings;
+use crate::error::{AppError, AppResult};
+
+use super::{AuthService, TokenSet, TokenStore};
+
+/// Hands out access tokens for one profile, refreshing ahead of expiry so a
+/// long-running command can ask again before each batch and never send one
+/// that lapses mid-flight. The current token is kept in memory, so asking
+/// is cheap; concurrent callers wait on a single refresh.
+#[derive(Debug)]
+pub struct TokenProvider {
+    margin: Duration,
+    current: Mutex<Option<TokenSet>>,
+}
+
+impl TokenProvider {
+    /// How long before expiry a token is refreshed.
+    pub const DEFAULT_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+    pub fn new(margin: Duration) -> Self {
+        Self {
+            margin,
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Return an access token valid for at least the margin, refreshing it
+    /// when it is closer to expiry. A token with no refresh token is used
+    /// until it actually expires.
+    pub async fn access_token<S: TokenStore>(
+        &self,
+        profile: &str,
+        settings: &Settings,
+        auth: &AuthService,
+        store: &S,
+    ) -> AppResult<String> {
+        let mut current = self.current.lock().await;
+        let soon = SystemTime::now() + self.margin;
+        if let Some(token) = current.as_ref()
+            && !token.is_expired(soon)
+        {
+            return Ok(token.access_token.clone());
+        }
+
+        let stored = store.load(profile)?.ok_or_else(|| {
+            AppError::InvalidInput("not logged in. run `gmail auth login`".to_string())
+        })?;
+        let usable = !stored.is_expired(soon)
+            || (!stored.has_refresh_token() && !stored.is_expired(SystemTime::now()));
+        let token = if usable {
+            stored
+        } else {
+            tracing::debug!(profile, "access token expiring; refreshing");
+            auth.force_refresh(profile, settings, store).await?
+        };
+
+        let access_token = token.access_token.clone();
+        *current = Some(token);
+        Ok(access_token)
+    }
+}
+
+impl Default for TokenProvider {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MARGIN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryStore(RefCell<HashMap<String, TokenSet>>);
+
+    impl TokenStore for MemoryStore {
+        fn load(&self, profile: &str) -> AppResult<Option<TokenSet>> {
+            Ok(self.0.borrow().get(profile).cloned())
+        }
+
+        fn save(&self, profile: &str, token: &TokenSet) -> AppResult<()> {
+            self.0
+                .borrow_mut()
+                .insert(profile.to_string(), token.clone());
+            Ok(())
+        }
+
+        fn clear(&self, profile: &str) -> AppResult<()> {
+            self.0.borrow_mut().remove(profile);
+            Ok(())
+        }
+    }
+
+    fn token(access_token: &str, expires_in_secs: u64, refresh: bool) -> TokenSet {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        TokenSet {
+            access_token: access_token.to_string(),
+            refresh_token: refresh.then(|| "refresh".to_string()),
+            expires_at_unix: Some(now.as_secs() + expires_in_secs),
+            token_type: None,
+            scope: None,
+            email: None,
+            name: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn reuses_a_token_until_it_nears_expiry() {
+        let store = MemoryStore::default();
+        store.save("work", &token("first", 3600, true)).unwrap();
+        let provider = TokenProvider::default();
+        let (settings, auth) = (Settings::default(), AuthService::default());
+
+        let first = provider
+            .access_token("work", &settings, &auth, &store)
+            .await;
+        assert_eq!(first.unwrap(), "first");
+
+        store.save("work", &token("second", 3600, true)).unwrap();
+        let again = provider
+            .access_token("work", &settings, &auth, &store)
+            .await;
+        assert_eq!(again.unwrap(), "first");
+    }
+
+    #[tokio::test]
+    async fn refreshes_early_but_uses_a_token_it_cannot_refresh() {
+        let store = MemoryStore::default();
+        let (settings, auth) = (Settings::default(), AuthService::default());
+
+        store.save("work", &token("last", 120, false)).unwrap();
+        let provider = TokenProvider::default();
+        let last = provider
+            .access_token("work", &settings, &auth, &store)
+            .await;
+        assert_eq!(last.unwrap(), "last");
+
+        // Inside the margin with a refresh token: a refresh is attempted,
+        // which fails here since no OAuth client is configured.
+        store.save("work", &token("stale", 120, true)).unwrap();
+        let provider = TokenProvider::default();
+        let refreshed = provider
+            .access_token("work", &settings, &auth, &store)
+            .await;
+        assert!(refreshed.is_err());
+    }
+
+    #[tokio::test]
+    async fn a_loop_asking_per_page_moves_past_an_expired_token() {
+        let store = MemoryStore::default();
+        let (settings, auth) = (Settings::default(), AuthService::default());
+        let margin = Duration::from_secs(60);
+        // Valid for the first page, expired (with the skew) a second later.
+        store
+            .save("work", &token("first", margin.as_secs() + 31, true))
+            .unwrap();
+        let provider = TokenProvider::new(margin);
+
+        let mut used = Vec::new();
+        for page in 0..3 {
+            if page == 1 {
+                tokio::time::sleep(Duration::from_millis(1100)).await;
+                // Another run refreshed the token in the meantime.
+                store.save("work", &token("second", 3600, true)).unwrap();
+            }
+            let token = provider.access_token("work", &settings, &auth, &store);
+            used.push(token.await.unwrap());
+        }
+        assert_eq!(used, ["first", "second", "second"]);
+    }
+}
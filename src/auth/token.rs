@@ -37,6 +37,14 @@ impl TokenSet {
         Some(expires_at - now_secs)
     }
 
+    /// Whether every one of `scopes` was granted; an unrecorded grant counts as none.
+    pub fn has_scopes(&self, scopes: &[&str]) -> bool {
+        let granted = self.scope.as_deref().unwrap_or_default();
+        scopes
+            .iter()
+            .all(|scope| granted.split_whitespace().any(|granted| granted == *scope))
+    }
+
     /// Whether a refresh token is stored.
     pub fn has_refresh_token(&self) -> bool {
         self.refresh_token.is_some()
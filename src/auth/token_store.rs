@@ -1,9 +1,10 @@
 use std::fs;
 
-use crate::config::AppPaths;
-use crate::error::AppResult;
+use crate::config::{AppPaths, Settings};
+use crate::error::{AppError, AppResult};
 
 use super::TokenSet;
+use super::pass_store::{DEFAULT_PASS_PREFIX, PassTokenStore};
 
 /// Persistence backend for a profile's OAuth token set.
 pub trait TokenStore {
@@ -72,3 +73,98 @@ impl TokenStore for FileTokenStore {
         Ok(())
     }
 }
+
+/// The token store a profile's `token_store` setting selects.
+#[derive(Debug, Clone)]
+pub enum ConfiguredTokenStore {
+    File(FileTokenStore),
+    Pass(PassTokenStore),
+}
+
+impl ConfiguredTokenStore {
+    /// Pick the backend named by `settings.token_store`: `file` (the default),
+    /// `pass`, or `gopass`.
+    pub fn from_settings(paths: &AppPaths, settings: &Settings) -> AppResult<Self> {
+        let prefix = settings
+            .pass_prefix
+            .as_deref()
+            .unwrap_or(DEFAULT_PASS_PREFIX);
+        match settings.token_store.as_deref().map(str::trim) {
+            None | Some("file") => Ok(Self::File(FileTokenStore::new(paths.clone()))),
+            Some(binary @ ("pass" | "gopass")) => {
+                Ok(Self::Pass(PassTokenStore::new(binary, prefix)))
+            }
+            Some(other) => Err(AppError::Config(format!(
+                "unknown token_store `{other}`; use file, pass, or gopass"
+            ))),
+        }
+    }
+
+    /// Where `profile`'s token lives, for messages pointing the user at it.
+    pub fn location(&self, profile: &str) -> String {
+        match self {
+            Self::File(store) => store.paths.token_file(profile).display().to_string(),
+            Self::Pass(store) => format!("{} entry {}", store.binary(), store.entry(profile)),
+        }
+    }
+}
+
+impl TokenStore for ConfiguredTokenStore {
+    fn load(&self, profile: &str) -> AppResult<Option<TokenSet>> {
+        match self {
+            Self::File(store) => store.load(profile),
+            Self::Pass(store) => store.load(profile),
+        }
+    }
+
+    fn save(&self, profile: &str, token: &TokenSet) -> AppResult<()> {
+        match self {
+            Self::File(store) => store.save(profile, token),
+            Self::Pass(store) => store.save(profile, token),
+        }
+    }
+
+    fn clear(&self, profile: &str) -> AppResult<()> {
+        match self {
+            Self::File(store) => store.clear(profile),
+            Self::Pass(store) => store.clear(profile),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_backend_from_settings() {
+        let root = std::env::temp_dir().join(format!("gmail-token-store-{}", std::process::id()));
+        let paths = AppPaths::discover(Some(root.join("config")), Some(root.join("data"))).unwrap();
+        let store = |token_store: Option<&str>, prefix: Option<&str>| {
+            let settings = Settings {
+                token_store: token_store.map(str::to_string),
+                pass_prefix: prefix.map(str::to_string),
+                ..Settings::default()
+            };
+            ConfiguredTokenStore::from_settings(&paths, &settings)
+        };
+
+        assert!(matches!(
+            store(None, None),
+            Ok(ConfiguredTokenStore::File(_))
+        ));
+        assert_eq!(
+            store(Some("gopass"), None).unwrap().location("work"),
+            "gopass entry gmail/work"
+        );
+        assert_eq!(
+            store(Some("pass"), Some("/secrets/google/"))
+                .unwrap()
+                .location("work"),
+            "pass entry secrets/google/work"
+        );
+        assert!(store(Some("keychain"), None).is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 
-use clap::{ArgAction, Args, Parser, Subcommand};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+
+use crate::output::OutputMode;
+use crate::output::text::ColorChoice;
 
 #[derive(Debug, Parser)]
 #[command(name = "gmail", version, about = "Gmail command line interface")]
@@ -8,13 +11,95 @@ pub struct Cli {
     #[arg(
         long,
         global = true,
-        help = "Profile name to use (overrides GMAIL_PROFILE and the configured default)"
+        help = "Profile name to use (overrides GMAIL_CLI_PROFILE and the configured default)"
     )]
     pub profile: Option<String>,
-    #[arg(long, global = true, help = "Emit JSON output")]
+    #[arg(
+        long,
+        global = true,
+        value_delimiter = ',',
+        value_name = "NAMES",
+        conflicts_with = "profile",
+        help = "Run `list`, `stats`, or `legacy-access status` against several profiles concurrently and merge the results, attributing each to its account"
+    )]
+    pub profiles: Vec<String>,
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        help = "Output format [env: GMAIL_CLI_OUTPUT]"
+    )]
+    pub output: Option<OutputMode>,
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "output",
+        help = "Emit JSON output (alias for --output json)"
+    )]
     pub json: bool,
-    #[arg(short = 'v', long, global = true, action = ArgAction::Count, help = "Verbose logging")]
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        help = "Color text output (auto honors NO_COLOR and TTY detection) [default: auto] [env: GMAIL_CLI_COLOR]"
+    )]
+    pub color: Option<ColorChoice>,
+    #[arg(
+        long,
+        global = true,
+        help = "Never pipe long text output through a pager"
+    )]
+    pub no_pager: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Write the command's output to PATH (atomically) instead of stdout; for `man` and `attachments get`, the directory to write files into"
+    )]
+    pub out: Option<PathBuf>,
+    #[arg(
+        short = 'v',
+        long,
+        global = true,
+        action = ArgAction::Count,
+        help = "Verbose logging to stderr (-v info, -vv debug with API requests, -vvv trace)"
+    )]
     pub verbose: u8,
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Also append log events to this file"
+    )]
+    pub log_file: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        help = "Print Gmail API request latency, errors, and estimated quota use to stderr when the command ends"
+    )]
+    pub timings: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Resolve a mutating command and print what it would change (ids, labels, payload) without writing anything"
+    )]
+    pub dry_run: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Directory for config.json and profiles [env: GMAIL_CLI_CONFIG_DIR]"
+    )]
+    pub config_dir: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Directory for tokens and other state [env: GMAIL_CLI_DATA_DIR]"
+    )]
+    pub data_dir: Option<PathBuf>,
     #[command(subcommand)]
     pub command: Command,
 }
@@ -25,21 +110,199 @@ pub enum Command {
     Profile(ProfileArgs),
     Signature(SignatureArgs),
     List(ListArgs),
-    Send(SendArgs),
+    /// List messages matching filter flags (--from, --after, ...) composed into a Gmail query, or manage saved searches
+    Search(SearchArgs),
+    Send(Box<SendArgs>),
+    /// Save, list, edit, and send drafts
+    Draft(DraftArgs),
+    /// Send git format-patch files as a threaded plain-text series
+    SendPatch(SendPatchArgs),
+    /// Sendmail-compatible: send an RFC 822 message read from stdin
+    Sendmail(SendmailArgs),
+    /// Add messages from EML or mbox files to the mailbox without sending them
+    Import(ImportArgs),
+    /// Save matching messages as .eml files or one mbox, resuming where a previous run stopped
+    Export(ExportArgs),
+    /// List, cancel, and send messages scheduled with `send --at` or `--in`
+    Queue(QueueArgs),
+    /// Forward a message, with its attachments, to new recipients
+    Forward(ForwardArgs),
+    /// Answer a calendar invitation with an iTIP REPLY to its organizer
+    Rsvp(RsvpArgs),
+    /// Leave the mailing list a message came from via its List-Unsubscribe header
+    Unsubscribe(UnsubscribeArgs),
+    /// Report top senders, daily volume, reply latency, and label counts
+    Stats(StatsArgs),
+    /// Show estimated Gmail API quota use per day, recorded by every command
+    Quota(QuotaArgs),
+    /// Estimate storage use by label, sender, and age, and list the largest messages
+    Usage(UsageArgs),
+    /// Review the local log of sends, label changes, and settings changes
+    Log(LogArgs),
+    /// Apply the query-and-action rules in a TOML file, e.g. trash old promotions
+    Cleanup(CleanupArgs),
     Get(GetArgs),
+    /// Open a message or conversation in the Gmail web UI
+    Open(OpenArgs),
+    /// Read whole conversations: every message in a thread, or a list of threads
+    Thread(ThreadArgs),
     Label(LabelArgs),
+    /// Archive messages, given by id or matched by --q: take them out of the
+    /// inbox, keeping their other labels
+    Archive(BulkArgs),
+    /// Mark messages read, given by id or matched by --q
+    Read(BulkArgs),
+    /// Mark messages unread, given by id or matched by --q
+    Unread(BulkArgs),
+    /// Report messages as spam, moving them out of the inbox
+    Spam(BulkArgs),
+    /// Rescue messages from spam back to the inbox
+    NotSpam(BulkArgs),
+    /// Star messages, given by id or matched by --q
+    Star(BulkArgs),
+    /// Remove the star from messages, given by id or matched by --q
+    Unstar(BulkArgs),
+    /// Take messages out of the inbox until a given time, then put them back
+    Snooze(SnoozeArgs),
+    /// Archive conversations and keep their new replies out of the inbox
+    Mute(MuteArgs),
+    /// Stop archiving new replies to muted conversations
+    Unmute(UnmuteArgs),
+    /// Move messages to the trash, where Gmail deletes them after 30 days
+    Trash(BulkArgs),
+    /// Restore messages from the trash
+    Untrash(BulkArgs),
+    /// Permanently delete messages, skipping the trash; asks first unless --yes
+    Delete(DeleteArgs),
+    /// Permanently delete every message in the trash; asks first unless --yes
+    EmptyTrash(EmptyArgs),
+    /// Permanently delete every message in spam; asks first unless --yes
+    EmptySpam(EmptyArgs),
+    #[command(visible_alias = "attachment")]
     Attachments(AttachmentsArgs),
     Aliases(AliasesArgs),
+    /// Manage send-as addresses: add, verify, set the signature or the default
+    Sendas(SendasArgs),
+    /// Audit and change mail forwarding: forwarding addresses and auto-forwarding
+    Forwarding(ForwardingArgs),
+    /// Audit or switch IMAP and POP access
+    LegacyAccess(LegacyAccessArgs),
+    /// Manage mutt-style recipient aliases, so `--to team` expands to addresses
+    Alias(AliasArgs),
+    /// Harvest correspondents from matching messages
+    Contacts(ContactsArgs),
+    Watch(WatchArgs),
+    Daemon(DaemonArgs),
+    /// Check the local mail rules (rules.toml) the daemon applies with --rules
+    Rules(RulesArgs),
+    Notify(NotifyArgs),
+    Stream(StreamArgs),
+    /// Print mailbox changes (messages and labels) since a history id, once
+    History(HistoryArgs),
+    Man(ManArgs),
+    Tui(TuiArgs),
+    /// Serve list/get/search/send/label operations as MCP tools over stdio
+    Mcp,
+    /// Print JSON Schema documents for the structured output of each command
+    Schema(SchemaArgs),
+    /// Check config, permissions, OAuth setup, token refresh, and Gmail API access
+    Doctor,
 }
 
-#[derive(Debug, Args)]
+#[derive(Debug, Clone, Args)]
 pub struct ListArgs {
-    #[arg(long, default_value_t = 10, help = "Maximum messages to return")]
+    #[arg(
+        long,
+        default_value_t = 10,
+        help = "Maximum messages (threads with --threads) to return"
+    )]
     pub limit: u32,
     #[arg(long, help = "Restrict to inbox messages")]
     pub inbox: bool,
     #[arg(long, help = "Gmail search query")]
     pub q: Option<String>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Run a saved search (see `gmail search ls`), combined with any --q"
+    )]
+    pub saved: Option<String>,
+    #[arg(
+        long,
+        help = "Print only message ids, one per line, without fetching each message"
+    )]
+    pub ids_only: bool,
+    #[arg(
+        long,
+        help = "List conversations (subject, participants, message count, latest date) instead of messages"
+    )]
+    pub threads: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct SearchArgs {
+    #[command(subcommand)]
+    pub command: Option<SearchCommand>,
+    #[arg(help = "Free-text words, or any other Gmail query terms")]
+    pub words: Vec<String>,
+    #[arg(long, action = ArgAction::Append, help = "Sender address or name (repeatable)")]
+    pub from: Vec<String>,
+    #[arg(long, action = ArgAction::Append, help = "Recipient address or name (repeatable)")]
+    pub to: Vec<String>,
+    #[arg(long, help = "Words or phrase in the subject")]
+    pub subject: Option<String>,
+    #[arg(long, action = ArgAction::Append, help = "Label name (repeatable)")]
+    pub label: Vec<String>,
+    #[arg(
+        long,
+        value_name = "DATE|AGE",
+        help = "Sent after this date (2024-06-01) or within this age (7d, 2m, 1y)"
+    )]
+    pub after: Option<String>,
+    #[arg(
+        long,
+        value_name = "DATE|AGE",
+        help = "Sent before this date (2024-06-01) or longer ago than this age (7d, 2m, 1y)"
+    )]
+    pub before: Option<String>,
+    #[arg(long, help = "Only messages with attachments")]
+    pub has_attachment: bool,
+    #[arg(
+        long,
+        value_name = "SIZE",
+        help = "Larger than this size, e.g. 5M or 500K"
+    )]
+    pub larger: Option<String>,
+    #[arg(
+        long,
+        value_name = "SIZE",
+        help = "Smaller than this size, e.g. 5M or 500K"
+    )]
+    pub smaller: Option<String>,
+    #[command(flatten)]
+    pub list: ListArgs,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum SearchCommand {
+    /// Save a Gmail query under a name, to run with `gmail list --saved <name>`
+    Save {
+        /// Name to save the query as
+        name: String,
+        /// Gmail query, e.g. label:work is:unread
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+    },
+    /// List saved searches
+    #[command(visible_alias = "list")]
+    Ls,
+    /// Remove saved searches from the active profile
+    Rm {
+        /// Names of saved searches
+        #[arg(required = true, num_args = 1..)]
+        names: Vec<String>,
+    },
 }
 
 #[derive(Debug, Args)]
@@ -72,6 +335,8 @@ pub enum ProfileCommand {
     },
     /// Show the profile that resolves for this invocation
     Show,
+    /// Show the profile's mailbox: address, message and thread totals, and history id
+    Info,
 }
 
 #[derive(Debug, Args)]
@@ -98,7 +363,7 @@ pub enum SignatureCommand {
     Clear,
 }
 
-#[derive(Debug, Args)]
+#[derive(Debug, Default, Args)]
 pub struct SendArgs {
     #[arg(long, value_delimiter = ',', num_args = 1.., help = "Recipient addresses")]
     pub to: Vec<String>,
@@ -118,6 +383,36 @@ pub struct SendArgs {
     pub stdin: bool,
     #[arg(long, help = "Reply to an existing message id")]
     pub reply: Option<String>,
+    #[arg(
+        long,
+        requires = "reply",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        help = "Also address the parent's To and Cc (overrides reply_all_default)"
+    )]
+    pub reply_all: Option<bool>,
+    #[arg(
+        long,
+        requires = "reply",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        help = "Keep the parent's Cc recipients (overrides reply_keep_cc_default)"
+    )]
+    pub keep_cc: Option<bool>,
+    #[arg(
+        long,
+        requires = "reply",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        help = "Quote the parent's body below the reply (overrides reply_quote_default)"
+    )]
+    pub quote: Option<bool>,
     #[arg(long, action = ArgAction::Append, help = "Attach file (repeatable)")]
     pub attach: Vec<PathBuf>,
     #[arg(
@@ -133,82 +428,1177 @@ pub struct SendArgs {
     pub signature: Option<String>,
     #[arg(long, help = "Do not append the profile signature to this send")]
     pub no_signature: bool,
+    #[arg(
+        long,
+        value_name = "SUMMARY",
+        requires = "when",
+        help = "Attach a calendar invitation for this event to To and Cc (needs --when)"
+    )]
+    pub invite: Option<String>,
+    #[arg(
+        long,
+        requires = "invite",
+        help = "Invitation start in local time, e.g. `mon 9:30am` or `2025-03-04 14:00`"
+    )]
+    pub when: Option<String>,
+    #[arg(
+        long,
+        requires = "invite",
+        help = "Invitation length, e.g. `30m` or `1h30m` [default: 30m]"
+    )]
+    pub duration: Option<String>,
+    #[arg(long, requires = "invite", help = "Invitation location")]
+    pub location: Option<String>,
+    #[arg(
+        long,
+        help = "Open the rendered message in the browser and ask before sending"
+    )]
+    pub preview: bool,
+    #[arg(
+        long,
+        conflicts_with = "preview",
+        help = "Save the message as a draft instead of sending it, and print the draft id"
+    )]
+    pub save_draft: bool,
+    #[arg(
+        long,
+        conflicts_with_all = ["preview", "save_draft"],
+        help = "Queue the message to send at this local time, e.g. `mon 9:30am` or `2025-03-04 14:00`"
+    )]
+    pub at: Option<String>,
+    #[arg(
+        long = "in",
+        value_name = "DURATION",
+        conflicts_with_all = ["at", "preview", "save_draft"],
+        help = "Queue the message to send after this long, e.g. `30m` or `2h`"
+    )]
+    pub in_: Option<String>,
 }
 
 #[derive(Debug, Args)]
-pub struct GetArgs {
-    #[arg(help = "Gmail message id")]
-    pub id: String,
+pub struct QueueArgs {
+    #[command(subcommand)]
+    pub command: QueueCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum QueueCommand {
+    /// List queued messages, soonest first
+    #[command(visible_alias = "list")]
+    Ls,
+    /// Cancel queued messages
+    Rm(QueueRmArgs),
+    /// Send every queued message that is due, then exit (the daemon does this each tick)
+    Run,
 }
 
 #[derive(Debug, Args)]
-pub struct AttachmentsArgs {
+pub struct QueueRmArgs {
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Queue ids (from `gmail queue ls`), or `-` to read ids from stdin"
+    )]
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct DraftArgs {
     #[command(subcommand)]
-    pub command: AttachmentsCommand,
+    pub command: DraftCommand,
 }
 
 #[derive(Debug, Subcommand)]
-pub enum AttachmentsCommand {
+pub enum DraftCommand {
+    /// Save a new draft, taking the same options as `send`
+    Create(Box<SendArgs>),
+    /// List drafts, newest first
     #[command(visible_alias = "list")]
-    Ls(AttachmentsLsArgs),
-    Get(AttachmentsGetArgs),
+    Ls(DraftLsArgs),
+    /// Print a draft's headers and body
+    Get(DraftIdArgs),
+    /// Replace a draft's content; options left out keep the draft's current
+    /// recipients, subject, and body
+    Edit(Box<DraftEditArgs>),
+    /// Send a draft
+    Send(DraftIdArgs),
+    /// Delete drafts for good
+    Rm(DraftRmArgs),
 }
 
 #[derive(Debug, Args)]
-pub struct AttachmentsLsArgs {
-    #[arg(help = "Gmail message id")]
+pub struct DraftLsArgs {
+    #[arg(long, default_value_t = 10, help = "Maximum drafts to return")]
+    pub limit: u32,
+    #[arg(long, help = "Gmail search query")]
+    pub q: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct DraftIdArgs {
+    #[arg(help = "Draft id (from `gmail draft ls`)")]
     pub id: String,
 }
 
 #[derive(Debug, Args)]
-pub struct AttachmentsGetArgs {
-    #[arg(help = "Gmail message id")]
+pub struct DraftEditArgs {
+    #[arg(help = "Draft id (from `gmail draft ls`)")]
     pub id: String,
+    #[command(flatten)]
+    pub message: SendArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct DraftRmArgs {
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Draft ids, or `-` to read ids from stdin"
+    )]
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct SendPatchArgs {
+    #[arg(
+        required = true,
+        value_name = "PATCH",
+        help = "Patch files from `git format-patch`, in series order"
+    )]
+    pub patches: Vec<PathBuf>,
+    #[arg(long, value_delimiter = ',', num_args = 1.., required = true, help = "Recipient addresses")]
+    pub to: Vec<String>,
+    #[arg(long, value_delimiter = ',', num_args = 1.., help = "CC addresses")]
+    pub cc: Vec<String>,
     #[arg(
         long,
-        default_value = ".",
-        help = "Directory to write attachments into (created if missing)"
+        help = "Send from this address (must be a verified send-as alias; see `gmail aliases ls`)"
     )]
-    pub out: PathBuf,
+    pub from: Option<String>,
     #[arg(
         long,
-        conflicts_with = "name",
-        help = "Only download the attachment at this 1-based index"
+        value_name = "MESSAGE_ID",
+        help = "Thread the series under this Message-ID (e.g. the previous version's cover letter)"
     )]
-    pub index: Option<usize>,
-    #[arg(long, help = "Only download attachments matching this filename")]
-    pub name: Option<String>,
+    pub in_reply_to: Option<String>,
+    #[arg(
+        long,
+        help = "Reply each patch to the one before it instead of all to the first"
+    )]
+    pub chain_reply_to: bool,
 }
 
 #[derive(Debug, Args)]
-pub struct AliasesArgs {
-    #[command(subcommand)]
-    pub command: AliasesCommand,
+pub struct ImportArgs {
+    #[arg(
+        required = true,
+        help = "RFC 822 message files (.eml) or mbox files, detected by their `From ` first line"
+    )]
+    pub files: Vec<PathBuf>,
+    #[arg(
+        long = "label",
+        value_delimiter = ',',
+        action = ArgAction::Append,
+        help = "Label to apply, by name or id, e.g. INBOX or UNREAD (repeatable)"
+    )]
+    pub labels: Vec<String>,
+    #[arg(
+        long,
+        help = "Store the messages as is with messages.insert, skipping spam and phishing checks"
+    )]
+    pub insert: bool,
 }
 
-#[derive(Debug, Subcommand)]
-pub enum AliasesCommand {
-    #[command(visible_alias = "list")]
-    Ls,
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[arg(
+        long,
+        help = "Gmail search query [default: all mail outside spam and trash]"
+    )]
+    pub q: Option<String>,
+    #[arg(
+        long,
+        value_name = "DIR",
+        required_unless_present = "mbox",
+        conflicts_with = "mbox",
+        help = "Write one <id>.eml file per message into this directory"
+    )]
+    pub dir: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Append the messages to this mbox file (exported ids are kept in FILE.ids)"
+    )]
+    pub mbox: Option<PathBuf>,
+    #[arg(long, help = "Stop after this many messages [default: every match]")]
+    pub limit: Option<u32>,
 }
 
 #[derive(Debug, Args)]
-pub struct LabelArgs {
-    #[command(subcommand)]
-    pub command: LabelCommand,
+pub struct SendmailArgs {
+    #[arg(short = 't', help = "Take recipients from the To, Cc, and Bcc headers")]
+    pub read_recipients: bool,
+    #[arg(
+        short = 'i',
+        help = "Don't end input at a line holding a single dot (also -oi)"
+    )]
+    pub ignore_dots: bool,
+    #[arg(
+        short = 'f',
+        value_name = "ADDRESS",
+        help = "Sender used for the From header when the message has none"
+    )]
+    pub sender: Option<String>,
+    #[arg(
+        short = 'F',
+        value_name = "NAME",
+        help = "Display name for a From header built from -f"
+    )]
+    pub full_name: Option<String>,
+    #[arg(
+        short = 'o',
+        value_name = "OPTION",
+        action = ArgAction::Append,
+        help = "Sendmail option; -oi is honored, others (e.g. -oem) are accepted and ignored"
+    )]
+    pub options: Vec<String>,
+    #[arg(
+        short = 'B',
+        value_name = "TYPE",
+        hide = true,
+        help = "Body type; accepted for compatibility and ignored"
+    )]
+    pub body_type: Option<String>,
+    #[arg(
+        value_name = "RECIPIENT",
+        help = "Recipients; with -t, added to those in the headers"
+    )]
+    pub recipients: Vec<String>,
 }
 
-#[derive(Debug, Subcommand)]
-pub enum LabelCommand {
-    Ls,
-    Add(LabelMutateArgs),
-    Rm(LabelMutateArgs),
+#[derive(Debug, Args)]
+pub struct ForwardArgs {
+    #[arg(help = "Gmail message id to forward")]
+    pub id: String,
+    #[arg(long, value_delimiter = ',', num_args = 1.., required = true, help = "Recipient addresses")]
+    pub to: Vec<String>,
+    #[arg(long, value_delimiter = ',', num_args = 1.., help = "CC addresses")]
+    pub cc: Vec<String>,
+    #[arg(long, value_delimiter = ',', num_args = 1.., help = "BCC addresses")]
+    pub bcc: Vec<String>,
+    #[arg(long, help = "Note above the forwarded message (markdown)")]
+    pub body: Option<String>,
+    #[arg(
+        long,
+        conflicts_with = "body",
+        help = "Read the note above the forwarded message from a file"
+    )]
+    pub body_file: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "Send from this address (must be a verified send-as alias; see `gmail aliases ls`)"
+    )]
+    pub from: Option<String>,
+    #[arg(long, help = "Do not append the profile signature to the note")]
+    pub no_signature: bool,
+    #[arg(long, help = "Leave out the original's attachments")]
+    pub no_attachments: bool,
+    #[arg(
+        long,
+        help = "Save the forward as a draft instead of sending it, and print the draft id"
+    )]
+    pub save_draft: bool,
 }
 
 #[derive(Debug, Args)]
-pub struct LabelMutateArgs {
-    #[arg(help = "Gmail message id")]
+pub struct RsvpArgs {
+    #[arg(help = "Gmail message id of the invitation")]
     pub id: String,
-    #[arg(required = true, num_args = 1.., help = "Labels to mutate")]
-    pub labels: Vec<String>,
+    #[arg(value_enum, help = "Your answer")]
+    pub response: RsvpResponse,
+    #[arg(long, help = "Note for the organizer, sent in the reply and its body")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum RsvpResponse {
+    Accept,
+    Decline,
+    Tentative,
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    #[arg(
+        value_name = "COMMAND",
+        help = "Command path, e.g. `list` or `label ls`; every schema when omitted"
+    )]
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct GetArgs {
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Gmail message ids, or `-` to read ids from stdin"
+    )]
+    pub ids: Vec<String>,
+    #[arg(
+        long,
+        help = "Write the original RFC 822 message (.eml) instead of a summary; combine with --out to save it"
+    )]
+    pub raw: bool,
+    #[arg(
+        long,
+        conflicts_with = "raw",
+        help = "Print the whole conversation each message belongs to, oldest first"
+    )]
+    pub thread: bool,
+    #[arg(
+        long,
+        conflicts_with_all = ["raw", "thread", "save_attachments"],
+        help = "Print every header (Received chain, authentication results, List-*, and the rest) instead of the body"
+    )]
+    pub headers: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with = "raw",
+        help = "Also download every attachment into DIR (created if missing), without overwriting files already there"
+    )]
+    pub save_attachments: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct OpenArgs {
+    #[arg(help = "Gmail message or thread id")]
+    pub id: String,
+    #[arg(long, help = "Print the URL without opening a browser")]
+    pub print: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ThreadArgs {
+    #[command(subcommand)]
+    pub command: ThreadCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ThreadCommand {
+    /// Print every message in a conversation, oldest first
+    Get(ThreadGetArgs),
+    /// List conversations with their subject, participants, and latest date
+    #[command(visible_alias = "list")]
+    Ls(ThreadLsArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ThreadGetArgs {
+    #[arg(help = "Gmail thread id (a message's `thread_id`)")]
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ThreadLsArgs {
+    #[arg(long, default_value_t = 10, help = "Maximum threads to return")]
+    pub limit: u32,
+    #[arg(long, help = "Restrict to inbox threads")]
+    pub inbox: bool,
+    #[arg(long, help = "Gmail search query")]
+    pub q: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct AttachmentsArgs {
+    #[command(subcommand)]
+    pub command: AttachmentsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AttachmentsCommand {
+    #[command(visible_alias = "list")]
+    Ls(AttachmentsLsArgs),
+    Get(AttachmentsGetArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct AttachmentsLsArgs {
+    #[arg(
+        required_unless_present = "q",
+        conflicts_with = "q",
+        help = "Gmail message id"
+    )]
+    pub id: Option<String>,
+    #[arg(
+        long,
+        help = "List attachments across the messages matching this Gmail search instead"
+    )]
+    pub q: Option<String>,
+    #[arg(
+        long,
+        default_value_t = 100,
+        help = "Maximum messages to scan with --q"
+    )]
+    pub limit: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct AttachmentsGetArgs {
+    #[arg(
+        required_unless_present = "all",
+        conflicts_with_all = ["all", "q"],
+        help = "Gmail message id"
+    )]
+    pub id: Option<String>,
+    #[arg(
+        conflicts_with_all = ["index", "name"],
+        help = "Only download the attachment at this MIME part id, as `attachments ls` shows it (e.g. `1` or `0.2`)"
+    )]
+    pub part: Option<String>,
+    /// Directory to write attachments into (created if missing); set from the
+    /// global `--out`, defaulting to the current directory.
+    #[arg(skip = PathBuf::from("."))]
+    pub out: PathBuf,
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Directory to write attachments into (overrides --out)"
+    )]
+    pub dir: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "Download the attachments of every message matching --q (default: has:attachment)"
+    )]
+    pub all: bool,
+    #[arg(
+        long,
+        requires = "all",
+        help = "Gmail search selecting messages for --all"
+    )]
+    pub q: Option<String>,
+    #[arg(
+        long,
+        default_value_t = 100,
+        help = "Maximum messages to download from with --all"
+    )]
+    pub limit: u32,
+    #[arg(
+        long,
+        conflicts_with_all = ["name", "all"],
+        help = "Only download the attachment at this 1-based index"
+    )]
+    pub index: Option<usize>,
+    #[arg(long, help = "Only download attachments matching this filename")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ContactsArgs {
+    #[command(subcommand)]
+    pub command: ContactsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContactsCommand {
+    /// Write the From, To, and Cc addresses of matching messages as vCards
+    Export(ContactsExportArgs),
+    /// Look people up in Google Contacts and the Workspace directory
+    Search(ContactsSearchArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ContactsSearchArgs {
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Name, email, or organization to match (words are joined)"
+    )]
+    pub query: Vec<String>,
+    #[arg(long, default_value_t = 10, help = "Maximum matches to return")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct ContactsExportArgs {
+    #[arg(long, help = "Gmail search query selecting the messages to scan")]
+    pub q: Option<String>,
+    #[arg(long, default_value_t = 100, help = "Maximum messages to scan")]
+    pub limit: u32,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the vCards to this file instead of stdout"
+    )]
+    pub vcf: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct StatsArgs {
+    #[arg(
+        long,
+        default_value = "90d",
+        conflicts_with = "after",
+        help = "Window to report on: a count of days, weeks, months, or years (30d, 6w, 3m, 1y)"
+    )]
+    pub since: String,
+    #[arg(
+        long,
+        value_name = "DATE|AGE",
+        help = "Start the window at this date (2024-06-01) or age (7d, 2m, 1y) instead of --since"
+    )]
+    pub after: Option<String>,
+    #[arg(
+        long,
+        value_name = "DATE|AGE",
+        help = "End the window before this date (2024-06-01) or age (7d, 2m, 1y)"
+    )]
+    pub before: Option<String>,
+    #[arg(long, help = "Gmail search query narrowing the window")]
+    pub q: Option<String>,
+    #[arg(long, default_value_t = 2000, help = "Maximum messages to scan")]
+    pub limit: u32,
+    #[arg(
+        long,
+        default_value_t = 10,
+        help = "Number of top senders and domains to show"
+    )]
+    pub top: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct UsageArgs {
+    #[arg(
+        long,
+        help = "Gmail search query selecting the messages to measure (default: in:anywhere)"
+    )]
+    pub q: Option<String>,
+    #[arg(long, default_value_t = 2000, help = "Maximum messages to scan")]
+    pub limit: u32,
+    #[arg(
+        long,
+        default_value_t = 20,
+        help = "Number of labels, senders, and messages to show"
+    )]
+    pub top: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct CleanupArgs {
+    #[arg(long, value_name = "PATH", help = "Rules file (cleanup.toml)")]
+    pub rules: PathBuf,
+    #[arg(
+        long,
+        default_value_t = 5000,
+        help = "Maximum messages each rule changes per run"
+    )]
+    pub limit: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct LogArgs {
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Only changes newer than this, e.g. 7d, 12h, or 30m"
+    )]
+    pub since: Option<String>,
+    #[arg(long, help = "Show every profile's changes, not just the active one's")]
+    pub all_profiles: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct QuotaArgs {
+    #[arg(long, default_value_t = 7, help = "Number of most recent days to show")]
+    pub days: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct UnsubscribeArgs {
+    #[arg(help = "Gmail message id, or `-` to read ids from stdin")]
+    pub id: String,
+    #[arg(
+        long,
+        help = "Send a mailto unsubscribe request instead of saving it as a draft"
+    )]
+    pub send: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AliasesArgs {
+    #[command(subcommand)]
+    pub command: AliasesCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AliasesCommand {
+    #[command(visible_alias = "list")]
+    Ls,
+}
+
+#[derive(Debug, Args)]
+pub struct SendasArgs {
+    #[command(subcommand)]
+    pub command: SendasCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SendasCommand {
+    /// List send-as addresses with their verification state
+    #[command(visible_alias = "list")]
+    Ls,
+    /// Add a send-as address; Gmail emails a verification link to addresses outside your domain
+    Add(SendasAddArgs),
+    /// Resend the verification email for a pending address
+    Verify {
+        /// The send-as address
+        email: String,
+    },
+    /// Set an address's signature from Markdown, uploaded as HTML
+    SetSignature(SendasSignatureArgs),
+    /// Make an address the default From for new mail
+    SetDefault {
+        /// The send-as address
+        email: String,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct SendasAddArgs {
+    #[arg(help = "Address to send as")]
+    pub email: String,
+    #[arg(long, help = "Display name for the From header")]
+    pub name: Option<String>,
+    #[arg(long, help = "Reply-To address for mail sent from it")]
+    pub reply_to: Option<String>,
+    #[arg(long, help = "Signature (markdown)")]
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct SendasSignatureArgs {
+    #[arg(help = "The send-as address")]
+    pub email: String,
+    #[arg(
+        required_unless_present = "file",
+        conflicts_with = "file",
+        help = "Signature text (markdown); an empty string clears it"
+    )]
+    pub text: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Read the markdown signature from a file"
+    )]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ForwardingArgs {
+    #[command(subcommand)]
+    pub command: ForwardingCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ForwardingCommand {
+    /// List forwarding addresses and the auto-forwarding setting
+    #[command(visible_alias = "list")]
+    Ls,
+    /// Add a forwarding address; Gmail emails it a confirmation link
+    Add {
+        /// Address to forward to
+        email: String,
+    },
+    /// Remove a forwarding address
+    Rm {
+        /// The forwarding address
+        email: String,
+    },
+    /// Turn auto-forwarding of new mail on or off
+    Auto(ForwardingAutoArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ForwardingAutoArgs {
+    #[arg(value_enum, help = "Whether to forward new mail")]
+    pub state: Toggle,
+    #[arg(
+        long,
+        required_if_eq("state", "on"),
+        help = "Verified forwarding address to send to"
+    )]
+    pub to: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "keep",
+        help = "What to do with Gmail's copy once forwarded"
+    )]
+    pub action: ForwardAction,
+}
+
+#[derive(Debug, Args)]
+pub struct LegacyAccessArgs {
+    #[command(subcommand)]
+    pub command: LegacyAccessCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LegacyAccessCommand {
+    /// Show whether IMAP and POP are enabled (works with --profiles)
+    Status,
+    /// Turn IMAP access on or off
+    Imap {
+        #[arg(value_enum)]
+        state: Toggle,
+    },
+    /// Turn POP access on or off
+    Pop(PopArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct PopArgs {
+    #[arg(value_enum)]
+    pub state: Toggle,
+    #[arg(
+        long,
+        help = "With `on`, let POP clients download all mail, not just mail arriving from now on"
+    )]
+    pub all_mail: bool,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum Toggle {
+    On,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum ForwardAction {
+    /// Leave it in the inbox
+    Keep,
+    /// Mark it as read
+    MarkRead,
+    /// Archive it
+    Archive,
+    /// Move it to the trash
+    Trash,
+}
+
+#[derive(Debug, Args)]
+pub struct AliasArgs {
+    #[command(subcommand)]
+    pub command: AliasCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AliasCommand {
+    #[command(visible_alias = "list")]
+    Ls,
+    /// Define an alias, replacing any existing one with the same name
+    Add(AliasAddArgs),
+    /// Remove an alias
+    Rm(AliasRmArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct AliasAddArgs {
+    #[arg(help = "Alias name, e.g. `team`")]
+    pub name: String,
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Addresses (`Ada <ada@example.com>`) or other alias names"
+    )]
+    pub addresses: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct AliasRmArgs {
+    #[arg(help = "Alias name")]
+    pub name: String,
+}
+
+#[derive(Debug, Args)]
+pub struct LabelArgs {
+    #[command(subcommand)]
+    pub command: LabelCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LabelCommand {
+    Ls,
+    Add(LabelMutateArgs),
+    Rm(LabelMutateArgs),
+    /// Create labels; `Parent/Child` nests, creating missing parents too
+    Create(LabelCreateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct LabelCreateArgs {
+    #[arg(required = true, num_args = 1.., help = "Label names, `/` separating nested levels")]
+    pub names: Vec<String>,
+    #[arg(
+        long,
+        value_enum,
+        help = "Whether the label shows in Gmail's label list"
+    )]
+    pub list_visibility: Option<LabelListVisibility>,
+    #[arg(
+        long,
+        value_enum,
+        help = "Whether the label shows on messages in the message list"
+    )]
+    pub message_visibility: Option<MessageListVisibility>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum LabelListVisibility {
+    /// Always show it
+    Show,
+    /// Show it while it has unread mail
+    ShowIfUnread,
+    /// Hide it
+    Hide,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum MessageListVisibility {
+    /// Show it on messages
+    Show,
+    /// Hide it on messages
+    Hide,
+}
+
+#[derive(Debug, Args)]
+pub struct BulkArgs {
+    #[arg(
+        required_unless_present = "q",
+        conflicts_with = "q",
+        num_args = 1..,
+        help = "Gmail message ids, or `-` to read ids from stdin"
+    )]
+    pub ids: Vec<String>,
+    #[arg(long, help = "Act on every message matching this Gmail search instead")]
+    pub q: Option<String>,
+    #[arg(
+        long,
+        default_value_t = 500,
+        help = "Maximum messages to act on with --q"
+    )]
+    pub limit: u32,
+    #[arg(
+        long,
+        conflicts_with_all = ["ids", "limit"],
+        help = "Act on every message matching --q, however many, instead of stopping at --limit"
+    )]
+    pub all: bool,
+    #[arg(
+        long,
+        help = "Take the ids as thread ids, or match threads with --q, and act on every message in each conversation"
+    )]
+    pub thread: bool,
+    #[arg(
+        short,
+        long,
+        help = "Act on --q matches without asking for confirmation (needed when stdin is not a terminal)"
+    )]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct SnoozeArgs {
+    #[command(subcommand)]
+    pub command: Option<SnoozeCommand>,
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Gmail message ids, or `-` to read ids from stdin"
+    )]
+    pub ids: Vec<String>,
+    #[arg(
+        long,
+        required = true,
+        value_name = "WHEN",
+        help = "Local time to return them to the inbox: `tomorrow`, `mon 9am`, `2025-03-04 14:00` (a day alone means 8am)"
+    )]
+    pub until: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SnoozeCommand {
+    /// List snoozed messages, soonest back first
+    #[command(visible_alias = "list")]
+    Ls,
+    /// Return every snoozed message that is due to the inbox, then exit (the daemon does this each tick)
+    Run,
+    /// Return snoozed messages to the inbox now
+    Wake {
+        #[arg(
+            required = true,
+            num_args = 1..,
+            help = "Snoozed message ids, or `-` to read ids from stdin"
+        )]
+        ids: Vec<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct MuteArgs {
+    #[command(subcommand)]
+    pub command: Option<MuteCommand>,
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Gmail thread ids (a message's `thread_id`), or `-` to read ids from stdin"
+    )]
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MuteCommand {
+    /// List muted conversations
+    #[command(visible_alias = "list")]
+    Ls,
+    /// Archive muted conversations that are back in the inbox, then exit (the daemon does this each tick)
+    Run,
+}
+
+#[derive(Debug, Args)]
+pub struct UnmuteArgs {
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Muted thread ids, or `-` to read ids from stdin"
+    )]
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct DeleteArgs {
+    #[arg(
+        required = true,
+        num_args = 1..,
+        help = "Gmail message ids, or `-` to read ids from stdin"
+    )]
+    pub ids: Vec<String>,
+    #[arg(
+        short,
+        long,
+        help = "Delete without asking for confirmation (needed when stdin is not a terminal)"
+    )]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct EmptyArgs {
+    // No `-y`: emptying a folder can't be undone, so skipping the question
+    // takes the spelled-out flag.
+    #[arg(
+        long,
+        help = "Delete without asking for confirmation (needed when stdin is not a terminal)"
+    )]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct LabelMutateArgs {
+    #[arg(
+        required = true,
+        num_args = 1,
+        action = ArgAction::Set,
+        value_delimiter = ',',
+        help = "Gmail message ids, comma-separated, or `-` to read ids from stdin; with --q, the first label"
+    )]
+    pub ids: Vec<String>,
+    #[arg(
+        required_unless_present = "q",
+        num_args = 1..,
+        help = "Labels to mutate"
+    )]
+    pub labels: Vec<String>,
+    #[arg(long, help = "Act on every message matching this Gmail search instead")]
+    pub q: Option<String>,
+    #[arg(
+        long,
+        default_value_t = 500,
+        help = "Maximum messages to act on with --q"
+    )]
+    pub limit: u32,
+    #[arg(
+        long,
+        help = "Take the ids as thread ids, or match threads with --q, and act on every message in each conversation"
+    )]
+    pub thread: bool,
+    #[arg(
+        short,
+        long,
+        help = "Act on --q matches without asking for confirmation (needed when stdin is not a terminal)"
+    )]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[command(subcommand)]
+    pub command: WatchCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WatchCommand {
+    /// Register the mailbox for Cloud Pub/Sub push notifications
+    Start(WatchStartArgs),
+    /// Show the active watch registration and when it expires
+    Status,
+    /// Stop push notifications and forget the registration
+    Stop,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchStartArgs {
+    #[arg(
+        long,
+        help = "Pub/Sub topic (projects/<project>/topics/<topic>) [default: the watch_topic setting]"
+    )]
+    pub topic: Option<String>,
+    #[arg(
+        long = "label",
+        value_delimiter = ',',
+        num_args = 1..,
+        help = "Only notify for changes to these labels (repeatable)"
+    )]
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct DaemonArgs {
+    #[arg(
+        long,
+        default_value_t = 60,
+        help = "Seconds between upkeep passes (watch renewal, new-mail checks)"
+    )]
+    pub interval: u64,
+    #[arg(
+        long,
+        value_name = "QUERY",
+        num_args = 0..=1,
+        default_missing_value = "",
+        help = "Raise desktop notifications for new mail (this query, or the configured channels)"
+    )]
+    pub notify: Option<String>,
+    #[arg(
+        long,
+        help = "POST new-message events to the webhook configured in the profile settings"
+    )]
+    pub webhook: bool,
+    #[arg(
+        long,
+        help = "Run the profile's `on_message` hooks for new matching messages"
+    )]
+    pub hooks: bool,
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = "",
+        help = "Apply local mail rules to new messages (this file, or rules.toml in the config dir)"
+    )]
+    pub rules: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct RulesArgs {
+    #[command(subcommand)]
+    pub command: RulesCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RulesCommand {
+    /// Show which rules a message triggers, without applying them
+    Test {
+        id: String,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Rules file (default: rules.toml in the config dir)"
+        )]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct TuiArgs {
+    #[arg(
+        long,
+        default_value = "in:inbox",
+        help = "Gmail search query to browse"
+    )]
+    pub q: String,
+    #[arg(long, default_value_t = 50, help = "Maximum messages to load")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct ManArgs {
+    /// Directory for gmail.1 and a page per subcommand, set from the global
+    /// `--out`; without it the root page goes to stdout.
+    #[arg(skip)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct StreamArgs {
+    #[arg(
+        long,
+        value_name = "HISTORY_ID",
+        help = "Start after this history id (default: the mailbox's current one)"
+    )]
+    pub since: Option<String>,
+    #[arg(long, default_value_t = 30, help = "Seconds between history polls")]
+    pub interval: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    #[arg(
+        long,
+        value_name = "HISTORY_ID",
+        help = "Report changes after this history id (omit to print the current one)"
+    )]
+    pub since: Option<String>,
+    #[arg(
+        long,
+        requires = "since",
+        help = "Only changes to messages with this label (name or id)"
+    )]
+    pub label: Option<String>,
+    #[arg(
+        long = "type",
+        value_enum,
+        value_delimiter = ',',
+        requires = "since",
+        help = "Only these kinds of change (repeatable)"
+    )]
+    pub types: Vec<HistoryType>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum HistoryType {
+    MessageAdded,
+    MessageDeleted,
+    LabelAdded,
+    LabelRemoved,
+}
+
+#[derive(Debug, Args)]
+pub struct NotifyArgs {
+    #[arg(
+        long,
+        help = "Gmail search query to watch (default: configured channels, else in:inbox)"
+    )]
+    pub q: Option<String>,
+    #[arg(long, default_value_t = 60, help = "Seconds between checks")]
+    pub interval: u64,
 }
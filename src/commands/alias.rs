@@ -0,0 +1,130 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{AliasAddArgs, AliasCommand};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::address;
+use crate::mail::alias::{self, Alias};
+use crate::output::OutputMode;
+
+/// Dispatch a `gmail alias` subcommand (ls/add/rm).
+pub async fn run(ctx: &AppContext, command: AliasCommand) -> AppResult<()> {
+    match command {
+        AliasCommand::Ls => ls(ctx),
+        AliasCommand::Add(args) => add(ctx, args),
+        AliasCommand::Rm(args) => rm(ctx, &args.name),
+    }
+}
+
+/// The alias file in use: the `alias_file` setting, else `aliases` in the
+/// config directory.
+pub(crate) fn file(ctx: &AppContext) -> PathBuf {
+    match ctx.settings.alias_file.as_deref() {
+        Some(path) => match (path.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(path),
+        },
+        None => ctx.paths.aliases_file(),
+    }
+}
+
+/// Expand alias names among `recipients`; the file is only read when some
+/// recipient isn't already an address.
+pub(crate) fn expand(ctx: &AppContext, recipients: Vec<String>) -> AppResult<Vec<String>> {
+    if recipients.iter().all(|recipient| recipient.contains('@')) {
+        return Ok(recipients);
+    }
+    alias::expand(&alias::parse(&read(&file(ctx))?), recipients)
+}
+
+/// List the aliases defined in the alias file.
+fn ls(ctx: &AppContext) -> AppResult<()> {
+    let aliases = alias::parse(&read(&file(ctx))?);
+
+    if ctx.output.mode() == OutputMode::Text {
+        let mut out = ctx.output.paged();
+        if aliases.is_empty() {
+            writeln!(out, "0 aliases in {}", file(ctx).display())?;
+            return out.finish();
+        }
+        for alias in &aliases {
+            writeln!(out, "{}: {}", alias.name, alias.addresses.join(", "))?;
+        }
+        return out.finish();
+    }
+
+    let text = format!("{} aliases", aliases.len());
+    ctx.output.emit(&text, &aliases)
+}
+
+/// Define an alias, replacing an existing definition of the same name.
+fn add(ctx: &AppContext, args: AliasAddArgs) -> AppResult<()> {
+    if args.name.contains('@') || args.name.contains(char::is_whitespace) {
+        return Err(AppError::InvalidInput(format!(
+            "alias name `{}` must be a single word without `@`",
+            args.name
+        )));
+    }
+    let alias = Alias {
+        name: args.name,
+        addresses: args
+            .addresses
+            .iter()
+            .flat_map(|entry| address::split_addresses(entry))
+            .collect(),
+    };
+
+    let path = file(ctx);
+    if ctx.dry_run {
+        let change = PlannedChange::new("alias add", "define alias")
+            .with("name", alias.name.clone())
+            .with("addresses", alias.addresses.join(", "))
+            .with("file", path.display().to_string());
+        return dry_run::emit(ctx, &change);
+    }
+    let text = read(&path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, alias::add(&text, &alias))?;
+
+    let text = format!("added {}: {}", alias.name, alias.addresses.join(", "));
+    ctx.output.emit(&text, &alias)
+}
+
+/// Remove an alias's definitions from the alias file.
+fn rm(ctx: &AppContext, name: &str) -> AppResult<()> {
+    let path = file(ctx);
+    let text = read(&path)?;
+    let Some(removed) = alias::parse(&text)
+        .into_iter()
+        .find(|alias| alias.name.eq_ignore_ascii_case(name))
+    else {
+        return Err(AppError::InvalidInput(format!(
+            "no alias `{name}` in {}",
+            path.display()
+        )));
+    };
+    if ctx.dry_run {
+        let change = PlannedChange::new("alias rm", "remove alias")
+            .with("name", removed.name)
+            .with("file", path.display().to_string());
+        return dry_run::emit(ctx, &change);
+    }
+    fs::write(&path, alias::remove(&text, name).0)?;
+
+    let text = format!("removed alias {}", removed.name);
+    ctx.output.emit(&text, &removed)
+}
+
+/// The alias file's contents; a missing file is empty.
+fn read(path: &Path) -> AppResult<String> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -1,3 +1,5 @@
+use std::io::Write;
+
 use crate::cli::AliasesCommand;
 use crate::context::AppContext;
 use crate::error::AppResult;
@@ -11,14 +13,15 @@ pub async fn run(ctx: &AppContext, command: AliasesCommand) -> AppResult<()> {
 }
 
 /// List the account's send-as aliases with their verification state.
-async fn ls(ctx: &AppContext) -> AppResult<()> {
+pub(crate) async fn ls(ctx: &AppContext) -> AppResult<()> {
     let access_token = ctx.access_token().await?;
     let aliases = ctx.gmail_client.list_send_as(&access_token).await?;
 
     if ctx.output.mode() == OutputMode::Text {
+        let mut out = ctx.output.paged();
         if aliases.is_empty() {
-            println!("0 send-as aliases");
-            return Ok(());
+            writeln!(out, "0 send-as aliases")?;
+            return out.finish();
         }
 
         for (index, alias) in aliases.iter().enumerate() {
@@ -45,10 +48,10 @@ async fn ls(ctx: &AppContext) -> AppResult<()> {
                 format!(" ({})", flags.join(", "))
             };
 
-            println!("{}. {}{}{}", index + 1, alias.email, name, flags);
+            writeln!(out, "{}. {}{}{}", index + 1, alias.email, name, flags)?;
         }
 
-        return Ok(());
+        return out.finish();
     }
 
     let text = format!("{} send-as aliases", aliases.len());
@@ -1,12 +1,17 @@
+use std::collections::HashSet;
 use std::fs;
+use std::io::Write;
 use std::path::Path;
 
-use crate::api::models::{AttachmentMeta, SavedAttachment};
+use crate::api::models::{AttachmentMeta, AttachmentRow, MessageView, SavedAttachment};
 use crate::cli::{AttachmentsCommand, AttachmentsGetArgs, AttachmentsLsArgs};
 use crate::context::AppContext;
 use crate::error::{AppError, AppResult};
 use crate::output::OutputMode;
 
+/// Search used by `ls --q` and `get --all` when none is given.
+const DEFAULT_QUERY: &str = "has:attachment";
+
 /// Dispatch a `gmail attachments` subcommand to its handler.
 pub async fn run(ctx: &AppContext, command: AttachmentsCommand) -> AppResult<()> {
     match command {
@@ -15,63 +20,242 @@ pub async fn run(ctx: &AppContext, command: AttachmentsCommand) -> AppResult<()>
     }
 }
 
-/// List the downloadable attachments on a message without fetching their bytes.
+/// List the downloadable attachments on a message, or with `--q` across every
+/// matching message, without fetching their bytes.
 async fn ls(ctx: &AppContext, args: AttachmentsLsArgs) -> AppResult<()> {
     let access_token = ctx.access_token().await?;
+    let Some(id) = args.id else {
+        let query = args.q.as_deref().unwrap_or(DEFAULT_QUERY);
+        let rows = search(ctx, query, args.limit, &access_token).await?;
+        return emit_rows(ctx, &rows);
+    };
     let list = ctx
         .gmail_client
-        .list_attachments(&args.id, &access_token)
+        .list_attachments(&id, &access_token)
         .await?;
 
     if ctx.output.mode() == OutputMode::Text {
+        let mut out = ctx.output.paged();
         if list.attachments.is_empty() {
-            println!("no attachments on message {}", list.message_id);
-            return Ok(());
+            writeln!(out, "no attachments on message {}", list.message_id)?;
+            return out.finish();
         }
 
         for (index, attachment) in list.attachments.iter().enumerate() {
-            println!("{}. {}", index + 1, describe(attachment));
+            writeln!(out, "{}. {}", index + 1, describe(attachment))?;
         }
 
-        return Ok(());
+        return out.finish();
     }
 
     let text = format!("{} attachments", list.attachments.len());
     ctx.output.emit(&text, &list)
 }
 
-/// Download attachments to `--out`, optionally narrowed by `--index` or `--name`.
+/// Download attachments to `--dir`/`--out`: from one message, optionally
+/// narrowed to one MIME part or by `--index` or `--name`, or with `--all`
+/// from every message matching `--q`.
 async fn get(ctx: &AppContext, args: AttachmentsGetArgs) -> AppResult<()> {
     let access_token = ctx.access_token().await?;
-    let list = ctx
-        .gmail_client
-        .list_attachments(&args.id, &access_token)
-        .await?;
+    let dir = args.dir.clone().unwrap_or_else(|| args.out.clone());
+    let Some(id) = args.id.as_deref() else {
+        return get_all(ctx, &args, &dir, &access_token).await;
+    };
+    let list = ctx.gmail_client.list_attachments(id, &access_token).await?;
 
-    let selected = select(&list.attachments, args.index, args.name.as_deref())?;
+    let selected = select(
+        &list.attachments,
+        args.part.as_deref(),
+        args.index,
+        args.name.as_deref(),
+    )?;
 
-    fs::create_dir_all(&args.out)?;
+    fs::create_dir_all(&dir)?;
 
     let mut saved = Vec::new();
     for attachment in selected {
-        let bytes = ctx
-            .gmail_client
-            .get_attachment(&list.message_id, &attachment.attachment_id, &access_token)
-            .await?;
-
         let file_name = safe_file_name(&attachment.filename)?;
-        let path = args.out.join(file_name);
-        fs::write(&path, &bytes)?;
+        saved.push(
+            save(
+                ctx,
+                &list.message_id,
+                attachment,
+                &dir.join(file_name),
+                &access_token,
+            )
+            .await?,
+        );
+    }
+
+    emit_saved(ctx, &saved)
+}
+
+/// Bulk extraction for `get --all`: every attachment (or every one named
+/// `--name`) of the matching messages, saved through [`save_all`] so names
+/// already in `dir` or repeated in the run are numbered rather than
+/// overwritten. After Ctrl-C the files saved so far are reported.
+async fn get_all(
+    ctx: &AppContext,
+    args: &AttachmentsGetArgs,
+    dir: &Path,
+    access_token: &str,
+) -> AppResult<()> {
+    let query = args.q.as_deref().unwrap_or(DEFAULT_QUERY);
+    let mut messages = matching(ctx, query, args.limit, access_token).await?;
+    if let Some(name) = args.name.as_deref() {
+        for message in &mut messages {
+            message
+                .attachments
+                .retain(|attachment| attachment.filename.eq_ignore_ascii_case(name));
+        }
+    }
+
+    let saved = save_all(ctx, &messages, dir).await?;
+    emit_saved(ctx, &saved)?;
+    ctx.interrupt.check()
+}
+
+/// Save every attachment of `messages` into `dir`, creating it if missing.
+/// Names repeated in the run or already in `dir` are numbered, so nothing is
+/// overwritten (`get --save-attachments`, `attachments get --all`). After Ctrl-C the files saved so
+/// far are returned.
+pub(crate) async fn save_all<'a>(
+    ctx: &AppContext,
+    messages: impl IntoIterator<Item = &'a MessageView>,
+    dir: &Path,
+) -> AppResult<Vec<SavedAttachment>> {
+    fs::create_dir_all(dir)?;
+    let mut taken = HashSet::new();
+    for entry in fs::read_dir(dir)? {
+        if let Some(name) = entry?.file_name().to_str() {
+            taken.insert(name.to_lowercase());
+        }
+    }
+
+    let mut saved = Vec::new();
+    for message in messages {
+        for attachment in &message.attachments {
+            if ctx.interrupt.is_set() {
+                return Ok(saved);
+            }
+            let Ok(file_name) = safe_file_name(&attachment.filename) else {
+                eprintln!(
+                    "skipping attachment on {} with an unusable filename: `{}`",
+                    message.id, attachment.filename
+                );
+                continue;
+            };
+            let path = dir.join(unique_name(&file_name, &mut taken));
+            let access_token = ctx.access_token().await?;
+            saved.push(save(ctx, &message.id, attachment, &path, &access_token).await?);
+        }
+    }
+    Ok(saved)
+}
+
+/// Attachments across the messages matching `query`, newest message first,
+/// read from message metadata.
+async fn search(
+    ctx: &AppContext,
+    query: &str,
+    limit: u32,
+    access_token: &str,
+) -> AppResult<Vec<AttachmentRow>> {
+    Ok(matching(ctx, query, limit, access_token)
+        .await?
+        .into_iter()
+        .flat_map(|message| {
+            message
+                .attachments
+                .into_iter()
+                .map(move |attachment| AttachmentRow {
+                    message_id: message.id.clone(),
+                    date: message.date.clone(),
+                    from: message.from.clone(),
+                    filename: attachment.filename,
+                    mime_type: attachment.mime_type,
+                    size: attachment.size,
+                    part_id: attachment.part_id,
+                    attachment_id: attachment.attachment_id,
+                })
+        })
+        .collect())
+}
+
+/// Up to `limit` messages matching `query`, newest first, with their
+/// attachment metadata.
+async fn matching(
+    ctx: &AppContext,
+    query: &str,
+    limit: u32,
+    access_token: &str,
+) -> AppResult<Vec<MessageView>> {
+    if limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+    let messages = ctx
+        .gmail_client
+        .list(access_token, limit, Some(query))
+        .await?;
+    if messages.len() as u32 == limit {
+        eprintln!("attachments: stopped at --limit {limit} messages; raise it to see the rest");
+    }
+    Ok(messages)
+}
 
-        saved.push(SavedAttachment {
-            filename: attachment.filename.clone(),
-            path: path.display().to_string(),
-            bytes: bytes.len() as u64,
-        });
+fn emit_rows(ctx: &AppContext, rows: &[AttachmentRow]) -> AppResult<()> {
+    if ctx.output.mode() != OutputMode::Text {
+        return ctx.output.emit("", &rows);
     }
 
+    let mut out = ctx.output.paged();
+    if rows.is_empty() {
+        writeln!(out, "no attachments")?;
+    }
+    for row in rows {
+        let size = row
+            .size
+            .map(|size| format!("{size} bytes"))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{} | {} | {} | {} | {}",
+            row.message_id,
+            row.date.as_deref().unwrap_or(""),
+            row.filename,
+            row.mime_type,
+            size
+        )?;
+    }
+    out.finish()
+}
+
+/// Fetch one attachment's bytes and write them to `path`.
+async fn save(
+    ctx: &AppContext,
+    message_id: &str,
+    attachment: &AttachmentMeta,
+    path: &Path,
+    access_token: &str,
+) -> AppResult<SavedAttachment> {
+    let bytes = ctx
+        .gmail_client
+        .get_attachment(message_id, &attachment.attachment_id, access_token)
+        .await?;
+    fs::write(path, &bytes)?;
+
+    Ok(SavedAttachment {
+        filename: attachment.filename.clone(),
+        path: path.display().to_string(),
+        bytes: bytes.len() as u64,
+    })
+}
+
+fn emit_saved(ctx: &AppContext, saved: &[SavedAttachment]) -> AppResult<()> {
     if ctx.output.mode() == OutputMode::Text {
-        for item in &saved {
+        for item in saved {
             println!(
                 "saved {} ({} bytes) -> {}",
                 item.filename, item.bytes, item.path
@@ -84,10 +268,12 @@ async fn get(ctx: &AppContext, args: AttachmentsGetArgs) -> AppResult<()> {
     ctx.output.emit(&text, &saved)
 }
 
-/// Pick which attachments to download: a single 1-based `index`, all filename
-/// matches for `name`, or every attachment when neither filter is supplied.
+/// Pick which attachments to download: the one at MIME `part`, a single
+/// 1-based `index`, all filename matches for `name`, or every attachment when
+/// no filter is supplied.
 fn select<'a>(
     attachments: &'a [AttachmentMeta],
+    part: Option<&str>,
     index: Option<usize>,
     name: Option<&str>,
 ) -> AppResult<Vec<&'a AttachmentMeta>> {
@@ -97,6 +283,23 @@ fn select<'a>(
         ));
     }
 
+    if let Some(part) = part {
+        let Some(attachment) = attachments
+            .iter()
+            .find(|attachment| attachment.part_id.as_deref() == Some(part))
+        else {
+            let known = attachments
+                .iter()
+                .filter_map(|attachment| attachment.part_id.as_deref())
+                .collect::<Vec<_>>();
+            return Err(AppError::InvalidInput(format!(
+                "no attachment at part `{part}`; this message has parts {}",
+                known.join(", ")
+            )));
+        };
+        return Ok(vec![attachment]);
+    }
+
     if let Some(index) = index {
         if index == 0 || index > attachments.len() {
             return Err(AppError::InvalidInput(format!(
@@ -125,13 +328,40 @@ fn select<'a>(
 
 /// Render a single attachment as a one-line summary for text output.
 fn describe(attachment: &AttachmentMeta) -> String {
-    match attachment.size {
+    let mut line = match attachment.size {
         Some(size) => format!(
             "{} | {} | {} bytes",
             attachment.filename, attachment.mime_type, size
         ),
         None => format!("{} | {}", attachment.filename, attachment.mime_type),
+    };
+    if let Some(part) = &attachment.part_id {
+        line.push_str(&format!(" | part {part}"));
+    }
+    line
+}
+
+/// `name`, or `name-2`, `name-3`, ... (before the extension) if it was
+/// already used in this run.
+fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let path = Path::new(name);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(name);
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| format!(".{extension}"))
+        .unwrap_or_default();
+
+    let mut candidate = name.to_string();
+    let mut counter = 1;
+    while !taken.insert(candidate.to_lowercase()) {
+        counter += 1;
+        candidate = format!("{stem}-{counter}{extension}");
     }
+    candidate
 }
 
 /// Strip any directory components so a crafted `filename` can't write outside `--out`.
@@ -145,3 +375,36 @@ fn safe_file_name(filename: &str) -> AppResult<String> {
             AppError::InvalidInput(format!("attachment has an unusable filename: `{filename}`"))
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_repeated_filenames() {
+        let mut taken = HashSet::new();
+        assert_eq!(unique_name("invoice.pdf", &mut taken), "invoice.pdf");
+        assert_eq!(unique_name("Invoice.pdf", &mut taken), "Invoice-2.pdf");
+        assert_eq!(unique_name("invoice.pdf", &mut taken), "invoice-3.pdf");
+        assert_eq!(unique_name("README", &mut taken), "README");
+        assert_eq!(unique_name("README", &mut taken), "README-2");
+    }
+
+    #[test]
+    fn selects_attachments_by_part_id() {
+        let attachment = |part: &str, filename: &str| AttachmentMeta {
+            part_id: Some(part.to_string()),
+            attachment_id: format!("att-{part}"),
+            filename: filename.to_string(),
+            mime_type: "application/pdf".to_string(),
+            size: None,
+        };
+        let attachments = [attachment("1", "a.pdf"), attachment("2.1", "b.pdf")];
+        let selected = select(&attachments, Some("2.1"), None, None).unwrap();
+        assert_eq!(selected[0].filename, "b.pdf");
+
+        let err = select(&attachments, Some("3"), None, None).unwrap_err();
+        assert!(err.to_string().contains("parts 1, 2.1"), "{err}");
+        assert_eq!(select(&attachments, None, None, None).unwrap().len(), 2);
+    }
+}
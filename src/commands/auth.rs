@@ -1,10 +1,9 @@
 use std::io::{self, IsTerminal, Write};
 
-use crate::auth::AuthService;
 use crate::cli::AuthCommand;
-use crate::config::{self, Settings};
+use crate::config::{self, SecretSetting, Settings};
 use crate::context::AppContext;
-use crate::error::{AppError, AppResult};
+use crate::error::{ApiError, AppError, AppResult};
 
 /// Dispatch a `gmail auth` subcommand (login/status/logout) and emit its result.
 pub async fn run(ctx: &AppContext, command: AuthCommand) -> AppResult<()> {
@@ -12,11 +11,11 @@ pub async fn run(ctx: &AppContext, command: AuthCommand) -> AppResult<()> {
         AuthCommand::Login => {
             let profile = ctx.profile()?;
             let settings = ensure_login_settings(ctx)?;
-            let result = match AuthService::login(profile, &settings, &ctx.token_store).await {
+            let result = match ctx.auth.login(profile, &settings, &ctx.token_store).await {
                 Ok(result) => result,
-                Err(AppError::Auth(message)) if missing_client_secret_error(&message) => {
-                    let settings = prompt_for_missing_client_secret(ctx, &settings, &message)?;
-                    AuthService::login(profile, &settings, &ctx.token_store).await?
+                Err(AppError::Auth(err)) if missing_client_secret_error(&err.message) => {
+                    let settings = prompt_for_missing_client_secret(ctx, &settings, &err)?;
+                    ctx.auth.login(profile, &settings, &ctx.token_store).await?
                 }
                 Err(err) => return Err(err),
             };
@@ -29,7 +28,7 @@ pub async fn run(ctx: &AppContext, command: AuthCommand) -> AppResult<()> {
             ctx.output.emit(&text, &result)
         }
         AuthCommand::Status => {
-            let status = AuthService::status(ctx.profile()?, &ctx.token_store).await?;
+            let status = ctx.auth.status(ctx.profile()?, &ctx.token_store).await?;
             let text = if status.logged_in {
                 let refresh_hint = status
                     .has_refresh_token
@@ -58,7 +57,7 @@ pub async fn run(ctx: &AppContext, command: AuthCommand) -> AppResult<()> {
             ctx.output.emit(&text, &status)
         }
         AuthCommand::Logout => {
-            let status = AuthService::logout(ctx.profile()?, &ctx.token_store).await?;
+            let status = ctx.auth.logout(ctx.profile()?, &ctx.token_store).await?;
             let text = format!("{}: logged out", status.profile);
             ctx.output.emit(&text, &status)
         }
@@ -76,9 +75,8 @@ fn ensure_login_settings(ctx: &AppContext) -> AppResult<Settings> {
         .is_none_or(str::is_empty);
     let missing_client_secret = settings
         .client_secret
-        .as_deref()
-        .map(str::trim)
-        .is_none_or(str::is_empty);
+        .as_ref()
+        .is_none_or(SecretSetting::is_blank);
 
     if !missing_client_id && !missing_client_secret {
         return Ok(settings);
@@ -88,7 +86,7 @@ fn ensure_login_settings(ctx: &AppContext) -> AppResult<Settings> {
     if !io::stdin().is_terminal() {
         let missing = format_missing_fields(missing_client_id, missing_client_secret);
         return Err(AppError::Config(format!(
-            "missing oauth {missing} in {}. run `gmail auth login` in an interactive terminal to be prompted, or add the values manually",
+            "missing oauth {missing} in {}. run `gmail auth login` in an interactive terminal to be prompted, add the values manually, or set GMAIL_CLI_CLIENT_ID / GMAIL_CLI_CLIENT_SECRET",
             settings_path.display(),
         )));
     }
@@ -101,7 +99,9 @@ fn ensure_login_settings(ctx: &AppContext) -> AppResult<Settings> {
     }
 
     if missing_client_secret {
-        settings.client_secret = Some(prompt_required("OAuth client_secret: ")?);
+        settings.client_secret = Some(SecretSetting::Literal(prompt_required(
+            "OAuth client_secret: ",
+        )?));
     }
 
     let default_redirect = settings.redirect_uri();
@@ -113,7 +113,16 @@ fn ensure_login_settings(ctx: &AppContext) -> AppResult<Settings> {
         redirect_uri
     });
 
-    config::save_settings(&ctx.paths, profile, &settings)?;
+    // Only persist what was prompted for; values from GMAIL_CLI_* stay out of the file.
+    config::update_settings(&ctx.paths, profile, |saved| {
+        if missing_client_id {
+            saved.client_id = settings.client_id.clone();
+        }
+        if missing_client_secret {
+            saved.client_secret = settings.client_secret.clone();
+        }
+        saved.redirect_uri = settings.redirect_uri.clone();
+    })?;
     println!("Saved profile settings to {}.", settings_path.display());
 
     Ok(settings)
@@ -166,21 +175,20 @@ fn missing_client_secret_error(message: &str) -> bool {
 fn prompt_for_missing_client_secret(
     ctx: &AppContext,
     settings: &Settings,
-    original_error: &str,
+    original_error: &ApiError,
 ) -> AppResult<Settings> {
     if settings
         .client_secret
-        .as_deref()
-        .map(str::trim)
-        .is_some_and(|value| !value.is_empty())
+        .as_ref()
+        .is_some_and(|secret| !secret.is_blank())
     {
-        return Err(AppError::Auth(original_error.to_string()));
+        return Err(AppError::Auth(original_error.clone()));
     }
 
     let profile = ctx.profile()?;
     let settings_path = ctx.paths.settings_file(profile);
     if !io::stdin().is_terminal() {
-        return Err(AppError::Auth(format!(
+        return Err(AppError::auth(format!(
             "{original_error}. add client_secret to {}",
             settings_path.display()
         )));
@@ -190,8 +198,10 @@ fn prompt_for_missing_client_secret(
     let client_secret = prompt_required("OAuth client_secret: ")?;
 
     let mut updated = settings.clone();
-    updated.client_secret = Some(client_secret);
-    config::save_settings(&ctx.paths, profile, &updated)?;
+    updated.client_secret = Some(SecretSetting::Literal(client_secret.clone()));
+    config::update_settings(&ctx.paths, profile, |saved| {
+        saved.client_secret = Some(SecretSetting::Literal(client_secret));
+    })?;
     println!("Updated profile settings at {}.", settings_path.display());
 
     Ok(updated)
@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::CleanupArgs;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::cleanup::{CleanupAction, CleanupRules};
+use crate::output::OutputMode;
+
+/// What one cleanup rule matched and changed.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CleanupOutcome {
+    pub rule: String,
+    pub query: String,
+    pub action: CleanupAction,
+    pub matched: usize,
+    /// Messages changed; 0 on a dry run.
+    pub changed: usize,
+    /// Whether the search stopped at `--limit`, leaving matches for a later run.
+    pub truncated: bool,
+}
+
+/// Run each rule in the file in order: search, then apply its action to the
+/// matches with `messages.batchModify`. `--dry-run` only counts.
+pub async fn run(ctx: &AppContext, args: CleanupArgs) -> AppResult<()> {
+    let dry_run = ctx.dry_run;
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+    let rules = CleanupRules::load(&args.rules)?;
+
+    let mut outcomes = Vec::new();
+    for rule in &rules.rules {
+        ctx.interrupt.check()?;
+        let access_token = ctx.access_token().await?;
+        let query = rule.search()?;
+        let ids = ctx
+            .gmail_client
+            .list_ids(&access_token, args.limit, Some(&query))
+            .await?;
+        let changed = if dry_run || ids.is_empty() {
+            0
+        } else {
+            let (add, rm) = rule.changes()?;
+            ctx.gmail_client
+                .batch_modify(&ids, &add, &rm, &access_token)
+                .await?
+        };
+        let truncated = ids.len() as u32 == args.limit;
+        if truncated {
+            eprintln!(
+                "cleanup: `{}` stopped at --limit {}; run again or raise it for the rest",
+                rule.name(),
+                args.limit
+            );
+        }
+        outcomes.push(CleanupOutcome {
+            rule: rule.name(),
+            query,
+            action: rule.action,
+            matched: ids.len(),
+            changed,
+            truncated,
+        });
+    }
+
+    if ctx.output.mode() != OutputMode::Text {
+        return ctx.output.emit("", &outcomes);
+    }
+    let mut out = ctx.output.paged();
+    for outcome in &outcomes {
+        let verb = outcome.action.verb();
+        if dry_run {
+            writeln!(
+                out,
+                "{}: would {verb} {} messages ({})",
+                outcome.rule, outcome.matched, outcome.query
+            )?;
+        } else {
+            writeln!(
+                out,
+                "{}: {verb} {} messages ({})",
+                outcome.rule, outcome.changed, outcome.query
+            )?;
+        }
+    }
+    out.finish()
+}
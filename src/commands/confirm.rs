@@ -0,0 +1,22 @@
+use std::io::{self, IsTerminal, Write};
+
+use crate::error::{AppError, AppResult};
+
+/// Ask `question` on stderr and read the answer from the terminal; only `y`
+/// or `yes` counts as yes. Without a terminal to ask on it fails, naming
+/// `skip_flag` as the way to go ahead unasked.
+pub(crate) fn ask(question: &str, skip_flag: &str) -> AppResult<bool> {
+    if !io::stdin().is_terminal() {
+        return Err(AppError::InvalidInput(format!(
+            "this asks for confirmation, so it needs an interactive terminal; pass {skip_flag} to skip the question"
+        )));
+    }
+    eprint!("{question} [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
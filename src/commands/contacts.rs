@@ -0,0 +1,117 @@
+use std::fs;
+use std::io::Write;
+
+use crate::api::people;
+use crate::auth::token_store::TokenStore;
+use crate::cli::{ContactsCommand, ContactsExportArgs, ContactsSearchArgs};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::vcard;
+use crate::output::OutputMode;
+
+/// Dispatch a `gmail contacts` subcommand (export/search).
+pub async fn run(ctx: &AppContext, command: ContactsCommand) -> AppResult<()> {
+    match command {
+        ContactsCommand::Export(args) => export(ctx, args).await,
+        ContactsCommand::Search(args) => search(ctx, args).await,
+    }
+}
+
+/// Harvest correspondents from the messages matching `--q` and write them as
+/// vCards, to `--vcf` or stdout. Our own send-as addresses are left out.
+async fn export(ctx: &AppContext, args: ContactsExportArgs) -> AppResult<()> {
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+
+    let access_token = ctx.access_token().await?;
+    let messages = ctx
+        .gmail_client
+        .list(&access_token, args.limit, args.q.as_deref())
+        .await?;
+
+    let mut own = ctx
+        .gmail_client
+        .list_send_as(&access_token)
+        .await?
+        .into_iter()
+        .map(|alias| alias.email.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    if let Some(email) = ctx
+        .token_store
+        .load(ctx.profile()?)?
+        .and_then(|token| token.email)
+    {
+        own.push(email.to_ascii_lowercase());
+    }
+
+    let contacts = vcard::harvest(&messages, &own);
+    let cards = contacts.iter().map(vcard::to_vcard).collect::<String>();
+
+    let Some(path) = &args.vcf else {
+        return ctx.output.emit(cards.trim_end(), &contacts);
+    };
+    fs::write(path, &cards)?;
+    let text = format!(
+        "wrote {} contact(s) from {} message(s) to {}",
+        contacts.len(),
+        messages.len(),
+        path.display()
+    );
+    ctx.output.emit(&text, &contacts)
+}
+
+/// Look `query` up with the People API, first asking for the contacts scopes
+/// in the browser when the stored token lacks them. Text output is one
+/// `Name <email>` per line, ready for `send --to`.
+async fn search(ctx: &AppContext, args: ContactsSearchArgs) -> AppResult<()> {
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+    let query = args.query.join(" ");
+
+    let profile = ctx.profile()?;
+    let granted = ctx
+        .token_store
+        .load(profile)?
+        .is_some_and(|token| token.has_scopes(&people::CONTACTS_SCOPES));
+    if !granted {
+        eprintln!("contacts search needs read access to your Google contacts; asking for it now");
+        ctx.auth
+            .login_with_scopes(
+                profile,
+                &ctx.settings,
+                &ctx.token_store,
+                &people::CONTACTS_SCOPES,
+            )
+            .await?;
+    }
+
+    let access_token = ctx.access_token().await?;
+    let matches = ctx
+        .gmail_client
+        .search_people(&query, args.limit, &access_token)
+        .await?;
+
+    if ctx.output.mode() == OutputMode::Text {
+        let mut out = ctx.output.paged();
+        if matches.is_empty() {
+            writeln!(out, "no contacts match `{query}`")?;
+            return out.finish();
+        }
+        for found in &matches {
+            match &found.organization {
+                Some(organization) => writeln!(out, "{}\t({organization})", found.address)?,
+                None => writeln!(out, "{}", found.address)?,
+            }
+        }
+        return out.finish();
+    }
+
+    let text = format!("{} contact(s)", matches.len());
+    ctx.output.emit(&text, &matches)
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use crate::cli::DaemonArgs;
+use crate::commands::rules::rules_path;
+use crate::context::AppContext;
+use crate::daemon::hooks::HookRunner;
+use crate::daemon::notify::MailNotifier;
+use crate::daemon::rules::RuleRunner;
+use crate::daemon::webhook::WebhookDispatcher;
+use crate::daemon::{self, DaemonOptions};
+use crate::error::{AppError, AppResult};
+use crate::mail::rules::MailRules;
+
+/// Run the long-lived daemon loop in the foreground.
+pub async fn run(ctx: &AppContext, args: DaemonArgs) -> AppResult<()> {
+    // Every tick acts: hook commands, webhook POSTs, queued sends, and the
+    // inbox upkeep. None of it can be previewed, so refuse up front.
+    if ctx.dry_run {
+        return Err(AppError::InvalidInput(
+            "--dry-run: the daemon runs hooks, webhooks, and queued sends and cannot preview them"
+                .to_string(),
+        ));
+    }
+    if args.interval == 0 {
+        return Err(AppError::InvalidInput(
+            "--interval must be greater than 0".to_string(),
+        ));
+    }
+
+    let webhook = if args.webhook {
+        let settings = ctx.settings.webhook.as_ref().ok_or_else(|| {
+            AppError::Config(
+                "--webhook needs a `webhook` section (url, secret, queries) in the profile settings"
+                    .to_string(),
+            )
+        })?;
+        Some(WebhookDispatcher::from_settings(
+            settings,
+            ctx.http.clone(),
+        )?)
+    } else {
+        None
+    };
+
+    let hooks = if args.hooks {
+        if ctx.settings.on_message.is_empty() {
+            return Err(AppError::Config(
+                "--hooks needs at least one `on_message` entry in the profile settings".to_string(),
+            ));
+        }
+        Some(HookRunner::new(&ctx.settings.on_message)?)
+    } else {
+        None
+    };
+
+    let rules = match args.rules {
+        Some(path) => Some(RuleRunner::new(MailRules::load(&rules_path(ctx, &path))?)),
+        None => None,
+    };
+
+    let notify = match args.notify {
+        Some(query) => Some(MailNotifier::new(
+            Some(query),
+            ctx.settings.notify.as_ref(),
+        )?),
+        None => None,
+    };
+
+    let options = DaemonOptions {
+        notify,
+        webhook,
+        hooks,
+        rules,
+    };
+    daemon::run(ctx, Duration::from_secs(args.interval), options).await
+}
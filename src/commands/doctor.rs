@@ -0,0 +1,432 @@
+use std::fs;
+use std::net::{IpAddr, TcpListener};
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use url::Url;
+
+use crate::auth::token_store::TokenStore;
+use crate::config::{self, settings};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::output::text::Role;
+
+/// Outcome of a single diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+    /// Not run because an earlier check it depends on failed.
+    Skip,
+}
+
+/// One line of the doctor report, with a hint on how to fix anything not passing.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skip(name: &'static str, reason: &str) -> Self {
+        Self {
+            name,
+            status: Status::Skip,
+            detail: format!("skipped: {reason}"),
+            hint: None,
+        }
+    }
+}
+
+/// The full doctor report.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DoctorReport {
+    pub ok: bool,
+    pub checks: Vec<Check>,
+}
+
+/// Run every diagnostic in order and print a pass/fail report; exits non-zero
+/// when any check fails.
+pub async fn run(ctx: &AppContext) -> AppResult<()> {
+    let checks = collect(ctx).await;
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.status == Status::Fail)
+        .count();
+    let warned = checks
+        .iter()
+        .filter(|check| check.status == Status::Warn)
+        .count();
+    let passed = checks
+        .iter()
+        .filter(|check| check.status == Status::Pass)
+        .count();
+
+    let mut lines = checks
+        .iter()
+        .map(|check| render(ctx, check))
+        .collect::<Vec<_>>();
+    lines.push(String::new());
+    lines.push(format!(
+        "{passed} passed, {warned} warnings, {failed} failed"
+    ));
+
+    ctx.output.emit(
+        &lines.join("\n"),
+        &DoctorReport {
+            ok: failed == 0,
+            checks,
+        },
+    )?;
+
+    if failed > 0 {
+        return Err(AppError::Config(format!("{failed} doctor check(s) failed")));
+    }
+    Ok(())
+}
+
+async fn collect(ctx: &AppContext) -> Vec<Check> {
+    let mut checks = vec![
+        check_dir("config dir", ctx.paths.config_dir()),
+        check_dir("data dir", ctx.paths.data_dir()),
+        check_app_config(ctx),
+    ];
+    match ctx.paths.list_profiles() {
+        Ok(profiles) if profiles.is_empty() => checks.push(Check::warn(
+            "settings file",
+            "no profile files found",
+            "run `gmail auth login` to create one, or configure via GMAIL_CLI_* variables",
+        )),
+        Ok(profiles) => checks.extend(
+            profiles
+                .iter()
+                .map(|name| check_settings_file(&ctx.paths.settings_file(name))),
+        ),
+        Err(err) => checks.push(Check::fail(
+            "settings file",
+            err.to_string(),
+            "fix the profiles directory's permissions",
+        )),
+    }
+
+    let profile = match ctx.profile() {
+        Ok(profile) => {
+            checks.push(Check::pass("profile", profile));
+            profile
+        }
+        Err(err) => {
+            checks.push(Check::fail(
+                "profile",
+                err.to_string(),
+                "pass --profile <name>, run `gmail profile use <name>`, or fix the file reported above",
+            ));
+            return with_skipped(checks, "no usable profile");
+        }
+    };
+
+    let client_check = match (&ctx.settings.client_id, ctx.settings.client_secret()) {
+        (None, _) => Check::fail(
+            "oauth client",
+            "client_id is missing",
+            "run `gmail auth login` to be prompted, or set GMAIL_CLI_CLIENT_ID",
+        ),
+        (Some(_), Err(err)) => Check::fail(
+            "oauth client",
+            format!("client_secret did not resolve: {err}"),
+            "check the client_secret command or environment variable",
+        ),
+        (Some(_), Ok(secret)) => Check::pass(
+            "oauth client",
+            if secret.is_some() {
+                "client_id and client_secret configured"
+            } else {
+                "client_id configured (no client_secret)"
+            },
+        ),
+    };
+    let client_ok = client_check.status == Status::Pass;
+    checks.push(client_check);
+    checks.push(check_redirect_uri(&ctx.settings.redirect_uri()));
+
+    let token = match ctx.token_store.load(profile) {
+        Ok(Some(token)) => {
+            checks.push(Check::pass(
+                "token",
+                token.email.as_deref().unwrap_or("stored").to_string(),
+            ));
+            token
+        }
+        Ok(None) => {
+            checks.push(Check::fail(
+                "token",
+                "no stored token",
+                "run `gmail auth login`",
+            ));
+            return with_skipped(checks, "not logged in");
+        }
+        Err(err) => {
+            checks.push(Check::fail(
+                "token",
+                err.to_string(),
+                format!(
+                    "remove {} and run `gmail auth login`",
+                    ctx.token_store.location(profile)
+                ),
+            ));
+            return with_skipped(checks, "token unreadable");
+        }
+    };
+
+    let refreshed = if !token.has_refresh_token() {
+        checks.push(Check::fail(
+            "token refresh",
+            "no refresh token stored",
+            "run `gmail auth logout` then `gmail auth login` to grant offline access",
+        ));
+        None
+    } else if !client_ok {
+        checks.push(Check::skip("token refresh", "oauth client is missing"));
+        None
+    } else {
+        match ctx
+            .auth
+            .force_refresh(profile, &ctx.settings, &ctx.token_store)
+            .await
+        {
+            Ok(token) => {
+                checks.push(Check::pass("token refresh", "refresh token accepted"));
+                Some(token)
+            }
+            Err(err) => {
+                checks.push(Check::fail(
+                    "token refresh",
+                    err.to_string(),
+                    "the grant may be revoked or expired; run `gmail auth login`",
+                ));
+                None
+            }
+        }
+    };
+
+    let access_token = match refreshed {
+        Some(token) => token.access_token,
+        None if !token.is_expired(std::time::SystemTime::now()) => token.access_token,
+        None => {
+            checks.push(Check::skip("gmail api", "no valid access token"));
+            return checks;
+        }
+    };
+    checks.push(match ctx.gmail_client.get_profile(&access_token).await {
+        Ok(mailbox) => Check::pass(
+            "gmail api",
+            format!(
+                "reached {} ({} messages)",
+                mailbox.email, mailbox.messages_total
+            ),
+        ),
+        Err(err) => Check::fail(
+            "gmail api",
+            err.to_string(),
+            "check network access and that the Gmail API is enabled for the OAuth client's project",
+        ),
+    });
+
+    checks
+}
+
+/// Mark the checks that could not run after an early failure.
+fn with_skipped(mut checks: Vec<Check>, reason: &str) -> Vec<Check> {
+    const LATER: [&str; 5] = [
+        "oauth client",
+        "redirect uri",
+        "token",
+        "token refresh",
+        "gmail api",
+    ];
+    let done = checks.iter().map(|check| check.name).collect::<Vec<_>>();
+    let skipped = LATER
+        .into_iter()
+        .filter(|name| !done.contains(name))
+        .map(|name| Check::skip(name, reason))
+        .collect::<Vec<_>>();
+    checks.extend(skipped);
+    checks
+}
+
+fn render(ctx: &AppContext, check: &Check) -> String {
+    let tag = match check.status {
+        Status::Pass => "ok  ".to_string(),
+        Status::Warn => "warn".to_string(),
+        Status::Fail => ctx.output.paint(Role::Error, "FAIL"),
+        Status::Skip => "skip".to_string(),
+    };
+    let mut line = format!("{tag}  {:<14} {}", check.name, check.detail);
+    if let Some(hint) = &check.hint {
+        line.push_str(&format!("\n      {:<14} hint: {hint}", ""));
+    }
+    line
+}
+
+/// A directory must exist and accept new files.
+fn check_dir(name: &'static str, dir: &Path) -> Check {
+    let probe = dir.join(format!(".doctor-{}", std::process::id()));
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            Check::pass(name, dir.display().to_string())
+        }
+        Err(err) => Check::fail(
+            name,
+            format!("{}: {err}", dir.display()),
+            "fix the directory's ownership and permissions",
+        ),
+    }
+}
+
+fn check_app_config(ctx: &AppContext) -> Check {
+    let path = ctx.paths.config_file();
+    if !path.exists() {
+        return Check::pass("app config", format!("{} (not present)", path.display()));
+    }
+    match config::load_app_config(path.clone()) {
+        Ok(_) => Check::pass("app config", path.display().to_string()),
+        Err(err) => Check::fail(
+            "app config",
+            format!("{}: {err}", path.display()),
+            "fix or remove the file; it only holds the default profile and shared defaults",
+        ),
+    }
+}
+
+/// A profile file must parse, and should not be readable by other users
+/// since it can hold the OAuth client secret.
+fn check_settings_file(path: &Path) -> Check {
+    const NAME: &str = "settings file";
+    if let Err(err) = settings::read(path.to_path_buf()) {
+        return Check::fail(
+            NAME,
+            format!("{}: {err}", path.display()),
+            "fix the JSON syntax or field types in the file",
+        );
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if let Ok(metadata) = fs::metadata(path) {
+            let mode = metadata.permissions().mode() & 0o777;
+            if mode & 0o077 != 0 {
+                return Check::warn(
+                    NAME,
+                    format!("{} is mode {mode:o}", path.display()),
+                    format!("run `chmod 600 {}`", path.display()),
+                );
+            }
+        }
+    }
+
+    Check::pass(NAME, path.display().to_string())
+}
+
+/// The redirect URI must be plain-http loopback, and its port free to bind,
+/// for `auth login` to capture the OAuth callback.
+pub fn check_redirect_uri(uri: &str) -> Check {
+    const NAME: &str = "redirect uri";
+    let fix = "set redirect_uri to e.g. http://127.0.0.1:8787/callback";
+
+    let url = match Url::parse(uri) {
+        Ok(url) => url,
+        Err(err) => return Check::fail(NAME, format!("{uri}: {err}"), fix),
+    };
+    if url.scheme() != "http" {
+        return Check::fail(NAME, format!("{uri} does not use http"), fix);
+    }
+    let Some(host) = url.host_str() else {
+        return Check::fail(NAME, format!("{uri} has no host"), fix);
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let loopback = host == "localhost"
+        || host
+            .parse::<IpAddr>()
+            .is_ok_and(|address| address.is_loopback());
+    if !loopback {
+        return Check::fail(NAME, format!("{uri} is not a loopback address"), fix);
+    }
+    let Some(port) = url.port_or_known_default() else {
+        return Check::fail(NAME, format!("{uri} has no port"), fix);
+    };
+
+    match TcpListener::bind((host, port)) {
+        Ok(_) => Check::pass(NAME, format!("{uri} (port {port} free)")),
+        Err(err) => Check::fail(
+            NAME,
+            format!("cannot bind {host}:{port}: {err}"),
+            "stop whatever holds the port or pick another port in redirect_uri",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_non_loopback_and_non_http_redirects() {
+        assert_eq!(
+            check_redirect_uri("https://127.0.0.1:8787/callback").status,
+            Status::Fail
+        );
+        assert_eq!(
+            check_redirect_uri("http://example.com:8787/callback").status,
+            Status::Fail
+        );
+        assert_eq!(check_redirect_uri("not a url").status, Status::Fail);
+    }
+
+    #[test]
+    fn accepts_free_loopback_port_and_flags_busy_one() {
+        let held = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = held.local_addr().unwrap().port();
+        let busy = check_redirect_uri(&format!("http://127.0.0.1:{port}/callback"));
+        assert_eq!(busy.status, Status::Fail);
+
+        drop(held);
+        let free = check_redirect_uri(&format!("http://127.0.0.1:{port}/callback"));
+        assert_eq!(free.status, Status::Pass);
+    }
+}
@@ -0,0 +1,233 @@
+use std::io::Write;
+
+use crate::api::models::{DraftView, SendRequest};
+use crate::cli::{DraftCommand, DraftEditArgs, DraftLsArgs, DraftRmArgs, SendArgs};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::list::{fit, format_preview};
+use crate::commands::{get, ids, send, triage};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+use crate::mail::{address, mime};
+use crate::output::OutputMode;
+use crate::output::text::Role;
+
+/// Dispatch a `gmail draft` subcommand.
+pub async fn run(ctx: &AppContext, command: DraftCommand) -> AppResult<()> {
+    match command {
+        DraftCommand::Create(args) => create(ctx, *args).await,
+        DraftCommand::Ls(args) => {
+            ls(ctx, args).await?;
+            ctx.interrupt.check()
+        }
+        DraftCommand::Get(args) => {
+            let access_token = ctx.access_token().await?;
+            let draft = ctx
+                .gmail_client
+                .get_draft(&args.id, true, &access_token)
+                .await?;
+            if ctx.output.mode() != OutputMode::Text {
+                return ctx.output.emit("", &draft);
+            }
+            let mut out = ctx.output.paged();
+            writeln!(out, "{}: {}", i18n::text("field-draft"), draft.id)?;
+            get::render(ctx, &mut out, &draft.message)?;
+            out.finish()
+        }
+        DraftCommand::Edit(args) => edit(ctx, *args).await,
+        DraftCommand::Send(args) => {
+            if ctx.dry_run {
+                let change = PlannedChange::new("draft send", "send draft").with_ids(vec![args.id]);
+                return dry_run::emit(ctx, &change);
+            }
+            let access_token = ctx.access_token().await?;
+            let result = ctx.gmail_client.send_draft(&args.id, &access_token).await?;
+            let text = i18n::format("sent-message", &[("id", result.id.as_str().into())]);
+            ctx.output.emit(&text, &result)
+        }
+        DraftCommand::Rm(args) => rm(ctx, args).await,
+    }
+}
+
+/// Build a message exactly as `send` would and save it as a new draft.
+async fn create(ctx: &AppContext, args: SendArgs) -> AppResult<()> {
+    send_only_options(&args)?;
+    let access_token = ctx.access_token().await?;
+    let request = send::build_send_request(ctx, &access_token, args).await?;
+    save(ctx, "draft create", None, &request, &access_token).await
+}
+
+/// Rebuild a draft from the args, filling whatever they leave out (recipients,
+/// subject, body, threading) from the draft as it stands.
+async fn edit(ctx: &AppContext, args: DraftEditArgs) -> AppResult<()> {
+    send_only_options(&args.message)?;
+    let access_token = ctx.access_token().await?;
+    let current = ctx
+        .gmail_client
+        .get_draft(&args.id, true, &access_token)
+        .await?
+        .message;
+
+    let mut message = args.message;
+    let split = |header: &Option<String>| {
+        header
+            .as_deref()
+            .map(address::split_addresses)
+            .unwrap_or_default()
+    };
+    if message.to.is_empty() {
+        message.to = split(&current.to);
+    }
+    if message.cc.is_empty() {
+        message.cc = split(&current.cc);
+    }
+    if message.subject.is_none() && message.reply.is_none() {
+        message.subject = current.subject.clone();
+    }
+    let has_body = message.body.is_some()
+        || message.body_file.is_some()
+        || message.draft_file.is_some()
+        || message.stdin;
+    if !has_body {
+        // The stored body already carries any signature.
+        message.body = Some(current.body.clone().unwrap_or_default());
+        message.no_signature = true;
+    }
+
+    let mut request = send::build_send_request(ctx, &access_token, message).await?;
+    if request.thread_id.is_none() {
+        request.thread_id = current.thread_id;
+        request.in_reply_to = current.in_reply_to;
+        request.references = current.references;
+    }
+    save(ctx, "draft edit", Some(&args.id), &request, &access_token).await
+}
+
+/// Reject the `send` options that make no sense for a draft.
+fn send_only_options(args: &SendArgs) -> AppResult<()> {
+    if args.preview {
+        return Err(AppError::InvalidInput(
+            "--preview is for `gmail send`; open the draft in Gmail to review it".to_string(),
+        ));
+    }
+    if args.at.is_some() || args.in_.is_some() {
+        return Err(AppError::InvalidInput(
+            "--at and --in are for `gmail send`; send the draft with `gmail draft send`"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Encode `request` and store it: as a new draft, or over draft `id`
+/// (`draft create|edit`, `send --save-draft`).
+pub(crate) async fn save(
+    ctx: &AppContext,
+    command: &str,
+    id: Option<&str>,
+    request: &SendRequest,
+    access_token: &str,
+) -> AppResult<()> {
+    let raw = mime::build_raw_message(request);
+    if ctx.dry_run {
+        let mut change = PlannedChange::send(command, request, &raw);
+        change.action = "save draft".to_string();
+        if let Some(id) = id {
+            change.action = "update draft".to_string();
+            change = change.with_ids(vec![id.to_string()]);
+        }
+        return dry_run::emit(ctx, &change);
+    }
+
+    let thread_id = request.thread_id.as_deref();
+    let result = match id {
+        Some(id) => {
+            ctx.gmail_client
+                .update_draft(id, &raw, thread_id, access_token)
+                .await?
+        }
+        None => {
+            ctx.gmail_client
+                .create_draft(&raw, thread_id, access_token)
+                .await?
+        }
+    };
+    let text = i18n::format("saved-draft", &[("id", result.id.as_str().into())]);
+    ctx.output.emit(&text, &result)
+}
+
+async fn ls(ctx: &AppContext, args: DraftLsArgs) -> AppResult<()> {
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+
+    let access_token = ctx.access_token().await?;
+    let drafts = ctx
+        .gmail_client
+        .list_drafts(&access_token, args.limit, args.q.as_deref())
+        .await?;
+    if ctx.output.mode() == OutputMode::Text {
+        return write_drafts(ctx, &drafts);
+    }
+    let text = i18n::format("drafts-count", &[("count", drafts.len().into())]);
+    ctx.output.emit(&text, &drafts)
+}
+
+/// Delete each draft, reporting every id.
+async fn rm(ctx: &AppContext, args: DraftRmArgs) -> AppResult<()> {
+    let ids = ids::expand(args.ids)?;
+    if ctx.dry_run {
+        let change = PlannedChange::new("draft rm", "delete drafts").with_ids(ids);
+        return dry_run::emit(ctx, &change);
+    }
+
+    let outcomes = triage::for_each_id(ctx, ids, |id, token| async move {
+        ctx.gmail_client.delete_draft(&id, &token).await
+    })
+    .await?;
+    triage::report(ctx, "delete draft", "deleted", &outcomes)
+}
+
+/// Print drafts as numbered entries: draft id, recipients, subject, and a
+/// wrapped preview.
+fn write_drafts(ctx: &AppContext, drafts: &[DraftView]) -> AppResult<()> {
+    let mut out = ctx.output.paged();
+    if drafts.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            i18n::format("drafts-count", &[("count", 0.into())])
+        )?;
+        return out.finish();
+    }
+
+    let field = |id: &str| format!("   {}: ", i18n::text(id));
+    let (to_field, subject_field) = (field("field-to"), field("field-subject"));
+    let no_subject = i18n::text("no-subject");
+    let width = ctx.output.width();
+    for (index, draft) in drafts.iter().enumerate() {
+        let message = &draft.message;
+        let to = fit(message.to.as_deref().unwrap_or(""), width, &to_field);
+        let subject = fit(
+            message.subject.as_deref().unwrap_or(&no_subject),
+            width,
+            &subject_field,
+        );
+
+        writeln!(out, "{}. {}", index + 1, draft.id)?;
+        writeln!(out, "{to_field}{}", ctx.output.paint(Role::Sender, &to))?;
+        writeln!(out, "{subject_field}{subject}")?;
+        writeln!(out)?;
+        for line in format_preview(message.snippet.as_deref(), width) {
+            writeln!(out, "   {line}")?;
+        }
+
+        if index + 1 < drafts.len() {
+            writeln!(out)?;
+        }
+    }
+
+    out.finish()
+}
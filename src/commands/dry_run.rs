@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::SendRequest;
+use crate::context::AppContext;
+use crate::error::AppResult;
+use crate::mail::rfc822;
+use crate::mail::usage::human_bytes;
+
+/// A change `--dry-run` resolved but did not make.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PlannedChange {
+    /// Always `true`, so scripts can tell a preview from a result.
+    pub dry_run: bool,
+    /// The command whose write was skipped (`label add`, `send`, ...).
+    pub command: String,
+    /// What it would have done, e.g. `add labels`.
+    pub action: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub add_labels: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remove_labels: Vec<String>,
+    /// Summary of the request body: recipients, subject, setting values.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub payload: BTreeMap<String, String>,
+}
+
+impl PlannedChange {
+    pub fn new(command: &str, action: impl Into<String>) -> Self {
+        Self {
+            dry_run: true,
+            command: command.to_string(),
+            action: action.into(),
+            ids: Vec::new(),
+            add_labels: Vec::new(),
+            remove_labels: Vec::new(),
+            payload: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_ids(mut self, ids: Vec<String>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    pub fn with_labels(mut self, add: &[String], remove: &[String]) -> Self {
+        self.add_labels = add.to_vec();
+        self.remove_labels = remove.to_vec();
+        self
+    }
+
+    /// Add a payload field; blank values are left out.
+    pub fn with(mut self, key: &str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        if !value.trim().is_empty() {
+            self.payload.insert(key.to_string(), value);
+        }
+        self
+    }
+
+    /// A message `send` would submit.
+    pub fn send(command: &str, request: &SendRequest, raw: &str) -> Self {
+        let attachments = request
+            .attachments
+            .iter()
+            .map(|attachment| attachment.filename.as_str())
+            .collect::<Vec<_>>();
+        Self::new(command, "send message")
+            .with("from", request.from.clone().unwrap_or_default())
+            .with("to", request.to.join(", "))
+            .with("cc", request.cc.join(", "))
+            .with("bcc", request.bcc.join(", "))
+            .with("subject", request.subject.clone())
+            .with(
+                "in_reply_to",
+                request.in_reply_to.clone().unwrap_or_default(),
+            )
+            .with("thread_id", request.thread_id.clone().unwrap_or_default())
+            .with("attachments", attachments.join(", "))
+            .with("size", human_bytes(raw_size(raw)))
+    }
+
+    /// A finished RFC 822 message submitted as is (`sendmail`, `send-patch`),
+    /// summarized from its headers as written.
+    pub fn raw_message(command: &str, message: &[u8]) -> Self {
+        let (headers, _) = rfc822::headers(message);
+        let header = |name: &str| rfc822::values(&headers, name).join(", ");
+        Self::new(command, "send message")
+            .with("from", header("From"))
+            .with("to", header("To"))
+            .with("cc", header("Cc"))
+            .with("bcc", header("Bcc"))
+            .with("subject", header("Subject"))
+            .with("in_reply_to", header("In-Reply-To"))
+            .with("size", human_bytes(message.len() as u64))
+    }
+
+    /// `dry run: label add would add labels`, then one indented line per detail.
+    pub fn describe(&self) -> String {
+        let mut lines = vec![format!("dry run: {} would {}", self.command, self.action)];
+        if !self.ids.is_empty() {
+            lines.push(format!("  ids: {}", self.ids.join(", ")));
+        }
+        if !self.add_labels.is_empty() {
+            lines.push(format!("  add labels: {}", self.add_labels.join(", ")));
+        }
+        if !self.remove_labels.is_empty() {
+            lines.push(format!(
+                "  remove labels: {}",
+                self.remove_labels.join(", ")
+            ));
+        }
+        lines.extend(
+            self.payload
+                .iter()
+                .map(|(key, value)| format!("  {}: {value}", key.replace('_', " "))),
+        );
+        lines.join("\n")
+    }
+}
+
+/// Print one planned change.
+pub fn emit(ctx: &AppContext, change: &PlannedChange) -> AppResult<()> {
+    ctx.output.emit(&change.describe(), change)
+}
+
+/// Print planned changes as an array, for commands that make several.
+pub fn emit_all(ctx: &AppContext, changes: &[PlannedChange]) -> AppResult<()> {
+    let text = changes
+        .iter()
+        .map(PlannedChange::describe)
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.output.emit(&text, &changes)
+}
+
+/// Decoded size of a base64url raw message.
+fn raw_size(raw: &str) -> u64 {
+    (raw.trim_end_matches('=').len() * 3 / 4) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_raw_messages_and_skips_blank_fields() {
+        let message = b"From: a@example.com\r\nTo: b@example.com,\r\n c@example.com\r\nSubject: Hi\r\n\r\nbody\r\n";
+        let change = PlannedChange::raw_message("sendmail", message);
+        assert_eq!(change.payload["subject"], "Hi");
+        assert!(change.payload["to"].contains("c@example.com"));
+        assert!(!change.payload.contains_key("cc"));
+        assert!(!change.payload.contains_key("thread_id"));
+
+        let text = PlannedChange::new("label add", "add labels")
+            .with_ids(vec!["m1".to_string()])
+            .with_labels(&["Receipts".to_string()], &[])
+            .describe();
+        assert_eq!(
+            text,
+            "dry run: label add would add labels\n  ids: m1\n  add labels: Receipts"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::ExportArgs;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::mbox;
+
+/// What `export` wrote.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ExportSummary {
+    /// The directory of `.eml` files or the mbox file.
+    pub path: String,
+    /// Messages written by this run.
+    pub exported: usize,
+    /// Matches already exported by an earlier run.
+    pub skipped: usize,
+}
+
+/// Where exported messages go.
+enum Target {
+    /// One `<id>.eml` per message; the files present mark what is done.
+    Dir(PathBuf),
+    /// Entries appended to an mbox, with the exported ids in a `.ids` file
+    /// beside it.
+    Mbox { file: PathBuf, ids: PathBuf },
+}
+
+/// Write every message matching `--q` as raw RFC 822, to a directory of
+/// `.eml` files or one mbox. Messages an earlier run already wrote are
+/// skipped, so an interrupted export picks up where it stopped.
+pub async fn run(ctx: &AppContext, args: ExportArgs) -> AppResult<()> {
+    let target = match (args.dir, args.mbox) {
+        (Some(dir), None) => Target::Dir(dir),
+        (None, Some(file)) => {
+            let mut ids = file.clone().into_os_string();
+            ids.push(".ids");
+            Target::Mbox {
+                file,
+                ids: ids.into(),
+            }
+        }
+        _ => {
+            return Err(AppError::InvalidInput(
+                "pass one of --dir or --mbox".to_string(),
+            ));
+        }
+    };
+    if args.limit == Some(0) {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+
+    let access_token = ctx.access_token().await?;
+    let ids = ctx
+        .gmail_client
+        .list_ids(
+            &access_token,
+            args.limit.unwrap_or(u32::MAX),
+            args.q.as_deref(),
+        )
+        .await?;
+    let done = exported_ids(&target)?;
+    let matched = ids.len();
+    let pending = ids
+        .into_iter()
+        .filter(|id| !done.contains(id))
+        .collect::<Vec<_>>();
+    let skipped = matched - pending.len();
+
+    let progress = io::stderr().is_terminal();
+    let mut exported = 0;
+    for id in &pending {
+        if ctx.interrupt.is_set() {
+            break;
+        }
+        // A large export outlives one token; ask again for each message.
+        let access_token = ctx.access_token().await?;
+        let message = ctx.gmail_client.get_msg_raw(id, &access_token).await?;
+        write(&target, id, &message)?;
+        exported += 1;
+        if progress {
+            eprint!("\rexported {exported}/{}", pending.len());
+        }
+    }
+    if progress && exported > 0 {
+        eprintln!();
+    }
+
+    let summary = ExportSummary {
+        path: match &target {
+            Target::Dir(dir) => dir.display().to_string(),
+            Target::Mbox { file, .. } => file.display().to_string(),
+        },
+        exported,
+        skipped,
+    };
+    let mut text = format!("exported {} messages to {}", summary.exported, summary.path);
+    if summary.skipped > 0 {
+        text.push_str(&format!(" ({} already exported)", summary.skipped));
+    }
+    ctx.output.emit(&text, &summary)?;
+    ctx.interrupt.check()
+}
+
+/// Ids a previous run already wrote to `target`.
+fn exported_ids(target: &Target) -> AppResult<HashSet<String>> {
+    match target {
+        Target::Dir(dir) => {
+            if !dir.exists() {
+                return Ok(HashSet::new());
+            }
+            let mut ids = HashSet::new();
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|extension| extension == "eml")
+                    && let Some(stem) = path.file_stem().and_then(|stem| stem.to_str())
+                {
+                    ids.insert(stem.to_string());
+                }
+            }
+            Ok(ids)
+        }
+        Target::Mbox { ids, .. } => {
+            if !ids.exists() {
+                return Ok(HashSet::new());
+            }
+            Ok(fs::read_to_string(ids)?
+                .lines()
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(ToString::to_string)
+                .collect())
+        }
+    }
+}
+
+/// Write one message. A `.eml` goes to a temporary name first so a partial
+/// file is never taken for a finished one.
+fn write(target: &Target, id: &str, message: &[u8]) -> AppResult<()> {
+    match target {
+        Target::Dir(dir) => {
+            fs::create_dir_all(dir)?;
+            let partial = dir.join(format!("{id}.eml.part"));
+            fs::write(&partial, message)?;
+            fs::rename(&partial, dir.join(format!("{id}.eml")))?;
+        }
+        Target::Mbox { file, ids } => {
+            if let Some(parent) = file
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                fs::create_dir_all(parent)?;
+            }
+            append(file, &mbox::entry(message))?;
+            append(ids, format!("{id}\n").as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+fn append(path: &Path, bytes: &[u8]) -> AppResult<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(bytes)?;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use std::fs;
+
+use crate::api::models::{Attachment, MessageView, SendRequest};
+use crate::cli::ForwardArgs;
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::send::{compose_with_signature, resolve_from_header};
+use crate::commands::{alias, draft, outbox};
+use crate::context::AppContext;
+use crate::error::AppResult;
+use crate::i18n;
+use crate::mail::mime;
+
+/// Forward message `id` to new recipients: `Fwd:` subject, an optional note
+/// above the quoted original with its envelope, and the original's
+/// attachments downloaded and attached again (unless `--no-attachments`).
+pub async fn run(ctx: &AppContext, args: ForwardArgs) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let original = ctx
+        .gmail_client
+        .get_msg_full(&args.id, &access_token)
+        .await?;
+
+    let mut attachments = Vec::new();
+    if !args.no_attachments {
+        for meta in &original.attachments {
+            let data = ctx
+                .gmail_client
+                .get_attachment(&original.id, &meta.attachment_id, &access_token)
+                .await?;
+            attachments.push(Attachment {
+                filename: meta.filename.clone(),
+                mime_type: meta.mime_type.clone(),
+                data,
+            });
+        }
+    }
+
+    let note = match (&args.body, &args.body_file) {
+        (Some(body), _) => body.clone(),
+        (None, Some(path)) => fs::read_to_string(path)?,
+        (None, None) => String::new(),
+    };
+    let signature = ctx
+        .settings
+        .signature
+        .as_deref()
+        .filter(|_| !args.no_signature);
+    let note = compose_with_signature(note, signature);
+
+    let request = SendRequest {
+        from: resolve_from_header(ctx, &access_token, args.from.as_deref()).await?,
+        to: alias::expand(ctx, args.to)?,
+        cc: alias::expand(ctx, args.cc)?,
+        bcc: alias::expand(ctx, args.bcc)?,
+        subject: forward_subject(original.subject.as_deref()),
+        body: mime::markdown_to_html(&forward_body(&note, &original)),
+        in_reply_to: None,
+        references: None,
+        thread_id: None,
+        attachments,
+    };
+    if args.save_draft {
+        return draft::save(ctx, "forward", None, &request, &access_token).await;
+    }
+    let raw = mime::build_raw_message(&request);
+    if ctx.dry_run {
+        return dry_run::emit(ctx, &PlannedChange::send("forward", &request, &raw));
+    }
+
+    let result = outbox::send(ctx, &raw, None, &access_token).await?;
+    let text = i18n::format("sent-message", &[("id", result.id.as_str().into())]);
+    ctx.output.emit(&text, &result)
+}
+
+/// `Fwd: <subject>`, unless it already reads `Fwd:` or `Fw:`.
+fn forward_subject(subject: Option<&str>) -> String {
+    let subject = subject.unwrap_or("").trim();
+    let lower = subject.to_ascii_lowercase();
+    if lower.starts_with("fwd:") || lower.starts_with("fw:") {
+        subject.to_string()
+    } else {
+        format!("Fwd: {subject}").trim_end().to_string()
+    }
+}
+
+/// The note, then a `Forwarded message` block: the original's From, Date,
+/// Subject, To, and Cc, and its text body quoted line by line. Every line
+/// is hard-broken so the block renders as written.
+fn forward_body(note: &str, original: &MessageView) -> String {
+    let mut header = vec!["---------- Forwarded message ---------".to_string()];
+    for (name, value) in [
+        ("From", &original.from),
+        ("Date", &original.date),
+        ("Subject", &original.subject),
+        ("To", &original.to),
+        ("Cc", &original.cc),
+    ] {
+        if let Some(value) = value {
+            header.push(format!("{name}: {value}"));
+        }
+    }
+    let quoted = original
+        .body
+        .as_deref()
+        .or(original.snippet.as_deref())
+        .unwrap_or("")
+        .trim_end()
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("  \n");
+
+    let mut body = note.trim_end_matches(['\r', '\n', ' ']).to_string();
+    if !body.is_empty() {
+        body.push_str("\n\n");
+    }
+    body.push_str(&header.join("  \n"));
+    if !quoted.is_empty() {
+        body.push_str("\n\n");
+        body.push_str(&quoted);
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_the_subject_once() {
+        assert_eq!(forward_subject(Some("Lunch")), "Fwd: Lunch");
+        assert_eq!(forward_subject(Some("FW: Lunch")), "FW: Lunch");
+        assert_eq!(forward_subject(Some("fwd: Lunch")), "fwd: Lunch");
+        assert_eq!(forward_subject(None), "Fwd:");
+    }
+
+    #[test]
+    fn quotes_the_original_below_the_note() {
+        let original = MessageView {
+            id: "m1".to_string(),
+            subject: Some("Lunch".to_string()),
+            from: Some("Ada <ada@example.com>".to_string()),
+            to: Some("me@example.com".to_string()),
+            date: Some("Tue, 5 Mar 2024 14:03:00 +0000".to_string()),
+            body: Some("Noon?\nUsual place.\n".to_string()),
+            ..MessageView::default()
+        };
+
+        assert_eq!(
+            forward_body("FYI\n", &original),
+            "FYI\n\n---------- Forwarded message ---------  \nFrom: Ada <ada@example.com>  \nDate: Tue, 5 Mar 2024 14:03:00 +0000  \nSubject: Lunch  \nTo: me@example.com\n\n> Noon?  \n> Usual place."
+        );
+    }
+}
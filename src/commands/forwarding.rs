@@ -0,0 +1,177 @@
+use std::io::Write;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::{AutoForwarding, ForwardingAddress};
+use crate::cli::{ForwardAction, ForwardingAutoArgs, ForwardingCommand, Toggle};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::output::OutputMode;
+
+/// Forwarding posture for `forwarding ls`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ForwardingView {
+    pub addresses: Vec<ForwardingAddress>,
+    pub auto_forwarding: AutoForwarding,
+}
+
+/// A forwarding address removed by `forwarding rm`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ForwardingRemoved {
+    pub email: String,
+    pub note: String,
+}
+
+/// Dispatch a `gmail forwarding` subcommand (ls, add, rm, auto).
+pub async fn run(ctx: &AppContext, command: ForwardingCommand) -> AppResult<()> {
+    match command {
+        ForwardingCommand::Ls => ls(ctx).await,
+        ForwardingCommand::Add { email } => add(ctx, &email).await,
+        ForwardingCommand::Rm { email } => rm(ctx, &email).await,
+        ForwardingCommand::Auto(args) => auto(ctx, args).await,
+    }
+}
+
+async fn ls(ctx: &AppContext) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let view = ForwardingView {
+        addresses: ctx
+            .gmail_client
+            .list_forwarding_addresses(&access_token)
+            .await?,
+        auto_forwarding: ctx.gmail_client.get_auto_forwarding(&access_token).await?,
+    };
+    if ctx.output.mode() != OutputMode::Text {
+        return ctx.output.emit("", &view);
+    }
+
+    let mut out = ctx.output.paged();
+    if view.addresses.is_empty() {
+        writeln!(out, "0 forwarding addresses")?;
+    }
+    for address in &view.addresses {
+        let status = address.verification_status.as_deref().unwrap_or("unknown");
+        writeln!(out, "{} ({status})", address.email)?;
+    }
+    writeln!(out, "auto-forwarding: {}", describe(&view.auto_forwarding))?;
+    out.finish()
+}
+
+async fn add(ctx: &AppContext, email: &str) -> AppResult<()> {
+    if ctx.dry_run {
+        let change =
+            PlannedChange::new("forwarding add", "create forwarding address").with("email", email);
+        return dry_run::emit(ctx, &change);
+    }
+    let access_token = ctx.access_token().await?;
+    let address = ctx
+        .gmail_client
+        .create_forwarding_address(email, &access_token)
+        .await?;
+    let text = match address.verification_status.as_deref() {
+        Some("accepted") => format!("added {}", address.email),
+        _ => format!(
+            "added {} (pending); it has to follow the confirmation link Gmail emailed it before mail can be forwarded there",
+            address.email
+        ),
+    };
+    ctx.output.emit(&text, &address)
+}
+
+async fn rm(ctx: &AppContext, email: &str) -> AppResult<()> {
+    if ctx.dry_run {
+        let change =
+            PlannedChange::new("forwarding rm", "delete forwarding address").with("email", email);
+        return dry_run::emit(ctx, &change);
+    }
+    let access_token = ctx.access_token().await?;
+    ctx.gmail_client
+        .delete_forwarding_address(email, &access_token)
+        .await?;
+    let result = ForwardingRemoved {
+        email: email.to_string(),
+        note: format!("removed forwarding address {email}"),
+    };
+    ctx.output.emit(&result.note, &result)
+}
+
+/// Turn auto-forwarding on to a confirmed address, or off (keeping the
+/// address and action Gmail has on file).
+async fn auto(ctx: &AppContext, args: ForwardingAutoArgs) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let setting = match (args.state, args.to) {
+        (Toggle::On, Some(to)) => {
+            let addresses = ctx
+                .gmail_client
+                .list_forwarding_addresses(&access_token)
+                .await?;
+            let confirmed = addresses.iter().any(|address| {
+                address.email.eq_ignore_ascii_case(&to)
+                    && address.verification_status.as_deref() == Some("accepted")
+            });
+            if !confirmed {
+                return Err(AppError::InvalidInput(format!(
+                    "{to} is not a confirmed forwarding address; add it with `gmail forwarding add {to}` and follow the link Gmail emails it"
+                )));
+            }
+            AutoForwarding {
+                enabled: true,
+                email: Some(to),
+                disposition: Some(disposition(args.action).to_string()),
+            }
+        }
+        (Toggle::On, None) => {
+            return Err(AppError::InvalidInput(
+                "auto-forwarding needs --to <address>".to_string(),
+            ));
+        }
+        (Toggle::Off, _) => AutoForwarding {
+            enabled: false,
+            ..ctx.gmail_client.get_auto_forwarding(&access_token).await?
+        },
+    };
+
+    if ctx.dry_run {
+        let change = PlannedChange::new("forwarding auto", "update auto-forwarding")
+            .with("enabled", setting.enabled.to_string())
+            .with("email", setting.email.clone().unwrap_or_default())
+            .with(
+                "disposition",
+                setting.disposition.clone().unwrap_or_default(),
+            );
+        return dry_run::emit(ctx, &change);
+    }
+    let updated = ctx
+        .gmail_client
+        .update_auto_forwarding(&setting, &access_token)
+        .await?;
+    let text = format!("auto-forwarding: {}", describe(&updated));
+    ctx.output.emit(&text, &updated)
+}
+
+/// The API's disposition name for an action.
+fn disposition(action: ForwardAction) -> &'static str {
+    match action {
+        ForwardAction::Keep => "leaveInInbox",
+        ForwardAction::MarkRead => "markRead",
+        ForwardAction::Archive => "archive",
+        ForwardAction::Trash => "trash",
+    }
+}
+
+/// `on, to a@example.com, archiving Gmail's copy` or `off`.
+fn describe(setting: &AutoForwarding) -> String {
+    if !setting.enabled {
+        return "off".to_string();
+    }
+    let to = setting.email.as_deref().unwrap_or("(no address)");
+    let action = match setting.disposition.as_deref() {
+        Some("markRead") => "marking Gmail's copy read",
+        Some("archive") => "archiving Gmail's copy",
+        Some("trash") => "trashing Gmail's copy",
+        _ => "keeping Gmail's copy in the inbox",
+    };
+    format!("on, to {to}, {action}")
+}
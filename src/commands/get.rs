@@ -1,58 +1,328 @@
+use std::io::Write;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::MessageView;
 use crate::cli::GetArgs;
+use crate::commands::{attachments, ids};
 use crate::context::AppContext;
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+use crate::mail::usage::human_bytes;
 use crate::output::OutputMode;
+use crate::output::layout;
+use crate::output::text::Role;
+
+/// Every header of a message, in the order they appear (`get --headers`).
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MessageHeaders {
+    pub id: String,
+    pub headers: Vec<Header>,
+}
 
-/// Fetch a single message by id and emit its headers plus decoded body text.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+}
+
+/// Fetch each message (ids given, or read from stdin) concurrently and emit
+/// its headers plus decoded body text, in the order given. A single id on the
+/// command line emits its message alone; anything else emits an array.
 pub async fn run(ctx: &AppContext, args: GetArgs) -> AppResult<()> {
+    let single = args.ids.len() == 1 && args.ids[0] != "-";
+    let ids = ids::expand(args.ids)?;
     let access_token = ctx.access_token().await?;
-    let message = ctx
-        .gmail_client
-        .get_msg_full(&args.id, &access_token)
-        .await?;
+    if args.raw {
+        return raw(ctx, &ids, &access_token).await;
+    }
+    if args.headers {
+        return headers(ctx, &ids, single).await;
+    }
+    let save = args.save_attachments.as_deref();
+    if args.thread {
+        return threads(ctx, &ids, single, save, &access_token).await;
+    }
+    let messages = ctx.gmail_client.get_msgs_full(&ids, &access_token).await?;
+    if let Some(dir) = save {
+        save_attachments(ctx, &messages, dir).await?;
+    }
 
     if ctx.output.mode() == OutputMode::Text {
-        let from = message.from.as_deref().unwrap_or("(unknown sender)");
-        let subject = message.subject.as_deref().unwrap_or("(no subject)");
-        println!("{} | {} | {}", message.id, from, subject);
-        if let Some(date) = &message.date {
-            println!("date: {date}");
-        }
-
-        if !message.attachments.is_empty() {
-            println!("attachments ({}):", message.attachments.len());
-            for (index, attachment) in message.attachments.iter().enumerate() {
-                match attachment.size {
-                    Some(size) => println!(
-                        "  {}. {} | {} | {} bytes",
-                        index + 1,
-                        attachment.filename,
-                        attachment.mime_type,
-                        size
-                    ),
-                    None => println!(
-                        "  {}. {} | {}",
-                        index + 1,
-                        attachment.filename,
-                        attachment.mime_type
-                    ),
-                }
+        let mut out = ctx.output.paged();
+        for (index, message) in messages.iter().enumerate() {
+            if index > 0 {
+                writeln!(out)?;
             }
-            println!("  (download with: gmail attachments get {})", message.id);
+            render(ctx, &mut out, message)?;
         }
-        println!();
+        out.finish()?;
+        return ctx.interrupt.check();
+    }
+
+    let text = messages
+        .iter()
+        .map(|message| {
+            let from = message
+                .from
+                .clone()
+                .unwrap_or_else(|| i18n::text("unknown-sender"));
+            let subject = message
+                .subject
+                .clone()
+                .unwrap_or_else(|| i18n::text("no-subject"));
+            format!("{} | {} | {}", message.id, from, subject)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if let [message] = messages.as_slice()
+        && single
+    {
+        return ctx.output.emit(&text, message);
+    }
+    ctx.output.emit(&text, &messages)?;
+    ctx.interrupt.check()
+}
 
-        match message.body.as_deref() {
-            Some(body) => println!("{body}"),
-            // Fall back to the snippet when no decodable body part was found.
-            None => println!("{}", message.snippet.as_deref().unwrap_or("(no body)")),
+/// Print the conversation each message belongs to, every message in full,
+/// oldest first. Messages from the same thread print it once.
+async fn threads(
+    ctx: &AppContext,
+    ids: &[String],
+    single: bool,
+    save: Option<&Path>,
+    access_token: &str,
+) -> AppResult<()> {
+    let mut thread_ids = Vec::new();
+    for message in ctx.gmail_client.get_msgs(ids, access_token).await? {
+        if let Some(thread_id) = message.thread_id
+            && !thread_ids.contains(&thread_id)
+        {
+            thread_ids.push(thread_id);
         }
+    }
+    let mut threads = Vec::with_capacity(thread_ids.len());
+    for id in &thread_ids {
+        if !threads.is_empty() && ctx.interrupt.is_set() {
+            break;
+        }
+        let access_token = ctx.access_token().await?;
+        threads.push(ctx.gmail_client.get_thread_full(id, &access_token).await?);
+    }
+    if let Some(dir) = save {
+        let messages = threads.iter().flat_map(|thread| &thread.messages);
+        save_attachments(ctx, messages, dir).await?;
+    }
+
+    if ctx.output.mode() == OutputMode::Text {
+        let mut out = ctx.output.paged();
+        let messages = threads.iter().flat_map(|thread| &thread.messages);
+        for (index, message) in messages.enumerate() {
+            if index > 0 {
+                writeln!(out)?;
+            }
+            render(ctx, &mut out, message)?;
+        }
+        out.finish()?;
+        return ctx.interrupt.check();
+    }
+
+    let text = threads
+        .iter()
+        .map(|thread| {
+            let count = thread.messages.len();
+            format!(
+                "{} | {}",
+                thread.id,
+                i18n::format("messages-count", &[("count", count.into())])
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if let [thread] = threads.as_slice()
+        && single
+    {
+        return ctx.output.emit(&text, thread);
+    }
+    ctx.output.emit(&text, &threads)?;
+    ctx.interrupt.check()
+}
+
+/// Print every header of each message as `Name: value` lines, for debugging
+/// delivery: trace and authentication headers come through untouched. More
+/// than one message puts its id above each block.
+async fn headers(ctx: &AppContext, ids: &[String], single: bool) -> AppResult<()> {
+    let mut messages = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !messages.is_empty() && ctx.interrupt.is_set() {
+            break;
+        }
+        let access_token = ctx.access_token().await?;
+        let headers = ctx.gmail_client.get_headers(id, &[], &access_token).await?;
+        messages.push(MessageHeaders {
+            id: id.clone(),
+            headers: headers
+                .into_iter()
+                .map(|(name, value)| Header { name, value })
+                .collect(),
+        });
+    }
+
+    if ctx.output.mode() == OutputMode::Text {
+        let mut out = ctx.output.paged();
+        for (index, message) in messages.iter().enumerate() {
+            if index > 0 {
+                writeln!(out)?;
+            }
+            if messages.len() > 1 {
+                writeln!(out, "{}", message.id)?;
+            }
+            for header in &message.headers {
+                writeln!(out, "{}: {}", header.name, header.value)?;
+            }
+        }
+        out.finish()?;
+        return ctx.interrupt.check();
+    }
+
+    let text = messages
+        .iter()
+        .map(|message| format!("{} | {} headers", message.id, message.headers.len()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if let [message] = messages.as_slice()
+        && single
+    {
+        return ctx.output.emit(&text, message);
+    }
+    ctx.output.emit(&text, &messages)?;
+    ctx.interrupt.check()
+}
+
+/// Download the attachments of `messages` into `dir` for `--save-attachments`,
+/// listing each file and the bytes written on stderr so stdout keeps just the
+/// messages.
+async fn save_attachments<'a>(
+    ctx: &AppContext,
+    messages: impl IntoIterator<Item = &'a MessageView>,
+    dir: &Path,
+) -> AppResult<()> {
+    let saved = attachments::save_all(ctx, messages, dir).await?;
+    for item in &saved {
+        eprintln!(
+            "saved {} ({} bytes) -> {}",
+            item.filename, item.bytes, item.path
+        );
+    }
+    let bytes = saved.iter().map(|item| item.bytes).sum::<u64>();
+    eprintln!(
+        "{} attachment(s), {} written to {}",
+        saved.len(),
+        human_bytes(bytes),
+        dir.display()
+    );
+    ctx.interrupt.check()
+}
+
+/// Write a message's original bytes, unchanged, to stdout or the `--out`
+/// file. Structured output formats don't apply.
+async fn raw(ctx: &AppContext, ids: &[String], access_token: &str) -> AppResult<()> {
+    let [id] = ids else {
+        return Err(AppError::InvalidInput(format!(
+            "--raw writes one message, but {} ids were given",
+            ids.len()
+        )));
+    };
+    let message = ctx.gmail_client.get_msg_raw(id, access_token).await?;
+    ctx.output.write(&message)
+}
+
+/// Write one message's summary line, date, attachments, and body.
+pub(crate) fn render(
+    ctx: &AppContext,
+    out: &mut impl Write,
+    message: &MessageView,
+) -> AppResult<()> {
+    let (unknown_sender, no_subject) = (i18n::text("unknown-sender"), i18n::text("no-subject"));
+    let from = message.from.as_deref().unwrap_or(&unknown_sender);
+    let subject = message.subject.as_deref().unwrap_or(&no_subject);
+    // Keep the summary line on one terminal row: the sender gets at most
+    // half the width and the subject whatever is left.
+    let (from, subject) = match ctx.output.width() {
+        Some(width) => {
+            let from = layout::truncate(from, width / 2);
+            let used = layout::display_width(&message.id) + layout::display_width(&from) + 6;
+            let subject = layout::truncate(subject, width.saturating_sub(used));
+            (from, subject)
+        }
+        None => (from.to_string(), subject.to_string()),
+    };
+    writeln!(
+        out,
+        "{} | {} | {}",
+        message.id,
+        ctx.output.paint(Role::Sender, &from),
+        subject
+    )?;
+    for (field, value) in [("field-to", &message.to), ("field-cc", &message.cc)] {
+        if let Some(value) = value {
+            writeln!(out, "{}: {value}", i18n::text(field))?;
+        }
+    }
+    if let Some(date) = message.date.as_ref().or(message.internal_date.as_ref()) {
+        let date = ctx.output.date(date);
+        writeln!(
+            out,
+            "{}: {}",
+            i18n::text("field-date"),
+            ctx.output.paint(Role::Date, &date)
+        )?;
+    }
+
+    if !message.attachments.is_empty() {
+        let count = message.attachments.len();
+        writeln!(
+            out,
+            "{}",
+            i18n::format("attachments-heading", &[("count", count.into())])
+        )?;
+        for (index, attachment) in message.attachments.iter().enumerate() {
+            match attachment.size {
+                Some(size) => writeln!(
+                    out,
+                    "  {}. {} | {} | {}",
+                    index + 1,
+                    attachment.filename,
+                    attachment.mime_type,
+                    i18n::format("size-bytes", &[("size", size.into())])
+                )?,
+                None => writeln!(
+                    out,
+                    "  {}. {} | {}",
+                    index + 1,
+                    attachment.filename,
+                    attachment.mime_type
+                )?,
+            }
+        }
+        writeln!(
+            out,
+            "  {}",
+            i18n::format("attachments-hint", &[("id", message.id.as_str().into())])
+        )?;
+    }
+    writeln!(out)?;
 
-        return Ok(());
+    match message.body.as_deref() {
+        Some(body) => writeln!(out, "{body}")?,
+        // Fall back to the snippet when no decodable body part was found.
+        None => match message.snippet.as_deref() {
+            Some(snippet) => writeln!(out, "{snippet}")?,
+            None => writeln!(out, "{}", i18n::text("no-body"))?,
+        },
     }
 
-    let from = message.from.as_deref().unwrap_or("(unknown sender)");
-    let subject = message.subject.as_deref().unwrap_or("(no subject)");
-    let text = format!("{} | {} | {}", message.id, from, subject);
-    ctx.output.emit(&text, &message)
+    Ok(())
 }
@@ -0,0 +1,105 @@
+use std::io::Write;
+
+use crate::api::models::{HistoryPage, MailboxEventKind};
+use crate::cli::{HistoryArgs, HistoryType};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::output::OutputMode;
+use crate::output::text::Role;
+
+/// Print what changed in the mailbox after history id `--since`: messages
+/// added and deleted and labels added and removed, plus the history id to
+/// pass next time. Without `--since` only the current history id is printed,
+/// as a starting point.
+pub async fn run(ctx: &AppContext, args: HistoryArgs) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let Some(since) = args.since else {
+        let history_id = ctx
+            .gmail_client
+            .get_profile(&access_token)
+            .await?
+            .history_id;
+        let page = HistoryPage {
+            start_history_id: history_id.clone(),
+            history_id,
+            events: Vec::new(),
+        };
+        return ctx
+            .output
+            .emit(&format!("history id {}", page.history_id), &page);
+    };
+
+    let label_id = match args.label {
+        Some(label) => {
+            let [ids] = ctx
+                .gmail_client
+                .resolve_label_ids([&[label][..]], &access_token)
+                .await?;
+            ids.into_iter().next()
+        }
+        None => None,
+    };
+    let types = args
+        .types
+        .iter()
+        .map(|kind| api_name(*kind))
+        .collect::<Vec<_>>();
+    let mut page = ctx
+        .gmail_client
+        .list_history(&since, label_id.as_deref(), &types, &access_token)
+        .await
+        .map_err(|err| match err.status() {
+            Some(404) => AppError::InvalidInput(format!(
+                "history id {since} is too old for Gmail to answer from; run `gmail history` without --since for a current one and resync"
+            )),
+            _ => err,
+        })?;
+    // A record can carry changes of several types; keep only those asked for.
+    if !args.types.is_empty() {
+        page.events.retain(|event| {
+            args.types
+                .iter()
+                .any(|kind| event_kind(*kind) == event.kind)
+        });
+    }
+
+    if ctx.output.mode() != OutputMode::Text {
+        return ctx.output.emit("", &page);
+    }
+    let mut out = ctx.output.paged();
+    for event in &page.events {
+        let kind = match event.kind {
+            MailboxEventKind::MessageAdded => "added",
+            MailboxEventKind::MessageDeleted => "deleted",
+            MailboxEventKind::LabelsAdded => "labeled",
+            MailboxEventKind::LabelsRemoved => "unlabeled",
+        };
+        write!(out, "{} {kind} {}", event.history_id, event.message_id)?;
+        if !event.label_ids.is_empty() {
+            let labels = event.label_ids.join(",");
+            write!(out, " {}", ctx.output.paint(Role::Label, &labels))?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "history id {}", page.history_id)?;
+    out.finish()
+}
+
+/// The `historyTypes` value Gmail expects.
+fn api_name(kind: HistoryType) -> &'static str {
+    match kind {
+        HistoryType::MessageAdded => "messageAdded",
+        HistoryType::MessageDeleted => "messageDeleted",
+        HistoryType::LabelAdded => "labelAdded",
+        HistoryType::LabelRemoved => "labelRemoved",
+    }
+}
+
+fn event_kind(kind: HistoryType) -> MailboxEventKind {
+    match kind {
+        HistoryType::MessageAdded => MailboxEventKind::MessageAdded,
+        HistoryType::MessageDeleted => MailboxEventKind::MessageDeleted,
+        HistoryType::LabelAdded => MailboxEventKind::LabelsAdded,
+        HistoryType::LabelRemoved => MailboxEventKind::LabelsRemoved,
+    }
+}
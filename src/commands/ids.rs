@@ -0,0 +1,55 @@
+use std::io::{self, BufRead};
+
+use crate::error::{AppError, AppResult};
+
+/// Replace a `-` among `ids` with the ids read from stdin, one per line, so
+/// `gmail list --ids-only | gmail label add - Work` works. Only the first
+/// word of each line is used; blank lines and `#` comments are skipped.
+pub(crate) fn expand(ids: Vec<String>) -> AppResult<Vec<String>> {
+    if !ids.iter().any(|id| id == "-") {
+        return Ok(ids);
+    }
+    expand_from(ids, io::stdin().lock())
+}
+
+fn expand_from(ids: Vec<String>, input: impl BufRead) -> AppResult<Vec<String>> {
+    let mut piped = Some(input);
+    let mut out = Vec::new();
+    for id in ids {
+        if id != "-" {
+            out.push(id);
+            continue;
+        }
+        // stdin can only be read once; a second `-` adds nothing.
+        let Some(input) = piped.take() else {
+            continue;
+        };
+        for line in input.lines() {
+            let line = line?;
+            match line.split_whitespace().next() {
+                Some(id) if !id.starts_with('#') => out.push(id.to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    if out.is_empty() {
+        return Err(AppError::InvalidInput(
+            "no message ids on stdin".to_string(),
+        ));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_first_word_of_each_stdin_line() {
+        let ids = vec!["a".to_string(), "-".to_string(), "-".to_string()];
+        let input = "b\n\n  c  extra words\n# comment\nd\n".as_bytes();
+        assert_eq!(expand_from(ids, input).unwrap(), ["a", "b", "c", "d"]);
+        assert!(expand_from(vec!["-".to_string()], "\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::ImportArgs;
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::mbox;
+
+/// The outcome of importing one message from a file.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ImportedMessage {
+    /// The file it came from, with `#<n>` for the nth message of an mbox.
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    /// Why Gmail refused it, when it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// One message read from a file, not yet imported by an earlier run.
+struct Pending {
+    source: String,
+    /// Its 1-based position in the file, as recorded in the progress file.
+    number: usize,
+    progress: PathBuf,
+    message: Vec<u8>,
+}
+
+/// Add the messages in EML or mbox files to the mailbox with the requested
+/// labels, carrying on past messages Gmail refuses. Each imported message is
+/// noted in `<file>.imported`, so a rerun skips what an earlier one added.
+pub async fn run(ctx: &AppContext, args: ImportArgs) -> AppResult<()> {
+    let mut messages = Vec::new();
+    let mut skipped = 0;
+    for path in &args.files {
+        let progress = progress_file(path);
+        let done = imported_numbers(&progress)?;
+        for (index, (source, message)) in read_messages(path)?.into_iter().enumerate() {
+            let number = index + 1;
+            if done.contains(&number) {
+                skipped += 1;
+                continue;
+            }
+            messages.push(Pending {
+                source,
+                number,
+                progress: progress.clone(),
+                message,
+            });
+        }
+    }
+    if skipped > 0 {
+        eprintln!("skipping {skipped} messages already imported");
+    }
+    if messages.is_empty() {
+        if skipped > 0 {
+            let results: Vec<ImportedMessage> = Vec::new();
+            return ctx.output.emit("nothing left to import", &results);
+        }
+        return Err(AppError::InvalidInput(
+            "no messages found in the given files".to_string(),
+        ));
+    }
+
+    let access_token = ctx.access_token().await?;
+    let [label_ids] = ctx
+        .gmail_client
+        .resolve_label_ids([args.labels.as_slice()], &access_token)
+        .await?;
+    let action = if args.insert {
+        "insert messages"
+    } else {
+        "import messages"
+    };
+    if ctx.dry_run {
+        let sources = messages
+            .into_iter()
+            .map(|pending| pending.source)
+            .collect::<Vec<_>>();
+        let change = PlannedChange::new("import", action)
+            .with_labels(&label_ids, &[])
+            .with("messages", sources.len().to_string())
+            .with("sources", sources.join(", "));
+        return dry_run::emit(ctx, &change);
+    }
+
+    let mut results = Vec::with_capacity(messages.len());
+    for pending in messages {
+        if !results.is_empty() && ctx.interrupt.is_set() {
+            break;
+        }
+        let Pending {
+            source,
+            number,
+            progress,
+            message,
+        } = pending;
+        let raw = URL_SAFE_NO_PAD.encode(&message);
+        // A big migration outlives one token; ask again for each message.
+        let access_token = ctx.access_token().await?;
+        results.push(
+            match ctx
+                .gmail_client
+                .import_message(&raw, &label_ids, args.insert, &access_token)
+                .await
+            {
+                Ok(result) => {
+                    record(&progress, number)?;
+                    ImportedMessage {
+                        source,
+                        id: Some(result.id),
+                        thread_id: result.thread_id,
+                        error: None,
+                    }
+                }
+                Err(err @ AppError::Auth(_)) => return Err(err),
+                Err(err) => ImportedMessage {
+                    source,
+                    id: None,
+                    thread_id: None,
+                    error: Some(err.to_string()),
+                },
+            },
+        );
+    }
+
+    let text = results
+        .iter()
+        .map(|result| match (&result.id, &result.error) {
+            (Some(id), _) => format!("imported {} as {id}", result.source),
+            (None, error) => format!(
+                "failed to import {}: {}",
+                result.source,
+                error.as_deref().unwrap_or_default()
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.output.emit(&text, &results)?;
+    ctx.interrupt.check()?;
+
+    let failed = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+    if failed > 0 {
+        return Err(AppError::api(format!(
+            "failed to import {failed} of {} messages",
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Where the numbers of the messages imported from `path` are kept.
+fn progress_file(path: &Path) -> PathBuf {
+    let mut progress = path.to_path_buf().into_os_string();
+    progress.push(".imported");
+    progress.into()
+}
+
+/// Numbers of the messages a previous run imported, from `progress`.
+fn imported_numbers(progress: &Path) -> AppResult<HashSet<usize>> {
+    if !progress.exists() {
+        return Ok(HashSet::new());
+    }
+    Ok(fs::read_to_string(progress)?
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect())
+}
+
+/// Note message `number` as imported, right after Gmail accepts it.
+fn record(progress: &Path, number: usize) -> AppResult<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(progress)?;
+    writeln!(file, "{number}")?;
+    Ok(())
+}
+
+/// The messages in `path`, each with its source name: the whole file for a
+/// single message, or every message of an mbox.
+fn read_messages(path: &Path) -> AppResult<Vec<(String, Vec<u8>)>> {
+    let data = fs::read(path)?;
+    let name = path.display().to_string();
+    if !mbox::is_mbox(&data) {
+        return Ok(vec![(name, data)]);
+    }
+    Ok(mbox::split(&data)
+        .into_iter()
+        .enumerate()
+        .map(|(index, message)| (format!("{name}#{}", index + 1), message))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_imported_messages_next_to_the_file() {
+        let dir = std::env::temp_dir().join(format!("gmail-import-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let progress = progress_file(&dir.join("old.mbox"));
+        assert_eq!(progress, dir.join("old.mbox.imported"));
+        assert!(imported_numbers(&progress).unwrap().is_empty());
+
+        record(&progress, 1).unwrap();
+        record(&progress, 3).unwrap();
+        assert_eq!(imported_numbers(&progress).unwrap(), HashSet::from([1, 3]));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
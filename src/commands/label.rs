@@ -1,9 +1,19 @@
-use crate::cli::{LabelCommand, LabelMutateArgs};
+use std::io::Write;
+
+use crate::api::models::LabelView;
+use crate::cli::{
+    BulkArgs, LabelCommand, LabelCreateArgs, LabelListVisibility, LabelMutateArgs,
+    MessageListVisibility,
+};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::triage;
 use crate::context::AppContext;
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::i18n;
 use crate::output::OutputMode;
+use crate::output::text::Role;
 
-/// Dispatch a `gmail label` subcommand (ls/add/rm).
+/// Dispatch a `gmail label` subcommand (ls/add/rm/create).
 pub async fn run(ctx: &AppContext, command: LabelCommand) -> AppResult<()> {
     match command {
         LabelCommand::Ls => {
@@ -11,56 +21,255 @@ pub async fn run(ctx: &AppContext, command: LabelCommand) -> AppResult<()> {
             let labels = ctx.gmail_client.list_labels(&access_token).await?;
 
             if ctx.output.mode() == OutputMode::Text {
+                let mut out = ctx.output.paged();
                 if labels.is_empty() {
-                    println!("0 labels");
-                    return Ok(());
+                    writeln!(
+                        out,
+                        "{}",
+                        i18n::format("labels-count", &[("count", 0.into())])
+                    )?;
+                    return out.finish();
                 }
 
                 for (index, label) in labels.iter().enumerate() {
+                    let name = ctx.output.paint(Role::Label, &label.name);
                     if label.id == label.name {
-                        println!("{}. {} [{}]", index + 1, label.name, label.kind);
+                        writeln!(out, "{}. {} [{}]", index + 1, name, label.kind)?;
                     } else {
-                        println!(
+                        writeln!(
+                            out,
                             "{}. {} [{}] (id: {})",
                             index + 1,
-                            label.name,
+                            name,
                             label.kind,
                             label.id
-                        );
+                        )?;
                     }
                 }
 
-                return Ok(());
+                return out.finish();
             }
 
-            let text = format!("{} labels", labels.len());
+            let text = i18n::format("labels-count", &[("count", labels.len().into())]);
             ctx.output.emit(&text, &labels)
         }
         LabelCommand::Add(args) => mutate_add(ctx, args).await,
         LabelCommand::Rm(args) => mutate_rm(ctx, args).await,
+        LabelCommand::Create(args) => create(ctx, args).await,
     }
 }
 
-/// Add labels to a message and emit the mutation result.
-async fn mutate_add(ctx: &AppContext, args: LabelMutateArgs) -> AppResult<()> {
+/// Create each label, and any `Parent/` levels above it that don't exist yet,
+/// parents first. A name that already exists is an error, before anything is
+/// created.
+async fn create(ctx: &AppContext, args: LabelCreateArgs) -> AppResult<()> {
     let access_token = ctx.access_token().await?;
-    let result = ctx
-        .gmail_client
-        .add_labels(&args.id, &args.labels, &access_token)
-        .await?;
+    let existing = ctx.gmail_client.list_labels(&access_token).await?;
+    let names = missing_labels(&args.names, &existing)?;
+    let label_list = args.list_visibility.map(label_list_visibility);
+    let message_list = args.message_visibility.map(message_list_visibility);
+    if ctx.dry_run {
+        let change = PlannedChange::new("label create", "create labels")
+            .with("labels", names.join(", "))
+            .with("label_list_visibility", label_list.unwrap_or_default())
+            .with("message_list_visibility", message_list.unwrap_or_default());
+        return dry_run::emit(ctx, &change);
+    }
+
+    let mut created = Vec::with_capacity(names.len());
+    for name in &names {
+        created.push(
+            ctx.gmail_client
+                .create_label(name, label_list, message_list, &access_token)
+                .await?,
+        );
+    }
+    let text = created
+        .iter()
+        .map(|label| format!("created label {} (id: {})", label.name, label.id))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.output.emit(&text, &created)
+}
+
+/// The labels to create for `names`, parents before children, each once.
+/// Fails on a blank level (`a//b`) or a name that is already a label; Gmail
+/// compares label names without regard to case.
+fn missing_labels(names: &[String], existing: &[LabelView]) -> AppResult<Vec<String>> {
+    let taken = |name: &str, planned: &[String]| {
+        existing
+            .iter()
+            .map(|label| label.name.as_str())
+            .chain(planned.iter().map(String::as_str))
+            .any(|known| known.eq_ignore_ascii_case(name))
+    };
+    let mut missing = Vec::new();
+    for name in names {
+        let levels = name.split('/').map(str::trim).collect::<Vec<_>>();
+        if levels.iter().any(|level| level.is_empty()) {
+            return Err(AppError::InvalidInput(format!(
+                "`{name}` has an empty level; write nested labels as `Parent/Child`"
+            )));
+        }
+        let name = levels.join("/");
+        if taken(&name, &missing) {
+            return Err(AppError::InvalidInput(format!(
+                "label `{name}` already exists"
+            )));
+        }
+        for depth in 1..=levels.len() {
+            let path = levels[..depth].join("/");
+            if !taken(&path, &missing) {
+                missing.push(path);
+            }
+        }
+    }
+    Ok(missing)
+}
+
+fn label_list_visibility(visibility: LabelListVisibility) -> &'static str {
+    match visibility {
+        LabelListVisibility::Show => "labelShow",
+        LabelListVisibility::ShowIfUnread => "labelShowIfUnread",
+        LabelListVisibility::Hide => "labelHide",
+    }
+}
+
+fn message_list_visibility(visibility: MessageListVisibility) -> &'static str {
+    match visibility {
+        MessageListVisibility::Show => "show",
+        MessageListVisibility::Hide => "hide",
+    }
+}
 
-    let text = format!("labels added on {}", result.id);
-    ctx.output.emit(&text, &result)
+/// Add labels to each message and emit the mutation results.
+async fn mutate_add(ctx: &AppContext, args: LabelMutateArgs) -> AppResult<()> {
+    mutate(ctx, "label add", args, true).await
 }
 
-/// Remove labels from a message and emit the mutation result.
+/// Remove labels from each message and emit the mutation results.
 async fn mutate_rm(ctx: &AppContext, args: LabelMutateArgs) -> AppResult<()> {
+    mutate(ctx, "label rm", args, false).await
+}
+
+/// Apply the change to every id (comma-separated, or `-` for stdin), or to
+/// every message matching `--q`, in as few `messages.batchModify` calls as
+/// possible; with `--thread`, to whole conversations by `threads.modify`. A
+/// single id given on the command line emits its result alone, as before;
+/// anything else emits an array.
+async fn mutate(
+    ctx: &AppContext,
+    command: &str,
+    args: LabelMutateArgs,
+    add: bool,
+) -> AppResult<()> {
+    let LabelMutateArgs {
+        mut ids,
+        mut labels,
+        q,
+        limit,
+        thread,
+        yes,
+    } = args;
+    if q.is_some() {
+        // With --q there are no ids, so the first positional is a label.
+        labels.insert(0, ids.join(","));
+        ids.clear();
+    }
+    let single = q.is_none() && ids.len() == 1 && ids[0] != "-";
+    let (action, done) = if add {
+        ("add labels on", "labels added on")
+    } else {
+        ("remove labels from", "labels removed on")
+    };
+
     let access_token = ctx.access_token().await?;
-    let result = ctx
+    let bulk = BulkArgs {
+        ids,
+        q,
+        limit,
+        all: false,
+        thread,
+        yes,
+    };
+    let Some(ids) = triage::bulk_ids(ctx, bulk, action, &access_token).await? else {
+        return Ok(());
+    };
+    let none = Vec::new();
+    let labels = if add {
+        [&labels, &none]
+    } else {
+        [&none, &labels]
+    };
+    if ctx.dry_run {
+        let labels = labels.map(Vec::as_slice);
+        return plan(ctx, command, ids, labels, thread, &access_token).await;
+    }
+
+    let labels = labels.map(Vec::as_slice);
+    let results = if thread {
+        triage::relabel_threads(ctx, &ids, labels).await?
+    } else {
+        triage::relabel(ctx, &ids, labels).await?
+    };
+    triage::report_relabeled(ctx, action, done, &results, single)
+}
+
+/// Resolve the labels as the real call would and print the change (`--dry-run`).
+async fn plan(
+    ctx: &AppContext,
+    command: &str,
+    ids: Vec<String>,
+    labels: [&[String]; 2],
+    thread: bool,
+    access_token: &str,
+) -> AppResult<()> {
+    let [add, remove] = ctx
         .gmail_client
-        .rm_labels(&args.id, &args.labels, &access_token)
+        .resolve_label_ids(labels, access_token)
         .await?;
+    let action = if add.is_empty() {
+        "remove labels"
+    } else {
+        "add labels"
+    };
+    let change = PlannedChange::new(command, action)
+        .with_ids(ids)
+        .with_labels(&add, &remove)
+        .with("target", triage::target(thread));
+    dry_run::emit(ctx, &change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let text = format!("labels removed on {}", result.id);
-    ctx.output.emit(&text, &result)
+    fn label(name: &str) -> LabelView {
+        LabelView {
+            id: format!("Label_{name}"),
+            name: name.to_string(),
+            kind: "user".to_string(),
+        }
+    }
+
+    #[test]
+    fn creates_missing_parents_first() {
+        let existing = [label("Work")];
+        let names = [
+            "Work/Clients/Acme".to_string(),
+            "work/clients/Beta".to_string(),
+        ];
+        assert_eq!(
+            missing_labels(&names, &existing).unwrap(),
+            ["Work/Clients", "Work/Clients/Acme", "work/clients/Beta"]
+        );
+    }
+
+    #[test]
+    fn refuses_existing_and_blank_levels() {
+        let existing = [label("Receipts")];
+        let err = missing_labels(&["receipts".to_string()], &existing).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{err}");
+        assert!(missing_labels(&["a//b".to_string()], &existing).is_err());
+    }
 }
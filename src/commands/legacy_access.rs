@@ -0,0 +1,85 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::{ImapSettings, PopSettings};
+use crate::cli::{LegacyAccessCommand, PopArgs, Toggle};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::context::AppContext;
+use crate::error::AppResult;
+
+/// IMAP and POP settings for `legacy-access status`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct LegacyAccess {
+    pub imap: ImapSettings,
+    pub pop: PopSettings,
+}
+
+impl LegacyAccess {
+    /// `imap on, pop off` (with POP's window when on).
+    pub fn describe(&self) -> String {
+        let imap = if self.imap.enabled { "on" } else { "off" };
+        let pop = if self.pop.enabled {
+            format!("on ({})", self.pop.access_window)
+        } else {
+            "off".to_string()
+        };
+        format!("imap {imap}, pop {pop}")
+    }
+}
+
+/// Dispatch a `gmail legacy-access` subcommand (status, imap, pop).
+pub async fn run(ctx: &AppContext, command: LegacyAccessCommand) -> AppResult<()> {
+    match command {
+        LegacyAccessCommand::Status => {
+            let access = status(ctx).await?;
+            ctx.output.emit(&access.describe(), &access)
+        }
+        LegacyAccessCommand::Imap { state } => imap(ctx, state).await,
+        LegacyAccessCommand::Pop(args) => pop(ctx, args).await,
+    }
+}
+
+/// Fetch both settings; shared with `--profiles` audits.
+pub(crate) async fn status(ctx: &AppContext) -> AppResult<LegacyAccess> {
+    let access_token = ctx.access_token().await?;
+    Ok(LegacyAccess {
+        imap: ctx.gmail_client.get_imap(&access_token).await?,
+        pop: ctx.gmail_client.get_pop(&access_token).await?,
+    })
+}
+
+async fn imap(ctx: &AppContext, state: Toggle) -> AppResult<()> {
+    if ctx.dry_run {
+        let change = PlannedChange::new("legacy-access imap", "update IMAP settings")
+            .with("enabled", (state == Toggle::On).to_string());
+        return dry_run::emit(ctx, &change);
+    }
+    let access_token = ctx.access_token().await?;
+    let settings = ctx
+        .gmail_client
+        .set_imap(state == Toggle::On, &access_token)
+        .await?;
+    let text = format!("imap {}", if settings.enabled { "on" } else { "off" });
+    ctx.output.emit(&text, &settings)
+}
+
+async fn pop(ctx: &AppContext, args: PopArgs) -> AppResult<()> {
+    let window = match (args.state, args.all_mail) {
+        (Toggle::Off, _) => "disabled",
+        (Toggle::On, true) => "allMail",
+        (Toggle::On, false) => "fromNowOn",
+    };
+    if ctx.dry_run {
+        let change = PlannedChange::new("legacy-access pop", "update POP settings")
+            .with("access_window", window);
+        return dry_run::emit(ctx, &change);
+    }
+    let access_token = ctx.access_token().await?;
+    let settings = ctx.gmail_client.set_pop(window, &access_token).await?;
+    let text = if settings.enabled {
+        format!("pop on ({})", settings.access_window)
+    } else {
+        "pop off".to_string()
+    };
+    ctx.output.emit(&text, &settings)
+}
@@ -1,73 +1,188 @@
+use std::io::Write;
+
+use crate::api::models::MessageView;
 use crate::cli::ListArgs;
+use crate::commands::{search, thread};
 use crate::context::AppContext;
 use crate::error::{AppError, AppResult};
+use crate::i18n;
 use crate::output::OutputMode;
+use crate::output::layout;
+use crate::output::text::Role;
+
+/// Lines of wrapped preview shown per message on a terminal.
+const PREVIEW_LINES: usize = 3;
+/// Columns the preview is indented by.
+const PREVIEW_INDENT: usize = 3;
+/// Narrowest the preview or a header value is laid out, however small the terminal.
+const MIN_PREVIEW_WIDTH: usize = 20;
+/// Preview length when output is piped and there is no terminal width.
+const PIPED_PREVIEW_CHARS: usize = 120;
 
-/// List messages matching the args and print each with a compact preview.
+/// List messages (or, with `--threads`, conversations) matching the args and
+/// print each with a compact preview.
+/// After Ctrl-C, whatever was fetched is printed before reporting the interrupt.
 pub async fn run(ctx: &AppContext, args: ListArgs) -> AppResult<()> {
+    print(ctx, args).await?;
+    ctx.interrupt.check()
+}
+
+async fn print(ctx: &AppContext, args: ListArgs) -> AppResult<()> {
     if args.limit == 0 {
         return Err(AppError::InvalidInput(
             "--limit must be greater than 0".to_string(),
         ));
     }
 
+    let user_query = user_query(ctx, &args)?;
     let access_token = ctx.access_token().await?;
-    let query = build_query(args.inbox, args.q.as_deref());
+    let query = build_query(args.inbox, user_query.as_deref());
+    if args.threads {
+        if args.ids_only {
+            let ids = ctx
+                .gmail_client
+                .list_thread_ids(&access_token, args.limit, query.as_deref())
+                .await?;
+            return ctx.output.emit(&ids.join("\n"), &ids);
+        }
+        return thread::print_threads(ctx, &access_token, args.limit, query.as_deref()).await;
+    }
+    if args.ids_only {
+        let ids = ctx
+            .gmail_client
+            .list_ids(&access_token, args.limit, query.as_deref())
+            .await?;
+        return ctx.output.emit(&ids.join("\n"), &ids);
+    }
+
     let messages = ctx
         .gmail_client
         .list(&access_token, args.limit, query.as_deref())
         .await?;
 
     if ctx.output.mode() == OutputMode::Text {
-        if messages.is_empty() {
-            println!("0 messages");
-            return Ok(());
-        }
+        let entries = messages
+            .iter()
+            .map(|message| (None, message))
+            .collect::<Vec<_>>();
+        return write_text(ctx, &entries);
+    }
+
+    let text = i18n::format("messages-count", &[("count", messages.len().into())]);
+    ctx.output.emit(&text, &messages)
+}
+
+/// Print messages as numbered entries with a wrapped preview, each under the
+/// account it came from when one is given (`--profiles`).
+pub(crate) fn write_text(
+    ctx: &AppContext,
+    messages: &[(Option<&str>, &MessageView)],
+) -> AppResult<()> {
+    let mut out = ctx.output.paged();
+    if messages.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            i18n::format("messages-count", &[("count", 0.into())])
+        )?;
+        return out.finish();
+    }
+
+    let field = |id: &str| format!("   {}: ", i18n::text(id));
+    let (account_field, from_field, subject_field, date_field) = (
+        field("field-account"),
+        field("field-from"),
+        field("field-subject"),
+        field("field-date"),
+    );
+    let (unknown_sender, no_subject) = (i18n::text("unknown-sender"), i18n::text("no-subject"));
+    let width = ctx.output.width();
+    for (index, (account, message)) in messages.iter().enumerate() {
+        let from = fit(
+            message.from.as_deref().unwrap_or(&unknown_sender),
+            width,
+            &from_field,
+        );
+        let subject = fit(
+            message.subject.as_deref().unwrap_or(&no_subject),
+            width,
+            &subject_field,
+        );
+        let date = message
+            .date
+            .as_deref()
+            .or(message.internal_date.as_deref())
+            .map_or_else(|| i18n::text("no-date"), |date| ctx.output.date(date));
 
-        for (index, message) in messages.iter().enumerate() {
-            let from = message.from.as_deref().unwrap_or("(unknown sender)");
-            let subject = message.subject.as_deref().unwrap_or("(no subject)");
-            let date = message.date.as_deref().unwrap_or("(no date)");
-            let preview = format_preview(message.snippet.as_deref());
-
-            println!("{}. {}", index + 1, message.id);
-            println!("   from: {from}");
-            println!("   subject: {subject}");
-            println!("   date: {date}");
-            println!();
-            println!("   {preview}");
-
-            if index + 1 < messages.len() {
-                println!();
-            }
+        writeln!(out, "{}. {}", index + 1, message.id)?;
+        if let Some(account) = account {
+            writeln!(out, "{account_field}{account}")?;
+        }
+        writeln!(out, "{from_field}{}", ctx.output.paint(Role::Sender, &from))?;
+        writeln!(out, "{subject_field}{subject}")?;
+        writeln!(out, "{date_field}{}", ctx.output.paint(Role::Date, &date))?;
+        writeln!(out)?;
+        for line in format_preview(message.snippet.as_deref(), width) {
+            writeln!(out, "   {line}")?;
         }
 
-        return Ok(());
+        if index + 1 < messages.len() {
+            writeln!(out)?;
+        }
     }
 
-    let text = format!("{} messages", messages.len());
-    ctx.output.emit(&text, &messages)
+    out.finish()
 }
 
-/// Decode HTML entities, collapse whitespace, and truncate a snippet to 120 chars for display.
-fn format_preview(snippet: Option<&str>) -> String {
+/// Decode HTML entities and collapse whitespace in a snippet, then lay it out:
+/// wrapped to the terminal `width` (at most [`PREVIEW_LINES`] lines) on a
+/// terminal, or a single line of at most [`PIPED_PREVIEW_CHARS`] otherwise.
+pub(crate) fn format_preview(snippet: Option<&str>, width: Option<usize>) -> Vec<String> {
     let snippet = snippet.unwrap_or("(no preview)");
     let decoded = html_escape::decode_html_entities(snippet).to_string();
     let compact = decoded.split_whitespace().collect::<Vec<_>>().join(" ");
 
-    if compact.len() <= 120 {
-        return compact;
+    match width {
+        Some(width) => layout::wrap_lines(
+            &compact,
+            width.saturating_sub(PREVIEW_INDENT).max(MIN_PREVIEW_WIDTH),
+            PREVIEW_LINES,
+        ),
+        None => vec![layout::truncate(&compact, PIPED_PREVIEW_CHARS)],
     }
+}
 
-    let mut end = 120;
-    while !compact.is_char_boundary(end) {
-        end -= 1;
+/// Ellipsize `text` so that `label` plus `text` fits in `width`, when known.
+pub(crate) fn fit(text: &str, width: Option<usize>, label: &str) -> String {
+    match width {
+        Some(width) => layout::truncate(
+            text,
+            width
+                .saturating_sub(layout::display_width(label))
+                .max(MIN_PREVIEW_WIDTH),
+        ),
+        None => text.to_string(),
     }
-    format!("{}...", &compact[..end])
 }
 
 /// Combine the `--inbox` flag and a user query into a Gmail search string.
-fn build_query(inbox: bool, user_query: Option<&str>) -> Option<String> {
+/// The query the user asked for: the `--saved` search, if any, then `--q`.
+/// A saved query is parenthesized when `--q` follows, so an `OR` in it
+/// stays inside.
+pub(crate) fn user_query(ctx: &AppContext, args: &ListArgs) -> AppResult<Option<String>> {
+    let saved = args
+        .saved
+        .as_deref()
+        .map(|name| search::saved_query(ctx, name))
+        .transpose()?;
+    Ok(match (saved, args.q.as_deref()) {
+        (Some(saved), Some(q)) => Some(format!("({saved}) {q}")),
+        (Some(saved), None) => Some(saved.to_string()),
+        (None, q) => q.map(ToString::to_string),
+    })
+}
+
+pub(crate) fn build_query(inbox: bool, user_query: Option<&str>) -> Option<String> {
     let user_query = user_query.map(str::trim).filter(|query| !query.is_empty());
 
     match (inbox, user_query) {
@@ -0,0 +1,130 @@
+use std::io::Write;
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::api::audit::{self, AuditEntry, AuditWrite};
+use crate::cli::LogArgs;
+use crate::context::AppContext;
+use crate::error::AppResult;
+use crate::mail::when;
+use crate::output::OutputMode;
+
+/// Ids shown per write before the rest are counted.
+const SHOWN_IDS: usize = 3;
+
+/// Print the audit log's entries for the active profile (or every profile),
+/// oldest first, optionally only those newer than `--since`.
+pub async fn run(ctx: &AppContext, args: LogArgs) -> AppResult<()> {
+    let cutoff = match &args.since {
+        Some(since) => Some(Utc::now() - when::parse_duration(since)?),
+        None => None,
+    };
+    let profile = if args.all_profiles {
+        None
+    } else {
+        Some(ctx.profile()?)
+    };
+    let entries = audit::read(&ctx.paths.audit_file())?
+        .into_iter()
+        .filter(|entry| profile.is_none_or(|profile| entry.profile == profile))
+        .filter(|entry| cutoff.is_none_or(|cutoff| time(entry).is_some_and(|time| time >= cutoff)))
+        .collect::<Vec<_>>();
+    if ctx.output.mode() != OutputMode::Text {
+        return ctx.output.emit("", &entries);
+    }
+
+    let mut out = ctx.output.paged();
+    if entries.is_empty() {
+        writeln!(out, "no changes logged")?;
+        return out.finish();
+    }
+    for entry in &entries {
+        let when = time(entry).map_or_else(
+            || entry.time.clone(),
+            |time| {
+                time.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            },
+        );
+        writeln!(
+            out,
+            "{when}  {}  gmail {}",
+            entry.profile,
+            command_line(&entry.args)
+        )?;
+        for write in &entry.writes {
+            writeln!(out, "  {}", describe(write))?;
+        }
+        if let Some(error) = &entry.error {
+            writeln!(out, "  error: {error}")?;
+        }
+    }
+    out.finish()
+}
+
+fn time(entry: &AuditEntry) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&entry.time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// The arguments as typed, quoting any with spaces.
+fn command_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `messages.batchModify m1, m2, m3 and 997 more +Label_1 -INBOX`.
+fn describe(write: &AuditWrite) -> String {
+    let mut parts = vec![write.operation.clone()];
+    if !write.ids.is_empty() {
+        let mut ids = write.ids[..write.ids.len().min(SHOWN_IDS)].join(", ");
+        if write.ids.len() > SHOWN_IDS {
+            ids.push_str(&format!(" and {} more", write.ids.len() - SHOWN_IDS));
+        }
+        parts.push(ids);
+    }
+    parts.extend(write.added_labels.iter().map(|label| format!("+{label}")));
+    parts.extend(write.removed_labels.iter().map(|label| format!("-{label}")));
+    if write.failed {
+        parts.push("(failed)".to_string());
+    }
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_writes_and_quotes_arguments() {
+        let write = AuditWrite {
+            operation: "messages.batchModify".to_string(),
+            ids: (1..=5).map(|n| format!("m{n}")).collect(),
+            added_labels: vec!["Label_1".to_string()],
+            removed_labels: vec!["INBOX".to_string()],
+            ..AuditWrite::default()
+        };
+        assert_eq!(
+            describe(&write),
+            "messages.batchModify m1, m2, m3 and 2 more +Label_1 -INBOX"
+        );
+        let write = AuditWrite {
+            operation: "messages.send".to_string(),
+            failed: true,
+            ..AuditWrite::default()
+        };
+        assert_eq!(describe(&write), "messages.send (failed)");
+        let args = ["send", "--subject", "it's done"].map(String::from);
+        assert_eq!(command_line(&args), r"send --subject 'it'\''s done'");
+    }
+}
@@ -0,0 +1,55 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::CommandFactory;
+use clap_mangen::Man;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::{Cli, ManArgs};
+use crate::context::AppContext;
+use crate::error::AppResult;
+
+/// The pages written by `man --out`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ManPages {
+    pub out_dir: PathBuf,
+    pub pages: Vec<PathBuf>,
+}
+
+/// Render roff man pages: the root page to stdout, or with `--out` one page per
+/// command and subcommand (`gmail.1`, `gmail-auth.1`, `gmail-auth-login.1`, ...).
+pub async fn run(ctx: &AppContext, args: ManArgs) -> AppResult<()> {
+    let Some(out_dir) = args.out else {
+        let mut stdout = std::io::stdout().lock();
+        Man::new(Cli::command()).render(&mut stdout)?;
+        stdout.flush()?;
+        return Ok(());
+    };
+
+    let pages = write_pages(&out_dir)?;
+    let text = format!("wrote {} man pages to {}", pages.len(), out_dir.display());
+    ctx.output.emit(&text, &ManPages { out_dir, pages })
+}
+
+/// Write a page for the root command and every visible subcommand into `out_dir`.
+pub fn write_pages(out_dir: &Path) -> AppResult<Vec<PathBuf>> {
+    fn generate(command: clap::Command, out_dir: &Path, pages: &mut Vec<PathBuf>) -> AppResult<()> {
+        pages.push(Man::new(command.clone()).generate_to(out_dir)?);
+        for subcommand in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+            generate(subcommand.clone(), out_dir, pages)?;
+        }
+        Ok(())
+    }
+
+    std::fs::create_dir_all(out_dir)?;
+
+    // Building assigns subcommands their `gmail-<sub>` display names, which
+    // become the page file names.
+    let mut command = Cli::command().disable_help_subcommand(true);
+    command.build();
+
+    let mut pages = Vec::new();
+    generate(command, out_dir, &mut pages)?;
+    Ok(pages)
+}
@@ -0,0 +1,16 @@
+use crate::context::AppContext;
+use crate::error::AppResult;
+use crate::mcp::{self, tools};
+
+/// Serve the profile's allowed operations as MCP tools over stdio until the
+/// client closes stdin.
+pub async fn run(ctx: &AppContext) -> AppResult<()> {
+    let tools = tools::allowed(&ctx.settings)?;
+    let names = tools.iter().map(|tool| tool.name()).collect::<Vec<_>>();
+    eprintln!(
+        "gmail mcp serving profile `{}` on stdio: {}",
+        ctx.profile()?,
+        names.join(", ")
+    );
+    mcp::Server::new(tools).serve(ctx).await
+}
@@ -1,9 +1,50 @@
+pub mod alias;
 pub mod aliases;
 pub mod attachments;
 pub mod auth;
+pub mod cleanup;
+pub(crate) mod confirm;
+pub mod contacts;
+pub mod daemon;
+pub mod doctor;
+pub mod draft;
+pub mod dry_run;
+pub mod export;
+pub mod forward;
+pub mod forwarding;
 pub mod get;
+pub mod history;
+pub(crate) mod ids;
+pub mod import;
 pub mod label;
+pub mod legacy_access;
 pub mod list;
+pub mod log;
+pub mod man;
+pub mod mcp;
+pub mod multi;
+pub mod mute;
+pub mod notify;
+pub mod open;
+pub(crate) mod outbox;
 pub mod profile;
+pub mod queue;
+pub mod quota;
+pub mod rsvp;
+pub mod rules;
+pub mod schema;
+pub mod search;
 pub mod send;
+pub mod send_patch;
+pub mod sendas;
+pub mod sendmail;
 pub mod signature;
+pub mod snooze;
+pub mod stats;
+pub mod stream;
+pub mod thread;
+pub mod triage;
+pub mod tui;
+pub mod unsubscribe;
+pub mod usage;
+pub mod watch;
@@ -0,0 +1,271 @@
+use std::cmp::Reverse;
+use std::future::Future;
+use std::io::Write;
+
+use chrono::DateTime;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tokio::task::JoinSet;
+
+use crate::api::models::MessageView;
+use crate::cli::{Command, LegacyAccessCommand, ListArgs, StatsArgs};
+use crate::commands::legacy_access::{self, LegacyAccess};
+use crate::commands::{list, stats};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::stats::{Report, StatRow};
+use crate::output::OutputMode;
+
+/// A listed message and the profile it was found in.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AccountMessage {
+    pub account: String,
+    #[serde(flatten)]
+    pub message: MessageView,
+}
+
+/// A listed id and the profile it belongs to (`list --ids-only`).
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AccountId {
+    pub account: String,
+    pub id: String,
+}
+
+/// One profile's stats report.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AccountReport {
+    pub account: String,
+    #[serde(flatten)]
+    pub report: Report,
+}
+
+/// One profile's IMAP and POP settings.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AccountLegacyAccess {
+    pub account: String,
+    #[serde(flatten)]
+    pub access: LegacyAccess,
+}
+
+/// A report row tagged with its profile, for CSV and tables.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AccountStatRow {
+    pub account: String,
+    #[serde(flatten)]
+    pub row: StatRow,
+}
+
+/// Run a read-only `command` against every profile in `accounts` at once
+/// (`--profiles`) and print the merged results through `ctx`'s output. A
+/// profile that fails is reported and skipped; the run fails only when every
+/// profile does.
+pub async fn run(ctx: &AppContext, accounts: Vec<AppContext>, command: Command) -> AppResult<()> {
+    match command {
+        Command::List(args) => run_list(ctx, accounts, args).await,
+        Command::Stats(args) => run_stats(ctx, accounts, args).await,
+        Command::LegacyAccess(args) if matches!(args.command, LegacyAccessCommand::Status) => {
+            run_legacy_access(ctx, accounts).await
+        }
+        _ => Err(AppError::InvalidInput(
+            "--profiles works with `list`, `stats`, and `legacy-access status` only".to_string(),
+        )),
+    }
+}
+
+async fn run_list(ctx: &AppContext, accounts: Vec<AppContext>, args: ListArgs) -> AppResult<()> {
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+    if args.threads {
+        return Err(AppError::InvalidInput(
+            "--threads doesn't combine with --profiles; list one profile's threads at a time"
+                .to_string(),
+        ));
+    }
+    let user_query = list::user_query(ctx, &args)?;
+    let query = list::build_query(args.inbox, user_query.as_deref());
+
+    if args.ids_only {
+        let query = query.clone();
+        let results = gather(accounts, move |account| {
+            let query = query.clone();
+            async move {
+                let access_token = account.access_token().await?;
+                account
+                    .gmail_client
+                    .list_ids(&access_token, args.limit, query.as_deref())
+                    .await
+            }
+        })
+        .await?;
+        let ids = results
+            .into_iter()
+            .flat_map(|(account, ids)| {
+                ids.into_iter().map(move |id| AccountId {
+                    account: account.clone(),
+                    id,
+                })
+            })
+            .collect::<Vec<_>>();
+        let text = ids
+            .iter()
+            .map(|entry| format!("{}\t{}", entry.account, entry.id))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ctx.output.emit(&text, &ids)?;
+        return ctx.interrupt.check();
+    }
+
+    let results = gather(accounts, move |account| {
+        let query = query.clone();
+        async move {
+            let access_token = account.access_token().await?;
+            account
+                .gmail_client
+                .list(&access_token, args.limit, query.as_deref())
+                .await
+        }
+    })
+    .await?;
+    let mut messages = results
+        .into_iter()
+        .flat_map(|(account, messages)| {
+            messages.into_iter().map(move |message| AccountMessage {
+                account: account.clone(),
+                message,
+            })
+        })
+        .collect::<Vec<_>>();
+    // Newest first across accounts; undated messages sink to the end.
+    messages.sort_by_key(|entry| {
+        Reverse(
+            entry
+                .message
+                .date
+                .as_deref()
+                .and_then(|date| DateTime::parse_from_rfc2822(date).ok()),
+        )
+    });
+
+    if ctx.output.mode() == OutputMode::Text {
+        let entries = messages
+            .iter()
+            .map(|entry| (Some(entry.account.as_str()), &entry.message))
+            .collect::<Vec<_>>();
+        list::write_text(ctx, &entries)?;
+    } else {
+        ctx.output
+            .emit(&format!("{} messages", messages.len()), &messages)?;
+    }
+    ctx.interrupt.check()
+}
+
+async fn run_stats(ctx: &AppContext, accounts: Vec<AppContext>, args: StatsArgs) -> AppResult<()> {
+    let reports = gather(accounts, move |account| {
+        let args = args.clone();
+        async move { stats::report(&account, &args).await }
+    })
+    .await?
+    .into_iter()
+    .map(|(account, report)| AccountReport { account, report })
+    .collect::<Vec<_>>();
+
+    match ctx.output.mode() {
+        OutputMode::Text => {}
+        OutputMode::Csv | OutputMode::Table | OutputMode::Ndjson => {
+            let rows = reports
+                .iter()
+                .flat_map(|entry| {
+                    entry.report.rows().into_iter().map(|row| AccountStatRow {
+                        account: entry.account.clone(),
+                        row,
+                    })
+                })
+                .collect::<Vec<_>>();
+            ctx.output.emit("", &rows)?;
+            return ctx.interrupt.check();
+        }
+        _ => {
+            ctx.output.emit("", &reports)?;
+            return ctx.interrupt.check();
+        }
+    }
+
+    let mut out = ctx.output.paged();
+    for (index, entry) in reports.iter().enumerate() {
+        if index > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "== {} ==", entry.account)?;
+        stats::write_text(&mut out, &entry.report)?;
+    }
+    out.finish()?;
+    ctx.interrupt.check()
+}
+
+async fn run_legacy_access(ctx: &AppContext, accounts: Vec<AppContext>) -> AppResult<()> {
+    let settings = gather(accounts, |account| async move {
+        legacy_access::status(&account).await
+    })
+    .await?
+    .into_iter()
+    .map(|(account, access)| AccountLegacyAccess { account, access })
+    .collect::<Vec<_>>();
+
+    let text = settings
+        .iter()
+        .map(|entry| format!("{}: {}", entry.account, entry.access.describe()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.output.emit(&text, &settings)?;
+    ctx.interrupt.check()
+}
+
+/// Run `task` for every account concurrently and collect the successes as
+/// `(profile, value)` pairs in `--profiles` order. Failures are written to
+/// stderr under their profile; if no account succeeds, the first error is
+/// returned instead.
+async fn gather<T, F, Fut>(accounts: Vec<AppContext>, task: F) -> AppResult<Vec<(String, T)>>
+where
+    T: Send + 'static,
+    F: Fn(AppContext) -> Fut,
+    Fut: Future<Output = AppResult<T>> + Send + 'static,
+{
+    let mut tasks = JoinSet::new();
+    for (index, account) in accounts.into_iter().enumerate() {
+        let profile = account.profile()?.to_string();
+        let run = task(account);
+        tasks.spawn(async move { (index, profile, run.await) });
+    }
+
+    let mut done = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        done.push(joined.map_err(|err| AppError::Config(format!("profile task failed: {err}")))?);
+    }
+    done.sort_by_key(|(index, _, _)| *index);
+
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for (_, profile, result) in done {
+        match result {
+            Ok(value) => results.push((profile, value)),
+            Err(AppError::Interrupted) => {}
+            Err(err) => errors.push((profile, err)),
+        }
+    }
+    // With nothing to show, the first failure becomes the command's error.
+    let first = if results.is_empty() && !errors.is_empty() {
+        Some(errors.remove(0).1)
+    } else {
+        None
+    };
+    for (profile, err) in &errors {
+        eprintln!("{profile}: {err}");
+    }
+    match first {
+        Some(err) => Err(err),
+        None => Ok(results),
+    }
+}
@@ -0,0 +1,214 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{MuteArgs, MuteCommand, UnmuteArgs};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::triage::{self, MessageOutcome};
+use crate::commands::{ids, queue};
+use crate::context::AppContext;
+use crate::error::AppResult;
+use crate::i18n;
+use crate::output::OutputMode;
+use crate::output::text::Role;
+
+/// Inbox threads `mute run` looks through for muted ones, newest first.
+const INBOX_SCAN: u32 = 100;
+
+/// A conversation whose new messages are archived as they arrive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MutedThread {
+    pub id: String,
+    /// When it was muted, RFC 3339 in UTC.
+    pub muted: String,
+}
+
+/// Mute the given threads, or run a `ls`/`run` subcommand.
+pub async fn run(ctx: &AppContext, args: MuteArgs) -> AppResult<()> {
+    match args.command {
+        Some(MuteCommand::Ls) => ls(ctx),
+        Some(MuteCommand::Run) => {
+            if ctx.dry_run {
+                let muted = load(&ctx.paths.muted_file(ctx.profile()?))?;
+                let change = PlannedChange::new("mute run", "archive muted threads in the inbox")
+                    .with_ids(muted.into_iter().map(|thread| thread.id).collect())
+                    .with_labels(&[], &["INBOX".to_string()]);
+                return dry_run::emit(ctx, &change);
+            }
+            let outcomes = archive_muted(ctx).await?;
+            if outcomes.is_empty() {
+                return ctx
+                    .output
+                    .emit(&i18n::text("muted-nothing-in-inbox"), &outcomes);
+            }
+            triage::report(ctx, "archive muted thread", "archived", &outcomes)
+        }
+        None => mute(ctx, ids::expand(args.ids)?).await,
+    }
+}
+
+/// Archive each thread and note it in the profile's muted store, so replies
+/// that bring it back to the inbox are archived too.
+async fn mute(ctx: &AppContext, ids: Vec<String>) -> AppResult<()> {
+    let inbox = ["INBOX".to_string()];
+    if ctx.dry_run {
+        let change = PlannedChange::new("mute", "mute threads")
+            .with_ids(ids)
+            .with_labels(&[], &inbox);
+        return dry_run::emit(ctx, &change);
+    }
+
+    let inbox = &inbox;
+    let outcomes = triage::for_each_id(ctx, ids, |id, token| async move {
+        ctx.gmail_client
+            .modify_thread(&id, &[], inbox, &token)
+            .await
+            .map(|_| ())
+    })
+    .await?;
+
+    let path = ctx.paths.muted_file(ctx.profile()?);
+    let mut muted = load(&path)?;
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    for outcome in outcomes.iter().filter(|outcome| outcome.ok) {
+        if !muted.iter().any(|thread| thread.id == outcome.id) {
+            muted.push(MutedThread {
+                id: outcome.id.clone(),
+                muted: now.clone(),
+            });
+        }
+    }
+    save(&path, &muted)?;
+    triage::report(ctx, "mute", "muted", &outcomes)
+}
+
+/// Forget muted threads; later replies stay in the inbox again. Threads
+/// already archived are left where they are.
+pub async fn unmute(ctx: &AppContext, args: UnmuteArgs) -> AppResult<()> {
+    let ids = ids::expand(args.ids)?;
+    if ctx.dry_run {
+        let change = PlannedChange::new("unmute", "unmute threads").with_ids(ids);
+        return dry_run::emit(ctx, &change);
+    }
+
+    let path = ctx.paths.muted_file(ctx.profile()?);
+    let mut muted = load(&path)?;
+    let outcomes = ids
+        .into_iter()
+        .map(|id| {
+            let before = muted.len();
+            muted.retain(|thread| thread.id != id);
+            let error = (muted.len() == before)
+                .then(|| format!("thread `{id}` is not muted; see `gmail mute ls`"));
+            MessageOutcome {
+                id,
+                ok: error.is_none(),
+                error,
+            }
+        })
+        .collect::<Vec<_>>();
+    save(&path, &muted)?;
+    triage::report(ctx, "unmute", "unmuted", &outcomes)
+}
+
+/// Archive muted threads found among the newest inbox threads. Used by `mute
+/// run` and on every daemon tick; with nothing muted it makes no calls.
+pub(crate) async fn archive_muted(ctx: &AppContext) -> AppResult<Vec<MessageOutcome>> {
+    let muted = load(&ctx.paths.muted_file(ctx.profile()?))?;
+    if muted.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let access_token = ctx.access_token().await?;
+    let inbox_ids = ctx
+        .gmail_client
+        .list_thread_ids(&access_token, INBOX_SCAN, Some("in:inbox"))
+        .await?;
+    let back = inbox_ids
+        .into_iter()
+        .filter(|id| muted.iter().any(|thread| &thread.id == id))
+        .collect::<Vec<_>>();
+    let inbox = &["INBOX".to_string()];
+    triage::for_each_id(ctx, back, |id, token| async move {
+        ctx.gmail_client
+            .modify_thread(&id, &[], inbox, &token)
+            .await
+            .map(|_| ())
+    })
+    .await
+}
+
+fn ls(ctx: &AppContext) -> AppResult<()> {
+    let muted = load(&ctx.paths.muted_file(ctx.profile()?))?;
+    if ctx.output.mode() != OutputMode::Text {
+        let text = i18n::format("muted-count", &[("count", muted.len().into())]);
+        return ctx.output.emit(&text, &muted);
+    }
+
+    let mut out = ctx.output.paged();
+    if muted.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            i18n::format("muted-count", &[("count", 0.into())])
+        )?;
+        return out.finish();
+    }
+    for thread in &muted {
+        writeln!(
+            out,
+            "{} | muted {}",
+            thread.id,
+            ctx.output
+                .paint(Role::Date, &queue::local_time(&thread.muted))
+        )?;
+    }
+    out.finish()
+}
+
+fn load(path: &Path) -> AppResult<Vec<MutedThread>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save(path: &Path, muted: &[MutedThread]) -> AppResult<()> {
+    if muted.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(muted)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_threads_and_removes_an_empty_store() {
+        let dir = std::env::temp_dir().join(format!("gmail-mute-test-{}", std::process::id()));
+        let path = dir.join("muted.json");
+        let thread = MutedThread {
+            id: "t1".to_string(),
+            muted: "2024-06-01T10:00:00Z".to_string(),
+        };
+        save(&path, std::slice::from_ref(&thread)).unwrap();
+        assert_eq!(load(&path).unwrap(), [thread]);
+
+        save(&path, &[]).unwrap();
+        assert!(!path.exists());
+        assert!(load(&path).unwrap().is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use crate::cli::NotifyArgs;
+use crate::context::AppContext;
+use crate::daemon::notify::MailNotifier;
+use crate::error::{AppError, AppResult};
+
+/// Poll for new messages matching `--q` (or the profile's notify channels)
+/// until interrupted, raising a desktop notification and printing a line for
+/// each arrival that isn't muted or held back by quiet hours.
+pub async fn run(ctx: &AppContext, args: NotifyArgs) -> AppResult<()> {
+    if args.interval == 0 {
+        return Err(AppError::InvalidInput(
+            "--interval must be greater than 0".to_string(),
+        ));
+    }
+    ctx.output.require_stdout("notify")?;
+
+    let mut notifier = MailNotifier::new(args.q, ctx.settings.notify.as_ref())?;
+    eprintln!(
+        "watching `{}` for new mail (every {}s); ctrl-c to stop",
+        notifier.queries().join("`, `"),
+        args.interval
+    );
+
+    loop {
+        match notifier.poll(ctx).await {
+            Ok(arrived) => {
+                for message in &arrived {
+                    let from = message.from.as_deref().unwrap_or("(unknown sender)");
+                    let subject = message.subject.as_deref().unwrap_or("(no subject)");
+                    let text = format!("{} | {} | {}", message.id, from, subject);
+                    ctx.output.emit(&text, message)?;
+                }
+            }
+            Err(err) => eprintln!("new-mail check failed: {err}"),
+        }
+
+        ctx.interrupt
+            .sleep(Duration::from_secs(args.interval))
+            .await?;
+    }
+}
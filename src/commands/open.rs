@@ -0,0 +1,39 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::auth::token_store::TokenStore;
+use crate::cli::OpenArgs;
+use crate::context::AppContext;
+use crate::daemon::notify;
+use crate::desktop;
+use crate::error::AppResult;
+
+/// Where `gmail open` sent the browser.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OpenedMessage {
+    pub id: String,
+    pub url: String,
+    /// Whether a browser was launched; when not, the URL is printed to open by hand.
+    pub opened: bool,
+}
+
+/// Open a message or conversation in the Gmail web UI, in the signed-in
+/// account when the profile knows its address, printing the URL when no
+/// browser can be launched.
+pub fn run(ctx: &AppContext, args: OpenArgs) -> AppResult<()> {
+    let account = ctx
+        .token_store
+        .load(ctx.profile()?)?
+        .and_then(|token| token.email);
+    let url = notify::web_url(account.as_deref(), &args.id);
+    let opened = !args.print && desktop::open_browser(&url);
+    if !opened && !args.print {
+        eprintln!("could not open a browser; open the message at the URL below");
+    }
+    let opened_message = OpenedMessage {
+        id: args.id,
+        url: url.clone(),
+        opened,
+    };
+    ctx.output.emit(&url, &opened_message)
+}
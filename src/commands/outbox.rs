@@ -0,0 +1,256 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::models::SendResult;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::{address, rfc822};
+
+/// Journal entries older than this are forgotten rather than checked.
+const STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Pause before looking for a send that failed ambiguously, giving Gmail time
+/// to index it.
+const CONFIRM_DELAY: Duration = Duration::from_secs(3);
+
+/// Recent sent messages compared by Message-ID when search finds nothing.
+const RECENT_SENT: u32 = 10;
+
+/// A send whose outcome is not known yet: written before the API call and
+/// removed once Gmail accepts or clearly rejects the message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PendingSend {
+    /// SHA-256 of the message as the caller built it.
+    hash: String,
+    message_id: String,
+    started_unix: u64,
+}
+
+/// Send a base64url raw message without risking a duplicate.
+///
+/// The message gets a Message-ID (unless it has one) and is journaled under
+/// the profile's outbox file for the duration of the call. A timeout or 5xx
+/// leaves it unclear whether Gmail accepted it, so before retrying once we
+/// look the Message-ID up in the mailbox. A journal entry left behind by an
+/// earlier run for identical content is checked the same way first.
+pub(crate) async fn send(
+    ctx: &AppContext,
+    raw: &str,
+    thread_id: Option<&str>,
+    access_token: &str,
+) -> AppResult<SendResult> {
+    let message = URL_SAFE_NO_PAD
+        .decode(raw.trim_end_matches('='))
+        .map_err(|err| AppError::InvalidInput(format!("message is not base64url: {err}")))?;
+    let hash = hex(&Sha256::digest(&message));
+    let path = ctx.paths.outbox_file(ctx.profile()?);
+    let now = unix_now();
+
+    let mut journal = load(&path)?;
+    journal.retain(|entry| now.saturating_sub(entry.started_unix) < STALE_AFTER.as_secs());
+    let earlier = journal.iter().position(|entry| entry.hash == hash);
+
+    let (headers, _) = rfc822::headers(&message);
+    let existing_id = rfc822::values(&headers, "Message-ID")
+        .first()
+        .map(|id| id.to_string());
+    let message_id = match (earlier, existing_id) {
+        (Some(index), _) => journal[index].message_id.clone(),
+        (None, Some(id)) => id,
+        (None, None) => new_message_id(rfc822::values(&headers, "From").first().copied()),
+    };
+
+    if let Some(index) = earlier {
+        eprintln!("an earlier attempt to send this message may have gone through; checking");
+        if let Some(id) = find_sent(ctx, &message_id, access_token).await? {
+            journal.remove(index);
+            save(&path, &journal)?;
+            return Ok(SendResult {
+                id,
+                thread_id: None,
+                note: "already sent by an earlier attempt; not sent again".to_string(),
+            });
+        }
+        journal.remove(index);
+    }
+
+    let raw = if rfc822::values(&headers, "Message-ID").is_empty() {
+        let mut stamped = format!("Message-ID: {message_id}\r\n").into_bytes();
+        stamped.extend_from_slice(&message);
+        URL_SAFE_NO_PAD.encode(stamped)
+    } else {
+        URL_SAFE_NO_PAD.encode(&message)
+    };
+
+    journal.push(PendingSend {
+        hash,
+        message_id: message_id.clone(),
+        started_unix: now,
+    });
+    save(&path, &journal)?;
+
+    let mut retried = false;
+    let outcome = loop {
+        let err = match ctx.gmail_client.send(&raw, thread_id, access_token).await {
+            Ok(result) => break Ok(result),
+            Err(err) => err,
+        };
+        if !ambiguous(&err) {
+            break Err(err);
+        }
+
+        ctx.interrupt.sleep(CONFIRM_DELAY).await?;
+        match find_sent(ctx, &message_id, access_token).await {
+            Ok(Some(id)) => {
+                break Ok(SendResult {
+                    id,
+                    thread_id: None,
+                    note: format!("confirmed sent after: {err}"),
+                });
+            }
+            Ok(None) if !retried => {
+                eprintln!("send failed ({err}) and the message is not in the mailbox; retrying");
+                retried = true;
+            }
+            // Still unknown: keep the journal entry so the next run checks first.
+            _ => {
+                return Err(AppError::Api(crate::error::ApiError::new(format!(
+                    "{err}; the message may still have been sent. Run the same command again to check before sending"
+                ))));
+            }
+        }
+    };
+
+    let mut journal = load(&path)?;
+    journal.retain(|entry| entry.message_id != message_id);
+    save(&path, &journal)?;
+    outcome
+}
+
+/// Whether Gmail may have accepted the message despite the error: the
+/// request went out but no answer came back (timeout, dropped connection)
+/// or the server failed.
+fn ambiguous(err: &AppError) -> bool {
+    match err {
+        AppError::Http(err) => !err.is_connect() && !err.is_builder(),
+        other => other.status().is_some_and(|status| status >= 500),
+    }
+}
+
+/// Look for a message with `message_id`: by `rfc822msgid:` search, then among
+/// the most recent sent messages in case the search index lags.
+async fn find_sent(
+    ctx: &AppContext,
+    message_id: &str,
+    access_token: &str,
+) -> AppResult<Option<String>> {
+    let bare = message_id
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+    let query = format!("rfc822msgid:{bare}");
+    if let Some(id) = ctx
+        .gmail_client
+        .list_ids(access_token, 1, Some(&query))
+        .await?
+        .into_iter()
+        .next()
+    {
+        return Ok(Some(id));
+    }
+
+    for id in ctx
+        .gmail_client
+        .list_ids(access_token, RECENT_SENT, Some("in:sent"))
+        .await?
+    {
+        let headers = ctx
+            .gmail_client
+            .get_headers(&id, &["Message-ID"], access_token)
+            .await?;
+        if headers
+            .iter()
+            .any(|(_, value)| value.trim().trim_matches(['<', '>']) == bare)
+        {
+            return Ok(Some(id));
+        }
+    }
+    Ok(None)
+}
+
+/// A fresh `<random@domain>` Message-ID, on the sender's domain when known.
+fn new_message_id(from: Option<&str>) -> String {
+    let domain = from
+        .map(address::email_of)
+        .and_then(|email| email.split_once('@').map(|(_, domain)| domain.to_string()))
+        .filter(|domain| !domain.is_empty())
+        .unwrap_or_else(|| "gmail-cli.invalid".to_string());
+    let token = hex(&rand::random::<[u8; 16]>());
+    format!("<{token}@{domain}>")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn load(path: &Path) -> AppResult<Vec<PendingSend>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save(path: &Path, journal: &[PendingSend]) -> AppResult<()> {
+    if journal.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(journal)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_ids_use_the_sender_domain() {
+        let id = new_message_id(Some("Ada <ada@example.com>"));
+        assert!(id.starts_with('<') && id.ends_with("@example.com>"), "{id}");
+        assert_ne!(id, new_message_id(Some("Ada <ada@example.com>")));
+        assert!(new_message_id(None).ends_with("@gmail-cli.invalid>"));
+    }
+
+    #[test]
+    fn only_unanswered_and_server_failures_are_ambiguous() {
+        use crate::error::ApiError;
+        assert!(ambiguous(&AppError::Api(ApiError::http(
+            503,
+            "unavailable"
+        ))));
+        assert!(!ambiguous(&AppError::Api(ApiError::http(
+            400,
+            "bad request"
+        ))));
+        assert!(!ambiguous(&AppError::Api(ApiError::http(429, "slow down"))));
+        assert!(!ambiguous(&AppError::InvalidInput("nope".to_string())));
+    }
+}
@@ -1,16 +1,40 @@
-use serde_json::json;
+use schemars::JsonSchema;
+use serde::Serialize;
 
 use crate::cli::ProfileCommand;
+use crate::commands::dry_run::{self, PlannedChange};
 use crate::config;
 use crate::context::AppContext;
 use crate::error::{AppError, AppResult};
 
-/// Dispatch a `gmail profile` subcommand (list/use/show) and emit its result.
+/// `profile list`: every profile on disk and the configured default.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ProfileList {
+    pub profiles: Vec<String>,
+    pub default: Option<String>,
+}
+
+/// `profile use`: the new default profile.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DefaultProfile {
+    pub default: String,
+}
+
+/// `profile show`: the resolved profile, or the candidates when none resolves.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ResolvedProfile {
+    pub profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profiles: Option<Vec<String>>,
+}
+
+/// Dispatch a `gmail profile` subcommand (list/use/show/info) and emit its result.
 pub async fn run(ctx: &AppContext, command: ProfileCommand) -> AppResult<()> {
     match command {
         ProfileCommand::List => list(ctx),
         ProfileCommand::Use { name } => use_profile(ctx, &name),
         ProfileCommand::Show => show(ctx),
+        ProfileCommand::Info => info(ctx).await,
     }
 }
 
@@ -22,7 +46,7 @@ fn list(ctx: &AppContext) -> AppResult<()> {
     if profiles.is_empty() {
         return ctx.output.emit(
             "no profiles configured. run `gmail auth login` to create one",
-            &json!({ "profiles": [], "default": default }),
+            &ProfileList { profiles, default },
         );
     }
 
@@ -37,10 +61,8 @@ fn list(ctx: &AppContext) -> AppResult<()> {
         })
         .collect();
 
-    ctx.output.emit(
-        &lines.join("\n"),
-        &json!({ "profiles": profiles, "default": default }),
-    )
+    ctx.output
+        .emit(&lines.join("\n"), &ProfileList { profiles, default })
 }
 
 /// Set the default profile, verifying it exists first.
@@ -57,13 +79,21 @@ fn use_profile(ctx: &AppContext, name: &str) -> AppResult<()> {
         )));
     }
 
+    if ctx.dry_run {
+        let change =
+            PlannedChange::new("profile use", "set the default profile").with("profile", name);
+        return dry_run::emit(ctx, &change);
+    }
+
     let mut app_config = config::load_app_config(ctx.paths.config_file())?;
     app_config.default_profile = Some(name.to_string());
     config::save_app_config(ctx.paths.config_file(), &app_config)?;
 
     ctx.output.emit(
         &format!("default profile set to `{name}`"),
-        &json!({ "default": name }),
+        &DefaultProfile {
+            default: name.to_string(),
+        },
     )
 }
 
@@ -72,7 +102,10 @@ fn show(ctx: &AppContext) -> AppResult<()> {
     match ctx.profile() {
         Ok(profile) => ctx.output.emit(
             &format!("resolved profile: {profile}"),
-            &json!({ "profile": profile }),
+            &ResolvedProfile {
+                profile: Some(profile.to_string()),
+                profiles: None,
+            },
         ),
         Err(_) => {
             let profiles = ctx.paths.list_profiles()?;
@@ -81,8 +114,23 @@ fn show(ctx: &AppContext) -> AppResult<()> {
                     "no default profile set. profiles: {}. run `gmail profile use <name>`",
                     profiles.join(", ")
                 ),
-                &json!({ "profile": null, "profiles": profiles }),
+                &ResolvedProfile {
+                    profile: None,
+                    profiles: Some(profiles),
+                },
             )
         }
     }
 }
+
+/// Fetch the mailbox behind the profile with `users.getProfile`, a cheap
+/// check that its login works.
+async fn info(ctx: &AppContext) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let profile = ctx.gmail_client.get_profile(&access_token).await?;
+    let text = format!(
+        "email: {}\nmessages: {}\nthreads: {}\nhistory id: {}",
+        profile.email, profile.messages_total, profile.threads_total, profile.history_id
+    );
+    ctx.output.emit(&text, &profile)
+}
@@ -0,0 +1,372 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{DateTime, Local, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::SendRequest;
+use crate::cli::{QueueCommand, QueueRmArgs, SendArgs};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::triage::{self, MessageOutcome};
+use crate::commands::{ids, outbox};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+use crate::mail::{mime, when};
+use crate::output::OutputMode;
+use crate::output::text::Role;
+
+/// A message waiting in the local send queue for its due time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct QueuedMessage {
+    pub id: String,
+    /// When to send, RFC 3339 in UTC.
+    pub due: String,
+    /// When it was queued, RFC 3339 in UTC.
+    pub queued: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub thread_id: Option<String>,
+}
+
+/// A queue entry as stored: the summary plus the fully built raw message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    #[serde(flatten)]
+    message: QueuedMessage,
+    raw: String,
+}
+
+/// Dispatch a `gmail queue` subcommand.
+pub async fn run(ctx: &AppContext, command: QueueCommand) -> AppResult<()> {
+    match command {
+        QueueCommand::Ls => ls(ctx),
+        QueueCommand::Rm(args) => rm(ctx, args),
+        QueueCommand::Run => {
+            let path = ctx.paths.queue_file(ctx.profile()?);
+            if ctx.dry_run {
+                let due = due_entries(&load(&path)?, Utc::now());
+                let change = PlannedChange::new("queue run", "send queued messages")
+                    .with_ids(due.into_iter().map(|entry| entry.message.id).collect());
+                return dry_run::emit(ctx, &change);
+            }
+            let outcomes = dispatch_due(ctx).await?;
+            if outcomes.is_empty() && ctx.output.mode() == OutputMode::Text {
+                println!("{}", i18n::text("queue-nothing-due"));
+                return Ok(());
+            }
+            triage::report(ctx, "send queued message", "sent", &outcomes)
+        }
+    }
+}
+
+/// The due time `send --at`/`--in` asks for, in UTC. `--at` is local time
+/// (`mon 9:30am`, `2025-03-04 14:00`); `--in` is a duration from now.
+pub(crate) fn due_time(args: &SendArgs) -> AppResult<Option<DateTime<Utc>>> {
+    if let Some(text) = args.in_.as_deref() {
+        return Ok(Some(Utc::now() + when::parse_duration(text)?));
+    }
+    let Some(text) = args.at.as_deref() else {
+        return Ok(None);
+    };
+    let local = when::parse_when(text, Local::now().naive_local())?;
+    in_local_time(local, text).map(Some)
+}
+
+/// `local`, read from `text`, as a UTC time.
+pub(crate) fn in_local_time(local: NaiveDateTime, text: &str) -> AppResult<DateTime<Utc>> {
+    let time = Local
+        .from_local_datetime(&local)
+        .earliest()
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("`{text}` falls in a daylight-saving gap"))
+        })?;
+    Ok(time.with_timezone(&Utc))
+}
+
+/// Encode `request` and store it in the profile's queue to go out at `due`.
+pub(crate) fn enqueue(
+    ctx: &AppContext,
+    command: &str,
+    request: &SendRequest,
+    due: DateTime<Utc>,
+) -> AppResult<()> {
+    let raw = mime::build_raw_message(request);
+    let due_text = due.to_rfc3339_opts(SecondsFormat::Secs, true);
+    if ctx.dry_run {
+        let mut change = PlannedChange::send(command, request, &raw).with("due", due_text);
+        change.action = "queue message".to_string();
+        return dry_run::emit(ctx, &change);
+    }
+
+    let path = ctx.paths.queue_file(ctx.profile()?);
+    let mut queue = load(&path)?;
+    let message = QueuedMessage {
+        id: format!("{:08x}", rand::random::<u32>()),
+        due: due_text,
+        queued: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        to: request.to.clone(),
+        subject: request.subject.clone(),
+        thread_id: request.thread_id.clone(),
+    };
+    queue.push(Entry {
+        message: message.clone(),
+        raw,
+    });
+    save(&path, &queue)?;
+
+    let text = i18n::format(
+        "queued-message",
+        &[
+            ("id", message.id.as_str().into()),
+            ("due", local_time(&message.due).into()),
+        ],
+    );
+    ctx.output.emit(&text, &message)
+}
+
+/// Send every queued message that is due, oldest due first, dropping each
+/// from the queue once Gmail accepts it or rejects it outright (a 4xx other
+/// than 429). Other failures (offline, 5xx, rate limits, a send that may have
+/// gone out) stay queued for the next run. Used by `queue run` and on every
+/// daemon tick.
+pub(crate) async fn dispatch_due(ctx: &AppContext) -> AppResult<Vec<MessageOutcome>> {
+    let path = ctx.paths.queue_file(ctx.profile()?);
+    let due = due_entries(&load(&path)?, Utc::now());
+    if due.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut outcomes = Vec::new();
+    for entry in due {
+        if ctx.interrupt.is_set() {
+            break;
+        }
+        let access_token = ctx.access_token().await?;
+        let id = entry.message.id.clone();
+        let result = outbox::send(
+            ctx,
+            &entry.raw,
+            entry.message.thread_id.as_deref(),
+            &access_token,
+        )
+        .await;
+        let (outcome, done) = match result {
+            Ok(_) => (
+                MessageOutcome {
+                    id,
+                    ok: true,
+                    error: None,
+                },
+                true,
+            ),
+            // Keep the message queued until the profile can sign in again.
+            Err(err @ AppError::Auth(_)) => return Err(err),
+            Err(err) => (
+                MessageOutcome {
+                    id,
+                    ok: false,
+                    error: Some(err.to_string()),
+                },
+                rejected(&err),
+            ),
+        };
+
+        if done {
+            let mut queue = load(&path)?;
+            queue.retain(|queued| queued.message.id != outcome.id);
+            save(&path, &queue)?;
+        }
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+/// Whether Gmail turned the message down for good, so retrying it unchanged
+/// would fail the same way.
+fn rejected(err: &AppError) -> bool {
+    err.status()
+        .is_some_and(|status| (400..500).contains(&status) && status != 429)
+}
+
+fn ls(ctx: &AppContext) -> AppResult<()> {
+    let mut queue = load(&ctx.paths.queue_file(ctx.profile()?))?;
+    queue.sort_by_key(|entry| due_at(&entry.message));
+    let messages: Vec<QueuedMessage> = queue.into_iter().map(|entry| entry.message).collect();
+    if ctx.output.mode() != OutputMode::Text {
+        let text = i18n::format("queue-count", &[("count", messages.len().into())]);
+        return ctx.output.emit(&text, &messages);
+    }
+
+    let mut out = ctx.output.paged();
+    if messages.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            i18n::format("queue-count", &[("count", 0.into())])
+        )?;
+        return out.finish();
+    }
+    let no_subject = i18n::text("no-subject");
+    for message in &messages {
+        let subject = if message.subject.is_empty() {
+            no_subject.as_str()
+        } else {
+            message.subject.as_str()
+        };
+        writeln!(
+            out,
+            "{} | {} | {} | {}",
+            message.id,
+            ctx.output.paint(Role::Date, &local_time(&message.due)),
+            ctx.output.paint(Role::Sender, &message.to.join(", ")),
+            subject
+        )?;
+    }
+    out.finish()
+}
+
+/// Cancel queued messages before they go out.
+fn rm(ctx: &AppContext, args: QueueRmArgs) -> AppResult<()> {
+    let ids = ids::expand(args.ids)?;
+    if ctx.dry_run {
+        let change = PlannedChange::new("queue rm", "cancel queued messages").with_ids(ids);
+        return dry_run::emit(ctx, &change);
+    }
+
+    let path = ctx.paths.queue_file(ctx.profile()?);
+    let mut queue = load(&path)?;
+    let outcomes = ids
+        .into_iter()
+        .map(|id| {
+            let before = queue.len();
+            queue.retain(|entry| entry.message.id != id);
+            let error = (queue.len() == before)
+                .then(|| format!("no queued message `{id}`; see `gmail queue ls`"));
+            MessageOutcome {
+                id,
+                ok: error.is_none(),
+                error,
+            }
+        })
+        .collect::<Vec<_>>();
+    save(&path, &queue)?;
+    triage::report(ctx, "cancel queued message", "cancelled", &outcomes)
+}
+
+/// Entries due at or before `now`, earliest first.
+fn due_entries(queue: &[Entry], now: DateTime<Utc>) -> Vec<Entry> {
+    let mut due: Vec<Entry> = queue
+        .iter()
+        .filter(|entry| due_at(&entry.message) <= now)
+        .cloned()
+        .collect();
+    due.sort_by_key(|entry| due_at(&entry.message));
+    due
+}
+
+/// A queued message's due time; an unreadable one counts as due now.
+fn due_at(message: &QueuedMessage) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(&message.due)
+        .map(|due| due.with_timezone(&Utc))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// An RFC 3339 time as local `YYYY-MM-DD HH:MM`.
+pub(crate) fn local_time(rfc3339: &str) -> String {
+    DateTime::parse_from_rfc3339(rfc3339)
+        .map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| rfc3339.to_string())
+}
+
+fn load(path: &Path) -> AppResult<Vec<Entry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save(path: &Path, queue: &[Entry]) -> AppResult<()> {
+    if queue.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(queue)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, due: &str) -> Entry {
+        Entry {
+            message: QueuedMessage {
+                id: id.to_string(),
+                due: due.to_string(),
+                queued: "2024-05-01T08:00:00Z".to_string(),
+                to: vec!["ada@example.com".to_string()],
+                subject: "Hi".to_string(),
+                thread_id: None,
+            },
+            raw: "cmF3".to_string(),
+        }
+    }
+
+    #[test]
+    fn picks_due_entries_earliest_first() {
+        let queue = [
+            entry("late", "2024-06-01T10:00:00Z"),
+            entry("future", "2024-06-02T09:00:00Z"),
+            entry("early", "2024-06-01T09:00:00+02:00"),
+        ];
+        let now = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let due: Vec<String> = due_entries(&queue, now)
+            .into_iter()
+            .map(|entry| entry.message.id)
+            .collect();
+        assert_eq!(due, ["early", "late"]);
+    }
+
+    #[test]
+    fn keeps_messages_gmail_did_not_reject_outright() {
+        use crate::error::ApiError;
+
+        assert!(rejected(&AppError::Api(ApiError::http(400, "bad"))));
+        assert!(!rejected(&AppError::Api(ApiError::http(429, "slow down"))));
+        assert!(!rejected(&AppError::Api(ApiError::http(
+            503,
+            "unavailable"
+        ))));
+        assert!(!rejected(&AppError::api("connection reset")));
+    }
+
+    #[test]
+    fn stores_the_raw_message_beside_the_summary() {
+        let dir = std::env::temp_dir().join(format!("gmail-queue-test-{}", std::process::id()));
+        let path = dir.join("queue.json");
+        save(&path, &[entry("a1", "2024-06-01T10:00:00Z")]).unwrap();
+        let stored: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored[0]["id"], "a1");
+        assert_eq!(stored[0]["raw"], "cmF3");
+        assert_eq!(load(&path).unwrap(), [entry("a1", "2024-06-01T10:00:00Z")]);
+
+        save(&path, &[]).unwrap();
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
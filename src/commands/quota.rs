@@ -0,0 +1,74 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::quota::{DayUsage, PER_USER_UNITS_PER_MINUTE, QuotaLedger};
+use crate::cli::QuotaArgs;
+use crate::context::AppContext;
+use crate::error::AppResult;
+use crate::output::OutputMode;
+
+/// A profile's recorded quota use for `gmail quota`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct QuotaSummary {
+    pub profile: String,
+    /// Gmail's per-user rate limit, for comparison.
+    pub per_user_units_per_minute: u32,
+    /// Oldest first; days without API calls are absent.
+    pub days: Vec<DayUsage>,
+}
+
+/// Print the estimated quota units the profile used on each of the last
+/// `--days` days, with the latest day broken down by command and API method.
+pub async fn run(ctx: &AppContext, args: QuotaArgs) -> AppResult<()> {
+    let profile = ctx.profile()?;
+    let ledger = QuotaLedger::load(&ctx.paths.quota_file(profile))?;
+    let summary = QuotaSummary {
+        profile: profile.to_string(),
+        per_user_units_per_minute: PER_USER_UNITS_PER_MINUTE,
+        days: ledger.recent(args.days).to_vec(),
+    };
+    if ctx.output.mode() != OutputMode::Text {
+        return ctx.output.emit("", &summary);
+    }
+
+    let mut out = ctx.output.paged();
+    let Some(latest) = summary.days.last() else {
+        writeln!(out, "no Gmail API use recorded for {profile} yet")?;
+        return out.finish();
+    };
+    writeln!(
+        out,
+        "estimated Gmail API quota use for {profile} (limit: {PER_USER_UNITS_PER_MINUTE} units per user per minute)"
+    )?;
+    for day in &summary.days {
+        writeln!(
+            out,
+            "  {}  {:>8} units  {:>6} requests",
+            day.date, day.units, day.requests
+        )?;
+    }
+
+    writeln!(out, "\n{} by command", latest.date)?;
+    for (command, units) in by_units(&latest.commands) {
+        writeln!(out, "  {units:>8}  {command}")?;
+    }
+    writeln!(out, "\n{} by method", latest.date)?;
+    for (operation, units) in by_units(&latest.operations) {
+        writeln!(out, "  {units:>8}  {operation}")?;
+    }
+    out.finish()
+}
+
+/// Entries ordered by units, largest first.
+fn by_units(totals: &BTreeMap<String, u32>) -> Vec<(&str, u32)> {
+    let mut entries = totals
+        .iter()
+        .map(|(name, units)| (name.as_str(), *units))
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(_, units)| Reverse(*units));
+    entries
+}
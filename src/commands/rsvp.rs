@@ -0,0 +1,130 @@
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::{Attachment, SendRequest};
+use crate::cli::{RsvpArgs, RsvpResponse};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::outbox;
+use crate::commands::send::{merge_references, resolve_from_header};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::ics::{self, PartStat};
+use crate::mail::{address, mime};
+
+/// What was sent back to the organizer.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RsvpResult {
+    pub id: String,
+    pub thread_id: Option<String>,
+    pub uid: String,
+    pub organizer: String,
+    pub partstat: &'static str,
+}
+
+/// Answer the invitation in message `id` with a `METHOD:REPLY` calendar
+/// attachment addressed to the organizer, threaded under the invite, so the
+/// organizer's calendar records the new participation status.
+pub async fn run(ctx: &AppContext, args: RsvpArgs) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let calendar = ctx
+        .gmail_client
+        .get_calendar_part(&args.id, &access_token)
+        .await?
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("message {} has no calendar invitation", args.id))
+        })?;
+    let invite = ics::parse_invite(&calendar)?;
+    if let Some(method) = invite
+        .method
+        .as_deref()
+        .filter(|method| *method != "REQUEST")
+    {
+        return Err(AppError::InvalidInput(format!(
+            "message {} carries a calendar {method}, not an invitation to answer",
+            args.id
+        )));
+    }
+    let organizer = invite.organizer_email().ok_or_else(|| {
+        AppError::InvalidInput("calendar invite has no mailto: organizer".to_string())
+    })?;
+
+    let from = resolve_from_header(ctx, &access_token, None)
+        .await?
+        .ok_or_else(|| {
+            AppError::auth("no account email stored; run `gmail auth login` again".to_string())
+        })?;
+    let email = address::email_of(&from);
+    let attendee = invite.attendee(&email);
+    if attendee.is_none() {
+        eprintln!("warning: {email} is not listed as an attendee; replying for it anyway");
+    }
+
+    let partstat = match args.response {
+        RsvpResponse::Accept => PartStat::Accepted,
+        RsvpResponse::Decline => PartStat::Declined,
+        RsvpResponse::Tentative => PartStat::Tentative,
+    };
+    let reply = ics::build_reply(
+        &invite,
+        &email,
+        attendee,
+        partstat,
+        args.comment.as_deref(),
+        Utc::now(),
+    );
+
+    let parent = ctx.gmail_client.get_msg(&args.id, &access_token).await?;
+    let summary = invite
+        .summary
+        .clone()
+        .unwrap_or_else(|| "(no title)".to_string());
+    let mut body = format!(
+        "{from} has {} this invitation: **{summary}**",
+        match partstat {
+            PartStat::Accepted => "accepted",
+            PartStat::Declined => "declined",
+            PartStat::Tentative => "tentatively accepted",
+        }
+    );
+    if let Some(comment) = args.comment.as_deref().filter(|c| !c.trim().is_empty()) {
+        body.push_str(&format!("\n\n{comment}"));
+    }
+
+    let request = SendRequest {
+        from: Some(from),
+        to: vec![organizer.clone()],
+        cc: Vec::new(),
+        bcc: Vec::new(),
+        subject: format!("{}: {summary}", partstat.verb()),
+        body: mime::markdown_to_html(&body),
+        in_reply_to: parent.message_id.clone(),
+        references: merge_references(parent.references, parent.message_id),
+        thread_id: parent.thread_id,
+        attachments: vec![Attachment {
+            filename: "invite.ics".to_string(),
+            mime_type: "text/calendar; method=REPLY; charset=UTF-8".to_string(),
+            data: reply.into_bytes(),
+        }],
+    };
+    let raw = mime::build_raw_message(&request);
+    if ctx.dry_run {
+        let change =
+            PlannedChange::send("rsvp", &request, &raw).with("partstat", partstat.as_ical());
+        return dry_run::emit(ctx, &change);
+    }
+    let sent = outbox::send(ctx, &raw, request.thread_id.as_deref(), &access_token).await?;
+
+    let result = RsvpResult {
+        id: sent.id,
+        thread_id: sent.thread_id,
+        uid: invite.uid,
+        organizer,
+        partstat: partstat.as_ical(),
+    };
+    let text = format!(
+        "sent {} reply to {} (message {})",
+        result.partstat, result.organizer, result.id
+    );
+    ctx.output.emit(&text, &result)
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::RulesCommand;
+use crate::context::AppContext;
+use crate::daemon::rules;
+use crate::error::AppResult;
+use crate::mail::rules::MailRules;
+
+/// The rules a message would trigger, from `rules test`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RuleTest {
+    pub id: String,
+    /// Matching rule names, in the order they would run.
+    pub matched: Vec<String>,
+}
+
+/// Dispatch a `gmail rules` subcommand (test).
+pub async fn run(ctx: &AppContext, command: RulesCommand) -> AppResult<()> {
+    match command {
+        RulesCommand::Test { id, file } => test(ctx, &id, file).await,
+    }
+}
+
+async fn test(ctx: &AppContext, id: &str, file: Option<PathBuf>) -> AppResult<()> {
+    let rules = MailRules::load(&file.unwrap_or_else(|| ctx.paths.rules_file()))?;
+    let access_token = ctx.access_token().await?;
+    let message = ctx.gmail_client.get_msg(id, &access_token).await?;
+    let matched = rules::matching(ctx, &rules, &message).await?;
+
+    let result = RuleTest {
+        id: message.id.clone(),
+        matched: matched.iter().map(|rule| rule.name.clone()).collect(),
+    };
+    let text = match result.matched.as_slice() {
+        [] => format!("{id}: no rules match"),
+        names => format!("{id}: {}", names.join(", ")),
+    };
+    ctx.output.emit(&text, &result)
+}
+
+/// The rules file the daemon's `--rules` flag names; blank means the default.
+pub(crate) fn rules_path(ctx: &AppContext, flag: &str) -> PathBuf {
+    match flag.trim() {
+        "" => ctx.paths.rules_file(),
+        path => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,234 @@
+use schemars::{Schema, schema_for};
+use serde_json::{Map, Value};
+
+use crate::api::audit::AuditEntry;
+use crate::api::models::{
+    AttachmentList, AttachmentRow, AutoForwarding, DraftResult, DraftView, ForwardingAddress,
+    HistoryPage, ImapSettings, LabelMutationResult, LabelView, MailboxEvent, MailboxProfile,
+    MessageView, PersonMatch, PopSettings, SavedAttachment, SendAsView, SendResult, ThreadSummary,
+    ThreadView,
+};
+use crate::api::timings::TimingsReport;
+use crate::auth::{AuthLoginResult, AuthStatus};
+use crate::cli::SchemaArgs;
+use crate::commands::cleanup::CleanupOutcome;
+use crate::commands::doctor::DoctorReport;
+use crate::commands::dry_run::PlannedChange;
+use crate::commands::export::ExportSummary;
+use crate::commands::forwarding::{ForwardingRemoved, ForwardingView};
+use crate::commands::get::MessageHeaders;
+use crate::commands::import::ImportedMessage;
+use crate::commands::legacy_access::LegacyAccess;
+use crate::commands::man::ManPages;
+use crate::commands::multi::{AccountLegacyAccess, AccountMessage, AccountReport};
+use crate::commands::mute::MutedThread;
+use crate::commands::open::OpenedMessage;
+use crate::commands::profile::{DefaultProfile, ProfileList, ResolvedProfile};
+use crate::commands::queue::QueuedMessage;
+use crate::commands::quota::QuotaSummary;
+use crate::commands::rsvp::RsvpResult;
+use crate::commands::rules::RuleTest;
+use crate::commands::search::SavedSearch;
+use crate::commands::send_patch::SentPatch;
+use crate::commands::sendas::VerificationSent;
+use crate::commands::signature::SignatureView;
+use crate::commands::snooze::SnoozedMessage;
+use crate::commands::triage::{EmptiedFolder, MessageOutcome};
+use crate::commands::unsubscribe::UnsubscribeResult;
+use crate::commands::watch::WatchStatus;
+use crate::context::AppContext;
+use crate::daemon::watch::WatchState;
+use crate::daemon::webhook::MessageEvent;
+use crate::error::{AppError, AppResult, ErrorReport};
+use crate::mail::alias::Alias;
+use crate::mail::stats::Report;
+use crate::mail::usage::UsageReport;
+use crate::mail::vcard::Contact;
+
+/// The JSON Schema of every command's structured output, keyed by command path
+/// (`"label ls"`). `stream` describes one NDJSON line, `webhook` the body the
+/// daemon POSTs, `timings` the `--timings` report on stderr, and `error` the
+/// `{"error": ...}` object a failing command prints on stderr with `--json`
+/// or `--output json|ndjson`, and `dry-run` what a mutating command prints
+/// under `--dry-run` (an array for `send-patch`). Commands
+/// given `-` for ids emit an array of the item, and `list --ids-only` an
+/// array of id strings; `list --threads`, `attachments ls --q`, and
+/// `send --save-draft` have their own entries. `stats` flattens its report into `section,key,value`
+/// rows for CSV, table, and NDJSON output. `profiles <command>` entries are the
+/// merged `--profiles` output, each item tagged with `account`.
+pub fn documents() -> Vec<(&'static str, Schema)> {
+    vec![
+        ("alias add", schema_for!(Alias)),
+        ("alias ls", schema_for!(Vec<Alias>)),
+        ("alias rm", schema_for!(Alias)),
+        ("aliases ls", schema_for!(Vec<SendAsView>)),
+        ("archive", schema_for!(Vec<LabelMutationResult>)),
+        ("attachments get", schema_for!(Vec<SavedAttachment>)),
+        ("attachments ls", schema_for!(AttachmentList)),
+        ("attachments ls --q", schema_for!(Vec<AttachmentRow>)),
+        ("auth login", schema_for!(AuthLoginResult)),
+        ("auth logout", schema_for!(AuthStatus)),
+        ("auth status", schema_for!(AuthStatus)),
+        ("cleanup", schema_for!(Vec<CleanupOutcome>)),
+        ("contacts export", schema_for!(Vec<Contact>)),
+        ("contacts search", schema_for!(Vec<PersonMatch>)),
+        ("delete", schema_for!(Vec<MessageOutcome>)),
+        ("doctor", schema_for!(DoctorReport)),
+        ("draft create", schema_for!(DraftResult)),
+        ("draft edit", schema_for!(DraftResult)),
+        ("draft get", schema_for!(DraftView)),
+        ("draft ls", schema_for!(Vec<DraftView>)),
+        ("draft rm", schema_for!(Vec<MessageOutcome>)),
+        ("draft send", schema_for!(SendResult)),
+        ("dry-run", schema_for!(PlannedChange)),
+        ("empty-spam", schema_for!(EmptiedFolder)),
+        ("empty-trash", schema_for!(EmptiedFolder)),
+        ("error", schema_for!(ErrorReport)),
+        ("export", schema_for!(ExportSummary)),
+        ("forward", schema_for!(SendResult)),
+        ("forwarding add", schema_for!(ForwardingAddress)),
+        ("forwarding auto", schema_for!(AutoForwarding)),
+        ("forwarding ls", schema_for!(ForwardingView)),
+        ("forwarding rm", schema_for!(ForwardingRemoved)),
+        ("get", schema_for!(MessageView)),
+        ("get --headers", schema_for!(MessageHeaders)),
+        ("get --thread", schema_for!(ThreadView)),
+        ("history", schema_for!(HistoryPage)),
+        ("import", schema_for!(Vec<ImportedMessage>)),
+        ("label add", schema_for!(LabelMutationResult)),
+        ("label create", schema_for!(Vec<LabelView>)),
+        ("label ls", schema_for!(Vec<LabelView>)),
+        ("label rm", schema_for!(LabelMutationResult)),
+        ("legacy-access imap", schema_for!(ImapSettings)),
+        ("legacy-access pop", schema_for!(PopSettings)),
+        ("legacy-access status", schema_for!(LegacyAccess)),
+        ("list", schema_for!(Vec<MessageView>)),
+        ("list --threads", schema_for!(Vec<ThreadSummary>)),
+        ("log", schema_for!(Vec<AuditEntry>)),
+        ("man", schema_for!(ManPages)),
+        ("mute", schema_for!(Vec<MessageOutcome>)),
+        ("mute ls", schema_for!(Vec<MutedThread>)),
+        ("mute run", schema_for!(Vec<MessageOutcome>)),
+        ("not-spam", schema_for!(Vec<LabelMutationResult>)),
+        ("notify", schema_for!(MessageView)),
+        ("open", schema_for!(OpenedMessage)),
+        ("profile info", schema_for!(MailboxProfile)),
+        ("profile list", schema_for!(ProfileList)),
+        ("profile show", schema_for!(ResolvedProfile)),
+        ("profile use", schema_for!(DefaultProfile)),
+        (
+            "profiles legacy-access status",
+            schema_for!(Vec<AccountLegacyAccess>),
+        ),
+        ("profiles list", schema_for!(Vec<AccountMessage>)),
+        ("profiles stats", schema_for!(Vec<AccountReport>)),
+        ("queue ls", schema_for!(Vec<QueuedMessage>)),
+        ("queue rm", schema_for!(Vec<MessageOutcome>)),
+        ("queue run", schema_for!(Vec<MessageOutcome>)),
+        ("quota", schema_for!(QuotaSummary)),
+        ("read", schema_for!(Vec<LabelMutationResult>)),
+        ("rsvp", schema_for!(RsvpResult)),
+        ("rules test", schema_for!(RuleTest)),
+        ("search", schema_for!(Vec<MessageView>)),
+        ("search ls", schema_for!(Vec<SavedSearch>)),
+        ("search rm", schema_for!(Vec<SavedSearch>)),
+        ("search save", schema_for!(SavedSearch)),
+        ("send", schema_for!(SendResult)),
+        ("send --at", schema_for!(QueuedMessage)),
+        ("send --save-draft", schema_for!(DraftResult)),
+        ("send-patch", schema_for!(Vec<SentPatch>)),
+        ("sendas add", schema_for!(SendAsView)),
+        ("sendas ls", schema_for!(Vec<SendAsView>)),
+        ("sendas set-default", schema_for!(SendAsView)),
+        ("sendas set-signature", schema_for!(SendAsView)),
+        ("sendas verify", schema_for!(VerificationSent)),
+        ("sendmail", schema_for!(SendResult)),
+        ("signature clear", schema_for!(SignatureView)),
+        ("signature set", schema_for!(SignatureView)),
+        ("signature set-file", schema_for!(SignatureView)),
+        ("signature show", schema_for!(SignatureView)),
+        ("spam", schema_for!(Vec<LabelMutationResult>)),
+        ("star", schema_for!(Vec<LabelMutationResult>)),
+        ("snooze", schema_for!(Vec<LabelMutationResult>)),
+        ("snooze ls", schema_for!(Vec<SnoozedMessage>)),
+        ("snooze run", schema_for!(Vec<LabelMutationResult>)),
+        ("snooze wake", schema_for!(Vec<LabelMutationResult>)),
+        ("stats", schema_for!(Report)),
+        ("stream", schema_for!(MailboxEvent)),
+        ("thread get", schema_for!(ThreadView)),
+        ("thread ls", schema_for!(Vec<ThreadSummary>)),
+        ("timings", schema_for!(TimingsReport)),
+        ("trash", schema_for!(Vec<MessageOutcome>)),
+        ("unmute", schema_for!(Vec<MessageOutcome>)),
+        ("unread", schema_for!(Vec<LabelMutationResult>)),
+        ("unstar", schema_for!(Vec<LabelMutationResult>)),
+        ("unsubscribe", schema_for!(UnsubscribeResult)),
+        ("untrash", schema_for!(Vec<MessageOutcome>)),
+        ("usage", schema_for!(UsageReport)),
+        ("watch start", schema_for!(WatchState)),
+        ("watch status", schema_for!(WatchStatus)),
+        ("watch stop", schema_for!(WatchStatus)),
+        ("webhook", schema_for!(MessageEvent<'static>)),
+    ]
+}
+
+/// Print the schema for one command, or an object of every schema keyed by
+/// command path. Always JSON (or YAML), since the schemas are the point.
+pub async fn run(ctx: &AppContext, args: SchemaArgs) -> AppResult<()> {
+    let documents = documents();
+    let value = if args.command.is_empty() {
+        Value::Object(
+            documents
+                .into_iter()
+                .map(|(name, schema)| (name.to_string(), schema.to_value()))
+                .collect::<Map<_, _>>(),
+        )
+    } else {
+        let wanted = args.command.join(" ");
+        let Some((_, schema)) = documents.into_iter().find(|(name, _)| *name == wanted) else {
+            let known = self::documents()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>();
+            return Err(AppError::InvalidInput(format!(
+                "no JSON output schema for `{wanted}`; known: {}",
+                known.join(", ")
+            )));
+        };
+        schema.to_value()
+    };
+
+    let text = serde_json::to_string_pretty(&value)?;
+    ctx.output.emit(&text, &value)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+    use crate::cli::Cli;
+
+    #[test]
+    fn every_schema_names_a_real_command() {
+        let cli = Cli::command();
+        for (name, schema) in documents() {
+            if matches!(name, "dry-run" | "error" | "timings" | "webhook")
+                || name.starts_with("profiles ")
+            {
+                continue;
+            }
+            let mut command = &cli;
+            for part in name.split(' ').filter(|part| !part.starts_with("--")) {
+                command = command
+                    .find_subcommand(part)
+                    .unwrap_or_else(|| panic!("`{name}` is not a gmail command"));
+            }
+            assert!(
+                schema
+                    .as_object()
+                    .is_some_and(|object| object.contains_key("$schema"))
+            );
+        }
+    }
+}
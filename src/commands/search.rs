@@ -0,0 +1,304 @@
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::audit::AuditWrite;
+use crate::cli::{SearchArgs, SearchCommand};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::list;
+use crate::config;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+
+/// A named query from the profile's `searches` setting.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+}
+
+/// Compose a Gmail query from the filter flags and list the matches exactly
+/// as `list --q` would, or run a `save`/`ls`/`rm` subcommand.
+pub async fn run(ctx: &AppContext, mut args: SearchArgs) -> AppResult<()> {
+    match args.command.take() {
+        Some(SearchCommand::Save { name, query }) => return save(ctx, name, query.join(" ")),
+        Some(SearchCommand::Ls) => return ls(ctx),
+        Some(SearchCommand::Rm { names }) => return rm(ctx, &names),
+        None => {}
+    }
+
+    let query = build_query(&args)?;
+    if query.is_empty() && args.list.saved.is_none() {
+        return Err(AppError::InvalidInput(
+            "give search words or at least one filter, e.g. --from or --after".to_string(),
+        ));
+    }
+    tracing::debug!(query, "search query");
+    args.list.q = (!query.is_empty()).then_some(query);
+    list::run(ctx, args.list).await
+}
+
+/// The query saved as `name`, or an error naming the searches there are.
+pub fn saved_query<'a>(ctx: &'a AppContext, name: &str) -> AppResult<&'a str> {
+    if let Some(query) = ctx.settings.searches.get(name) {
+        return Ok(query);
+    }
+    Err(AppError::InvalidInput(
+        if ctx.settings.searches.is_empty() {
+            format!("no saved search `{name}`; save one with `gmail search save {name} <query>`")
+        } else {
+            let names = ctx
+                .settings
+                .searches
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            format!(
+                "no saved search `{name}`; saved searches: {}",
+                names.join(", ")
+            )
+        },
+    ))
+}
+
+/// Save `query` as `name` in the active profile's settings, replacing any
+/// query already saved under that name.
+fn save(ctx: &AppContext, name: String, query: String) -> AppResult<()> {
+    let (name, query) = (name.trim().to_string(), query.trim().to_string());
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(AppError::InvalidInput(
+            "a saved search name must be one word, e.g. `work`".to_string(),
+        ));
+    }
+    if query.is_empty() {
+        return Err(AppError::InvalidInput(
+            "the query to save is empty".to_string(),
+        ));
+    }
+    let profile = ctx.profile()?;
+    if ctx.dry_run {
+        let change = PlannedChange::new("search save", "update profile settings")
+            .with("profile", profile)
+            .with("name", name)
+            .with("query", query);
+        return dry_run::emit(ctx, &change);
+    }
+
+    config::update_settings(&ctx.paths, profile, |settings| {
+        settings.searches.insert(name.clone(), query.clone());
+    })?;
+    audit(ctx, profile);
+
+    ctx.output.emit(
+        &format!("saved search `{name}`: {query}; run it with `gmail list --saved {name}`"),
+        &SavedSearch { name, query },
+    )
+}
+
+/// Print the saved searches, shared defaults included, by name.
+fn ls(ctx: &AppContext) -> AppResult<()> {
+    let searches = ctx
+        .settings
+        .searches
+        .iter()
+        .map(|(name, query)| SavedSearch {
+            name: name.clone(),
+            query: query.clone(),
+        })
+        .collect::<Vec<_>>();
+    if searches.is_empty() {
+        return ctx.output.emit(
+            "no saved searches. save one with `gmail search save <name> <query>`",
+            &searches,
+        );
+    }
+    let width = searches
+        .iter()
+        .map(|search| search.name.chars().count())
+        .max()
+        .unwrap_or_default();
+    let text = searches
+        .iter()
+        .map(|search| format!("{:width$}  {}", search.name, search.query))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.output.emit(&text, &searches)
+}
+
+/// Remove saved searches from the active profile's settings file. A search
+/// set only in the shared defaults cannot be removed per profile.
+fn rm(ctx: &AppContext, names: &[String]) -> AppResult<()> {
+    let mut removed = Vec::with_capacity(names.len());
+    for name in names {
+        removed.push(SavedSearch {
+            name: name.clone(),
+            query: saved_query(ctx, name)?.to_string(),
+        });
+    }
+    let profile = ctx.profile()?;
+    if ctx.dry_run {
+        let change = PlannedChange::new("search rm", "update profile settings")
+            .with("profile", profile)
+            .with("names", names.join(", "));
+        return dry_run::emit(ctx, &change);
+    }
+
+    let mut shared = Vec::new();
+    config::update_settings(&ctx.paths, profile, |settings| {
+        for name in names {
+            if settings.searches.remove(name).is_none() {
+                shared.push(name.as_str());
+            }
+        }
+    })?;
+    if shared.len() < names.len() {
+        audit(ctx, profile);
+    }
+    if !shared.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "{} saved in the `defaults` of the shared config, not profile `{profile}`; remove it there",
+            shared.join(", ")
+        )));
+    }
+
+    let text = removed
+        .iter()
+        .map(|search| format!("removed saved search `{}`", search.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.output.emit(&text, &removed)
+}
+
+/// Note the profile's saved-search change for the audit log.
+fn audit(ctx: &AppContext, profile: &str) {
+    ctx.audit.record(AuditWrite {
+        operation: "profile.searches.update".to_string(),
+        ids: vec![profile.to_string()],
+        ..AuditWrite::default()
+    });
+}
+
+/// The Gmail query for the args: each filter as its operator, then any
+/// `--q` and free-text words as given.
+fn build_query(args: &SearchArgs) -> AppResult<String> {
+    let mut terms = Vec::new();
+    for from in &args.from {
+        terms.push(format!("from:{}", quote(from)));
+    }
+    for to in &args.to {
+        terms.push(format!("to:{}", quote(to)));
+    }
+    if let Some(subject) = &args.subject {
+        terms.push(format!("subject:{}", quote(subject)));
+    }
+    for label in &args.label {
+        terms.push(format!("label:{}", quote(label)));
+    }
+    if let Some(after) = &args.after {
+        terms.push(date_term("after", "newer_than", after)?);
+    }
+    if let Some(before) = &args.before {
+        terms.push(date_term("before", "older_than", before)?);
+    }
+    if args.has_attachment {
+        terms.push("has:attachment".to_string());
+    }
+    if let Some(size) = &args.larger {
+        terms.push(format!("larger:{}", size_term(size)?));
+    }
+    if let Some(size) = &args.smaller {
+        terms.push(format!("smaller:{}", size_term(size)?));
+    }
+    terms.extend(args.list.q.iter().cloned());
+    terms.extend(args.words.iter().cloned());
+    Ok(terms.join(" "))
+}
+
+/// `value`, double-quoted when it holds spaces or characters Gmail would
+/// read as query syntax.
+fn quote(value: &str) -> String {
+    let value = value.trim().replace('"', "");
+    if value.contains(|c: char| c.is_whitespace() || "(){}:".contains(c)) {
+        format!("\"{value}\"")
+    } else {
+        value
+    }
+}
+
+/// `after:`/`before:` for a date (`2024-06-01` or `2024/06/01`), or
+/// `newer_than:`/`older_than:` for an age such as `7d`, `2m`, or `1y`.
+pub(crate) fn date_term(date_operator: &str, age_operator: &str, value: &str) -> AppResult<String> {
+    let value = value.trim();
+    if let Some(unit) = value.chars().last().filter(|unit| "dmy".contains(*unit))
+        && let Ok(count) = value[..value.len() - 1].parse::<u32>()
+    {
+        return Ok(format!("{age_operator}:{count}{unit}"));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y/%m/%d"))
+        .map_err(|_| {
+            AppError::InvalidInput(format!(
+                "cannot read `{value}` as a date; use `2024-06-01` or an age like `7d`, `2m`, `1y`"
+            ))
+        })?;
+    Ok(format!("{date_operator}:{}", date.format("%Y/%m/%d")))
+}
+
+/// A size such as `5M`, `500K`, or `1048576` (bytes).
+fn size_term(value: &str) -> AppResult<String> {
+    let value = value.trim();
+    let digits = value.trim_end_matches(['k', 'K', 'm', 'M']);
+    if digits.is_empty() || value.len() - digits.len() > 1 || digits.parse::<u64>().is_err() {
+        return Err(AppError::InvalidInput(format!(
+            "cannot read `{value}` as a size; use bytes or a K or M suffix, e.g. `5M`"
+        )));
+    }
+    Ok(value.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::{Cli, Command};
+
+    fn query(args: &[&str]) -> AppResult<String> {
+        let cli = Cli::try_parse_from(["gmail", "search"].iter().chain(args)).unwrap();
+        let Command::Search(args) = cli.command else {
+            panic!("expected search command");
+        };
+        build_query(&args)
+    }
+
+    #[test]
+    fn composes_filters_into_gmail_operators() {
+        assert_eq!(
+            query(&[
+                "--from",
+                "Ada Lovelace",
+                "--subject",
+                "Q3 plan",
+                "--label",
+                "Projects/Engine",
+                "--after",
+                "2024-06-01",
+                "--before",
+                "7d",
+                "--has-attachment",
+                "--larger",
+                "5m",
+                "budget",
+            ])
+            .unwrap(),
+            "from:\"Ada Lovelace\" subject:\"Q3 plan\" label:Projects/Engine after:2024/06/01 older_than:7d has:attachment larger:5M budget"
+        );
+    }
+
+    #[test]
+    fn rejects_unreadable_dates_and_sizes() {
+        assert!(query(&["--after", "June"]).is_err());
+        assert!(query(&["--larger", "5MB"]).is_err());
+        assert_eq!(query(&[]).unwrap(), "");
+    }
+}
@@ -1,53 +1,173 @@
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
 
-use crate::api::models::{Attachment, SendAsView, SendRequest};
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+
+use crate::api::models::{Attachment, MessageView, SendAsView, SendRequest, SendResult};
 use crate::auth::TokenSet;
 use crate::auth::token_store::TokenStore;
 use crate::cli::SendArgs;
+use crate::commands::alias;
+use crate::commands::confirm;
+use crate::commands::draft;
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::outbox;
+use crate::commands::queue;
 use crate::context::AppContext;
+use crate::desktop;
 use crate::error::{AppError, AppResult};
-use crate::mail::mime;
+use crate::i18n;
+use crate::mail::{address, ics, mime, when};
+
+/// Invitation length when `--duration` is not given.
+const DEFAULT_INVITE_MINUTES: i64 = 30;
 
 /// Build a send request from the args, encode it as a raw message, and submit it.
+/// With `--preview` the rendered message is opened in the browser first and
+/// only sent once confirmed; with `--save-draft` it is saved as a draft instead,
+/// and with `--at`/`--in` it is queued locally for `gmail queue run`.
 pub async fn run(ctx: &AppContext, args: SendArgs) -> AppResult<()> {
+    if args.save_draft {
+        let access_token = ctx.access_token().await?;
+        let request = build_send_request(ctx, &access_token, args).await?;
+        return draft::save(ctx, "send", None, &request, &access_token).await;
+    }
+    if let Some(due) = queue::due_time(&args)? {
+        let access_token = ctx.access_token().await?;
+        let request = build_send_request(ctx, &access_token, args).await?;
+        return queue::enqueue(ctx, "send", &request, due);
+    }
+    if ctx.dry_run {
+        let access_token = ctx.access_token().await?;
+        let request = build_send_request(ctx, &access_token, args).await?;
+        let raw = mime::build_raw_message(&request);
+        return dry_run::emit(ctx, &PlannedChange::send("send", &request, &raw));
+    }
+    if !args.preview {
+        let result = submit(ctx, args).await?;
+        let text = i18n::format("sent-message", &[("id", result.id.as_str().into())]);
+        return ctx.output.emit(&text, &result);
+    }
+
+    if !io::stdin().is_terminal() {
+        return Err(AppError::InvalidInput(
+            "--preview asks for confirmation, so it needs an interactive terminal".to_string(),
+        ));
+    }
+    let base_dir = args
+        .body_file
+        .as_deref()
+        .or(args.draft_file.as_deref())
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
     let access_token = ctx.access_token().await?;
     let request = build_send_request(ctx, &access_token, args).await?;
-    let raw = mime::build_raw_message(&request);
-    let result = ctx
-        .gmail_client
-        .send(&raw, request.thread_id.as_deref(), &access_token)
-        .await?;
+    if !preview(&request, &base_dir)? {
+        eprintln!("not sent");
+        return Ok(());
+    }
 
-    let text = format!("sent message {}", result.id);
+    let raw = mime::build_raw_message(&request);
+    let result = outbox::send(ctx, &raw, request.thread_id.as_deref(), &access_token).await?;
+    let text = i18n::format("sent-message", &[("id", result.id.as_str().into())]);
     ctx.output.emit(&text, &result)
 }
 
+/// Write the preview page to a temporary file, open it in the browser, and
+/// ask whether to send. The file is removed once answered.
+fn preview(request: &SendRequest, base_dir: &Path) -> AppResult<bool> {
+    let path = std::env::temp_dir().join(format!("gmail-preview-{}.html", std::process::id()));
+    // The draft lands in a shared directory: never follow an existing file or
+    // symlink there, and keep it readable by us alone.
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        options.mode(0o600);
+    }
+    options
+        .open(&path)?
+        .write_all(mime::preview_html(request, base_dir).as_bytes())?;
+    let url = format!("file://{}", path.display());
+    if !desktop::open_browser(&url) {
+        eprintln!(
+            "could not open a browser; the preview is at {}",
+            path.display()
+        );
+    }
+
+    let question = format!("send \"{}\" to {}?", request.subject, request.to.join(", "));
+    let answer = confirm::ask(&question, "no --preview");
+    let _ = fs::remove_file(&path);
+    answer
+}
+
+/// Send a message described by `args` without printing anything, for callers
+/// such as the TUI that report the result themselves.
+pub async fn submit(ctx: &AppContext, args: SendArgs) -> AppResult<SendResult> {
+    let access_token = ctx.access_token().await?;
+    let request = build_send_request(ctx, &access_token, args).await?;
+    let raw = mime::build_raw_message(&request);
+    outbox::send(ctx, &raw, request.thread_id.as_deref(), &access_token).await
+}
+
 /// Assemble a `SendRequest` from args, rendering the markdown body and reading attachments;
 /// delegates to the reply path when `--reply` is set.
-async fn build_send_request(
+pub(crate) async fn build_send_request(
     ctx: &AppContext,
     access_token: &str,
-    args: SendArgs,
+    mut args: SendArgs,
 ) -> AppResult<SendRequest> {
+    args.to = alias::expand(ctx, args.to)?;
+    args.cc = alias::expand(ctx, args.cc)?;
+    args.bcc = alias::expand(ctx, args.bcc)?;
     let body_markdown = apply_signature(ctx, &args, read_body(&args)?);
-    let body = mime::markdown_to_html(&body_markdown);
     let attachments = read_attachments(&args.attach)?;
-    let from_override = args.from.clone().or_else(|| ctx.settings.send_from.clone());
-    let from = resolve_from_header(ctx, access_token, from_override.as_deref()).await?;
-
-    if let Some(reply_id) = args.reply.clone() {
-        return build_reply_request(ctx, access_token, args, body, attachments, from, &reply_id)
-            .await;
+    let invite = read_invite(&args)?;
+    let from = resolve_from_header(ctx, access_token, args.from.as_deref()).await?;
+
+    let mut request = match args.reply.clone() {
+        Some(reply_id) => {
+            build_reply_request(
+                ctx,
+                access_token,
+                args,
+                body_markdown,
+                attachments,
+                from,
+                &reply_id,
+            )
+            .await?
+        }
+        None => build_new_request(args, body_markdown, attachments, from)?,
+    };
+    if let Some(invite) = invite {
+        request
+            .attachments
+            .push(invite_attachment(invite, &request)?);
     }
+    Ok(request)
+}
 
+/// Build a new (non-reply) message; an invitation's summary stands in for a
+/// missing subject.
+fn build_new_request(
+    args: SendArgs,
+    body_markdown: String,
+    attachments: Vec<Attachment>,
+    from: Option<String>,
+) -> AppResult<SendRequest> {
     if args.to.is_empty() {
         return Err(AppError::InvalidInput(
             "--to is required unless --reply is used".to_string(),
         ));
     }
 
-    let subject = args.subject.ok_or_else(|| {
+    let subject = args.subject.or(args.invite).ok_or_else(|| {
         AppError::InvalidInput("--subject is required unless --reply is used".to_string())
     })?;
 
@@ -57,7 +177,7 @@ async fn build_send_request(
         cc: args.cc,
         bcc: args.bcc,
         subject,
-        body,
+        body: mime::markdown_to_html(&body_markdown),
         in_reply_to: None,
         references: None,
         thread_id: None,
@@ -66,27 +186,50 @@ async fn build_send_request(
 }
 
 /// Build a reply by fetching the parent message and deriving recipient, subject, threading headers.
+/// With `--quote` (or the `reply_quote_default` setting) the parent body is quoted below the reply.
 #[allow(clippy::too_many_arguments)]
 async fn build_reply_request(
     ctx: &AppContext,
     access_token: &str,
     args: SendArgs,
-    body: String,
+    body_markdown: String,
     attachments: Vec<Attachment>,
     from: Option<String>,
     reply_id: &str,
 ) -> AppResult<SendRequest> {
-    let parent = ctx.gmail_client.get_msg(reply_id, access_token).await?;
-    let mut to = args.to;
-    if to.is_empty() {
-        let fallback = parent.reply_to.clone().or_else(|| parent.from.clone());
-        let Some(recipient) = fallback else {
-            return Err(AppError::InvalidInput(
-                "unable to infer reply recipient; pass --to explicitly".to_string(),
-            ));
-        };
-        to.push(recipient);
-    }
+    let quote = args
+        .quote
+        .or(ctx.settings.reply_quote_default)
+        .unwrap_or(false);
+    let reply_all = args
+        .reply_all
+        .or(ctx.settings.reply_all_default)
+        .unwrap_or(false);
+    let keep_cc = args
+        .keep_cc
+        .or(ctx.settings.reply_keep_cc_default)
+        .unwrap_or(false);
+    let parent = if quote {
+        ctx.gmail_client
+            .get_msg_full(reply_id, access_token)
+            .await?
+    } else {
+        ctx.gmail_client.get_msg(reply_id, access_token).await?
+    };
+    let body_markdown = if quote {
+        append_quote(body_markdown, &parent)
+    } else {
+        body_markdown
+    };
+    let body = mime::markdown_to_html(&body_markdown);
+    let own_email = from.as_deref().map(address::email_of);
+    let (to, cc) = reply_recipients(
+        &parent,
+        args.to,
+        args.cc,
+        ReplyScope { reply_all, keep_cc },
+        own_email.as_deref(),
+    )?;
 
     let subject = match args.subject {
         Some(subject) => ensure_reply_subject(subject),
@@ -102,7 +245,7 @@ async fn build_reply_request(
     Ok(SendRequest {
         from,
         to,
-        cc: args.cc,
+        cc,
         bcc: args.bcc,
         subject,
         body,
@@ -113,17 +256,90 @@ async fn build_reply_request(
     })
 }
 
-/// Resolve the `From` header. An explicit alias (from `--from` or the
-/// `send_from` setting) is validated against the account's send-as aliases so
+/// Which of the parent's recipients a reply carries over.
+#[derive(Debug, Clone, Copy, Default)]
+struct ReplyScope {
+    /// Add the parent's To to ours and its Cc to our Cc.
+    reply_all: bool,
+    /// Add the parent's Cc to our Cc.
+    keep_cc: bool,
+}
+
+/// Work out reply recipients. An explicit `--to` is used as given; otherwise
+/// the parent's Reply-To (or From) is addressed, plus its To under reply-all.
+/// Our own address is dropped and duplicates removed, Cc never repeating To.
+fn reply_recipients(
+    parent: &MessageView,
+    explicit_to: Vec<String>,
+    explicit_cc: Vec<String>,
+    scope: ReplyScope,
+    own_email: Option<&str>,
+) -> AppResult<(Vec<String>, Vec<String>)> {
+    let split = |header: &Option<String>| {
+        header
+            .as_deref()
+            .map(address::split_addresses)
+            .unwrap_or_default()
+    };
+
+    let mut to = explicit_to;
+    if to.is_empty() {
+        let fallback = parent.reply_to.clone().or_else(|| parent.from.clone());
+        let Some(recipient) = fallback else {
+            return Err(AppError::InvalidInput(
+                "unable to infer reply recipient; pass --to explicitly".to_string(),
+            ));
+        };
+        to.push(recipient);
+        if scope.reply_all {
+            to.extend(split(&parent.to));
+        }
+    }
+
+    let mut cc = explicit_cc;
+    if scope.reply_all || scope.keep_cc {
+        cc.extend(split(&parent.cc));
+    }
+
+    let mut seen = own_email
+        .map(str::to_string)
+        .into_iter()
+        .collect::<Vec<_>>();
+    let mut keep = |entry: &String| {
+        let email = address::email_of(entry);
+        if seen.contains(&email) {
+            return false;
+        }
+        seen.push(email);
+        true
+    };
+    let original_to = to.clone();
+    to.retain(|entry| keep(entry));
+    if to.is_empty() {
+        // Replying to our own message: address whoever it went to.
+        to = split(&parent.to);
+        to.retain(|entry| keep(entry));
+        if to.is_empty() {
+            to = original_to;
+        }
+    }
+    cc.retain(|entry| keep(entry));
+
+    Ok((to, cc))
+}
+
+/// Resolve the `From` header from `--from`, else the profile's `send_from`
+/// (`default_from_alias`) setting. Such an explicit alias is validated against the account's send-as aliases so
 /// typos and unverified addresses fail loudly instead of Gmail silently
 /// rewriting them to the primary address. Without an override, the header is
 /// derived from the stored token's email as before (no extra API call).
-async fn resolve_from_header(
+pub(crate) async fn resolve_from_header(
     ctx: &AppContext,
     access_token: &str,
-    from_override: Option<&str>,
+    from_flag: Option<&str>,
 ) -> AppResult<Option<String>> {
     let token = ctx.token_store.load(ctx.profile()?)?;
+    let from_override = from_flag.or(ctx.settings.send_from.as_deref());
 
     if let Some(requested) = from_override {
         let alias = resolve_send_as_alias(ctx, access_token, requested).await?;
@@ -239,7 +455,7 @@ fn apply_signature(ctx: &AppContext, args: &SendArgs, body_markdown: String) ->
 /// Append `signature` (if non-blank) below the body, one blank line apart. Each
 /// signature line gets a markdown hard break so it renders as written rather
 /// than collapsing into a single paragraph.
-fn compose_with_signature(body_markdown: String, signature: Option<&str>) -> String {
+pub(crate) fn compose_with_signature(body_markdown: String, signature: Option<&str>) -> String {
     let Some(signature) = signature.filter(|sig| !sig.trim().is_empty()) else {
         return body_markdown;
     };
@@ -258,6 +474,34 @@ fn compose_with_signature(body_markdown: String, signature: Option<&str>) -> Str
     }
 }
 
+/// Append an `On <date>, <sender> wrote:` block quoting the parent's text body.
+/// Each quoted line is hard-broken so the original's line structure survives.
+pub(crate) fn append_quote(body_markdown: String, parent: &MessageView) -> String {
+    let original = parent
+        .body
+        .as_deref()
+        .or(parent.snippet.as_deref())
+        .unwrap_or("")
+        .trim_end();
+    if original.is_empty() {
+        return body_markdown;
+    }
+
+    let sender = parent.from.as_deref().unwrap_or("(unknown sender)");
+    let attribution = match parent.date.as_deref() {
+        Some(date) => format!("On {date}, {sender} wrote:"),
+        None => format!("{sender} wrote:"),
+    };
+    let quoted = original
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("  \n");
+
+    let body = body_markdown.trim_end_matches(['\r', '\n', ' ']);
+    format!("{body}\n\n{attribution}\n\n{quoted}")
+}
+
 /// Read the message body from exactly one of --body, --body-file, --draft-file, or --stdin.
 fn read_body(args: &SendArgs) -> AppResult<String> {
     let mut selected = 0;
@@ -332,6 +576,66 @@ fn read_attachments(paths: &[std::path::PathBuf]) -> AppResult<Vec<Attachment>>
     Ok(attachments)
 }
 
+/// An invitation's event details from `--invite`, `--when`, `--duration`, and
+/// `--location`, checked before anything is sent.
+struct InviteSpec {
+    summary: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    location: Option<String>,
+}
+
+fn read_invite(args: &SendArgs) -> AppResult<Option<InviteSpec>> {
+    let Some(summary) = args.invite.clone() else {
+        return Ok(None);
+    };
+    let when = args
+        .when
+        .as_deref()
+        .ok_or_else(|| AppError::InvalidInput("--invite needs --when".to_string()))?;
+    let local = when::parse_when(when, Local::now().naive_local())?;
+    let start = Local
+        .from_local_datetime(&local)
+        .earliest()
+        .ok_or_else(|| AppError::InvalidInput(format!("`{when}` falls in a daylight-saving gap")))?
+        .with_timezone(&Utc);
+    let duration = match args.duration.as_deref() {
+        Some(duration) => when::parse_duration(duration)?,
+        None => Duration::minutes(DEFAULT_INVITE_MINUTES),
+    };
+
+    Ok(Some(InviteSpec {
+        summary,
+        start,
+        end: start + duration,
+        location: args.location.clone(),
+    }))
+}
+
+/// The `invite.ics` REQUEST for `request`'s To and Cc, organized by its sender.
+fn invite_attachment(invite: InviteSpec, request: &SendRequest) -> AppResult<Attachment> {
+    let organizer = request.from.clone().ok_or_else(|| {
+        AppError::InvalidInput(
+            "--invite needs the sender's address; pass --from or run `gmail auth login`"
+                .to_string(),
+        )
+    })?;
+    let event = ics::NewEvent {
+        uid: format!("{:032x}@gmail-cli", rand::random::<u128>()),
+        summary: invite.summary,
+        start: invite.start,
+        end: invite.end,
+        location: invite.location,
+        organizer,
+        attendees: request.to.iter().chain(&request.cc).cloned().collect(),
+    };
+    Ok(Attachment {
+        filename: "invite.ics".to_string(),
+        mime_type: "text/calendar; method=REQUEST; charset=UTF-8".to_string(),
+        data: ics::build_request(&event, Utc::now()).into_bytes(),
+    })
+}
+
 /// Prefix a subject with `Re:` unless it already starts with one.
 fn ensure_reply_subject(subject: String) -> String {
     let trimmed = subject.trim();
@@ -343,7 +647,10 @@ fn ensure_reply_subject(subject: String) -> String {
 }
 
 /// Append the parent's Message-ID to the existing References chain, avoiding duplicates.
-fn merge_references(existing: Option<String>, message_id: Option<String>) -> Option<String> {
+pub(crate) fn merge_references(
+    existing: Option<String>,
+    message_id: Option<String>,
+) -> Option<String> {
     let message_id = message_id?.trim().to_string();
     if message_id.is_empty() {
         return None;
@@ -367,7 +674,8 @@ fn merge_references(existing: Option<String>, message_id: Option<String>) -> Opt
 
 #[cfg(test)]
 mod tests {
-    use super::compose_with_signature;
+    use super::{ReplyScope, append_quote, compose_with_signature, reply_recipients};
+    use crate::api::models::MessageView;
 
     const SIG: &str = "Andrew Jones\nEssentialist Design · Iceberg Labs\niceberglab.xyz";
 
@@ -403,4 +711,86 @@ mod tests {
         let out = compose_with_signature("Body.\n\n".to_string(), Some("Sig"));
         assert_eq!(out, "Body.\n\nSig");
     }
+
+    #[test]
+    fn quotes_parent_below_reply_with_attribution() {
+        let parent = MessageView {
+            id: "m1".to_string(),
+            from: Some("Ada <ada@example.com>".to_string()),
+            date: Some("Tue, 5 Mar 2024 14:03:00 +0000".to_string()),
+            body: Some("Lunch?\nAt noon.\n".to_string()),
+            ..MessageView::default()
+        };
+
+        let out = append_quote("Sure.\n".to_string(), &parent);
+        assert_eq!(
+            out,
+            "Sure.\n\nOn Tue, 5 Mar 2024 14:03:00 +0000, Ada <ada@example.com> wrote:\n\n> Lunch?  \n> At noon."
+        );
+    }
+
+    fn parent_with(from: &str, to: &str, cc: &str) -> MessageView {
+        MessageView {
+            id: "m1".to_string(),
+            from: Some(from.to_string()),
+            to: Some(to.to_string()),
+            cc: Some(cc.to_string()),
+            ..MessageView::default()
+        }
+    }
+
+    #[test]
+    fn reply_all_adds_parent_recipients_without_self_or_duplicates() {
+        let parent = parent_with(
+            "Ada <ada@example.com>",
+            "me@example.com, Bob <bob@example.com>",
+            "ada@example.com, Cy <cy@example.com>",
+        );
+        let scope = ReplyScope {
+            reply_all: true,
+            keep_cc: false,
+        };
+
+        let (to, cc) = reply_recipients(
+            &parent,
+            Vec::new(),
+            Vec::new(),
+            scope,
+            Some("me@example.com"),
+        )
+        .unwrap();
+        assert_eq!(to, vec!["Ada <ada@example.com>", "Bob <bob@example.com>"]);
+        assert_eq!(cc, vec!["Cy <cy@example.com>"]);
+
+        let (to, cc) = reply_recipients(
+            &parent,
+            Vec::new(),
+            Vec::new(),
+            ReplyScope::default(),
+            Some("me@example.com"),
+        )
+        .unwrap();
+        assert_eq!(to, vec!["Ada <ada@example.com>"]);
+        assert!(cc.is_empty());
+    }
+
+    #[test]
+    fn keep_cc_and_replies_to_own_message() {
+        let parent = parent_with("Me <me@example.com>", "bob@example.com", "cy@example.com");
+        let scope = ReplyScope {
+            reply_all: false,
+            keep_cc: true,
+        };
+
+        let (to, cc) = reply_recipients(
+            &parent,
+            Vec::new(),
+            Vec::new(),
+            scope,
+            Some("me@example.com"),
+        )
+        .unwrap();
+        assert_eq!(to, vec!["bob@example.com"]);
+        assert_eq!(cc, vec!["cy@example.com"]);
+    }
 }
@@ -0,0 +1,124 @@
+use std::fs;
+use std::path::PathBuf;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::SendPatchArgs;
+use crate::commands::alias;
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::outbox;
+use crate::commands::send::resolve_from_header;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::patch::{self, Patch, PatchEnvelope};
+
+/// One sent patch of the series.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SentPatch {
+    pub file: PathBuf,
+    pub subject: String,
+    pub id: String,
+    pub thread_id: Option<String>,
+    pub message_id: Option<String>,
+}
+
+/// Send each patch file as a plain-text message, threading the series the way
+/// `git send-email` does: every patch replies to the first (or, with
+/// `--chain-reply-to`, to the one before it).
+pub async fn run(ctx: &AppContext, mut args: SendPatchArgs) -> AppResult<()> {
+    args.to = alias::expand(ctx, args.to)?;
+    args.cc = alias::expand(ctx, args.cc)?;
+    // Parse everything up front so a bad file doesn't leave half a series sent.
+    let patches = args
+        .patches
+        .iter()
+        .map(|path| {
+            let text = fs::read_to_string(path).map_err(|err| {
+                AppError::InvalidInput(format!("cannot read {}: {err}", path.display()))
+            })?;
+            patch::parse(&text).map_err(|err| match err {
+                AppError::InvalidInput(message) => {
+                    AppError::InvalidInput(format!("{}: {message}", path.display()))
+                }
+                other => other,
+            })
+        })
+        .collect::<AppResult<Vec<Patch>>>()?;
+
+    let access_token = ctx.access_token().await?;
+    let from = resolve_from_header(ctx, &access_token, args.from.as_deref()).await?;
+
+    let mut references = args.in_reply_to.iter().cloned().collect::<Vec<_>>();
+    let mut thread_id = None;
+    let mut sent = Vec::<SentPatch>::new();
+    let mut planned = Vec::new();
+
+    for (index, (path, patch)) in args.patches.iter().zip(&patches).enumerate() {
+        let envelope = PatchEnvelope {
+            from: from.clone(),
+            to: args.to.clone(),
+            cc: args.cc.clone(),
+            in_reply_to: references.last().cloned(),
+            references: references.clone(),
+        };
+        let raw = patch::build_raw_patch(patch, &envelope);
+        if ctx.dry_run {
+            // Gmail assigns the Message-IDs the series threads on, so the
+            // preview shows each patch's own headers only.
+            let message = URL_SAFE_NO_PAD
+                .decode(raw.trim_end_matches('='))
+                .map_err(|err| AppError::InvalidInput(format!("patch is not base64url: {err}")))?;
+            planned.push(
+                PlannedChange::raw_message("send-patch", &message)
+                    .with("file", path.display().to_string()),
+            );
+            continue;
+        }
+        let access_token = ctx.access_token().await?;
+        let result = outbox::send(ctx, &raw, thread_id.as_deref(), &access_token).await?;
+
+        let last = index + 1 == patches.len();
+        let threads_on = index == 0 || args.chain_reply_to;
+        // Gmail assigns the Message-ID, so read it back for the next reply.
+        let message_id = if last || !threads_on {
+            None
+        } else {
+            ctx.gmail_client
+                .get_msg(&result.id, &access_token)
+                .await?
+                .message_id
+        };
+        if let Some(message_id) = &message_id {
+            references.push(message_id.clone());
+        } else if threads_on && !last {
+            eprintln!(
+                "warning: no Message-ID on sent message {}; later patches are not threaded under it",
+                result.id
+            );
+        }
+        if thread_id.is_none() && args.in_reply_to.is_none() {
+            thread_id = result.thread_id.clone();
+        }
+
+        sent.push(SentPatch {
+            file: path.clone(),
+            subject: patch.subject.replace("\r\n", ""),
+            id: result.id,
+            thread_id: result.thread_id,
+            message_id,
+        });
+    }
+
+    if ctx.dry_run {
+        return dry_run::emit_all(ctx, &planned);
+    }
+    let mut lines = sent
+        .iter()
+        .map(|patch| format!("{}  {}", patch.id, patch.subject))
+        .collect::<Vec<_>>();
+    lines.push(format!("sent {} patch(es)", sent.len()));
+    ctx.output.emit(&lines.join("\n"), &sent)
+}
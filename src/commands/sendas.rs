@@ -0,0 +1,151 @@
+use std::fs;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::{NewSendAs, SendAsUpdate, SendAsView};
+use crate::cli::{SendasAddArgs, SendasCommand, SendasSignatureArgs};
+use crate::commands::aliases;
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::context::AppContext;
+use crate::error::AppResult;
+use crate::mail::mime;
+
+/// A verification email resent by `sendas verify`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct VerificationSent {
+    pub email: String,
+    pub note: String,
+}
+
+/// Dispatch a `gmail sendas` subcommand (ls, add, verify, set-signature, set-default).
+pub async fn run(ctx: &AppContext, command: SendasCommand) -> AppResult<()> {
+    match command {
+        SendasCommand::Ls => aliases::ls(ctx).await,
+        SendasCommand::Add(args) => add(ctx, args).await,
+        SendasCommand::Verify { email } => verify(ctx, &email).await,
+        SendasCommand::SetSignature(args) => set_signature(ctx, args).await,
+        SendasCommand::SetDefault { email } => set_default(ctx, &email).await,
+    }
+}
+
+async fn add(ctx: &AppContext, args: SendasAddArgs) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let alias = NewSendAs {
+        email: args.email,
+        display_name: args.name,
+        reply_to: args.reply_to,
+        signature: args.signature.as_deref().map(signature_html),
+    };
+    if ctx.dry_run {
+        let change = PlannedChange::new("sendas add", "create send-as alias")
+            .with("email", alias.email.clone())
+            .with(
+                "display_name",
+                alias.display_name.clone().unwrap_or_default(),
+            )
+            .with("reply_to", alias.reply_to.clone().unwrap_or_default())
+            .with("signature", alias.signature.clone().unwrap_or_default());
+        return dry_run::emit(ctx, &change);
+    }
+    let created = ctx
+        .gmail_client
+        .create_send_as(&alias, &access_token)
+        .await?;
+
+    let text = if created.is_sendable() {
+        format!("added {}", created.email)
+    } else {
+        format!(
+            "added {} (pending); open the verification link Gmail emailed to it, or resend it with `gmail sendas verify {}`",
+            created.email, created.email
+        )
+    };
+    ctx.output.emit(&text, &created)
+}
+
+async fn verify(ctx: &AppContext, email: &str) -> AppResult<()> {
+    if ctx.dry_run {
+        let change = PlannedChange::new("sendas verify", "resend the verification email")
+            .with("email", email);
+        return dry_run::emit(ctx, &change);
+    }
+    let access_token = ctx.access_token().await?;
+    ctx.gmail_client
+        .verify_send_as(email, &access_token)
+        .await?;
+    let result = VerificationSent {
+        email: email.to_string(),
+        note: format!("sent a new verification email to {email}"),
+    };
+    ctx.output.emit(&result.note, &result)
+}
+
+/// Render the Markdown signature to HTML and store it on the alias, where
+/// Gmail's own clients append it.
+async fn set_signature(ctx: &AppContext, args: SendasSignatureArgs) -> AppResult<()> {
+    let markdown = match (&args.text, &args.file) {
+        (_, Some(path)) => fs::read_to_string(path)?,
+        (Some(text), None) => text.clone(),
+        (None, None) => String::new(),
+    };
+    let update = SendAsUpdate {
+        signature: Some(signature_html(&markdown)),
+        ..SendAsUpdate::default()
+    };
+    if ctx.dry_run {
+        let signature = update.signature.clone().unwrap_or_default();
+        let change = PlannedChange::new("sendas set-signature", "update send-as alias")
+            .with("email", args.email)
+            .with(
+                "signature",
+                if signature.is_empty() {
+                    "(cleared)".to_string()
+                } else {
+                    signature
+                },
+            );
+        return dry_run::emit(ctx, &change);
+    }
+    let updated = update_send_as(ctx, &args.email, &update).await?;
+    let text = match updated.signature {
+        Some(_) => format!("updated the signature for {}", updated.email),
+        None => format!("cleared the signature for {}", updated.email),
+    };
+    ctx.output.emit(&text, &updated)
+}
+
+async fn set_default(ctx: &AppContext, email: &str) -> AppResult<()> {
+    let update = SendAsUpdate {
+        is_default: Some(true),
+        ..SendAsUpdate::default()
+    };
+    if ctx.dry_run {
+        let change = PlannedChange::new("sendas set-default", "update send-as alias")
+            .with("email", email)
+            .with("is_default", "true");
+        return dry_run::emit(ctx, &change);
+    }
+    let updated = update_send_as(ctx, email, &update).await?;
+    let text = format!("{} is now the default From address", updated.email);
+    ctx.output.emit(&text, &updated)
+}
+
+async fn update_send_as(
+    ctx: &AppContext,
+    email: &str,
+    update: &SendAsUpdate,
+) -> AppResult<SendAsView> {
+    let access_token = ctx.access_token().await?;
+    ctx.gmail_client
+        .update_send_as(email, update, &access_token)
+        .await
+}
+
+/// HTML for a Markdown signature; blank input clears the signature.
+fn signature_html(markdown: &str) -> String {
+    if markdown.trim().is_empty() {
+        return String::new();
+    }
+    mime::markdown_fragment(markdown).trim_end().to_string()
+}
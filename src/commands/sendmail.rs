@@ -0,0 +1,185 @@
+use std::io::{self, Read};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+use crate::cli::SendmailArgs;
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::outbox;
+use crate::commands::send::resolve_from_header;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+use crate::mail::{address, rfc822};
+use crate::output::OutputMode;
+
+/// Read a complete message from stdin and submit it unchanged apart from any
+/// From/Bcc headers needed to deliver it. Silent on success, like sendmail,
+/// unless a structured output format is selected.
+pub async fn run(ctx: &AppContext, args: SendmailArgs) -> AppResult<()> {
+    let mut message = Vec::new();
+    io::stdin().read_to_end(&mut message)?;
+    if !args.ignore_dots && !args.options.iter().any(|option| option == "i") {
+        truncate_at_dot(&mut message);
+    }
+
+    let access_token = ctx.access_token().await?;
+    let (headers, _) = rfc822::headers(&message);
+    let from = if !rfc822::values(&headers, "From").is_empty() {
+        None
+    } else if let Some(sender) = &args.sender {
+        Some(match &args.full_name {
+            Some(name) => format!("{} <{sender}>", name.replace(['"', '\r', '\n'], "")),
+            None => sender.clone(),
+        })
+    } else {
+        resolve_from_header(ctx, &access_token, None).await?
+    };
+
+    let message = prepare(message, &args, from)?;
+    if ctx.dry_run {
+        return dry_run::emit(ctx, &PlannedChange::raw_message("sendmail", &message));
+    }
+    let raw = URL_SAFE_NO_PAD.encode(&message);
+    let result = outbox::send(ctx, &raw, None, &access_token).await?;
+
+    if ctx.output.mode() == OutputMode::Text {
+        return Ok(());
+    }
+    ctx.output.emit(
+        &i18n::format("sent-message", &[("id", result.id.as_str().into())]),
+        &result,
+    )
+}
+
+/// Drop everything from a line consisting of a single `.`, sendmail's
+/// traditional end-of-input marker.
+fn truncate_at_dot(message: &mut Vec<u8>) {
+    let mut start = 0;
+    for line in message.split_inclusive(|byte| *byte == b'\n') {
+        if matches!(line, b".\n" | b".\r\n" | b".") {
+            message.truncate(start);
+            return;
+        }
+        start += line.len();
+    }
+}
+
+/// Check the recipients and add the headers Gmail needs to deliver the
+/// message: a From header when the message lacks one, and a Bcc header for
+/// recipients given as arguments but absent from To/Cc/Bcc.
+///
+/// Gmail delivers to every address in the headers, so without `-t` a header
+/// recipient missing from the arguments is an error rather than silently
+/// mailed.
+fn prepare(message: Vec<u8>, args: &SendmailArgs, from: Option<String>) -> AppResult<Vec<u8>> {
+    let (headers, _) = rfc822::headers(&message);
+    let in_headers = rfc822::recipients(&headers);
+
+    let mut requested = Vec::<String>::new();
+    for entry in args
+        .recipients
+        .iter()
+        .flat_map(|arg| address::split_addresses(arg))
+    {
+        let email = address::email_of(&entry);
+        if !requested.contains(&email) {
+            requested.push(email);
+        }
+    }
+
+    if !args.read_recipients {
+        if requested.is_empty() {
+            return Err(AppError::InvalidInput(
+                "no recipients given; pass addresses or use -t to read them from the headers"
+                    .to_string(),
+            ));
+        }
+        let unlisted = in_headers
+            .iter()
+            .filter(|email| !requested.contains(email))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !unlisted.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "Gmail would also deliver to {} from the message headers; list them as recipients or use -t",
+                unlisted.join(", ")
+            )));
+        }
+    }
+
+    let extra = requested
+        .into_iter()
+        .filter(|email| !in_headers.contains(email))
+        .collect::<Vec<_>>();
+    if in_headers.is_empty() && extra.is_empty() {
+        return Err(AppError::InvalidInput(
+            "the message has no To, Cc, or Bcc recipients".to_string(),
+        ));
+    }
+
+    // Match the message's own line endings.
+    let eol = match message.iter().position(|byte| *byte == b'\n') {
+        Some(end) if end > 0 && message[end - 1] == b'\r' => "\r\n",
+        _ => "\n",
+    };
+    let mut prefix = String::new();
+    if let Some(from) = from {
+        prefix.push_str(&format!("From: {from}{eol}"));
+    }
+    if !extra.is_empty() {
+        prefix.push_str(&format!("Bcc: {}{eol}", extra.join(", ")));
+    }
+
+    let mut out = Vec::with_capacity(prefix.len() + message.len());
+    out.extend_from_slice(prefix.as_bytes());
+    out.extend(message);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"To: Ada <ada@example.com>\nSubject: hi\n\nbody\n.\nafter\n";
+
+    fn args(read_recipients: bool, recipients: &[&str]) -> SendmailArgs {
+        SendmailArgs {
+            read_recipients,
+            ignore_dots: false,
+            sender: None,
+            full_name: None,
+            options: Vec::new(),
+            body_type: None,
+            recipients: recipients.iter().map(|value| value.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn stops_at_a_lone_dot() {
+        let mut message = MESSAGE.to_vec();
+        truncate_at_dot(&mut message);
+        assert_eq!(message, b"To: Ada <ada@example.com>\nSubject: hi\n\nbody\n");
+    }
+
+    #[test]
+    fn adds_bcc_for_extra_recipients_and_from_when_missing() {
+        let out = prepare(
+            MESSAGE.to_vec(),
+            &args(true, &["audit@example.com", "ADA@example.com"]),
+            Some("me@example.com".to_string()),
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("From: me@example.com\nBcc: audit@example.com\nTo: Ada"));
+    }
+
+    #[test]
+    fn requires_header_recipients_to_be_listed_without_t() {
+        assert!(prepare(MESSAGE.to_vec(), &args(false, &[]), None).is_err());
+        assert!(prepare(MESSAGE.to_vec(), &args(false, &["bob@example.com"]), None).is_err());
+
+        let out = prepare(MESSAGE.to_vec(), &args(false, &["ada@example.com"]), None).unwrap();
+        assert_eq!(out, MESSAGE);
+    }
+}
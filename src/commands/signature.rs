@@ -1,12 +1,24 @@
 use std::fs;
 
-use serde_json::json;
+use schemars::JsonSchema;
+use serde::Serialize;
 
+use crate::api::audit::AuditWrite;
 use crate::cli::SignatureCommand;
+use crate::commands::dry_run::{self, PlannedChange};
 use crate::config;
 use crate::context::AppContext;
 use crate::error::AppResult;
 
+/// A profile's signature; `profile` is omitted by `show`, which reports the
+/// active settings.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SignatureView {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub signature: Option<String>,
+}
+
 /// Dispatch a `gmail signature` subcommand (show/set/set-file/clear).
 pub async fn run(ctx: &AppContext, command: SignatureCommand) -> AppResult<()> {
     match command {
@@ -25,12 +37,19 @@ fn show(ctx: &AppContext) -> AppResult<()> {
         .as_deref()
         .filter(|sig| !sig.trim().is_empty())
     {
-        Some(signature) => ctx
-            .output
-            .emit(signature, &json!({ "signature": signature })),
+        Some(signature) => ctx.output.emit(
+            signature,
+            &SignatureView {
+                profile: None,
+                signature: Some(signature.to_string()),
+            },
+        ),
         None => ctx.output.emit(
             "no signature set. set one with `gmail signature set \"...\"`",
-            &json!({ "signature": null }),
+            &SignatureView {
+                profile: None,
+                signature: None,
+            },
         ),
     }
 }
@@ -39,27 +58,56 @@ fn show(ctx: &AppContext) -> AppResult<()> {
 fn set(ctx: &AppContext, text: String) -> AppResult<()> {
     let signature = text.trim_matches(['\r', '\n']).to_string();
     let profile = ctx.profile()?;
+    if ctx.dry_run {
+        let change = PlannedChange::new("signature set", "update profile settings")
+            .with("profile", profile)
+            .with("signature", signature);
+        return dry_run::emit(ctx, &change);
+    }
 
-    let mut settings = ctx.settings.clone();
-    settings.signature = Some(signature.clone());
-    config::save_settings(&ctx.paths, profile, &settings)?;
+    config::update_settings(&ctx.paths, profile, |settings| {
+        settings.signature = Some(signature.clone());
+    })?;
+    audit(ctx, profile);
 
     ctx.output.emit(
         &format!("signature set for profile `{profile}`:\n{signature}"),
-        &json!({ "profile": profile, "signature": signature }),
+        &SignatureView {
+            profile: Some(profile.to_string()),
+            signature: Some(signature),
+        },
     )
 }
 
 /// Remove the signature from the active profile's settings file.
 fn clear(ctx: &AppContext) -> AppResult<()> {
     let profile = ctx.profile()?;
+    if ctx.dry_run {
+        let change = PlannedChange::new("signature clear", "update profile settings")
+            .with("profile", profile)
+            .with("signature", "(cleared)");
+        return dry_run::emit(ctx, &change);
+    }
 
-    let mut settings = ctx.settings.clone();
-    settings.signature = None;
-    config::save_settings(&ctx.paths, profile, &settings)?;
+    config::update_settings(&ctx.paths, profile, |settings| {
+        settings.signature = None;
+    })?;
+    audit(ctx, profile);
 
     ctx.output.emit(
         &format!("signature cleared for profile `{profile}`"),
-        &json!({ "profile": profile, "signature": null }),
+        &SignatureView {
+            profile: Some(profile.to_string()),
+            signature: None,
+        },
     )
 }
+
+/// Note the profile's signature change for the audit log.
+fn audit(ctx: &AppContext, profile: &str) {
+    ctx.audit.record(AuditWrite {
+        operation: "profile.signature.update".to_string(),
+        ids: vec![profile.to_string()],
+        ..AuditWrite::default()
+    });
+}
@@ -0,0 +1,301 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::LabelMutationResult;
+use crate::cli::{SnoozeArgs, SnoozeCommand};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::{ids, queue, triage};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+use crate::mail::when;
+use crate::output::OutputMode;
+use crate::output::text::Role;
+
+/// A message taken out of the inbox until `until`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SnoozedMessage {
+    pub id: String,
+    /// When it goes back to the inbox, RFC 3339 in UTC.
+    pub until: String,
+    /// When it was snoozed, RFC 3339 in UTC.
+    pub snoozed: String,
+    pub from: Option<String>,
+    pub subject: Option<String>,
+}
+
+/// Snooze the given messages, or run a `ls`/`run`/`wake` subcommand.
+pub async fn run(ctx: &AppContext, args: SnoozeArgs) -> AppResult<()> {
+    match args.command {
+        Some(SnoozeCommand::Ls) => ls(ctx),
+        Some(SnoozeCommand::Run) => {
+            let path = ctx.paths.snooze_file(ctx.profile()?);
+            let due = due_entries(&load(&path)?, Utc::now());
+            if ctx.dry_run {
+                let change = PlannedChange::new("snooze run", "return snoozed messages to inbox")
+                    .with_ids(due.into_iter().map(|entry| entry.id).collect())
+                    .with_labels(&["INBOX".to_string()], &[]);
+                return dry_run::emit(ctx, &change);
+            }
+            let results = wake_due(ctx).await?;
+            if results.is_empty() {
+                return ctx.output.emit(&i18n::text("snooze-nothing-due"), &results);
+            }
+            triage::report_relabeled(ctx, "unsnooze", "back in inbox:", &results, false)
+        }
+        Some(SnoozeCommand::Wake { ids }) => wake(ctx, ids::expand(ids)?).await,
+        None => {
+            let until = args.until.unwrap_or_default();
+            snooze(ctx, ids::expand(args.ids)?, &until).await
+        }
+    }
+}
+
+/// Take `ids` out of the inbox and note them in the profile's snooze store
+/// to come back at `until`. Snoozing a snoozed message moves its time.
+async fn snooze(ctx: &AppContext, ids: Vec<String>, until: &str) -> AppResult<()> {
+    let due = until_time(until)?;
+    if due <= Utc::now() {
+        return Err(AppError::InvalidInput(format!(
+            "`{until}` has already passed; snooze until a time ahead"
+        )));
+    }
+    let until = due.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let inbox = ["INBOX".to_string()];
+    if ctx.dry_run {
+        let change = PlannedChange::new("snooze", "snooze messages")
+            .with_ids(ids)
+            .with_labels(&[], &inbox)
+            .with("until", queue::local_time(&until));
+        return dry_run::emit(ctx, &change);
+    }
+
+    let results = triage::relabel(ctx, &ids, [&[], &inbox]).await?;
+    let snoozed = results
+        .iter()
+        .filter(|result| result.error.is_none())
+        .map(|result| result.id.clone())
+        .collect::<Vec<_>>();
+    // Sender and subject are only for `snooze ls`; without them it still works.
+    let access_token = ctx.access_token().await?;
+    let messages = ctx
+        .gmail_client
+        .get_msgs(&snoozed, &access_token)
+        .await
+        .unwrap_or_default();
+
+    let path = ctx.paths.snooze_file(ctx.profile()?);
+    let mut store = load(&path)?;
+    store.retain(|entry| !snoozed.contains(&entry.id));
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    store.extend(snoozed.iter().map(|id| {
+        let message = messages.iter().find(|message| &message.id == id);
+        SnoozedMessage {
+            id: id.clone(),
+            until: until.clone(),
+            snoozed: now.clone(),
+            from: message.and_then(|message| message.from.clone()),
+            subject: message.and_then(|message| message.subject.clone()),
+        }
+    }));
+    save(&path, &store)?;
+
+    let done = format!("snoozed until {}:", queue::local_time(&until));
+    triage::report_relabeled(ctx, "snooze", &done, &results, false)
+}
+
+/// Return every snoozed message that is due to the inbox. Used by `snooze
+/// run` and on every daemon tick.
+pub(crate) async fn wake_due(ctx: &AppContext) -> AppResult<Vec<LabelMutationResult>> {
+    let path = ctx.paths.snooze_file(ctx.profile()?);
+    let due = due_entries(&load(&path)?, Utc::now());
+    if due.is_empty() {
+        return Ok(Vec::new());
+    }
+    restore(ctx, due.into_iter().map(|entry| entry.id).collect()).await
+}
+
+/// Return snoozed messages to the inbox ahead of time.
+async fn wake(ctx: &AppContext, ids: Vec<String>) -> AppResult<()> {
+    if ctx.dry_run {
+        let change = PlannedChange::new("snooze wake", "return snoozed messages to inbox")
+            .with_ids(ids)
+            .with_labels(&["INBOX".to_string()], &[]);
+        return dry_run::emit(ctx, &change);
+    }
+
+    let store = load(&ctx.paths.snooze_file(ctx.profile()?))?;
+    let (snoozed, unknown): (Vec<_>, Vec<_>) = ids
+        .into_iter()
+        .partition(|id| store.iter().any(|entry| &entry.id == id));
+    let mut results = restore(ctx, snoozed).await?;
+    results.extend(unknown.into_iter().map(|id| LabelMutationResult {
+        error: Some(format!("`{id}` is not snoozed; see `gmail snooze ls`")),
+        id,
+        added: Vec::new(),
+        removed: Vec::new(),
+        note: "message labels not updated".to_string(),
+    }));
+    triage::report_relabeled(ctx, "unsnooze", "back in inbox:", &results, false)
+}
+
+/// Put `ids` back in the inbox one by one, dropping each from the store once
+/// it is back or Gmail no longer has it. Other failures stay snoozed, to be
+/// retried by the next run.
+async fn restore(ctx: &AppContext, ids: Vec<String>) -> AppResult<Vec<LabelMutationResult>> {
+    let path = ctx.paths.snooze_file(ctx.profile()?);
+    let inbox = ["INBOX".to_string()];
+    let mut results = Vec::with_capacity(ids.len());
+    for id in ids {
+        if ctx.interrupt.is_set() {
+            break;
+        }
+        let access_token = ctx.access_token().await?;
+        let (result, done) = match ctx
+            .gmail_client
+            .modify_labels(&id, &inbox, &[], &access_token)
+            .await
+        {
+            Ok(result) => (result, true),
+            Err(err @ AppError::Auth(_)) => return Err(err),
+            Err(err) => (triage::not_relabeled(&id, &err), err.status() == Some(404)),
+        };
+        if done {
+            let mut store = load(&path)?;
+            store.retain(|entry| entry.id != id);
+            save(&path, &store)?;
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+fn ls(ctx: &AppContext) -> AppResult<()> {
+    let mut store = load(&ctx.paths.snooze_file(ctx.profile()?))?;
+    store.sort_by_key(until_at);
+    if ctx.output.mode() != OutputMode::Text {
+        let text = i18n::format("snooze-count", &[("count", store.len().into())]);
+        return ctx.output.emit(&text, &store);
+    }
+
+    let mut out = ctx.output.paged();
+    if store.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            i18n::format("snooze-count", &[("count", 0.into())])
+        )?;
+        return out.finish();
+    }
+    let (unknown_sender, no_subject) = (i18n::text("unknown-sender"), i18n::text("no-subject"));
+    for message in &store {
+        writeln!(
+            out,
+            "{} | {} | {} | {}",
+            message.id,
+            ctx.output
+                .paint(Role::Date, &queue::local_time(&message.until)),
+            ctx.output.paint(
+                Role::Sender,
+                message.from.as_deref().unwrap_or(&unknown_sender)
+            ),
+            message.subject.as_deref().unwrap_or(&no_subject)
+        )?;
+    }
+    out.finish()
+}
+
+/// `--until` in UTC: a local date and time as `send --at` reads it, where a
+/// day alone (`tomorrow`, `mon`, `2025-03-04`) means 8am that day.
+fn until_time(text: &str) -> AppResult<DateTime<Utc>> {
+    let now = Local::now().naive_local();
+    let local = when::parse_when(text, now)
+        .or_else(|err| when::parse_when(&format!("{text} 8am"), now).map_err(|_| err))?;
+    queue::in_local_time(local, text)
+}
+
+/// Entries due at or before `now`, earliest first.
+fn due_entries(store: &[SnoozedMessage], now: DateTime<Utc>) -> Vec<SnoozedMessage> {
+    let mut due: Vec<SnoozedMessage> = store
+        .iter()
+        .filter(|entry| until_at(entry) <= now)
+        .cloned()
+        .collect();
+    due.sort_by_key(until_at);
+    due
+}
+
+/// When a snoozed message comes back; an unreadable time counts as now.
+fn until_at(message: &SnoozedMessage) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(&message.until)
+        .map(|until| until.with_timezone(&Utc))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+fn load(path: &Path) -> AppResult<Vec<SnoozedMessage>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save(path: &Path, store: &[SnoozedMessage]) -> AppResult<()> {
+    if store.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, until: &str) -> SnoozedMessage {
+        SnoozedMessage {
+            id: id.to_string(),
+            until: until.to_string(),
+            snoozed: "2024-05-01T08:00:00Z".to_string(),
+            from: None,
+            subject: None,
+        }
+    }
+
+    #[test]
+    fn picks_due_entries_earliest_first() {
+        let store = [
+            entry("late", "2024-06-01T10:00:00Z"),
+            entry("future", "2024-06-02T09:00:00Z"),
+            entry("early", "2024-06-01T09:00:00+02:00"),
+        ];
+        let now = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let due: Vec<String> = due_entries(&store, now)
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(due, ["early", "late"]);
+    }
+
+    #[test]
+    fn a_day_alone_means_eight_in_the_morning() {
+        let until = until_time("2099-03-04").unwrap().with_timezone(&Local);
+        assert_eq!(
+            until.format("%Y-%m-%d %H:%M").to_string(),
+            "2099-03-04 08:00"
+        );
+        assert!(until_time("someday").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use chrono::Local;
+
+use crate::cli::StatsArgs;
+use crate::commands::search;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::stats;
+use crate::mail::usage::human_bytes;
+use crate::output::OutputMode;
+
+/// Aggregate the messages of the `--since` (or `--after`/`--before`) window,
+/// narrowed by `--q`, into a mailbox report. CSV, table, and NDJSON output get the report as long-form
+/// `section,key,value` rows; JSON and YAML get the nested report.
+pub async fn run(ctx: &AppContext, args: StatsArgs) -> AppResult<()> {
+    let report = report(ctx, &args).await?;
+
+    match ctx.output.mode() {
+        OutputMode::Text => {}
+        OutputMode::Csv | OutputMode::Table | OutputMode::Ndjson => {
+            return ctx.output.emit("", &report.rows());
+        }
+        _ => return ctx.output.emit("", &report),
+    }
+
+    let mut out = ctx.output.paged();
+    write_text(&mut out, &report)?;
+    out.finish()
+}
+
+/// Fetch the window's messages and labels, and which of them carry
+/// attachments, and build the report.
+pub(crate) async fn report(ctx: &AppContext, args: &StatsArgs) -> AppResult<stats::Report> {
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+    let query = window_query(args)?;
+
+    let access_token = ctx.access_token().await?;
+    let messages = ctx
+        .gmail_client
+        .list(&access_token, args.limit, Some(&query))
+        .await?;
+    // A report over part of the window would mislead; stop instead.
+    ctx.interrupt.check()?;
+    // Metadata doesn't list parts, so ask Gmail which messages have attachments.
+    let with_attachments = ctx
+        .gmail_client
+        .list_ids(
+            &access_token,
+            args.limit,
+            Some(&format!("{query} has:attachment")),
+        )
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    ctx.interrupt.check()?;
+    let labels = ctx.gmail_client.list_labels(&access_token).await?;
+    let report = stats::build(
+        &query,
+        &messages,
+        &labels,
+        &with_attachments,
+        args.top,
+        &Local,
+    );
+    if messages.len() as u32 == args.limit {
+        eprintln!(
+            "stats: stopped at --limit {} messages; raise it to cover the whole window",
+            args.limit
+        );
+    }
+
+    Ok(report)
+}
+
+/// The Gmail query for the window: `--after` (else `--since`), `--before`,
+/// then `--q`.
+fn window_query(args: &StatsArgs) -> AppResult<String> {
+    let mut terms = vec![match &args.after {
+        Some(after) => search::date_term("after", "newer_than", after)?,
+        None => stats::newer_than(&args.since)?,
+    }];
+    if let Some(before) = &args.before {
+        terms.push(search::date_term("before", "older_than", before)?);
+    }
+    terms.extend(args.q.iter().cloned());
+    Ok(terms.join(" "))
+}
+
+/// Render a report as indented text sections.
+pub(crate) fn write_text(out: &mut impl Write, report: &stats::Report) -> AppResult<()> {
+    writeln!(
+        out,
+        "{} messages ({} received, {} sent) matching `{}`",
+        report.messages, report.received, report.sent, report.query
+    )?;
+
+    writeln!(out, "\ntop senders")?;
+    for sender in &report.top_senders {
+        writeln!(out, "  {:>5}  {}", sender.messages, sender.sender)?;
+    }
+
+    writeln!(out, "\ntop domains")?;
+    for domain in &report.top_domains {
+        writeln!(out, "  {:>5}  {}", domain.messages, domain.domain)?;
+    }
+
+    writeln!(
+        out,
+        "\nattachments\n  {} messages, {}",
+        report.attachments.messages,
+        human_bytes(report.attachments.bytes)
+    )?;
+
+    writeln!(out, "\nper day (received / sent)")?;
+    for day in &report.per_day {
+        writeln!(out, "  {}  {:>4} / {:<4}", day.date, day.received, day.sent)?;
+    }
+
+    writeln!(out, "\nresponse latency")?;
+    match (
+        report.response.average_minutes,
+        report.response.median_minutes,
+    ) {
+        (Some(average), Some(median)) => writeln!(
+            out,
+            "  {} replies, average {}, median {}",
+            report.response.replies,
+            minutes(average),
+            minutes(median)
+        )?,
+        _ => writeln!(out, "  no replies in this window")?,
+    }
+
+    writeln!(out, "\nlabels")?;
+    for label in &report.labels {
+        writeln!(out, "  {:>5}  {}", label.messages, label.label)?;
+    }
+    Ok(())
+}
+
+/// `95` → `1h 35m`; `3000` → `2d 2h`.
+fn minutes(total: u64) -> String {
+    match (total / (24 * 60), total / 60 % 24, total % 60) {
+        (0, 0, minutes) => format!("{minutes}m"),
+        (0, hours, minutes) => format!("{hours}h {minutes}m"),
+        (days, hours, _) => format!("{days}d {hours}h"),
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use crate::cli::StreamArgs;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::output::json;
+
+/// Emit one JSON line per mailbox event (message added/deleted, labels
+/// added/removed) from `users.history.list`, polling until interrupted.
+///
+/// Output is always NDJSON regardless of `--json`: the command exists to be
+/// piped into other programs. Progress and errors go to stderr. A history id
+/// Gmail no longer knows (404: too old or invalid) restarts the feed from the
+/// mailbox's current id, with a warning that events in between are skipped.
+pub async fn run(ctx: &AppContext, args: StreamArgs) -> AppResult<()> {
+    if args.interval == 0 {
+        return Err(AppError::InvalidInput(
+            "--interval must be greater than 0".to_string(),
+        ));
+    }
+
+    ctx.output.require_stdout("stream")?;
+
+    let mut history_id = match args.since {
+        Some(history_id) => history_id,
+        None => {
+            let access_token = ctx.access_token().await?;
+            ctx.gmail_client
+                .get_profile(&access_token)
+                .await?
+                .history_id
+        }
+    };
+    eprintln!("streaming mailbox events after history id {history_id}; ctrl-c to stop");
+
+    loop {
+        let access_token = ctx.access_token().await?;
+        match ctx
+            .gmail_client
+            .list_history(&history_id, None, &[], &access_token)
+            .await
+        {
+            Ok(page) => {
+                for event in &page.events {
+                    json::print_line(event)?;
+                }
+                history_id = page.history_id;
+            }
+            Err(err) if err.status() == Some(404) => {
+                let current = ctx
+                    .gmail_client
+                    .get_profile(&access_token)
+                    .await?
+                    .history_id;
+                eprintln!(
+                    "history id {history_id} has expired or is invalid; continuing from {current}, so earlier events are skipped"
+                );
+                history_id = current;
+            }
+            Err(err) => eprintln!("history poll failed: {err}"),
+        }
+
+        ctx.interrupt
+            .sleep(Duration::from_secs(args.interval))
+            .await?;
+    }
+}
@@ -0,0 +1,172 @@
+use std::io::Write;
+
+use crate::api::models::{ThreadSummary, ThreadView};
+use crate::cli::{ThreadCommand, ThreadLsArgs};
+use crate::commands::list::{self, fit, format_preview};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+use crate::output::OutputMode;
+use crate::output::text::Role;
+
+/// Dispatch a `gmail thread` subcommand (get/ls).
+pub async fn run(ctx: &AppContext, command: ThreadCommand) -> AppResult<()> {
+    match command {
+        ThreadCommand::Get(args) => {
+            let access_token = ctx.access_token().await?;
+            let thread = ctx.gmail_client.get_thread(&args.id, &access_token).await?;
+            if ctx.output.mode() == OutputMode::Text {
+                return write_thread(ctx, &thread);
+            }
+            ctx.output.emit("", &thread)
+        }
+        ThreadCommand::Ls(args) => {
+            ls(ctx, args).await?;
+            ctx.interrupt.check()
+        }
+    }
+}
+
+async fn ls(ctx: &AppContext, args: ThreadLsArgs) -> AppResult<()> {
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+
+    let access_token = ctx.access_token().await?;
+    let query = list::build_query(args.inbox, args.q.as_deref());
+    print_threads(ctx, &access_token, args.limit, query.as_deref()).await
+}
+
+/// Fetch up to `limit` conversations matching `query` and print their
+/// summaries (`thread ls`, `list --threads`).
+pub async fn print_threads(
+    ctx: &AppContext,
+    access_token: &str,
+    limit: u32,
+    query: Option<&str>,
+) -> AppResult<()> {
+    let summaries = ctx
+        .gmail_client
+        .list_threads(access_token, limit, query)
+        .await?
+        .iter()
+        .map(ThreadView::summary)
+        .collect::<Vec<_>>();
+
+    if ctx.output.mode() == OutputMode::Text {
+        return write_summaries(ctx, &summaries);
+    }
+    let text = i18n::format("threads-count", &[("count", summaries.len().into())]);
+    ctx.output.emit(&text, &summaries)
+}
+
+/// Print a conversation: its subject and size, then each message's sender,
+/// date, and wrapped preview, oldest first.
+fn write_thread(ctx: &AppContext, thread: &ThreadView) -> AppResult<()> {
+    let summary = thread.summary();
+    let mut out = ctx.output.paged();
+    writeln!(
+        out,
+        "{}",
+        summary.subject.unwrap_or_else(|| i18n::text("no-subject"))
+    )?;
+    writeln!(
+        out,
+        "{}  ({})",
+        summary.participants.join(", "),
+        i18n::format("messages-count", &[("count", summary.message_count.into())])
+    )?;
+
+    let unknown_sender = i18n::text("unknown-sender");
+    let width = ctx.output.width();
+    for (index, message) in thread.messages.iter().enumerate() {
+        let from = fit(
+            message.from.as_deref().unwrap_or(&unknown_sender),
+            width,
+            "   ",
+        );
+        let date = message
+            .date
+            .as_deref()
+            .map_or_else(|| i18n::text("no-date"), |date| ctx.output.date(date));
+
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{}. {}",
+            index + 1,
+            ctx.output.paint(Role::Sender, &from)
+        )?;
+        writeln!(
+            out,
+            "   {}  {}",
+            ctx.output.paint(Role::Date, &date),
+            message.id
+        )?;
+        for line in format_preview(message.snippet.as_deref(), width) {
+            writeln!(out, "   {line}")?;
+        }
+    }
+
+    out.finish()
+}
+
+/// Print conversations as numbered entries: id, size, participants, subject,
+/// latest date, and the latest message's preview.
+fn write_summaries(ctx: &AppContext, summaries: &[ThreadSummary]) -> AppResult<()> {
+    let mut out = ctx.output.paged();
+    if summaries.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            i18n::format("threads-count", &[("count", 0.into())])
+        )?;
+        return out.finish();
+    }
+
+    let field = |id: &str| format!("   {}: ", i18n::text(id));
+    let (participants_field, subject_field, date_field) = (
+        field("field-participants"),
+        field("field-subject"),
+        field("field-date"),
+    );
+    let (unread, no_subject) = (i18n::text("unread"), i18n::text("no-subject"));
+    let width = ctx.output.width();
+    for (index, summary) in summaries.iter().enumerate() {
+        let mut size = i18n::format("messages-count", &[("count", summary.message_count.into())]);
+        if summary.unread {
+            size.push_str(&format!(", {unread}"));
+        }
+        let participants = fit(&summary.participants.join(", "), width, &participants_field);
+        let subject = fit(
+            summary.subject.as_deref().unwrap_or(&no_subject),
+            width,
+            &subject_field,
+        );
+        let date = summary
+            .date
+            .as_deref()
+            .map_or_else(|| i18n::text("no-date"), |date| ctx.output.date(date));
+
+        writeln!(out, "{}. {}  ({size})", index + 1, summary.id)?;
+        writeln!(
+            out,
+            "{participants_field}{}",
+            ctx.output.paint(Role::Sender, &participants)
+        )?;
+        writeln!(out, "{subject_field}{subject}")?;
+        writeln!(out, "{date_field}{}", ctx.output.paint(Role::Date, &date))?;
+        writeln!(out)?;
+        for line in format_preview(summary.snippet.as_deref(), width) {
+            writeln!(out, "   {line}")?;
+        }
+
+        if index + 1 < summaries.len() {
+            writeln!(out)?;
+        }
+    }
+
+    out.finish()
+}
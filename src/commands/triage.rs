@@ -0,0 +1,583 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::messages;
+use crate::api::models::LabelMutationResult;
+use crate::auth::token_store::TokenStore;
+use crate::cli::{BulkArgs, DeleteArgs, EmptyArgs};
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::{confirm, ids};
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+
+/// A one-word triage command and the system labels it adds and removes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Triage {
+    Archive,
+    Read,
+    Unread,
+    Spam,
+    NotSpam,
+    Star,
+    Unstar,
+}
+
+impl Triage {
+    /// The command as typed, e.g. `archive`.
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Archive => "archive",
+            Self::Read => "read",
+            Self::Unread => "unread",
+            Self::Spam => "spam",
+            Self::NotSpam => "not-spam",
+            Self::Star => "star",
+            Self::Unstar => "unstar",
+        }
+    }
+
+    /// What it does, for dry runs and failures: `mark read`.
+    fn action(self) -> &'static str {
+        match self {
+            Self::Archive => "archive",
+            Self::Read => "mark read",
+            Self::Unread => "mark unread",
+            Self::Spam => "report spam",
+            Self::NotSpam => "move out of spam",
+            Self::Star => "star",
+            Self::Unstar => "unstar",
+        }
+    }
+
+    /// What the text output says was done to each id.
+    fn done(self) -> &'static str {
+        match self {
+            Self::Archive => "archived",
+            Self::Read => "marked read",
+            Self::Unread => "marked unread",
+            Self::Spam => "reported spam",
+            Self::NotSpam => "moved to inbox",
+            Self::Star => "starred",
+            Self::Unstar => "unstarred",
+        }
+    }
+
+    /// System labels to add and to remove.
+    fn labels(self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            Self::Archive => (&[], &["INBOX"]),
+            Self::Read => (&[], &["UNREAD"]),
+            Self::Unread => (&["UNREAD"], &[]),
+            Self::Spam => (&["SPAM"], &["INBOX"]),
+            Self::NotSpam => (&["INBOX"], &["SPAM"]),
+            Self::Star => (&["STARRED"], &[]),
+            Self::Unstar => (&[], &["STARRED"]),
+        }
+    }
+}
+
+/// Apply `triage` to the given messages, or to every message matching `--q`.
+pub async fn run(ctx: &AppContext, triage: Triage, args: BulkArgs) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let (query, thread) = (args.q.clone(), args.thread);
+    let Some(ids) = bulk_ids(ctx, args, triage.action(), &access_token).await? else {
+        return Ok(());
+    };
+    let (add, remove) = triage.labels();
+    let add = add.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let remove = remove.iter().map(ToString::to_string).collect::<Vec<_>>();
+    if ctx.dry_run {
+        let matches = query.as_ref().map(|_| ids.len().to_string());
+        let change = PlannedChange::new(triage.command(), triage.action())
+            .with_ids(ids)
+            .with_labels(&add, &remove)
+            .with("query", query.unwrap_or_default())
+            .with("matches", matches.unwrap_or_default())
+            .with("target", target(thread));
+        return dry_run::emit(ctx, &change);
+    }
+
+    let results = if thread {
+        relabel_threads(ctx, &ids, [&add, &remove]).await?
+    } else {
+        relabel(ctx, &ids, [&add, &remove]).await?
+    };
+    report_relabeled(ctx, triage.action(), triage.done(), &results, false)
+}
+
+/// Add and remove labels (names or ids) on `ids`, returning a result per id.
+/// One id is a `messages.modify`; more go out in `messages.batchModify` calls
+/// of up to [`messages::BATCH_SIZE`], each with a freshly checked token so a
+/// long run outlives the one it started with. A call that fails marks its ids
+/// failed and the rest still go ahead; an auth failure stops at once. After
+/// Ctrl-C no new call starts, and the ids not sent are left out.
+pub(crate) async fn relabel(
+    ctx: &AppContext,
+    ids: &[String],
+    labels: [&[String]; 2],
+) -> AppResult<Vec<LabelMutationResult>> {
+    let access_token = ctx.access_token().await?;
+    let [add, remove] = ctx
+        .gmail_client
+        .resolve_label_ids(labels, &access_token)
+        .await?;
+    if let [id] = ids {
+        return Ok(vec![match ctx
+            .gmail_client
+            .modify_labels(id, &add, &remove, &access_token)
+            .await
+        {
+            Err(err @ AppError::Auth(_)) => return Err(err),
+            Err(err) => not_relabeled(id, &err),
+            Ok(result) => result,
+        }]);
+    }
+
+    let mut results = Vec::with_capacity(ids.len());
+    for chunk in ids.chunks(messages::BATCH_SIZE) {
+        if ctx.interrupt.is_set() {
+            break;
+        }
+        let access_token = ctx.access_token().await?;
+        match ctx
+            .gmail_client
+            .batch_modify(chunk, &add, &remove, &access_token)
+            .await
+        {
+            Err(err @ AppError::Auth(_)) => return Err(err),
+            Err(err) => results.extend(chunk.iter().map(|id| not_relabeled(id, &err))),
+            Ok(0) => break,
+            Ok(_) => results.extend(chunk.iter().map(|id| LabelMutationResult {
+                id: id.clone(),
+                added: add.clone(),
+                removed: remove.clone(),
+                note: "message labels updated".to_string(),
+                error: None,
+            })),
+        }
+    }
+    Ok(results)
+}
+
+/// Add and remove labels on every message of each thread in `ids`, one
+/// `threads.modify` per thread, with the same failure handling as [`relabel`].
+pub(crate) async fn relabel_threads(
+    ctx: &AppContext,
+    ids: &[String],
+    labels: [&[String]; 2],
+) -> AppResult<Vec<LabelMutationResult>> {
+    let access_token = ctx.access_token().await?;
+    let [add, remove] = ctx
+        .gmail_client
+        .resolve_label_ids(labels, &access_token)
+        .await?;
+    let mut results = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !results.is_empty() && ctx.interrupt.is_set() {
+            break;
+        }
+        let access_token = ctx.access_token().await?;
+        results.push(
+            match ctx
+                .gmail_client
+                .modify_thread(id, &add, &remove, &access_token)
+                .await
+            {
+                Err(err @ AppError::Auth(_)) => return Err(err),
+                Err(err) => not_relabeled(id, &err),
+                Ok(result) => result,
+            },
+        );
+    }
+    Ok(results)
+}
+
+/// The dry-run `target` field: `threads` when `--thread` widened the change
+/// to whole conversations, blank (and so left out) otherwise.
+pub(crate) fn target(thread: bool) -> &'static str {
+    if thread { "threads" } else { "" }
+}
+
+pub(crate) fn not_relabeled(id: &str, err: &AppError) -> LabelMutationResult {
+    LabelMutationResult {
+        id: id.to_string(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        note: "message labels not updated".to_string(),
+        error: Some(err.to_string()),
+    }
+}
+
+/// Print each id's result (the result alone when `single`), then fail if any
+/// id failed. `action` completes "failed to ...", `done` leads each success.
+pub(crate) fn report_relabeled(
+    ctx: &AppContext,
+    action: &str,
+    done: &str,
+    results: &[LabelMutationResult],
+    single: bool,
+) -> AppResult<()> {
+    let text = results
+        .iter()
+        .map(|result| match &result.error {
+            None => format!("{done} {}", result.id),
+            Some(error) => format!("failed to {action} {}: {error}", result.id),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    match results {
+        [result] if single => ctx.output.emit(&text, result)?,
+        _ => ctx.output.emit(&text, &results)?,
+    }
+    ctx.interrupt.check()?;
+
+    let failed = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+    if failed > 0 {
+        return Err(AppError::api(format!(
+            "failed to {action} {failed} of {} messages",
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+/// The ids given, with `-` read from stdin, or those of up to `--limit`
+/// messages matching `--q` (every match with `--all`; threads rather than
+/// messages with `--thread`). A query's matches are only acted on once the
+/// user confirms the count (or passed `--yes`, or this is a dry run); `None`
+/// means they declined.
+pub(crate) async fn bulk_ids(
+    ctx: &AppContext,
+    args: BulkArgs,
+    action: &str,
+    access_token: &str,
+) -> AppResult<Option<Vec<String>>> {
+    let Some(query) = args.q else {
+        return ids::expand(args.ids).map(Some);
+    };
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+    let limit = if args.all { u32::MAX } else { args.limit };
+    let (ids, noun) = if args.thread {
+        let ids = ctx
+            .gmail_client
+            .list_thread_ids(access_token, limit, Some(&query))
+            .await?;
+        (ids, "thread")
+    } else {
+        let ids = ctx
+            .gmail_client
+            .list_ids(access_token, limit, Some(&query))
+            .await?;
+        (ids, "message")
+    };
+    // A partial list would act on fewer messages than the question says.
+    ctx.interrupt.check()?;
+    if ids.is_empty() {
+        eprintln!("no {noun}s match `{query}`");
+        return Ok(Some(ids));
+    }
+    if ids.len() as u32 == limit {
+        eprintln!(
+            "stopped at --limit {} {noun}s; raise it to include the rest",
+            args.limit
+        );
+    }
+
+    let question = match ids.len() {
+        1 => format!("{action} 1 {noun} matching `{query}`?"),
+        count => format!("{action} {count} {noun}s matching `{query}`?"),
+    };
+    if ctx.dry_run || args.yes || confirm::ask(&question, "--yes")? {
+        return Ok(Some(ids));
+    }
+    eprintln!("nothing changed");
+    Ok(None)
+}
+
+/// What a per-message command did to one id.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MessageOutcome {
+    pub id: String,
+    pub ok: bool,
+    /// Why the call failed, when it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Move each message to the trash (or, with `trash` false, back out of it),
+/// reporting every id. Messages matched by `--q` are moved in
+/// `messages.batchModify` calls on the `TRASH` label instead of one call each;
+/// with `--thread` each id is a conversation moved by `threads.trash`.
+pub async fn trash(ctx: &AppContext, args: BulkArgs, trash: bool) -> AppResult<()> {
+    let (command, action, done) = if trash {
+        ("trash", "move to trash", "trashed")
+    } else {
+        ("untrash", "restore from trash", "restored")
+    };
+    let access_token = ctx.access_token().await?;
+    let (query, thread) = (args.q.clone(), args.thread);
+    let Some(ids) = bulk_ids(ctx, args, action, &access_token).await? else {
+        return Ok(());
+    };
+    if ctx.dry_run {
+        let matches = query.as_ref().map(|_| ids.len().to_string());
+        let change = PlannedChange::new(command, action)
+            .with_ids(ids)
+            .with("query", query.unwrap_or_default())
+            .with("matches", matches.unwrap_or_default())
+            .with("target", target(thread));
+        return dry_run::emit(ctx, &change);
+    }
+
+    let outcomes = if thread {
+        for_each_id(ctx, ids, |id, token| async move {
+            if trash {
+                ctx.gmail_client.trash_thread(&id, &token).await
+            } else {
+                ctx.gmail_client.untrash_thread(&id, &token).await
+            }
+        })
+        .await?
+    } else if query.is_some() {
+        let label = ["TRASH".to_string()];
+        let labels: [&[String]; 2] = if trash { [&label, &[]] } else { [&[], &label] };
+        relabel(ctx, &ids, labels)
+            .await?
+            .into_iter()
+            .map(|result| MessageOutcome {
+                ok: result.error.is_none(),
+                id: result.id,
+                error: result.error,
+            })
+            .collect()
+    } else {
+        for_each_id(ctx, ids, |id, token| async move {
+            if trash {
+                ctx.gmail_client.trash(&id, &token).await
+            } else {
+                ctx.gmail_client.untrash(&id, &token).await
+            }
+        })
+        .await?
+    };
+    report(ctx, command, done, &outcomes)
+}
+
+/// Permanently delete each message after confirming (unless `--yes`),
+/// asking for full mail access in the browser first when the stored token
+/// lacks it.
+pub async fn delete(ctx: &AppContext, args: DeleteArgs) -> AppResult<()> {
+    let ids = ids::expand(args.ids)?;
+    if ctx.dry_run {
+        let change = PlannedChange::new("delete", "delete permanently").with_ids(ids);
+        return dry_run::emit(ctx, &change);
+    }
+    let question = match ids.as_slice() {
+        [id] => format!("permanently delete message {id}? This can't be undone."),
+        ids => format!(
+            "permanently delete {} messages? This can't be undone.",
+            ids.len()
+        ),
+    };
+    if !args.yes && !confirm::ask(&question, "--yes")? {
+        eprintln!("nothing deleted");
+        return Ok(());
+    }
+
+    ensure_delete_scope(ctx).await?;
+    let outcomes = for_each_id(ctx, ids, |id, token| async move {
+        ctx.gmail_client.delete_msg(&id, &token).await
+    })
+    .await?;
+    report(ctx, "delete", "deleted", &outcomes)
+}
+
+/// A system folder `empty-trash` / `empty-spam` clears.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Folder {
+    Trash,
+    Spam,
+}
+
+impl Folder {
+    fn command(self) -> &'static str {
+        match self {
+            Self::Trash => "empty-trash",
+            Self::Spam => "empty-spam",
+        }
+    }
+
+    /// The folder as the text output names it.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Trash => "the trash",
+            Self::Spam => "spam",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Trash => "TRASH",
+            Self::Spam => "SPAM",
+        }
+    }
+
+    fn query(self) -> &'static str {
+        match self {
+            Self::Trash => "in:trash",
+            Self::Spam => "in:spam",
+        }
+    }
+}
+
+/// What `empty-trash` / `empty-spam` removed.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EmptiedFolder {
+    /// `TRASH` or `SPAM`.
+    pub label: String,
+    /// Messages permanently deleted.
+    pub deleted: usize,
+}
+
+/// Permanently delete every message in the trash or spam, in
+/// `messages.batchDelete` calls, after confirming the count (unless `--yes`).
+pub async fn empty(ctx: &AppContext, folder: Folder, args: EmptyArgs) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let ids = ctx
+        .gmail_client
+        .list_ids(&access_token, u32::MAX, Some(folder.query()))
+        .await?;
+    // Only delete what the question counted.
+    ctx.interrupt.check()?;
+    let label = folder.label().to_string();
+    if ids.is_empty() {
+        return ctx.output.emit(
+            &format!("{} is already empty", folder.name()),
+            &EmptiedFolder { label, deleted: 0 },
+        );
+    }
+    if ctx.dry_run {
+        let change = PlannedChange::new(folder.command(), "delete permanently")
+            .with("messages", ids.len().to_string())
+            .with_ids(ids);
+        return dry_run::emit(ctx, &change);
+    }
+    let question = match ids.len() {
+        1 => format!(
+            "permanently delete the 1 message in {}? This can't be undone.",
+            folder.name()
+        ),
+        count => format!(
+            "permanently delete all {count} messages in {}? This can't be undone.",
+            folder.name()
+        ),
+    };
+    if !args.yes && !confirm::ask(&question, "--yes")? {
+        eprintln!("nothing deleted");
+        return Ok(());
+    }
+
+    ensure_delete_scope(ctx).await?;
+    let access_token = ctx.access_token().await?;
+    let deleted = ctx.gmail_client.batch_delete(&ids, &access_token).await?;
+    ctx.output.emit(
+        &format!(
+            "permanently deleted {deleted} messages from {}",
+            folder.name()
+        ),
+        &EmptiedFolder { label, deleted },
+    )?;
+    ctx.interrupt.check()
+}
+
+/// Ask for full mail access in the browser when the stored token lacks the
+/// scope permanent deletion needs.
+async fn ensure_delete_scope(ctx: &AppContext) -> AppResult<()> {
+    let profile = ctx.profile()?;
+    let granted = ctx
+        .token_store
+        .load(profile)?
+        .is_some_and(|token| token.has_scopes(&messages::DELETE_SCOPES));
+    if !granted {
+        eprintln!("permanent deletion needs full access to your mail; asking for it now");
+        ctx.auth
+            .login_with_scopes(
+                profile,
+                &ctx.settings,
+                &ctx.token_store,
+                &messages::DELETE_SCOPES,
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Run `call` on each id in turn with a freshly checked access token,
+/// carrying on past ids that fail. An auth failure stops it at once, since
+/// every later id would fail the same way; after Ctrl-C no new id is started.
+pub(crate) async fn for_each_id<F, Fut>(
+    ctx: &AppContext,
+    ids: Vec<String>,
+    call: F,
+) -> AppResult<Vec<MessageOutcome>>
+where
+    F: Fn(String, String) -> Fut,
+    Fut: Future<Output = AppResult<()>>,
+{
+    let mut outcomes = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !outcomes.is_empty() && ctx.interrupt.is_set() {
+            break;
+        }
+        let access_token = ctx.access_token().await?;
+        outcomes.push(match call(id.clone(), access_token).await {
+            Ok(()) => MessageOutcome {
+                id,
+                ok: true,
+                error: None,
+            },
+            Err(err @ AppError::Auth(_)) => return Err(err),
+            Err(err) => MessageOutcome {
+                id,
+                ok: false,
+                error: Some(err.to_string()),
+            },
+        });
+    }
+    Ok(outcomes)
+}
+
+/// Print each id's outcome, then fail if any id did.
+pub(crate) fn report(
+    ctx: &AppContext,
+    command: &str,
+    done: &str,
+    outcomes: &[MessageOutcome],
+) -> AppResult<()> {
+    let text = outcomes
+        .iter()
+        .map(|outcome| match &outcome.error {
+            None => format!("{done} {}", outcome.id),
+            Some(error) => format!("failed to {command} {}: {error}", outcome.id),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.output.emit(&text, &outcomes)?;
+    ctx.interrupt.check()?;
+
+    let failed = outcomes.iter().filter(|outcome| !outcome.ok).count();
+    if failed > 0 {
+        return Err(AppError::api(format!(
+            "{failed} of {} messages could not be {done}",
+            outcomes.len()
+        )));
+    }
+    Ok(())
+}
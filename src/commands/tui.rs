@@ -0,0 +1,23 @@
+use std::io::IsTerminal;
+
+use crate::cli::TuiArgs;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::tui;
+
+/// Open the interactive inbox browser on the terminal.
+pub async fn run(ctx: &AppContext, args: TuiArgs) -> AppResult<()> {
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+    if !std::io::stdout().is_terminal() {
+        return Err(AppError::InvalidInput(
+            "gmail tui needs an interactive terminal".to_string(),
+        ));
+    }
+
+    let query = Some(args.q.trim().to_string()).filter(|query| !query.is_empty());
+    tui::run(ctx, query, args.limit).await
+}
@@ -0,0 +1,247 @@
+use reqwest::header::CONTENT_TYPE;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::SendRequest;
+use crate::cli::UnsubscribeArgs;
+use crate::commands::dry_run::{self, PlannedChange};
+use crate::commands::ids;
+use crate::commands::outbox;
+use crate::commands::send::resolve_from_header;
+use crate::context::AppContext;
+use crate::error::{ApiError, AppError, AppResult};
+use crate::mail::mime;
+use crate::mail::unsubscribe::{self, Mailto, Unsubscribe};
+
+/// How a message was unsubscribed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnsubscribeMethod {
+    /// An RFC 8058 one-click POST to the sender's HTTPS endpoint.
+    OneClick,
+    /// The requested unsubscribe email was saved as a draft.
+    MailtoDraft,
+    /// The requested unsubscribe email was sent (`--send`).
+    MailtoSent,
+    /// Only a web page is offered; it has to be visited by hand.
+    Link,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct UnsubscribeResult {
+    pub id: String,
+    pub from: Option<String>,
+    pub method: UnsubscribeMethod,
+    /// The URL posted to or to visit, or the address mailed.
+    pub target: String,
+    /// Id of the draft (`mailto_draft`) or sent message (`mailto_sent`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+}
+
+impl UnsubscribeResult {
+    fn describe(&self) -> String {
+        let from = self.from.as_deref().unwrap_or("(unknown sender)");
+        match self.method {
+            UnsubscribeMethod::OneClick => {
+                format!("{}: unsubscribed from {from} (one-click)", self.id)
+            }
+            UnsubscribeMethod::MailtoDraft => format!(
+                "{}: drafted unsubscribe email to {} for {from}; send it from your drafts",
+                self.id, self.target
+            ),
+            UnsubscribeMethod::MailtoSent => format!(
+                "{}: sent unsubscribe email to {} for {from}",
+                self.id, self.target
+            ),
+            UnsubscribeMethod::Link => format!(
+                "{}: {from} only offers a web page; open {} to unsubscribe",
+                self.id, self.target
+            ),
+        }
+    }
+}
+
+/// Unsubscribe from the list each message came from, preferring a one-click
+/// POST, then the mailto address (drafted, or sent with `--send`), and
+/// otherwise reporting the web link.
+pub async fn run(ctx: &AppContext, args: UnsubscribeArgs) -> AppResult<()> {
+    let piped = args.id == "-";
+    let ids = ids::expand(vec![args.id])?;
+    if ctx.dry_run {
+        let mut planned = Vec::with_capacity(ids.len());
+        for id in &ids {
+            planned.push(plan(ctx, id, args.send).await?);
+        }
+        if !piped {
+            return dry_run::emit(ctx, &planned[0]);
+        }
+        return dry_run::emit_all(ctx, &planned);
+    }
+    let mut results = Vec::with_capacity(ids.len());
+    for id in &ids {
+        if !results.is_empty() && ctx.interrupt.is_set() {
+            break;
+        }
+        results.push(unsubscribe(ctx, id, args.send).await?);
+    }
+
+    let text = results
+        .iter()
+        .map(UnsubscribeResult::describe)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !piped {
+        return ctx.output.emit(&text, &results[0]);
+    }
+    ctx.output.emit(&text, &results)?;
+    ctx.interrupt.check()
+}
+
+/// What `unsubscribe` would do for `id` (`--dry-run`): the one-click POST
+/// and its URL, the mailto message and its address, or the link to visit.
+/// Nothing is posted, drafted, or sent.
+async fn plan(ctx: &AppContext, id: &str, send: bool) -> AppResult<PlannedChange> {
+    let access_token = ctx.access_token().await?;
+    let (from, offered) = offered(ctx, id, &access_token).await?;
+    let change = |action: &str| {
+        PlannedChange::new("unsubscribe", action)
+            .with_ids(vec![id.to_string()])
+            .with("from", from.clone().unwrap_or_default())
+    };
+    if let Some(link) = offered.link.as_ref().filter(|_| offered.one_click) {
+        let fallback = offered.mailto.as_ref().map(|mailto| mailto.to.join(", "));
+        return Ok(change("one-click unsubscribe")
+            .with("url", link.to_string())
+            .with("fallback_to", fallback.unwrap_or_default()));
+    }
+    if let Some(mailto) = &offered.mailto {
+        let action = if send {
+            "send unsubscribe email"
+        } else {
+            "draft unsubscribe email"
+        };
+        return Ok(change(action).with("to", mailto.to.join(", ")));
+    }
+    let link = offered.link.map(String::from).unwrap_or_default();
+    Ok(change("report unsubscribe link").with("url", link))
+}
+
+/// The message's sender and the unsubscribe routes its `List-Unsubscribe`
+/// headers offer; a message offering none is an error.
+async fn offered(
+    ctx: &AppContext,
+    id: &str,
+    access_token: &str,
+) -> AppResult<(Option<String>, Unsubscribe)> {
+    let headers = ctx
+        .gmail_client
+        .get_headers(
+            id,
+            &["List-Unsubscribe", "List-Unsubscribe-Post", "From"],
+            access_token,
+        )
+        .await?;
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    };
+    let from = header("From").map(str::to_string);
+    let offered = header("List-Unsubscribe")
+        .map(|value| unsubscribe::parse(value, header("List-Unsubscribe-Post")))
+        .unwrap_or_default();
+    if offered.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "message {id} has no List-Unsubscribe header to act on"
+        )));
+    }
+    Ok((from, offered))
+}
+
+async fn unsubscribe(ctx: &AppContext, id: &str, send: bool) -> AppResult<UnsubscribeResult> {
+    let access_token = ctx.access_token().await?;
+    let (from, offered) = offered(ctx, id, &access_token).await?;
+    let result = |method, target: String, message_id| UnsubscribeResult {
+        id: id.to_string(),
+        from: from.clone(),
+        method,
+        target,
+        message_id,
+    };
+
+    if let Some(link) = offered.link.as_ref().filter(|_| offered.one_click) {
+        match one_click(ctx, link.as_str()).await {
+            Ok(()) => return Ok(result(UnsubscribeMethod::OneClick, link.to_string(), None)),
+            // The mailto route may still work; without one, the failure stands.
+            Err(err) if offered.mailto.is_some() => {
+                tracing::info!(id, error = %err, "one-click unsubscribe failed; using mailto");
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    if let Some(mailto) = &offered.mailto {
+        let (method, message_id) = mail(ctx, &access_token, mailto, send).await?;
+        return Ok(result(method, mailto.to.join(", "), Some(message_id)));
+    }
+
+    let link = offered.link.map(String::from).unwrap_or_default();
+    Ok(result(UnsubscribeMethod::Link, link, None))
+}
+
+/// POST `List-Unsubscribe=One-Click` to `url`, without credentials (RFC 8058).
+async fn one_click(ctx: &AppContext, url: &str) -> AppResult<()> {
+    let response = ctx
+        .http
+        .post(url)
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body("List-Unsubscribe=One-Click")
+        .send()
+        .await?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    Err(AppError::Api(ApiError::http(
+        status.as_u16(),
+        format!("one-click unsubscribe to {url} failed ({status})"),
+    )))
+}
+
+/// Draft (or send) the message a `mailto:` unsubscribe asks for, returning
+/// how it went out and the draft or message id.
+async fn mail(
+    ctx: &AppContext,
+    access_token: &str,
+    mailto: &Mailto,
+    send: bool,
+) -> AppResult<(UnsubscribeMethod, String)> {
+    let request = SendRequest {
+        from: resolve_from_header(ctx, access_token, None).await?,
+        to: mailto.to.clone(),
+        cc: Vec::new(),
+        bcc: Vec::new(),
+        subject: mailto
+            .subject
+            .clone()
+            .unwrap_or_else(|| "unsubscribe".to_string()),
+        body: mime::markdown_to_html(mailto.body.as_deref().unwrap_or("unsubscribe")),
+        in_reply_to: None,
+        references: None,
+        thread_id: None,
+        attachments: Vec::new(),
+    };
+    let raw = mime::build_raw_message(&request);
+
+    if send {
+        let sent = outbox::send(ctx, &raw, None, access_token).await?;
+        return Ok((UnsubscribeMethod::MailtoSent, sent.id));
+    }
+    let draft = ctx
+        .gmail_client
+        .create_draft(&raw, None, access_token)
+        .await?;
+    Ok((UnsubscribeMethod::MailtoDraft, draft.id))
+}
@@ -0,0 +1,84 @@
+use std::io::Write;
+
+use chrono::Local;
+
+use crate::cli::UsageArgs;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+use crate::mail::usage::{self, UsageGroup, human_bytes};
+use crate::output::OutputMode;
+
+/// Estimate storage by label, sender, and age for the messages matching
+/// `--q` (the whole mailbox by default) and list the largest messages. CSV,
+/// table, and NDJSON output get long-form `section,key,messages,bytes` rows;
+/// JSON and YAML get the nested report.
+pub async fn run(ctx: &AppContext, args: UsageArgs) -> AppResult<()> {
+    if args.limit == 0 {
+        return Err(AppError::InvalidInput(
+            "--limit must be greater than 0".to_string(),
+        ));
+    }
+
+    let query = args.q.as_deref().unwrap_or("in:anywhere");
+    let access_token = ctx.access_token().await?;
+    let messages = ctx
+        .gmail_client
+        .list(&access_token, args.limit, Some(query))
+        .await?;
+    // Totals over part of the mailbox would mislead; stop instead.
+    ctx.interrupt.check()?;
+    let labels = ctx.gmail_client.list_labels(&access_token).await?;
+    let report = usage::build(Some(query), &messages, &labels, args.top, &Local::now());
+    if messages.len() as u32 == args.limit {
+        eprintln!(
+            "usage: stopped at --limit {} messages; raise it to cover the whole mailbox",
+            args.limit
+        );
+    }
+
+    match ctx.output.mode() {
+        OutputMode::Text => {}
+        OutputMode::Csv | OutputMode::Table | OutputMode::Ndjson => {
+            return ctx.output.emit("", &report.rows());
+        }
+        _ => return ctx.output.emit("", &report),
+    }
+
+    let mut out = ctx.output.paged();
+    writeln!(
+        out,
+        "{} in {} messages matching `{query}`",
+        human_bytes(report.bytes),
+        report.messages
+    )?;
+    write_groups(&mut out, "by label", &report.labels)?;
+    write_groups(&mut out, "by sender", &report.senders)?;
+    write_groups(&mut out, "by age", &report.ages)?;
+
+    writeln!(out, "\nlargest messages")?;
+    for message in &report.largest {
+        writeln!(
+            out,
+            "  {:>9}  {}  {} — {}",
+            human_bytes(message.bytes),
+            message.id,
+            message.from.as_deref().unwrap_or("(unknown sender)"),
+            message.subject.as_deref().unwrap_or("(no subject)")
+        )?;
+    }
+    out.finish()
+}
+
+fn write_groups(out: &mut impl Write, title: &str, groups: &[UsageGroup]) -> AppResult<()> {
+    writeln!(out, "\n{title}")?;
+    for group in groups {
+        writeln!(
+            out,
+            "  {:>9}  {:>5}  {}",
+            human_bytes(group.bytes),
+            group.messages,
+            group.key
+        )?;
+    }
+    Ok(())
+}
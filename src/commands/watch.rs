@@ -0,0 +1,146 @@
+use std::time::SystemTime;
+
+use chrono::{Local, TimeZone};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::{WatchCommand, WatchStartArgs};
+use crate::context::AppContext;
+use crate::daemon::watch::{self, WatchState};
+use crate::error::{AppError, AppResult};
+
+/// `watch status` and `watch stop`: whether a registration is stored, and
+/// its details when one is.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WatchStatus {
+    pub profile: String,
+    pub watching: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_in_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch: Option<WatchState>,
+}
+
+impl WatchStatus {
+    fn stopped(profile: &str) -> Self {
+        Self {
+            profile: profile.to_string(),
+            watching: false,
+            expires_in_ms: None,
+            watch: None,
+        }
+    }
+}
+
+/// Dispatch a `gmail watch` subcommand (start/status/stop).
+pub async fn run(ctx: &AppContext, command: WatchCommand) -> AppResult<()> {
+    match command {
+        WatchCommand::Start(args) => start(ctx, args).await,
+        WatchCommand::Status => status(ctx),
+        WatchCommand::Stop => stop(ctx).await,
+    }
+}
+
+/// Register a push watch on the topic (`--topic`, else the `watch_topic`
+/// setting) and remember it for status and renewal.
+async fn start(ctx: &AppContext, args: WatchStartArgs) -> AppResult<()> {
+    let profile = ctx.profile()?;
+    let topic = args
+        .topic
+        .or_else(|| ctx.settings.watch_topic.clone())
+        .ok_or_else(|| {
+            AppError::Config(
+                "no Pub/Sub topic; pass --topic or set `watch_topic` in the profile settings"
+                    .to_string(),
+            )
+        })?;
+    let access_token = ctx.access_token().await?;
+    let result = ctx
+        .gmail_client
+        .watch(&topic, &args.labels, &access_token)
+        .await?;
+
+    let state = WatchState::new(profile, &topic, &args.labels, result);
+    watch::save(ctx.paths.watch_file(profile), &state)?;
+
+    let text = format!(
+        "watching {} (history id {}, {})",
+        state.topic,
+        state.history_id,
+        describe_expiry(&state)
+    );
+    ctx.output.emit(&text, &state)
+}
+
+/// Report the stored registration, if any. Gmail has no endpoint to query a
+/// watch, so this reflects what the last `start` or renewal recorded.
+fn status(ctx: &AppContext) -> AppResult<()> {
+    let profile = ctx.profile()?;
+    let Some(state) = watch::load(ctx.paths.watch_file(profile))? else {
+        return ctx.output.emit(
+            &format!("{profile}: not watching. run `gmail watch start --topic <topic>`"),
+            &WatchStatus::stopped(profile),
+        );
+    };
+
+    let labels = if state.labels.is_empty() {
+        "all labels".to_string()
+    } else {
+        state.labels.join(", ")
+    };
+    let text = format!(
+        "{profile}: watching {} ({labels})\nhistory id: {}\n{}",
+        state.topic,
+        state.history_id,
+        describe_expiry(&state)
+    );
+    ctx.output.emit(
+        &text,
+        &WatchStatus {
+            profile: profile.to_string(),
+            watching: true,
+            expires_in_ms: Some(state.expires_in_ms(SystemTime::now())),
+            watch: Some(state),
+        },
+    )
+}
+
+/// Stop push notifications and drop the stored registration.
+async fn stop(ctx: &AppContext) -> AppResult<()> {
+    let profile = ctx.profile()?;
+    let access_token = ctx.access_token().await?;
+    ctx.gmail_client.stop_watch(&access_token).await?;
+    watch::clear(ctx.paths.watch_file(profile))?;
+
+    ctx.output.emit(
+        &format!("{profile}: watch stopped"),
+        &WatchStatus::stopped(profile),
+    )
+}
+
+/// Describe when the registration expires, e.g.
+/// `expires 2024-06-08 09:00 (in 6d 23h)`.
+fn describe_expiry(state: &WatchState) -> String {
+    let remaining = state.expires_in_ms(SystemTime::now()) / 1000;
+    if remaining <= 0 {
+        return "expired; run `gmail watch start` or `gmail daemon` to renew".to_string();
+    }
+
+    let days = remaining / 86_400;
+    let hours = (remaining % 86_400) / 3_600;
+    let minutes = (remaining % 3_600) / 60;
+    let left = if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    };
+    match Local
+        .timestamp_millis_opt(state.expiration_unix_ms as i64)
+        .single()
+    {
+        Some(at) => format!("expires {} (in {left})", at.format("%Y-%m-%d %H:%M")),
+        None => format!("expires in {left}"),
+    }
+}
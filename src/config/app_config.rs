@@ -1,36 +1,82 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
-use crate::error::AppResult;
+use crate::config::settings::Settings;
+use crate::error::{AppError, AppResult};
 
-/// Top-level (profile-independent) app configuration, stored at `config.json`.
+/// Top-level (profile-independent) app configuration, stored at `config.json`
+/// or `config.toml`.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AppConfig {
     /// Name of the profile to use when none is given via flag or environment.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_profile: Option<String>,
+    /// Settings shared by every profile; a profile's own file overrides them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defaults: Option<Settings>,
 }
 
 /// Load app config from `path`, returning defaults when the file is absent.
+/// A `.toml` extension selects TOML; anything else is read as JSON.
 pub fn load(path: PathBuf) -> AppResult<AppConfig> {
     if !path.exists() {
         return Ok(AppConfig::default());
     }
 
-    let raw = fs::read_to_string(path)?;
+    let raw = fs::read_to_string(&path)?;
+    if is_toml(&path) {
+        return toml::from_str(&raw)
+            .map_err(|err| AppError::Config(format!("invalid {}: {err}", path.display())));
+    }
     let config = serde_json::from_str(&raw)?;
     Ok(config)
 }
 
-/// Write app config as pretty JSON to `path`.
+/// Write app config to `path`, as TOML or pretty JSON by extension.
 pub fn save(path: PathBuf, config: &AppConfig) -> AppResult<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    let payload = serde_json::to_string_pretty(config)?;
+    let payload = if is_toml(&path) {
+        toml::to_string_pretty(config)
+            .map_err(|err| AppError::Config(format!("unable to encode config: {err}")))?
+    } else {
+        serde_json::to_string_pretty(config)?
+    };
     fs::write(&path, payload)?;
     Ok(())
 }
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("toml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_shared_defaults_from_toml() {
+        let config: AppConfig = toml::from_str(
+            r#"
+default_profile = "work"
+
+[defaults]
+output = "json"
+color = "never"
+timeout_secs = 20
+editor = "nvim"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.default_profile.as_deref(), Some("work"));
+        let defaults = config.defaults.unwrap();
+        assert_eq!(defaults.output.as_deref(), Some("json"));
+        assert_eq!(defaults.timeout_secs, Some(20));
+        assert_eq!(defaults.editor.as_deref(), Some("nvim"));
+    }
+}
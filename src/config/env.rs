@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use crate::config::secret::SecretSetting;
+use crate::config::settings::{PagerSetting, Settings};
+use crate::error::{AppError, AppResult};
+
+/// Prefix shared by every environment override, e.g. `GMAIL_CLI_CLIENT_ID`.
+pub const ENV_PREFIX: &str = "GMAIL_CLI_";
+
+/// Read `GMAIL_CLI_<name>`, treating an empty value as unset.
+pub fn var(name: &str) -> Option<String> {
+    std::env::var(format!("{ENV_PREFIX}{name}"))
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Overlay `GMAIL_CLI_*` variables onto settings loaded from the profile file.
+///
+/// Precedence is flags > environment > profile file; flags are applied later by
+/// the commands that read these settings.
+pub fn apply(settings: &mut Settings) -> AppResult<()> {
+    apply_from(settings, var)
+}
+
+/// [`apply`] against an arbitrary lookup (keyed by the name after the prefix).
+pub fn apply_from(
+    settings: &mut Settings,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> AppResult<()> {
+    let fields: [(&str, &mut Option<String>); 12] = [
+        ("CLIENT_ID", &mut settings.client_id),
+        ("REDIRECT_URI", &mut settings.redirect_uri),
+        ("SENDER_NAME", &mut settings.sender_name),
+        ("SEND_FROM", &mut settings.send_from),
+        ("SIGNATURE", &mut settings.signature),
+        ("DATE_FORMAT", &mut settings.date_format),
+        ("LANGUAGE", &mut settings.language),
+        ("OUTPUT", &mut settings.output),
+        ("COLOR", &mut settings.color),
+        ("EDITOR", &mut settings.editor),
+        ("PROXY", &mut settings.proxy),
+        ("TOKEN_STORE", &mut settings.token_store),
+    ];
+    for (name, field) in fields {
+        if let Some(value) = lookup(name) {
+            *field = Some(value);
+        }
+    }
+
+    if let Some(value) = lookup("CLIENT_SECRET") {
+        settings.client_secret = Some(SecretSetting::Literal(value));
+    }
+
+    if let Some(value) = lookup("PAGER") {
+        settings.pager = Some(match bool::from_str(&value.trim().to_ascii_lowercase()) {
+            Ok(enabled) => PagerSetting::Enabled(enabled),
+            Err(_) if matches!(value.trim(), "0" | "off" | "no") => PagerSetting::Enabled(false),
+            Err(_) => PagerSetting::Command(value),
+        });
+    }
+
+    if let Some(value) = lookup("TIMEOUT_SECS") {
+        let secs = value.trim().parse().map_err(|_| {
+            AppError::Config(format!(
+                "invalid {ENV_PREFIX}TIMEOUT_SECS `{value}`: expected whole seconds"
+            ))
+        })?;
+        settings.timeout_secs = Some(secs);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn env_values_override_file_values() {
+        let mut settings = Settings {
+            client_id: Some("from-file".to_string()),
+            sender_name: Some("File Name".to_string()),
+            ..Settings::default()
+        };
+        let env = HashMap::from([
+            ("CLIENT_ID", "from-env"),
+            ("CLIENT_SECRET", "secret"),
+            ("PAGER", "off"),
+        ]);
+
+        apply_from(&mut settings, |name| {
+            env.get(name).map(|value| value.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(settings.client_id.as_deref(), Some("from-env"));
+        assert_eq!(
+            settings.client_secret,
+            Some(SecretSetting::Literal("secret".to_string()))
+        );
+        assert_eq!(settings.sender_name.as_deref(), Some("File Name"));
+        assert_eq!(settings.pager, Some(PagerSetting::Enabled(false)));
+    }
+
+    #[test]
+    fn pager_names_a_command_unless_boolean() {
+        let mut settings = Settings::default();
+        apply_from(&mut settings, |name| {
+            (name == "PAGER").then(|| "less -S".to_string())
+        })
+        .unwrap();
+        assert_eq!(
+            settings.pager,
+            Some(PagerSetting::Command("less -S".to_string()))
+        );
+
+        apply_from(&mut settings, |name| {
+            (name == "PAGER").then(|| "TRUE".to_string())
+        })
+        .unwrap();
+        assert_eq!(settings.pager, Some(PagerSetting::Enabled(true)));
+
+        let invalid = apply_from(&mut settings, |name| {
+            (name == "TIMEOUT_SECS").then(|| "soon".to_string())
+        });
+        assert!(invalid.is_err());
+    }
+}
@@ -1,16 +1,23 @@
 pub mod app_config;
+pub mod env;
+pub mod pass;
 pub mod paths;
 pub mod profile;
+pub mod secret;
 pub mod settings;
 
 pub use app_config::AppConfig;
 pub use paths::AppPaths;
 pub use profile::{PROFILE_ENV, resolve_profile};
-pub use settings::Settings;
+pub use secret::SecretSetting;
+pub use settings::{
+    McpSettings, MessageHook, NotifyChannel, NotifySettings, PagerSetting, QuietHours, Settings,
+    ThemeSettings, WebhookSettings,
+};
 
 use std::path::PathBuf;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 
 /// Load the top-level app config from `path`.
 pub fn load_app_config(path: PathBuf) -> AppResult<AppConfig> {
@@ -22,12 +29,86 @@ pub fn save_app_config(path: PathBuf, config: &AppConfig) -> AppResult<()> {
     app_config::save(path, config)
 }
 
-/// Load a profile's settings from its settings file.
-pub fn load_settings(paths: &AppPaths, profile: &str) -> AppResult<Settings> {
-    settings::load(paths.settings_file(profile))
+/// Load a profile's effective settings: `GMAIL_CLI_*` environment overrides
+/// (see [`env::apply`]) over the profile file over the shared `defaults` in
+/// the app config (see [`settings::load`]).
+pub fn load_settings(
+    paths: &AppPaths,
+    profile: &str,
+    app_config: &AppConfig,
+) -> AppResult<Settings> {
+    let mut settings = settings::load(paths.settings_file(profile), app_config.defaults.as_ref())?;
+    env::apply(&mut settings)?;
+    Ok(settings)
+}
+
+/// Change a profile's settings file in place, without folding in environment
+/// overrides, so values supplied via `GMAIL_CLI_*` are never written to disk.
+pub fn update_settings(
+    paths: &AppPaths,
+    profile: &str,
+    change: impl FnOnce(&mut Settings),
+) -> AppResult<()> {
+    let path = paths.settings_file(profile);
+    let mut settings = settings::read(path.clone())?;
+    change(&mut settings);
+    settings::save(path, &settings)
 }
 
 /// Persist a profile's settings to its settings file.
 pub fn save_settings(paths: &AppPaths, profile: &str, settings: &Settings) -> AppResult<()> {
     settings::save(paths.settings_file(profile), settings)
 }
+
+/// The profile an invocation runs as, with its effective settings.
+#[derive(Debug, Clone)]
+pub struct LoadedProfile {
+    pub name: String,
+    /// Why the profile is unusable (ambiguous, or its config or settings file
+    /// failed to load), kept rather than raised so profile-management commands
+    /// and `doctor` still run.
+    pub error: Option<String>,
+    pub settings: Settings,
+}
+
+/// Resolve the profile for this invocation (see [`resolve_profile`]) and load
+/// its settings (see [`load_settings`]).
+pub fn load_profile(paths: &AppPaths, flag: Option<&str>) -> AppResult<LoadedProfile> {
+    let (app_config, config_error) = match load_app_config(paths.config_file()) {
+        Ok(app_config) => (app_config, None),
+        Err(err) => (
+            AppConfig::default(),
+            Some(format!(
+                "unable to read {}: {err}",
+                paths.config_file().display()
+            )),
+        ),
+    };
+    let available = paths.list_profiles()?;
+    let env_profile = env::var("PROFILE").or_else(|| std::env::var(PROFILE_ENV).ok());
+    let (name, profile_error) = match resolve_profile(
+        flag,
+        env_profile.as_deref(),
+        app_config.default_profile.as_deref(),
+        &available,
+    ) {
+        Ok(name) => (name, None),
+        Err(AppError::Config(message)) => (profile::FALLBACK_PROFILE.to_string(), Some(message)),
+        Err(err) => return Err(err),
+    };
+    let (settings, settings_error) = match load_settings(paths, &name, &app_config) {
+        Ok(settings) => (settings, None),
+        Err(err) => (
+            Settings::default(),
+            Some(format!(
+                "unable to load settings for profile `{name}`: {err}. run `gmail doctor` for details"
+            )),
+        ),
+    };
+
+    Ok(LoadedProfile {
+        name,
+        error: profile_error.or(config_error).or(settings_error),
+        settings,
+    })
+}
@@ -0,0 +1,143 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::error::{AppError, AppResult};
+
+/// A `pass`-compatible password store CLI (`pass` or `gopass`), driven
+/// through its `show`, `insert`, and `rm` subcommands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassCli {
+    binary: String,
+}
+
+impl PassCli {
+    pub fn new(binary: impl Into<String>) -> Self {
+        Self {
+            binary: binary.into(),
+        }
+    }
+
+    pub fn binary(&self) -> &str {
+        &self.binary
+    }
+
+    /// The entry's full contents, or `None` when the store has no such entry.
+    pub fn show(&self, entry: &str) -> AppResult<Option<String>> {
+        let output = self
+            .command(&["show", entry])
+            .output()
+            .map_err(|err| self.spawn_error(err))?;
+        if output.status.success() {
+            return Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()));
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("not in the password store") {
+            return Ok(None);
+        }
+        Err(AppError::Config(format!(
+            "`{} show {entry}` exited with {}: {}",
+            self.binary,
+            output.status,
+            stderr.trim()
+        )))
+    }
+
+    /// Create or overwrite the entry with `contents`.
+    pub fn insert(&self, entry: &str, contents: &str) -> AppResult<()> {
+        let mut child = self
+            .command(&["insert", "--multiline", "--force", entry])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|err| self.spawn_error(err))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(contents.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        self.check("insert", entry, output)
+    }
+
+    /// Delete the entry; a missing entry is not an error.
+    pub fn remove(&self, entry: &str) -> AppResult<()> {
+        if self.show(entry)?.is_none() {
+            return Ok(());
+        }
+        let output = self
+            .command(&["rm", "--force", entry])
+            .output()
+            .map_err(|err| self.spawn_error(err))?;
+        self.check("rm", entry, output)
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(&self.binary);
+        command.args(args).stderr(Stdio::piped());
+        command
+    }
+
+    fn check(&self, action: &str, entry: &str, output: std::process::Output) -> AppResult<()> {
+        if output.status.success() {
+            return Ok(());
+        }
+        Err(AppError::Config(format!(
+            "`{} {action} {entry}` exited with {}: {}",
+            self.binary,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+
+    fn spawn_error(&self, err: std::io::Error) -> AppError {
+        AppError::Config(format!("unable to run `{}`: {err}", self.binary))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    /// A stand-in `pass` keeping entries as files in the script's directory.
+    fn fake_pass(dir: &std::path::Path) -> PassCli {
+        let script = dir.join("pass");
+        fs::write(
+            &script,
+            format!(
+                r#"#!/bin/sh
+store="{store}"
+case "$1" in
+  show) [ -f "$store/$2" ] || {{ echo "Error: $2 is not in the password store." >&2; exit 1; }}; cat "$store/$2" ;;
+  insert) mkdir -p "$(dirname "$store/$4")"; cat > "$store/$4" ;;
+  rm) rm "$store/$3" ;;
+esac
+"#,
+                store = dir.join("store").display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        PassCli::new(script.display().to_string())
+    }
+
+    #[test]
+    fn round_trips_entries_through_the_cli() {
+        let dir = std::env::temp_dir().join(format!("gmail-pass-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let pass = fake_pass(&dir);
+
+        assert_eq!(pass.show("gmail/default").unwrap(), None);
+        pass.insert("gmail/default", "{\"a\":1}\n").unwrap();
+        assert_eq!(
+            pass.show("gmail/default").unwrap().as_deref(),
+            Some("{\"a\":1}\n")
+        );
+        pass.remove("gmail/default").unwrap();
+        pass.remove("gmail/default").unwrap();
+        assert_eq!(pass.show("gmail/default").unwrap(), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::config::env;
 use crate::error::{AppError, AppResult};
 
 const APP_DIR: &str = "gmail";
@@ -14,15 +15,16 @@ pub struct AppPaths {
 }
 
 impl AppPaths {
-    /// Resolve the platform config/data directories, creating the profile and token subdirs.
-    pub fn discover() -> AppResult<Self> {
-        let config_root = dirs::config_dir()
+    /// Resolve the config/data directories, creating the profile and token subdirs.
+    ///
+    /// Each directory comes from, in order: the explicit override (`--config-dir`
+    /// / `--data-dir`), `GMAIL_CLI_CONFIG_DIR` / `GMAIL_CLI_DATA_DIR`, or the
+    /// platform directory plus `gmail`. Overrides are used as given.
+    pub fn discover(config_dir: Option<PathBuf>, data_dir: Option<PathBuf>) -> AppResult<Self> {
+        let config_dir = resolve_dir(config_dir, env::var("CONFIG_DIR"), dirs::config_dir)
             .ok_or_else(|| AppError::Config("unable to resolve config directory".to_string()))?;
-        let data_root = dirs::data_dir()
+        let data_dir = resolve_dir(data_dir, env::var("DATA_DIR"), dirs::data_dir)
             .ok_or_else(|| AppError::Config("unable to resolve data directory".to_string()))?;
-
-        let config_dir = config_root.join(APP_DIR);
-        let data_dir = data_root.join(APP_DIR);
         let profiles_dir = config_dir.join("profiles");
         let tokens_dir = data_dir.join("tokens");
 
@@ -37,8 +39,12 @@ impl AppPaths {
         })
     }
 
-    /// Path to the top-level app config JSON file.
+    /// Path to the top-level app config: `config.toml` when present, else `config.json`.
     pub fn config_file(&self) -> PathBuf {
+        let toml = self.config_dir.join("config.toml");
+        if toml.exists() {
+            return toml;
+        }
         self.config_dir.join("config.json")
     }
 
@@ -70,6 +76,51 @@ impl AppPaths {
         self.tokens_dir.join(format!("{profile}.json"))
     }
 
+    /// Path to a profile's persisted Pub/Sub watch registration.
+    pub fn watch_file(&self, profile: &str) -> PathBuf {
+        self.data_dir.join("watch").join(format!("{profile}.json"))
+    }
+
+    /// Path to a profile's journal of sends whose outcome is not yet known.
+    pub fn outbox_file(&self, profile: &str) -> PathBuf {
+        self.data_dir.join("outbox").join(format!("{profile}.json"))
+    }
+
+    /// Path to a profile's queue of messages scheduled with `send --at`/`--in`.
+    pub fn queue_file(&self, profile: &str) -> PathBuf {
+        self.data_dir.join("queue").join(format!("{profile}.json"))
+    }
+
+    /// Path to a profile's messages snoozed out of the inbox with `snooze`.
+    pub fn snooze_file(&self, profile: &str) -> PathBuf {
+        self.data_dir.join("snooze").join(format!("{profile}.json"))
+    }
+
+    /// Path to a profile's threads muted with `mute`.
+    pub fn muted_file(&self, profile: &str) -> PathBuf {
+        self.data_dir.join("muted").join(format!("{profile}.json"))
+    }
+
+    /// Path to a profile's daily quota usage ledger.
+    pub fn quota_file(&self, profile: &str) -> PathBuf {
+        self.data_dir.join("quota").join(format!("{profile}.json"))
+    }
+
+    /// Path to the append-only log of every profile's mailbox and settings changes.
+    pub fn audit_file(&self) -> PathBuf {
+        self.data_dir.join("audit.jsonl")
+    }
+
+    /// Default path of the mutt-style recipient alias file.
+    pub fn aliases_file(&self) -> PathBuf {
+        self.config_dir.join("aliases")
+    }
+
+    /// Default path of the local mail rules file.
+    pub fn rules_file(&self) -> PathBuf {
+        self.config_dir.join("rules.toml")
+    }
+
     /// The app's config directory.
     pub fn config_dir(&self) -> &Path {
         &self.config_dir
@@ -80,3 +131,39 @@ impl AppPaths {
         &self.data_dir
     }
 }
+
+/// Pick a directory: explicit flag, then environment, then `<platform root>/gmail`.
+fn resolve_dir(
+    flag: Option<PathBuf>,
+    env: Option<String>,
+    platform_root: impl FnOnce() -> Option<PathBuf>,
+) -> Option<PathBuf> {
+    flag.or_else(|| env.map(PathBuf::from))
+        .or_else(|| platform_root().map(|root| root.join(APP_DIR)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flag_then_env_then_platform_dir() {
+        let platform = || Some(PathBuf::from("/home/me/.config"));
+        assert_eq!(
+            resolve_dir(
+                Some(PathBuf::from("/flag")),
+                Some("/env".to_string()),
+                platform
+            ),
+            Some(PathBuf::from("/flag"))
+        );
+        assert_eq!(
+            resolve_dir(None, Some("/env".to_string()), platform),
+            Some(PathBuf::from("/env"))
+        );
+        assert_eq!(
+            resolve_dir(None, None, platform),
+            Some(PathBuf::from("/home/me/.config/gmail"))
+        );
+    }
+}
@@ -1,13 +1,14 @@
 use crate::error::{AppError, AppResult};
 
-/// Environment variable that overrides the default profile (below an explicit `--profile`).
+/// Environment variable that overrides the default profile (below an explicit
+/// `--profile` and `GMAIL_CLI_PROFILE`).
 pub const PROFILE_ENV: &str = "GMAIL_PROFILE";
 
 /// Profile name used as the ultimate fallback and for legacy single-profile setups.
 pub const FALLBACK_PROFILE: &str = "default";
 
 /// Resolve which profile to use, in precedence order:
-/// explicit `--profile` flag > `GMAIL_PROFILE` env > configured default profile >
+/// explicit `--profile` flag > `GMAIL_CLI_PROFILE` / `GMAIL_PROFILE` env > configured default profile >
 /// the sole profile if only one exists > the `default` profile if present.
 ///
 /// Errors only when several profiles exist, none is named `default`, and no
@@ -0,0 +1,159 @@
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::pass::PassCli;
+use crate::error::{AppError, AppResult};
+
+/// A secret given literally, or a reference resolved only when the secret is
+/// needed so it never has to sit in a config file:
+///
+/// ```json
+/// "client_secret": "literal"
+/// "client_secret": { "command": "op read op://vault/gmail/secret" }
+/// "client_secret": { "env": "GMAIL_SECRET" }
+/// "client_secret": { "pass": "google/gmail-client-secret" }
+/// "client_secret": { "gopass": "google/gmail-client-secret" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SecretSetting {
+    Literal(String),
+    /// Run through the platform shell; trimmed stdout is the secret.
+    Command {
+        command: String,
+    },
+    /// Read from this environment variable.
+    Env {
+        env: String,
+    },
+    /// The first line of this `pass` entry.
+    Pass {
+        pass: String,
+    },
+    /// The first line of this `gopass` entry.
+    Gopass {
+        gopass: String,
+    },
+}
+
+impl SecretSetting {
+    /// Whether this is an empty literal, i.e. effectively unset.
+    pub fn is_blank(&self) -> bool {
+        matches!(self, Self::Literal(value) if value.trim().is_empty())
+    }
+
+    /// Produce the secret value, running the command or reading the variable.
+    pub fn resolve(&self) -> AppResult<String> {
+        match self {
+            Self::Literal(value) => Ok(value.clone()),
+            Self::Env { env } => std::env::var(env)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .ok_or_else(|| {
+                    AppError::Config(format!("secret environment variable `{env}` is not set"))
+                }),
+            Self::Command { command } => run(command),
+            Self::Pass { pass: entry } => first_line(&PassCli::new("pass"), entry),
+            Self::Gopass { gopass: entry } => first_line(&PassCli::new("gopass"), entry),
+        }
+    }
+}
+
+fn first_line(store: &PassCli, entry: &str) -> AppResult<String> {
+    let contents = store.show(entry)?.ok_or_else(|| {
+        AppError::Config(format!(
+            "secret `{entry}` is not in the {} store",
+            store.binary()
+        ))
+    })?;
+    let secret = contents
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if secret.is_empty() {
+        return Err(AppError::Config(format!(
+            "{} entry `{entry}` is empty",
+            store.binary()
+        )));
+    }
+    Ok(secret)
+}
+
+fn run(command: &str) -> AppResult<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .map_err(|err| AppError::Config(format!("unable to run secret command `{command}`: {err}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::Config(format!(
+            "secret command `{command}` exited with {}: {}",
+            output.status,
+            stderr.trim()
+        )));
+    }
+
+    let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if secret.is_empty() {
+        return Err(AppError::Config(format!(
+            "secret command `{command}` printed nothing"
+        )));
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_literal_command_env_and_pass_forms() {
+        let parse = |json: &str| serde_json::from_str::<SecretSetting>(json).unwrap();
+        assert_eq!(parse(r#""shh""#), SecretSetting::Literal("shh".to_string()));
+        assert_eq!(
+            parse(r#"{ "command": "op read x" }"#),
+            SecretSetting::Command {
+                command: "op read x".to_string()
+            }
+        );
+        assert_eq!(
+            parse(r#"{ "env": "GMAIL_SECRET" }"#),
+            SecretSetting::Env {
+                env: "GMAIL_SECRET".to_string()
+            }
+        );
+        assert_eq!(
+            parse(r#"{ "pass": "google/gmail" }"#),
+            SecretSetting::Pass {
+                pass: "google/gmail".to_string()
+            }
+        );
+        assert_eq!(
+            parse(r#"{ "gopass": "google/gmail" }"#),
+            SecretSetting::Gopass {
+                gopass: "google/gmail".to_string()
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_command_output_and_reports_failures() {
+        let secret = SecretSetting::Command {
+            command: "printf ' s3cret \\n'".to_string(),
+        };
+        assert_eq!(secret.resolve().unwrap(), "s3cret");
+
+        let failing = SecretSetting::Command {
+            command: "echo nope >&2; exit 3".to_string(),
+        };
+        let err = failing.resolve().unwrap_err().to_string();
+        assert!(err.contains("nope"), "{err}");
+    }
+}
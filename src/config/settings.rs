@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::secret::SecretSetting;
 use crate::error::{AppError, AppResult};
 
 const DEFAULT_REDIRECT_URI: &str = "http://127.0.0.1:8787/callback";
@@ -11,16 +13,188 @@ const DEFAULT_REDIRECT_URI: &str = "http://127.0.0.1:8787/callback";
 pub struct Settings {
     #[serde(default)]
     pub client_id: Option<String>,
+    /// A literal, or `{"command": ...}` / `{"env": ...}` / `{"pass": ...}` /
+    /// `{"gopass": ...}` resolved at use time.
     #[serde(default)]
-    pub client_secret: Option<String>,
+    pub client_secret: Option<SecretSetting>,
+    /// Where OAuth tokens are kept: `file` (default), `pass`, or `gopass`.
+    #[serde(default)]
+    pub token_store: Option<String>,
+    /// Store path under which `pass`/`gopass` token entries live (default `gmail`).
+    #[serde(default)]
+    pub pass_prefix: Option<String>,
     #[serde(default)]
     pub redirect_uri: Option<String>,
     #[serde(default)]
     pub sender_name: Option<String>,
-    #[serde(default)]
+    /// Send-as alias used when `--from` is not given (`default_from_alias` also accepted).
+    #[serde(default, alias = "default_from_alias")]
     pub send_from: Option<String>,
     #[serde(default)]
     pub signature: Option<String>,
+    /// Quote the parent message below `send --reply` bodies by default.
+    #[serde(default)]
+    pub reply_quote_default: Option<bool>,
+    /// Reply to everyone on the parent (its To and Cc) by default.
+    #[serde(default)]
+    pub reply_all_default: Option<bool>,
+    /// Carry the parent's Cc recipients into replies by default.
+    #[serde(default)]
+    pub reply_keep_cc_default: Option<bool>,
+    /// Pub/Sub topic `watch start` registers when `--topic` is not given.
+    #[serde(default)]
+    pub watch_topic: Option<String>,
+    #[serde(default)]
+    pub webhook: Option<WebhookSettings>,
+    #[serde(default)]
+    pub on_message: Vec<MessageHook>,
+    #[serde(default)]
+    pub notify: Option<NotifySettings>,
+    #[serde(default)]
+    pub theme: Option<ThemeSettings>,
+    #[serde(default)]
+    pub mcp: Option<McpSettings>,
+    #[serde(default)]
+    pub pager: Option<PagerSetting>,
+    /// `relative` (default), `iso`, `raw`, or a strftime pattern for text output dates.
+    #[serde(default)]
+    pub date_format: Option<String>,
+    /// Language for text output (`en`, `de`, or `auto` to follow the locale); JSON is unaffected.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Default output format when `--output` is not given (`text`, `json`, ...).
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Default `--color` choice (`auto`, `always`, `never`).
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Per-request timeout for OAuth and Gmail API calls, in seconds; none by default.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Proxy URL for all requests; otherwise `HTTPS_PROXY` and friends apply.
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Seconds an idle pooled connection is kept for reuse (reqwest's default: 90).
+    #[serde(default)]
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// Idle connections kept per host; `0` disables pooling.
+    #[serde(default)]
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Interval for TCP keepalive probes on open connections; off by default.
+    #[serde(default)]
+    pub tcp_keepalive_secs: Option<u64>,
+    /// Editor for composing replies, ahead of `$VISUAL` / `$EDITOR`.
+    #[serde(default)]
+    pub editor: Option<String>,
+    /// Mutt alias file expanding `--to team` and friends; defaults to
+    /// `aliases` in the config directory. A leading `~/` means the home directory.
+    #[serde(default)]
+    pub alias_file: Option<String>,
+    /// Named Gmail queries run with `list --saved <name>`, e.g.
+    /// `{"work": "label:work is:unread"}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub searches: BTreeMap<String, String>,
+    /// Default flags per command, e.g. `{"list": {"limit": 25}}`; nested
+    /// objects address subcommands (`{"attachments": {"get": {"out": "..."}}}`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, serde_json::Value>,
+}
+
+/// `"pager": false` turns paging off; a string names the pager command.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PagerSetting {
+    Enabled(bool),
+    Command(String),
+}
+
+/// Text-output styles per role, each a spec like `bold cyan`, `dim`, or `208`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeSettings {
+    #[serde(default)]
+    pub sender: Option<String>,
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Desktop notification routing: which queries notify, who is muted, and when to stay quiet.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotifySettings {
+    /// Named queries that raise notifications; used when `--q` is not given.
+    #[serde(default)]
+    pub channels: Vec<NotifyChannel>,
+    /// Never notify for messages whose From contains any of these (case-insensitive).
+    #[serde(default)]
+    pub mute_senders: Vec<String>,
+    /// Never notify for messages carrying any of these labels (names or ids).
+    #[serde(default)]
+    pub mute_labels: Vec<String>,
+    /// Local-time window during which notifications are held back.
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// A named query whose matches raise notifications at a given urgency.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotifyChannel {
+    pub name: String,
+    pub query: String,
+    /// `low`, `normal` (default), or `critical`.
+    #[serde(default)]
+    pub urgency: Option<String>,
+    /// Keep notifying for this channel during quiet hours.
+    #[serde(default)]
+    pub ignore_quiet_hours: bool,
+}
+
+/// A daily local-time window in `HH:MM` form; may wrap past midnight (`22:00`–`07:00`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+/// A command the daemon runs for each new message matching `query`, with the
+/// message JSON on stdin. On a zero exit the message can be archived and/or labeled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessageHook {
+    /// Gmail search query selecting the messages this hook handles.
+    pub query: String,
+    /// Shell command to run (`sh -c` on unix, `cmd /C` on windows).
+    pub run: String,
+    /// Remove the message from the inbox after the command succeeds.
+    #[serde(default)]
+    pub archive: bool,
+    /// Labels (names or ids) to add after the command succeeds.
+    #[serde(default)]
+    pub label: Vec<String>,
+}
+
+/// Which operations `gmail mcp` exposes to agents.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpSettings {
+    /// Tool groups (`read`, `label`, `send`) or tool names to allow; `read`
+    /// alone when empty.
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+/// Where the daemon delivers new-message events, and which named queries trigger them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebhookSettings {
+    /// Endpoint that receives a JSON POST per matching message.
+    pub url: String,
+    /// Shared secret for the `X-Gmail-Signature` HMAC-SHA256 header; unsigned when absent.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Named Gmail queries; each event reports which one matched. Defaults to
+    /// a single `inbox` query for `in:inbox` when empty.
+    #[serde(default)]
+    pub queries: BTreeMap<String, String>,
 }
 
 impl Settings {
@@ -34,9 +208,14 @@ impl Settings {
         })
     }
 
-    /// The configured OAuth client secret, if any.
-    pub fn client_secret(&self) -> Option<&str> {
-        self.client_secret.as_deref()
+    /// The configured OAuth client secret, if any, running its command or
+    /// reading its variable when it is an indirection.
+    pub fn client_secret(&self) -> AppResult<Option<String>> {
+        self.client_secret
+            .as_ref()
+            .filter(|secret| !secret.is_blank())
+            .map(SecretSetting::resolve)
+            .transpose()
     }
 
     /// The configured redirect URI, or the built-in loopback default.
@@ -45,10 +224,85 @@ impl Settings {
             .clone()
             .unwrap_or_else(|| DEFAULT_REDIRECT_URI.to_string())
     }
+
+    /// Fill every setting this profile leaves unset from `defaults`.
+    ///
+    /// Scalars merge field by field; sections (`webhook`, `notify`, `theme`, `mcp`)
+    /// and the `on_message` list are taken whole from whichever side sets them,
+    /// and `searches` and `commands` entries per name.
+    pub fn with_defaults(self, defaults: &Settings) -> Settings {
+        let defaults = defaults.clone();
+        Settings {
+            client_id: self.client_id.or(defaults.client_id),
+            client_secret: self.client_secret.or(defaults.client_secret),
+            token_store: self.token_store.or(defaults.token_store),
+            pass_prefix: self.pass_prefix.or(defaults.pass_prefix),
+            redirect_uri: self.redirect_uri.or(defaults.redirect_uri),
+            sender_name: self.sender_name.or(defaults.sender_name),
+            send_from: self.send_from.or(defaults.send_from),
+            signature: self.signature.or(defaults.signature),
+            reply_quote_default: self.reply_quote_default.or(defaults.reply_quote_default),
+            reply_all_default: self.reply_all_default.or(defaults.reply_all_default),
+            reply_keep_cc_default: self
+                .reply_keep_cc_default
+                .or(defaults.reply_keep_cc_default),
+            watch_topic: self.watch_topic.or(defaults.watch_topic),
+            webhook: self.webhook.or(defaults.webhook),
+            on_message: if self.on_message.is_empty() {
+                defaults.on_message
+            } else {
+                self.on_message
+            },
+            notify: self.notify.or(defaults.notify),
+            theme: self.theme.or(defaults.theme),
+            mcp: self.mcp.or(defaults.mcp),
+            pager: self.pager.or(defaults.pager),
+            date_format: self.date_format.or(defaults.date_format),
+            language: self.language.or(defaults.language),
+            output: self.output.or(defaults.output),
+            color: self.color.or(defaults.color),
+            timeout_secs: self.timeout_secs.or(defaults.timeout_secs),
+            proxy: self.proxy.or(defaults.proxy),
+            pool_idle_timeout_secs: self
+                .pool_idle_timeout_secs
+                .or(defaults.pool_idle_timeout_secs),
+            pool_max_idle_per_host: self
+                .pool_max_idle_per_host
+                .or(defaults.pool_max_idle_per_host),
+            tcp_keepalive_secs: self.tcp_keepalive_secs.or(defaults.tcp_keepalive_secs),
+            editor: self.editor.or(defaults.editor),
+            alias_file: self.alias_file.or(defaults.alias_file),
+            searches: {
+                let mut searches = defaults.searches;
+                searches.extend(self.searches);
+                searches
+            },
+            commands: {
+                let mut commands = defaults.commands;
+                commands.extend(self.commands);
+                commands
+            },
+        }
+    }
 }
 
-/// Load settings from `path`, returning defaults when the file is absent.
-pub fn load(path: PathBuf) -> AppResult<Settings> {
+/// Load a profile's settings in merge order, highest first:
+///
+/// 1. the profile file at `path`
+/// 2. `defaults` from the shared `config.json` / `config.toml`
+/// 3. built-in defaults
+///
+/// `GMAIL_CLI_*` environment overrides and flags are layered on top by the caller.
+pub fn load(path: PathBuf, defaults: Option<&Settings>) -> AppResult<Settings> {
+    let settings = read(path)?;
+    Ok(match defaults {
+        Some(defaults) => settings.with_defaults(defaults),
+        None => settings,
+    })
+}
+
+/// Read just the profile file at `path`, returning defaults when it is absent.
+pub fn read(path: PathBuf) -> AppResult<Settings> {
     if !path.exists() {
         return Ok(Settings::default());
     }
@@ -78,3 +332,39 @@ pub fn save(path: PathBuf, settings: &Settings) -> AppResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_values_win_over_shared_defaults() {
+        let defaults = Settings {
+            output: Some("json".to_string()),
+            editor: Some("hx".to_string()),
+            timeout_secs: Some(30),
+            on_message: vec![MessageHook::default()],
+            ..Settings::default()
+        };
+        let profile = Settings {
+            output: Some("yaml".to_string()),
+            ..Settings::default()
+        };
+
+        let merged = profile.with_defaults(&defaults);
+        assert_eq!(merged.output.as_deref(), Some("yaml"));
+        assert_eq!(merged.editor.as_deref(), Some("hx"));
+        assert_eq!(merged.timeout_secs, Some(30));
+        assert_eq!(merged.on_message.len(), 1);
+    }
+
+    #[test]
+    fn accepts_default_from_alias_for_send_from() {
+        let settings: Settings = serde_json::from_str(
+            r#"{ "default_from_alias": "me@work.example", "reply_quote_default": true }"#,
+        )
+        .unwrap();
+        assert_eq!(settings.send_from.as_deref(), Some("me@work.example"));
+        assert_eq!(settings.reply_quote_default, Some(true));
+    }
+}
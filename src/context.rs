@@ -1,59 +1,108 @@
+use clap::ValueEnum;
+use std::path::PathBuf;
+
+use crate::api::audit::AuditTrail;
 use crate::api::client::GmailClient;
-use crate::auth::token_store::TokenStore;
-use crate::auth::{AuthService, FileTokenStore};
-use crate::config::{self, AppPaths, Settings};
+use crate::api::http;
+use crate::auth::{AuthService, ConfiguredTokenStore, TokenProvider};
+use crate::config::{AppPaths, LoadedProfile, Settings};
 use crate::error::{AppError, AppResult};
-use crate::output::Output;
+use crate::i18n;
+use crate::interrupt::Interrupt;
+use crate::output::dates::DateFormat;
+use crate::output::text::{self, ColorChoice, Theme};
+use crate::output::{Output, OutputMode, layout, pager};
 
 #[derive(Debug)]
 pub struct AppContext {
     profile: String,
     profile_error: Option<String>,
     pub verbose: u8,
+    pub color: ColorChoice,
     pub paths: AppPaths,
     pub settings: Settings,
-    pub token_store: FileTokenStore,
+    pub token_store: ConfiguredTokenStore,
+    /// The one HTTP client behind `auth` and `gmail_client`; clone it for any
+    /// other outgoing calls so they share connections and policy.
+    pub http: reqwest::Client,
+    pub auth: AuthService,
+    tokens: TokenProvider,
     pub gmail_client: GmailClient,
     pub output: Output,
+    /// Set by Ctrl-C; long-running commands check it between steps.
+    pub interrupt: Interrupt,
+    /// `--dry-run`: mutating commands print a [`PlannedChange`] instead of
+    /// writing.
+    ///
+    /// [`PlannedChange`]: crate::commands::dry_run::PlannedChange
+    pub dry_run: bool,
+    /// Writes made so far, for the audit log; the API client records its own,
+    /// commands that change local settings add theirs.
+    pub audit: AuditTrail,
 }
 
 impl AppContext {
-    /// Resolve paths, settings, token store, client, and output mode into an app context.
+    /// Build the token store, HTTP/API clients, and output mode/theme for a loaded profile.
     ///
-    /// Profile resolution is deferred: an ambiguous result is captured rather
-    /// than raised, so profile-management commands still run. Commands that act
-    /// on a mailbox reach for [`AppContext::profile`], which surfaces the error.
-    pub fn bootstrap(profile: Option<String>, json: bool, verbose: u8) -> AppResult<Self> {
-        let paths = AppPaths::discover()?;
-        let app_config = config::load_app_config(paths.config_file())?;
-        let available = paths.list_profiles()?;
-        let env_profile = std::env::var(config::PROFILE_ENV).ok();
-        let (profile, profile_error) = match config::resolve_profile(
-            profile.as_deref(),
-            env_profile.as_deref(),
-            app_config.default_profile.as_deref(),
-            &available,
-        ) {
-            Ok(name) => (name, None),
-            Err(AppError::Config(message)) => {
-                (config::profile::FALLBACK_PROFILE.to_string(), Some(message))
-            }
-            Err(err) => return Err(err),
+    /// A deferred profile error (see [`config::load_profile`]) is kept so
+    /// profile-management commands and `doctor` still run. Commands that act
+    /// on a mailbox reach for [`AppContext::profile`], which surfaces it.
+    pub fn bootstrap(
+        paths: AppPaths,
+        loaded: LoadedProfile,
+        output: Option<OutputMode>,
+        color: Option<ColorChoice>,
+        no_pager: bool,
+        out: Option<PathBuf>,
+        verbose: u8,
+    ) -> AppResult<Self> {
+        let LoadedProfile {
+            name: profile,
+            error: profile_error,
+            settings,
+        } = loaded;
+        let token_store = ConfiguredTokenStore::from_settings(&paths, &settings)?;
+        let http = http::build_client(&settings)?;
+        let auth = AuthService::new(http.clone());
+        let interrupt = Interrupt::new();
+        let audit = AuditTrail::new();
+        let gmail_client = GmailClient::with_http(http.clone())
+            .with_interrupt(interrupt.clone())
+            .with_audit(audit.clone());
+        // Flags win; otherwise the merged settings (env > profile > shared defaults).
+        let output = match output {
+            Some(output) => output,
+            None => parse_setting("output", settings.output.as_deref())?.unwrap_or_default(),
         };
-        let settings = config::load_settings(&paths, &profile)?;
-        let token_store = FileTokenStore::new(paths.clone());
-        let gmail_client = GmailClient::new();
-        let output = Output::new(json);
+        let color = match color {
+            Some(color) => color,
+            None => parse_setting("color", settings.color.as_deref())?.unwrap_or_default(),
+        };
+        let theme = Theme::resolve(color, settings.theme.as_ref())?;
+        text::install_error_style(color, &theme);
+        i18n::install(settings.language.as_deref())?;
+        let output = Output::new(output, theme)
+            .with_pager(pager::resolve(no_pager, settings.pager.as_ref()))
+            .with_width(layout::terminal_width())
+            .with_dates(DateFormat::parse(settings.date_format.as_deref())?)
+            .with_out(out);
 
         Ok(Self {
             profile,
             profile_error,
             verbose,
+            color,
             paths,
             settings,
             token_store,
+            http,
+            auth,
+            tokens: TokenProvider::default(),
             gmail_client,
             output,
+            interrupt,
+            dry_run: false,
+            audit,
         })
     }
 
@@ -66,19 +115,33 @@ impl AppContext {
         }
     }
 
-    /// Return a valid access token, refreshing it if the stored one has expired.
+    /// Return a valid access token, refreshing it shortly before it expires.
+    ///
+    /// Cheap after the first call, so long-running commands should call it
+    /// again before each batch rather than holding on to one token.
     pub async fn access_token(&self) -> AppResult<String> {
         let profile = self.profile()?;
-        let token = self.token_store.load(profile)?.ok_or_else(|| {
-            AppError::InvalidInput("not logged in. run `gmail auth login`".to_string())
-        })?;
-
-        if token.is_expired(std::time::SystemTime::now()) {
-            let refreshed =
-                AuthService::refresh(profile, &self.settings, &self.token_store).await?;
-            return Ok(refreshed.access_token);
-        }
-
-        Ok(token.access_token)
+        self.tokens
+            .access_token(profile, &self.settings, &self.auth, &self.token_store)
+            .await
     }
 }
+
+/// Parse an enum-valued setting such as `output` or `color`.
+fn parse_setting<T: ValueEnum>(name: &str, value: Option<&str>) -> AppResult<Option<T>> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            T::from_str(value, true).map_err(|_| {
+                let choices = T::value_variants()
+                    .iter()
+                    .filter_map(|variant| variant.to_possible_value())
+                    .map(|value| value.get_name().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                AppError::Config(format!("invalid {name} `{value}`; use one of {choices}"))
+            })
+        })
+        .transpose()
+}
@@ -0,0 +1,166 @@
+use std::process::Stdio;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::api::models::MessageView;
+use crate::config::MessageHook;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+
+use super::poll::NewMailPoller;
+
+/// Runs the profile's `on_message` hooks against newly arrived matches.
+#[derive(Debug)]
+pub struct HookRunner {
+    hooks: Vec<(MessageHook, NewMailPoller)>,
+}
+
+impl HookRunner {
+    /// Build a runner for the configured hooks, rejecting blank queries or commands.
+    pub fn new(hooks: &[MessageHook]) -> AppResult<Self> {
+        let mut out = Vec::new();
+        for (index, hook) in hooks.iter().enumerate() {
+            if hook.query.trim().is_empty() || hook.run.trim().is_empty() {
+                return Err(AppError::Config(format!(
+                    "on_message hook #{} needs both `query` and `run`",
+                    index + 1
+                )));
+            }
+            out.push((hook.clone(), NewMailPoller::new(hook.query.trim())));
+        }
+
+        Ok(Self { hooks: out })
+    }
+
+    /// Run one pass: for each hook, execute its command once per new match and
+    /// apply the follow-up actions when it exits successfully. Failures are
+    /// reported on stderr so one broken hook doesn't stop the others.
+    pub async fn tick(&mut self, ctx: &AppContext) {
+        for (hook, poller) in &mut self.hooks {
+            let arrived = match poller.poll(ctx).await {
+                Ok(arrived) => arrived,
+                Err(err) => {
+                    eprintln!("hook `{}` query failed: {err}", hook.run);
+                    continue;
+                }
+            };
+
+            for message in &arrived {
+                if let Err(err) = handle(ctx, hook, &message.id).await {
+                    eprintln!("hook `{}` on {}: {err}", hook.run, message.id);
+                }
+            }
+        }
+    }
+}
+
+/// Fetch the full message, pipe it to the hook command, and on success apply
+/// the hook's archive/label actions.
+async fn handle(ctx: &AppContext, hook: &MessageHook, message_id: &str) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let message = ctx
+        .gmail_client
+        .get_msg_full(message_id, &access_token)
+        .await?;
+
+    run_command(&hook.run, ctx, &message).await?;
+
+    if hook.archive {
+        ctx.gmail_client
+            .rm_labels(message_id, &["INBOX".to_string()], &access_token)
+            .await?;
+    }
+    if !hook.label.is_empty() {
+        ctx.gmail_client
+            .add_labels(message_id, &hook.label, &access_token)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Run `command` through the platform shell with the message JSON on stdin and
+/// `GMAIL_PROFILE`, `GMAIL_MESSAGE_ID`, `GMAIL_THREAD_ID` in its environment
+/// (plus the config/data dirs, so nested `gmail` calls see the same state),
+/// erroring on a non-zero exit.
+pub(crate) async fn run_command(
+    command: &str,
+    ctx: &AppContext,
+    message: &MessageView,
+) -> AppResult<()> {
+    let payload = serde_json::to_vec(message)?;
+
+    let profile = ctx.profile()?;
+    let mut child = shell(command)
+        .env("GMAIL_PROFILE", profile)
+        .env("GMAIL_CLI_PROFILE", profile)
+        .env("GMAIL_CLI_CONFIG_DIR", ctx.paths.config_dir())
+        .env("GMAIL_CLI_DATA_DIR", ctx.paths.data_dir())
+        .env("GMAIL_MESSAGE_ID", &message.id)
+        .env(
+            "GMAIL_THREAD_ID",
+            message.thread_id.as_deref().unwrap_or_default(),
+        )
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may exit before reading it; that's not a failure.
+        let _ = stdin.write_all(&payload).await;
+    }
+
+    let status = child.wait().await?;
+    if status.success() {
+        return Ok(());
+    }
+
+    Err(AppError::Config(format!(
+        "hook command `{command}` exited with {status}"
+    )))
+}
+
+/// A `Command` that runs `command` through the platform shell.
+fn shell(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    }
+
+    #[cfg(not(windows))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_hooks_without_command() {
+        let err = HookRunner::new(&[MessageHook {
+            query: "from:alerts@".to_string(),
+            run: " ".to_string(),
+            archive: false,
+            label: vec![],
+        }])
+        .unwrap_err();
+        assert!(matches!(err, AppError::Config(_)));
+    }
+
+    #[test]
+    fn parses_hook_settings() {
+        let hooks: Vec<MessageHook> = serde_json::from_str(
+            r#"[{ "query": "from:alerts@", "run": "./handle-alert.sh", "archive": true }]"#,
+        )
+        .expect("hook settings should deserialize");
+        assert_eq!(hooks[0].run, "./handle-alert.sh");
+        assert!(hooks[0].archive);
+        assert!(hooks[0].label.is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+pub mod hooks;
+pub mod notify;
+pub mod poll;
+pub mod rules;
+pub mod watch;
+pub mod webhook;
+
+use std::time::Duration;
+
+use crate::commands::{mute, queue, snooze};
+use crate::context::AppContext;
+use crate::error::AppResult;
+
+use self::hooks::HookRunner;
+use self::notify::MailNotifier;
+use self::rules::RuleRunner;
+use self::webhook::WebhookDispatcher;
+
+/// Optional tasks the daemon performs on each tick alongside watch renewal.
+#[derive(Debug, Default)]
+pub struct DaemonOptions {
+    /// Raise desktop notifications for new messages matching this query.
+    pub notify: Option<MailNotifier>,
+    /// POST new-message events to the profile's configured webhook.
+    pub webhook: Option<WebhookDispatcher>,
+    /// Run the profile's `on_message` hooks against new matches.
+    pub hooks: Option<HookRunner>,
+    /// Apply the local `rules.toml` to new messages.
+    pub rules: Option<RuleRunner>,
+}
+
+/// Run the foreground daemon loop until Ctrl-C (or the process is killed), performing
+/// periodic mailbox upkeep every `interval`: renewing the Pub/Sub watch,
+/// sending queued messages that are due, returning snoozed messages to the
+/// inbox, archiving muted threads that came back, and any optional tasks
+/// enabled in `options`.
+///
+/// Failures inside a tick are reported on stderr and retried on the next tick
+/// rather than ending the loop, so a transient API error doesn't kill the daemon.
+pub async fn run(
+    ctx: &AppContext,
+    interval: Duration,
+    mut options: DaemonOptions,
+) -> AppResult<()> {
+    let profile = ctx.profile()?;
+    eprintln!(
+        "gmail daemon running for profile `{profile}` (every {}s)",
+        interval.as_secs()
+    );
+
+    loop {
+        if let Some(notifier) = options.notify.as_mut()
+            && let Err(err) = notifier.poll(ctx).await
+        {
+            eprintln!("new-mail check failed: {err}");
+        }
+
+        if let Some(dispatcher) = options.webhook.as_mut() {
+            dispatcher.tick(ctx).await;
+        }
+
+        if let Some(runner) = options.rules.as_mut() {
+            runner.tick(ctx).await;
+        }
+
+        if let Some(runner) = options.hooks.as_mut() {
+            runner.tick(ctx).await;
+        }
+
+        match queue::dispatch_due(ctx).await {
+            Ok(outcomes) => {
+                for outcome in outcomes {
+                    match outcome.error {
+                        None => eprintln!("sent queued message {}", outcome.id),
+                        Some(err) => eprintln!("queued message {} failed: {err}", outcome.id),
+                    }
+                }
+            }
+            Err(err) => eprintln!("queued sends failed: {err}"),
+        }
+
+        match snooze::wake_due(ctx).await {
+            Ok(results) => {
+                for result in results {
+                    match result.error {
+                        None => eprintln!("snoozed message {} back in inbox", result.id),
+                        Some(err) => eprintln!("unsnoozing {} failed: {err}", result.id),
+                    }
+                }
+            }
+            Err(err) => eprintln!("snooze wake-ups failed: {err}"),
+        }
+
+        match mute::archive_muted(ctx).await {
+            Ok(outcomes) => {
+                for outcome in outcomes {
+                    match outcome.error {
+                        None => eprintln!("archived muted thread {}", outcome.id),
+                        Some(err) => {
+                            eprintln!("archiving muted thread {} failed: {err}", outcome.id)
+                        }
+                    }
+                }
+            }
+            Err(err) => eprintln!("muted thread check failed: {err}"),
+        }
+
+        match watch::renew_if_due(ctx).await {
+            Ok(Some(state)) => eprintln!(
+                "renewed watch on {} (history id {})",
+                state.topic, state.history_id
+            ),
+            Ok(None) => {}
+            Err(err) => eprintln!("watch renewal failed: {err}"),
+        }
+
+        ctx.interrupt.sleep(interval).await?;
+    }
+}
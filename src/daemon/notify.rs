@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+
+use chrono::{Local, NaiveTime};
+
+use crate::api::models::MessageView;
+use crate::auth::token_store::TokenStore;
+use crate::config::{NotifySettings, QuietHours};
+use crate::context::AppContext;
+use crate::desktop::{self, Urgency};
+use crate::error::{AppError, AppResult};
+
+use super::poll::NewMailPoller;
+
+/// Query watched when neither `--q` nor any configured channel is given.
+pub const DEFAULT_QUERY: &str = "in:inbox";
+
+/// A query whose new matches raise notifications.
+#[derive(Debug)]
+struct Channel {
+    name: String,
+    poller: NewMailPoller,
+    urgency: Urgency,
+    ignore_quiet_hours: bool,
+}
+
+/// Raises desktop notifications for new messages across one or more channels,
+/// dropping muted senders/labels and holding back during quiet hours. Messages
+/// held back are summarized in a single notification once quiet hours end.
+#[derive(Debug)]
+pub struct MailNotifier {
+    channels: Vec<Channel>,
+    mute_senders: Vec<String>,
+    mute_labels: Vec<String>,
+    mute_label_ids: Option<Vec<String>>,
+    quiet_hours: Option<(NaiveTime, NaiveTime)>,
+    held_back: usize,
+}
+
+impl MailNotifier {
+    /// Create a notifier. An explicit `query` becomes the only channel;
+    /// otherwise the configured channels are used, falling back to
+    /// [`DEFAULT_QUERY`]. Mute lists and quiet hours always come from settings.
+    pub fn new(query: Option<String>, settings: Option<&NotifySettings>) -> AppResult<Self> {
+        let defaults = NotifySettings::default();
+        let settings = settings.unwrap_or(&defaults);
+
+        let query = query
+            .map(|query| query.trim().to_string())
+            .filter(|query| !query.is_empty());
+        let channels = match query {
+            Some(query) => vec![Channel::adhoc(query)],
+            None if !settings.channels.is_empty() => settings
+                .channels
+                .iter()
+                .map(|channel| {
+                    let urgency = match channel.urgency.as_deref() {
+                        Some(value) => Urgency::parse(value).ok_or_else(|| {
+                            AppError::Config(format!(
+                                "notify channel `{}` has unknown urgency `{value}` (use low, normal, or critical)",
+                                channel.name
+                            ))
+                        })?,
+                        None => Urgency::Normal,
+                    };
+                    Ok(Channel {
+                        name: channel.name.clone(),
+                        poller: NewMailPoller::new(channel.query.trim()),
+                        urgency,
+                        ignore_quiet_hours: channel.ignore_quiet_hours,
+                    })
+                })
+                .collect::<AppResult<Vec<_>>>()?,
+            None => vec![Channel::adhoc(DEFAULT_QUERY.to_string())],
+        };
+
+        let quiet_hours = settings
+            .quiet_hours
+            .as_ref()
+            .map(parse_quiet_hours)
+            .transpose()?;
+
+        Ok(Self {
+            channels,
+            mute_senders: lowercase_all(&settings.mute_senders),
+            mute_labels: settings.mute_labels.clone(),
+            mute_label_ids: None,
+            quiet_hours,
+            held_back: 0,
+        })
+    }
+
+    /// The Gmail search queries being watched, one per channel.
+    pub fn queries(&self) -> Vec<&str> {
+        self.channels
+            .iter()
+            .map(|channel| channel.poller.query())
+            .collect()
+    }
+
+    /// Check every channel for new matches, notify for those that pass the
+    /// mute and quiet-hour rules, and return the notified messages. A channel
+    /// whose poll fails is logged and skipped; the others still notify.
+    pub async fn poll(&mut self, ctx: &AppContext) -> AppResult<Vec<MessageView>> {
+        let quiet = self
+            .quiet_hours
+            .is_some_and(|(start, end)| in_window(Local::now().time(), start, end));
+
+        if !quiet && self.held_back > 0 {
+            let count = std::mem::take(&mut self.held_back);
+            desktop::notify(
+                "gmail",
+                &format!("{count} new message(s) arrived during quiet hours"),
+                Urgency::Normal,
+                None,
+            );
+        }
+
+        let mute_label_ids = self.mute_label_ids(ctx).await?;
+        let account = ctx
+            .token_store
+            .load(ctx.profile()?)?
+            .and_then(|token| token.email);
+
+        let mut notified = Vec::new();
+        let mut handled = HashSet::new();
+        for channel in &mut self.channels {
+            let arrived = match channel.poller.poll(ctx).await {
+                Ok(arrived) => arrived,
+                Err(err) => {
+                    tracing::warn!(
+                        channel = %channel.name,
+                        query = channel.poller.query(),
+                        error = %err,
+                        "notify channel poll failed"
+                    );
+                    continue;
+                }
+            };
+            for message in arrived {
+                if !handled.insert(message.id.clone())
+                    || is_muted(&message, &self.mute_senders, &mute_label_ids)
+                {
+                    continue;
+                }
+                if quiet && !channel.ignore_quiet_hours {
+                    self.held_back += 1;
+                    continue;
+                }
+
+                let summary = match channel.name.as_str() {
+                    "" => message
+                        .from
+                        .clone()
+                        .unwrap_or_else(|| "(unknown sender)".to_string()),
+                    name => format!(
+                        "[{name}] {}",
+                        message.from.as_deref().unwrap_or("(unknown sender)")
+                    ),
+                };
+                let body = message.subject.as_deref().unwrap_or("(no subject)");
+                let url = web_url(account.as_deref(), &message.id);
+                desktop::notify(&summary, body, channel.urgency, Some(&url));
+                notified.push(message);
+            }
+        }
+
+        Ok(notified)
+    }
+
+    /// Resolve the configured mute labels to ids once, accepting names or ids.
+    async fn mute_label_ids(&mut self, ctx: &AppContext) -> AppResult<Vec<String>> {
+        if let Some(ids) = &self.mute_label_ids {
+            return Ok(ids.clone());
+        }
+        if self.mute_labels.is_empty() {
+            self.mute_label_ids = Some(Vec::new());
+            return Ok(Vec::new());
+        }
+
+        let access_token = ctx.access_token().await?;
+        let known = ctx.gmail_client.list_labels(&access_token).await?;
+        let ids = self
+            .mute_labels
+            .iter()
+            .map(|wanted| {
+                known
+                    .iter()
+                    .find(|label| label.id == *wanted || label.name.eq_ignore_ascii_case(wanted))
+                    .map(|label| label.id.clone())
+                    .unwrap_or_else(|| wanted.clone())
+            })
+            .collect::<Vec<_>>();
+        self.mute_label_ids = Some(ids.clone());
+        Ok(ids)
+    }
+}
+
+impl Channel {
+    /// An unnamed, normal-urgency channel for an ad-hoc query.
+    fn adhoc(query: String) -> Self {
+        Self {
+            name: String::new(),
+            poller: NewMailPoller::new(query),
+            urgency: Urgency::Normal,
+            ignore_quiet_hours: false,
+        }
+    }
+}
+
+/// Whether a message is from a muted sender or carries a muted label.
+fn is_muted(message: &MessageView, mute_senders: &[String], mute_label_ids: &[String]) -> bool {
+    let from = message.from.as_deref().unwrap_or_default().to_lowercase();
+    if mute_senders.iter().any(|sender| from.contains(sender)) {
+        return true;
+    }
+
+    message
+        .label_ids
+        .iter()
+        .any(|label| mute_label_ids.iter().any(|muted| muted == label))
+}
+
+/// Whether `now` falls in the daily window `[start, end)`, which may wrap past midnight.
+fn in_window(now: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// Parse a quiet-hours window's `HH:MM` endpoints.
+fn parse_quiet_hours(hours: &QuietHours) -> AppResult<(NaiveTime, NaiveTime)> {
+    let parse = |value: &str| {
+        NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| {
+            AppError::Config(format!(
+                "invalid quiet_hours time `{value}`; expected HH:MM (24-hour)"
+            ))
+        })
+    };
+    Ok((parse(&hours.start)?, parse(&hours.end)?))
+}
+
+/// Lowercase and trim each entry, dropping blanks.
+fn lowercase_all(values: &[String]) -> Vec<String> {
+    values
+        .iter()
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Gmail web UI link for a message, scoped to the account when it is known.
+pub(crate) fn web_url(account: Option<&str>, message_id: &str) -> String {
+    let account = account.unwrap_or("0");
+    format!("https://mail.google.com/mail/u/{account}/#all/{message_id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(value, "%H:%M").unwrap()
+    }
+
+    fn message(from: &str, labels: &[&str]) -> MessageView {
+        MessageView {
+            id: "m1".to_string(),
+            label_ids: labels.iter().map(|label| label.to_string()).collect(),
+            from: Some(from.to_string()),
+            ..MessageView::default()
+        }
+    }
+
+    #[test]
+    fn explicit_query_overrides_channels() {
+        let settings = NotifySettings {
+            channels: vec![crate::config::NotifyChannel {
+                name: "vip".to_string(),
+                query: "from:boss@example.com".to_string(),
+                urgency: Some("critical".to_string()),
+                ignore_quiet_hours: true,
+            }],
+            ..Default::default()
+        };
+
+        let notifier = MailNotifier::new(Some("is:important".to_string()), Some(&settings))
+            .expect("valid settings");
+        assert_eq!(notifier.queries(), ["is:important"]);
+
+        let notifier = MailNotifier::new(None, Some(&settings)).expect("valid settings");
+        assert_eq!(notifier.queries(), ["from:boss@example.com"]);
+
+        let notifier = MailNotifier::new(None, None).expect("defaults");
+        assert_eq!(notifier.queries(), [DEFAULT_QUERY]);
+    }
+
+    #[test]
+    fn rejects_unknown_urgency() {
+        let settings = NotifySettings {
+            channels: vec![crate::config::NotifyChannel {
+                name: "x".to_string(),
+                query: "in:inbox".to_string(),
+                urgency: Some("loud".to_string()),
+                ignore_quiet_hours: false,
+            }],
+            ..Default::default()
+        };
+        assert!(MailNotifier::new(None, Some(&settings)).is_err());
+    }
+
+    #[test]
+    fn quiet_hours_window_wraps_midnight() {
+        let (start, end) = (time("22:00"), time("07:00"));
+        assert!(in_window(time("23:30"), start, end));
+        assert!(in_window(time("06:59"), start, end));
+        assert!(!in_window(time("07:00"), start, end));
+        assert!(!in_window(time("12:00"), start, end));
+
+        assert!(in_window(time("13:00"), time("12:00"), time("14:00")));
+        assert!(!in_window(time("14:00"), time("12:00"), time("14:00")));
+    }
+
+    #[test]
+    fn mutes_by_sender_substring_and_label() {
+        let senders = lowercase_all(&["@News.Example.com".to_string()]);
+        let labels = vec!["CATEGORY_PROMOTIONS".to_string()];
+
+        assert!(is_muted(
+            &message("Digest <digest@news.example.com>", &[]),
+            &senders,
+            &labels
+        ));
+        assert!(is_muted(
+            &message("shop@store.com", &["CATEGORY_PROMOTIONS"]),
+            &senders,
+            &labels
+        ));
+        assert!(!is_muted(
+            &message("friend@example.com", &["INBOX"]),
+            &senders,
+            &labels
+        ));
+    }
+
+    #[test]
+    fn web_url_scopes_to_account_when_known() {
+        assert_eq!(
+            web_url(Some("me@example.com"), "abc"),
+            "https://mail.google.com/mail/u/me@example.com/#all/abc"
+        );
+        assert_eq!(
+            web_url(None, "abc"),
+            "https://mail.google.com/mail/u/0/#all/abc"
+        );
+    }
+}
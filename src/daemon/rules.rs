@@ -0,0 +1,118 @@
+use crate::api::models::MessageView;
+use crate::auth::token_store::TokenStore;
+use crate::context::AppContext;
+use crate::desktop::{self, Urgency};
+use crate::error::AppResult;
+use crate::mail::rules::{MailRules, Rule};
+
+use super::hooks;
+use super::notify;
+use super::poll::NewMailPoller;
+
+/// Applies the local `rules.toml` to messages newly matching its query.
+#[derive(Debug)]
+pub struct RuleRunner {
+    rules: MailRules,
+    poller: NewMailPoller,
+}
+
+impl RuleRunner {
+    pub fn new(rules: MailRules) -> Self {
+        let poller = NewMailPoller::new(rules.query.clone());
+        Self { rules, poller }
+    }
+
+    /// Run one pass over the newly arrived messages. Failures are reported on
+    /// stderr so one broken rule or message doesn't stop the others.
+    pub async fn tick(&mut self, ctx: &AppContext) {
+        let arrived = match self.poller.poll(ctx).await {
+            Ok(arrived) => arrived,
+            Err(err) => {
+                eprintln!("rules query `{}` failed: {err}", self.rules.query);
+                return;
+            }
+        };
+
+        for message in &arrived {
+            let matched = match matching(ctx, &self.rules, message).await {
+                Ok(matched) => matched,
+                Err(err) => {
+                    eprintln!("rules on {}: {err}", message.id);
+                    continue;
+                }
+            };
+            for rule in matched {
+                if let Err(err) = apply(ctx, rule, message).await {
+                    eprintln!("rule `{}` on {}: {err}", rule.name, message.id);
+                }
+            }
+        }
+    }
+}
+
+/// The rules `message` triggers, fetching the extra headers they test.
+pub async fn matching<'a>(
+    ctx: &AppContext,
+    rules: &'a MailRules,
+    message: &MessageView,
+) -> AppResult<Vec<&'a Rule>> {
+    let names = rules.header_names();
+    let headers = if names.is_empty() {
+        Vec::new()
+    } else {
+        let access_token = ctx.access_token().await?;
+        ctx.gmail_client
+            .get_headers(&message.id, &names, &access_token)
+            .await?
+    };
+    Ok(rules.matching(message, &headers))
+}
+
+/// Run a matched rule's actions: label changes first, then the notification,
+/// then the command.
+async fn apply(ctx: &AppContext, rule: &Rule, message: &MessageView) -> AppResult<()> {
+    let (add, remove) = rule.label_changes();
+    if !add.is_empty() || !remove.is_empty() {
+        let access_token = ctx.access_token().await?;
+        if !add.is_empty() {
+            ctx.gmail_client
+                .add_labels(&message.id, &add, &access_token)
+                .await?;
+        }
+        if !remove.is_empty() {
+            ctx.gmail_client
+                .rm_labels(&message.id, &remove, &access_token)
+                .await?;
+        }
+    }
+
+    if rule.spec.notify {
+        let account = ctx
+            .token_store
+            .load(ctx.profile()?)?
+            .and_then(|token| token.email);
+        let summary = format!(
+            "[{}] {}",
+            rule.name,
+            message.from.as_deref().unwrap_or("(unknown sender)")
+        );
+        let body = message.subject.as_deref().unwrap_or("(no subject)");
+        let url = notify::web_url(account.as_deref(), &message.id);
+        desktop::notify(&summary, body, Urgency::Normal, Some(&url));
+    }
+
+    if let Some(command) = rule
+        .spec
+        .run
+        .as_deref()
+        .filter(|run| !run.trim().is_empty())
+    {
+        let access_token = ctx.access_token().await?;
+        let full = ctx
+            .gmail_client
+            .get_msg_full(&message.id, &access_token)
+            .await?;
+        hooks::run_command(command, ctx, &full).await?;
+    }
+    Ok(())
+}
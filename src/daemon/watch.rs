@@ -0,0 +1,146 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::WatchResult;
+use crate::context::AppContext;
+use crate::error::AppResult;
+
+/// Renew a registration once less than this much time remains before Gmail
+/// expires it (registrations last 7 days; Google recommends renewing daily).
+pub const RENEW_BEFORE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A profile's active `users.watch` registration, persisted so `watch status`
+/// and the daemon can inspect and renew it without re-supplying the topic.
+#[derive(Debug, Clone, Serialize, JsonSchema, Deserialize)]
+pub struct WatchState {
+    pub profile: String,
+    pub topic: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub history_id: String,
+    pub expiration_unix_ms: u64,
+}
+
+impl WatchState {
+    /// Build the state recorded after a successful `users.watch` call.
+    pub fn new(profile: &str, topic: &str, labels: &[String], result: WatchResult) -> Self {
+        Self {
+            profile: profile.to_string(),
+            topic: topic.to_string(),
+            labels: labels.to_vec(),
+            history_id: result.history_id,
+            expiration_unix_ms: result.expiration_unix_ms,
+        }
+    }
+
+    /// Milliseconds until expiry relative to `now` (negative once expired).
+    pub fn expires_in_ms(&self, now: SystemTime) -> i64 {
+        let now_ms = now
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
+        self.expiration_unix_ms as i64 - now_ms
+    }
+
+    /// Whether the registration is inside the renewal window (or already expired) at `now`.
+    pub fn renewal_due(&self, now: SystemTime) -> bool {
+        self.expires_in_ms(now) <= RENEW_BEFORE.as_millis() as i64
+    }
+}
+
+/// Load a watch state from `path`, returning `None` when the file is absent.
+pub fn load(path: PathBuf) -> AppResult<Option<WatchState>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = fs::read_to_string(path)?;
+    let state = serde_json::from_str(&raw)?;
+    Ok(Some(state))
+}
+
+/// Write a watch state as pretty JSON to `path`.
+pub fn save(path: PathBuf, state: &WatchState) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let payload = serde_json::to_string_pretty(state)?;
+    fs::write(&path, payload)?;
+    Ok(())
+}
+
+/// Delete the watch state at `path` if it exists.
+pub fn clear(path: PathBuf) -> AppResult<()> {
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// Re-register the profile's watch when it is inside the renewal window,
+/// returning the refreshed state, or `None` when nothing needed renewing.
+pub async fn renew_if_due(ctx: &AppContext) -> AppResult<Option<WatchState>> {
+    let profile = ctx.profile()?;
+    let path = ctx.paths.watch_file(profile);
+    let Some(state) = load(path.clone())? else {
+        return Ok(None);
+    };
+
+    if !state.renewal_due(SystemTime::now()) {
+        return Ok(None);
+    }
+
+    let access_token = ctx.access_token().await?;
+    let result = ctx
+        .gmail_client
+        .watch(&state.topic, &state.labels, &access_token)
+        .await?;
+    let renewed = WatchState::new(profile, &state.topic, &state.labels, result);
+    save(path, &renewed)?;
+    Ok(Some(renewed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_expiring_at(expiration_unix_ms: u64) -> WatchState {
+        WatchState {
+            profile: "default".to_string(),
+            topic: "projects/p/topics/t".to_string(),
+            labels: vec!["INBOX".to_string()],
+            history_id: "1".to_string(),
+            expiration_unix_ms,
+        }
+    }
+
+    fn at_ms(ms: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn fresh_registration_is_not_due() {
+        let week = 7 * 24 * 60 * 60 * 1000;
+        let state = state_expiring_at(week);
+        assert!(!state.renewal_due(at_ms(0)));
+    }
+
+    #[test]
+    fn registration_inside_window_is_due() {
+        let state = state_expiring_at(RENEW_BEFORE.as_millis() as u64);
+        assert!(state.renewal_due(at_ms(1)));
+    }
+
+    #[test]
+    fn expired_registration_is_due() {
+        let state = state_expiring_at(1_000);
+        assert!(state.renewal_due(at_ms(5_000)));
+        assert_eq!(state.expires_in_ms(at_ms(5_000)), -4_000);
+    }
+}
@@ -0,0 +1,235 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use schemars::JsonSchema;
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::time;
+use tracing::Instrument;
+
+use crate::api::models::MessageView;
+use crate::config::WebhookSettings;
+use crate::context::AppContext;
+use crate::error::{ApiError, AppError, AppResult};
+
+use super::poll::NewMailPoller;
+
+/// Header carrying `sha256=<hex hmac>` of the request body when a secret is configured.
+pub const SIGNATURE_HEADER: &str = "X-Gmail-Signature";
+
+/// Delivery attempts per event before it is dropped.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry; doubles on each subsequent attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// JSON body POSTed to the webhook for each newly matched message.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MessageEvent<'a> {
+    pub event: &'static str,
+    pub profile: &'a str,
+    pub query_name: &'a str,
+    pub query: &'a str,
+    pub delivered_at_unix: u64,
+    pub message: &'a MessageView,
+}
+
+/// Polls each configured named query and POSTs an event per new match.
+#[derive(Debug)]
+pub struct WebhookDispatcher {
+    url: String,
+    secret: Option<String>,
+    queries: Vec<(String, NewMailPoller)>,
+    http: Client,
+}
+
+impl WebhookDispatcher {
+    /// Build a dispatcher from the profile's webhook settings, posting through `http`.
+    pub fn from_settings(settings: &WebhookSettings, http: Client) -> AppResult<Self> {
+        let url = settings.url.trim();
+        if url.is_empty() {
+            return Err(AppError::Config(
+                "webhook.url is empty in profile settings".to_string(),
+            ));
+        }
+        url::Url::parse(url)?;
+
+        let mut queries = settings
+            .queries
+            .iter()
+            .map(|(name, query)| (name.clone(), NewMailPoller::new(query.clone())))
+            .collect::<Vec<_>>();
+        if queries.is_empty() {
+            queries.push(("inbox".to_string(), NewMailPoller::new("in:inbox")));
+        }
+
+        Ok(Self {
+            url: url.to_string(),
+            secret: settings.secret.clone().filter(|secret| !secret.is_empty()),
+            queries,
+            http,
+        })
+    }
+
+    /// Run one pass: poll every query and deliver an event per new match.
+    /// Errors are reported on stderr so one failing query or delivery doesn't
+    /// stop the others.
+    pub async fn tick(&mut self, ctx: &AppContext) {
+        let profile = ctx.profile().unwrap_or_default().to_string();
+
+        for (name, poller) in &mut self.queries {
+            let arrived = match poller.poll(ctx).await {
+                Ok(arrived) => arrived,
+                Err(err) => {
+                    eprintln!("webhook query `{name}` failed: {err}");
+                    continue;
+                }
+            };
+
+            for message in &arrived {
+                let event = MessageEvent {
+                    event: "message.new",
+                    profile: &profile,
+                    query_name: name,
+                    query: poller.query(),
+                    delivered_at_unix: unix_now(),
+                    message,
+                };
+                if let Err(err) =
+                    deliver(&self.http, &self.url, self.secret.as_deref(), &event).await
+                {
+                    eprintln!("webhook delivery for {} dropped: {err}", message.id);
+                }
+            }
+        }
+    }
+}
+
+/// POST `event` to `url`, retrying network failures, 429s, and 5xx responses
+/// with exponential backoff. Other 4xx responses fail immediately.
+async fn deliver(
+    http: &Client,
+    url: &str,
+    secret: Option<&str>,
+    event: &MessageEvent<'_>,
+) -> AppResult<()> {
+    let body = serde_json::to_vec(event)?;
+    let signature = secret.map(|secret| sign(secret, &body));
+    let mut backoff = INITIAL_BACKOFF;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = http
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        let span = tracing::debug_span!("webhook", method = "POST", endpoint = url, attempt);
+        let started = Instant::now();
+        let result = request.send().instrument(span.clone()).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        if let Ok(response) = &result {
+            tracing::debug!(
+                parent: &span,
+                status = response.status().as_u16(),
+                latency_ms,
+                "webhook responded"
+            );
+        }
+
+        let failure = match result {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) if !is_retryable(response.status()) => {
+                let status = response.status();
+                return Err(AppError::Api(ApiError::http(
+                    status.as_u16(),
+                    format!("webhook returned {status}"),
+                )));
+            }
+            Ok(response) => format!("webhook returned {}", response.status()),
+            Err(err) => format!("webhook request failed: {err}"),
+        };
+
+        if attempt == MAX_ATTEMPTS {
+            return Err(AppError::api(format!(
+                "{failure} (gave up after {MAX_ATTEMPTS} attempts)"
+            )));
+        }
+        tracing::warn!(
+            parent: &span,
+            retry_in_ms = backoff.as_millis() as u64,
+            "{failure}; retrying"
+        );
+        time::sleep(backoff).await;
+        backoff *= 2;
+    }
+
+    Ok(())
+}
+
+/// Whether a webhook response status is worth retrying.
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// HMAC-SHA256 of `body` keyed by `secret`, formatted as `sha256=<lowercase hex>`.
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex = digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("sha256={hex}")
+}
+
+/// Current time as whole seconds since the unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_body_with_hmac_sha256() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn retries_only_rate_limits_and_server_errors() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn empty_queries_default_to_inbox() {
+        let dispatcher = WebhookDispatcher::from_settings(
+            &WebhookSettings {
+                url: "https://hooks.example.com/gmail".to_string(),
+                secret: None,
+                queries: Default::default(),
+            },
+            Client::new(),
+        )
+        .expect("valid settings");
+        assert_eq!(dispatcher.queries.len(), 1);
+        assert_eq!(dispatcher.queries[0].0, "inbox");
+        assert_eq!(dispatcher.queries[0].1.query(), "in:inbox");
+    }
+}
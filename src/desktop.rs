@@ -0,0 +1,159 @@
+use std::process::Command;
+
+/// How insistently a desktop notification should be presented.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    /// Parse `low`, `normal`, or `critical` (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "normal" => Some(Self::Normal),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    /// The libnotify spelling of this urgency level.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// Open a URL in the platform's default browser, returning whether the launch succeeded.
+pub fn open_browser(url: &str) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        return Command::new("open")
+            .arg(url)
+            .status()
+            .is_ok_and(|status| status.success());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        return Command::new("xdg-open")
+            .arg(url)
+            .status()
+            .is_ok_and(|status| status.success());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return Command::new("cmd")
+            .args(["/C", "start", "", url])
+            .status()
+            .is_ok_and(|status| status.success());
+    }
+
+    #[allow(unreachable_code)]
+    false
+}
+
+/// Raise a native desktop notification, returning whether one was shown.
+///
+/// When `open_url` is set and the platform supports notification actions
+/// (libnotify's `notify-send --action`), clicking the notification opens the
+/// URL in the browser; the wait for the click happens on a background thread.
+/// `urgency` is honored where the platform has the concept (libnotify).
+pub fn notify(summary: &str, body: &str, urgency: Urgency, open_url: Option<&str>) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let urgency = format!("--urgency={}", urgency.as_str());
+        if let Some(url) = open_url {
+            let (summary, body, url) = (summary.to_string(), body.to_string(), url.to_string());
+            std::thread::spawn(move || notify_send_with_action(&summary, &body, &urgency, &url));
+            return true;
+        }
+
+        return Command::new("notify-send")
+            .args(["--app-name=gmail", &urgency, summary, body])
+            .status()
+            .is_ok_and(|status| status.success());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = (urgency, open_url);
+        let script = format!(
+            "display notification \"{}\" with title \"{}\"",
+            escape_applescript(body),
+            escape_applescript(summary)
+        );
+        return Command::new("osascript")
+            .args(["-e", &script])
+            .status()
+            .is_ok_and(|status| status.success());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = (urgency, open_url);
+        let script = format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $n = New-Object System.Windows.Forms.NotifyIcon; \
+             $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; \
+             $n.ShowBalloonTip(5000, '{}', '{}', 'Info'); Start-Sleep 6; $n.Dispose()",
+            summary.replace('\'', "''"),
+            body.replace('\'', "''")
+        );
+        return Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .stdout(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (summary, body, urgency, open_url);
+        false
+    }
+}
+
+/// Show a notification with an "Open" action and block until it is dismissed,
+/// opening `url` if the action was chosen. Falls back to a plain notification
+/// on libnotify versions without `--action` support.
+#[cfg(target_os = "linux")]
+fn notify_send_with_action(summary: &str, body: &str, urgency: &str, url: &str) {
+    let output = Command::new("notify-send")
+        .args([
+            "--app-name=gmail",
+            urgency,
+            "--action=open=Open",
+            "--wait",
+            summary,
+            body,
+        ])
+        .stderr(std::process::Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            if String::from_utf8_lossy(&output.stdout).trim() == "open" {
+                open_browser(url);
+            }
+        }
+        _ => {
+            let _ = Command::new("notify-send")
+                .args(["--app-name=gmail", urgency, summary, body])
+                .status();
+        }
+    }
+}
+
+/// Escape a string for inclusion inside an AppleScript double-quoted literal.
+#[cfg(target_os = "macos")]
+fn escape_applescript(input: &str) -> String {
+    input.replace('\\', "\\\\").replace('"', "\\\"")
+}
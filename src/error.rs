@@ -1,21 +1,34 @@
+use std::fmt;
 use std::io;
 
+use schemars::JsonSchema;
+use serde::Serialize;
 use thiserror::Error;
 
 pub type AppResult<T> = Result<T, AppError>;
 
+/// Gmail reasons (`error.errors[].reason`) that mean "slow down and try again".
+const RETRYABLE_REASONS: &[&str] = &[
+    "rateLimitExceeded",
+    "userRateLimitExceeded",
+    "backendError",
+    "internalError",
+];
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("configuration error: {0}")]
     Config(String),
     #[error("auth error: {0}")]
-    Auth(String),
+    Auth(ApiError),
     #[error("api error: {0}")]
-    Api(String),
+    Api(ApiError),
     #[error("invalid input: {0}")]
     InvalidInput(String),
     #[error("not implemented: {0}")]
     NotImplemented(&'static str),
+    #[error("interrupted")]
+    Interrupted,
     #[error("io error: {0}")]
     Io(#[from] io::Error),
     #[error("http error: {0}")]
@@ -25,3 +38,218 @@ pub enum AppError {
     #[error("url parse error: {0}")]
     Url(#[from] url::ParseError),
 }
+
+/// What a remote service reported for a failed request (or what went wrong
+/// handling its answer): the HTTP status and Gmail reason when there was a
+/// response, and the request it was for.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ApiError {
+    pub message: String,
+    /// HTTP status of the failed response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Machine-readable reason, e.g. `rateLimitExceeded` or `notFound`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The failed request, as `METHOD /path`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+impl ApiError {
+    /// An error with no response behind it.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Self::default()
+        }
+    }
+
+    /// An error for a response with HTTP `status`.
+    pub fn http(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status: Some(status),
+            ..Self::new(message)
+        }
+    }
+
+    pub fn with_reason(mut self, reason: Option<String>) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    pub fn with_endpoint(mut self, method: &str, endpoint: &str) -> Self {
+        self.endpoint = Some(format!("{method} {endpoint}"));
+        self
+    }
+
+    /// Whether sending the same request again later may succeed: rate limits,
+    /// server errors, and Gmail's rate-limit and backend reasons (which Gmail
+    /// also reports with 403).
+    pub fn retryable(&self) -> bool {
+        self.status.is_some_and(retryable_status)
+            || self
+                .reason
+                .as_deref()
+                .is_some_and(|reason| RETRYABLE_REASONS.contains(&reason))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Whether an HTTP status is worth retrying: 408, 429, and 5xx.
+pub fn retryable_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500..=599)
+}
+
+impl AppError {
+    /// A local auth failure (no response behind it).
+    pub fn auth(message: impl Into<String>) -> Self {
+        Self::Auth(ApiError::new(message))
+    }
+
+    /// A local API failure (no response behind it).
+    pub fn api(message: impl Into<String>) -> Self {
+        Self::Api(ApiError::new(message))
+    }
+
+    /// Record the request an auth or API error came from.
+    pub fn at_endpoint(self, method: &str, endpoint: &str) -> Self {
+        match self {
+            Self::Auth(err) => Self::Auth(err.with_endpoint(method, endpoint)),
+            Self::Api(err) => Self::Api(err.with_endpoint(method, endpoint)),
+            other => other,
+        }
+    }
+
+    /// Process exit status for this error: 130 for Ctrl-C, otherwise 1.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Interrupted => crate::interrupt::EXIT_INTERRUPTED,
+            _ => 1,
+        }
+    }
+
+    /// Stable name of the variant, for structured error output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config",
+            Self::Auth(_) => "auth",
+            Self::Api(_) => "api",
+            Self::InvalidInput(_) => "invalid_input",
+            Self::NotImplemented(_) => "not_implemented",
+            Self::Interrupted => "interrupted",
+            Self::Io(_) => "io",
+            Self::Http(_) => "http",
+            Self::Json(_) => "json",
+            Self::Url(_) => "url",
+        }
+    }
+
+    /// The remote service's side of the error, if it came from a response.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::Auth(err) | Self::Api(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the failed response, if any.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Http(err) => err.status().map(|status| status.as_u16()),
+            other => other.api_error().and_then(|err| err.status),
+        }
+    }
+
+    /// Gmail's error reason, if the response carried one.
+    pub fn reason(&self) -> Option<&str> {
+        self.api_error().and_then(|err| err.reason.as_deref())
+    }
+
+    /// Whether the operation may succeed if retried unchanged: see
+    /// [`ApiError::retryable`]; timeouts and connection failures also qualify.
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::Http(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err
+                        .status()
+                        .is_some_and(|status| retryable_status(status.as_u16()))
+            }
+            other => other.api_error().is_some_and(ApiError::retryable),
+        }
+    }
+
+    /// The error as printed under `--json`: `kind`, `message`, `retryable`,
+    /// plus `status`, `reason`, and `endpoint` when known.
+    pub fn report(&self) -> ErrorReport {
+        let api = self.api_error();
+        ErrorReport {
+            kind: self.kind(),
+            message: self.to_string(),
+            status: self.status(),
+            reason: api.and_then(|err| err.reason.clone()),
+            endpoint: api.and_then(|err| err.endpoint.clone()),
+            retryable: self.retryable(),
+        }
+    }
+}
+
+/// Structured form of an [`AppError`], written to stderr with `--json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ErrorReport {
+    pub kind: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    pub retryable: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_retryable_errors() {
+        assert!(AppError::Api(ApiError::http(503, "unavailable")).retryable());
+        assert!(AppError::Api(ApiError::http(429, "slow down")).retryable());
+        assert!(
+            AppError::Auth(
+                ApiError::http(403, "quota").with_reason(Some("userRateLimitExceeded".into()))
+            )
+            .retryable()
+        );
+        assert!(!AppError::Auth(ApiError::http(403, "forbidden")).retryable());
+        assert!(!AppError::api("bad data").retryable());
+        assert!(!AppError::InvalidInput("nope".into()).retryable());
+    }
+
+    #[test]
+    fn reports_structured_fields() {
+        let err = AppError::Api(
+            ApiError::http(404, "gmail api request failed (404 Not Found): gone")
+                .with_reason(Some("notFound".into()))
+                .with_endpoint("GET", "/gmail/v1/users/me/messages/x"),
+        );
+        let report = serde_json::to_value(err.report()).unwrap();
+        assert_eq!(report["kind"], "api");
+        assert_eq!(report["status"], 404);
+        assert_eq!(report["reason"], "notFound");
+        assert_eq!(report["endpoint"], "GET /gmail/v1/users/me/messages/x");
+        assert_eq!(report["retryable"], false);
+        assert_eq!(
+            report["message"],
+            "api error: gmail api request failed (404 Not Found): gone"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+# German text output.
+
+error-prefix = Fehler
+unknown-sender = (unbekannter Absender)
+no-subject = (kein Betreff)
+no-date = (kein Datum)
+no-body = (kein Inhalt)
+
+messages-count = { $count ->
+    [one] 1 Nachricht
+   *[other] { $count } Nachrichten
+}
+labels-count = { $count ->
+    [one] 1 Label
+   *[other] { $count } Labels
+}
+threads-count = { $count ->
+    [one] 1 Unterhaltung
+   *[other] { $count } Unterhaltungen
+}
+drafts-count = { $count ->
+    [one] 1 Entwurf
+   *[other] { $count } Entwürfe
+}
+
+queue-count = { $count ->
+    [one] 1 geplante Nachricht
+   *[other] { $count } geplante Nachrichten
+}
+snooze-count = { $count ->
+    [one] 1 zurückgestellte Nachricht
+   *[other] { $count } zurückgestellte Nachrichten
+}
+muted-count = { $count ->
+    [one] 1 stummgeschaltete Unterhaltung
+   *[other] { $count } stummgeschaltete Unterhaltungen
+}
+
+field-account = Konto
+field-from = Von
+field-subject = Betreff
+field-date = Datum
+field-participants = Mit
+field-to = An
+field-cc = Cc
+field-draft = Entwurf
+unread = ungelesen
+
+attachments-heading = Anhänge ({ $count }):
+attachments-hint = (herunterladen mit: gmail attachments get { $id })
+size-bytes = { $size } Bytes
+
+sent-message = Nachricht { $id } gesendet
+saved-draft = Entwurf { $id } gespeichert
+queued-message = Nachricht { $id } für { $due } geplant
+queue-nothing-due = keine geplanten Nachrichten fällig
+snooze-nothing-due = keine zurückgestellten Nachrichten fällig
+muted-nothing-in-inbox = keine stummgeschalteten Unterhaltungen im Posteingang
@@ -0,0 +1,56 @@
+# English text output. Message ids are shared by every locale; a locale
+# missing an id falls back to the text here.
+
+error-prefix = error
+unknown-sender = (unknown sender)
+no-subject = (no subject)
+no-date = (no date)
+no-body = (no body)
+
+messages-count = { $count ->
+    [one] 1 message
+   *[other] { $count } messages
+}
+labels-count = { $count } labels
+threads-count = { $count ->
+    [one] 1 thread
+   *[other] { $count } threads
+}
+drafts-count = { $count ->
+    [one] 1 draft
+   *[other] { $count } drafts
+}
+
+queue-count = { $count ->
+    [one] 1 queued message
+   *[other] { $count } queued messages
+}
+snooze-count = { $count ->
+    [one] 1 snoozed message
+   *[other] { $count } snoozed messages
+}
+muted-count = { $count ->
+    [one] 1 muted thread
+   *[other] { $count } muted threads
+}
+
+field-account = account
+field-from = from
+field-subject = subject
+field-date = date
+field-participants = with
+field-to = to
+field-cc = cc
+field-draft = draft
+unread = unread
+
+attachments-heading = attachments ({ $count }):
+attachments-hint = (download with: gmail attachments get { $id })
+size-bytes = { $size } bytes
+
+sent-message = sent message { $id }
+saved-draft = saved draft { $id }
+queued-message = queued message { $id } for { $due }
+queue-nothing-due = no queued messages are due
+snooze-nothing-due = no snoozed messages are due
+muted-nothing-in-inbox = no muted threads are back in the inbox
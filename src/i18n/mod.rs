@@ -0,0 +1,173 @@
+//! Translated strings for human-readable text output.
+//!
+//! Messages live in Fluent files embedded at build time (`en.ftl`, `de.ftl`).
+//! JSON, NDJSON, CSV, and table output never go through here, so scripts see
+//! the same field names and values in every language.
+
+use std::env;
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+use crate::error::{AppError, AppResult};
+
+/// Embedded locales as (language, Fluent source); the first is the fallback.
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("en.ftl")),
+    ("de", include_str!("de.ftl")),
+];
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+struct Catalog {
+    bundle: FluentBundle<FluentResource>,
+    fallback: Option<FluentBundle<FluentResource>>,
+}
+
+impl Catalog {
+    fn new(language: &str) -> Self {
+        let fallback = (language != LOCALES[0].0).then(|| bundle(LOCALES[0].0));
+        Self {
+            bundle: bundle(language),
+            fallback,
+        }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        [Some(&self.bundle), self.fallback.as_ref()]
+            .into_iter()
+            .flatten()
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                Some(
+                    bundle
+                        .format_pattern(pattern, args, &mut errors)
+                        .into_owned(),
+                )
+            })
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+/// Build the bundle for one embedded locale.
+fn bundle(language: &str) -> FluentBundle<FluentResource> {
+    let (_, source) = LOCALES
+        .iter()
+        .find(|(name, _)| *name == language)
+        .unwrap_or(&LOCALES[0]);
+    let id: LanguageIdentifier = language.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Text goes to terminals and pipes, not bidi-aware renderers.
+    bundle.set_use_isolating(false);
+    let resource =
+        FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, _)| resource);
+    let _ = bundle.add_resource(resource);
+    bundle
+}
+
+/// Choose the output language once at startup from the `language` setting:
+/// a language tag, or `auto` for the `LC_ALL` / `LC_MESSAGES` / `LANG`
+/// locale. Unset (or an unsupported locale under `auto`) means English.
+/// Later calls keep the first choice.
+pub fn install(setting: Option<&str>) -> AppResult<()> {
+    let language = match setting.map(str::trim) {
+        None | Some("") => LOCALES[0].0,
+        Some("auto") => ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| supported(&value))
+            .unwrap_or(LOCALES[0].0),
+        Some(value) => supported(value).ok_or_else(|| {
+            let known = LOCALES.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+            AppError::Config(format!(
+                "unsupported language `{value}`; available: {}, auto",
+                known.join(", ")
+            ))
+        })?,
+    };
+    let _ = CATALOG.set(Catalog::new(language));
+    Ok(())
+}
+
+/// The embedded locale for a language tag or POSIX locale (`de`, `de-AT`,
+/// `de_DE.UTF-8`), if there is one.
+pub fn supported(value: &str) -> Option<&'static str> {
+    let tag = value.split(['.', '@']).next().unwrap_or_default();
+    let id: LanguageIdentifier = tag.replace('_', "-").parse().ok()?;
+    LOCALES
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| *name == id.language.as_str())
+}
+
+/// The message `id` in the installed language.
+pub fn text(id: &str) -> String {
+    format(id, &[])
+}
+
+/// The message `id` with its `{ $name }` placeables filled from `args`.
+pub fn format(id: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+    let catalog = CATALOG.get_or_init(|| Catalog::new(LOCALES[0].0));
+    if args.is_empty() {
+        return catalog.format(id, None);
+    }
+    let args = args.iter().cloned().collect::<FluentArgs>();
+    catalog.format(id, Some(&args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_language_tags_and_posix_locales() {
+        assert_eq!(supported("de"), Some("de"));
+        assert_eq!(supported("de_DE.UTF-8"), Some("de"));
+        assert_eq!(supported("de-AT"), Some("de"));
+        assert_eq!(supported("en_US"), Some("en"));
+        assert_eq!(supported("fr_FR.UTF-8"), None);
+        assert_eq!(supported("C"), None);
+    }
+
+    #[test]
+    fn formats_plurals_and_falls_back_to_english() {
+        let german = Catalog::new("de");
+        let count = |n: usize| {
+            let mut args = FluentArgs::new();
+            args.set("count", n);
+            german.format("messages-count", Some(&args))
+        };
+        assert_eq!(count(1), "1 Nachricht");
+        assert_eq!(count(3), "3 Nachrichten");
+        assert_eq!(german.format("no-subject", None), "(kein Betreff)");
+        assert_eq!(german.format("missing-id", None), "missing-id");
+
+        let english = Catalog::new("en");
+        assert_eq!(english.format("no-subject", None), "(no subject)");
+    }
+
+    #[test]
+    fn every_locale_parses_and_covers_english() {
+        let ids = LOCALES[0]
+            .1
+            .lines()
+            .filter_map(|line| line.split_once(" = ").map(|(id, _)| id))
+            .filter(|id| id.chars().all(|c| c.is_ascii_lowercase() || c == '-'))
+            .collect::<Vec<_>>();
+        assert!(ids.contains(&"messages-count"));
+        for (name, source) in LOCALES {
+            assert!(
+                FluentResource::try_new(source.to_string()).is_ok(),
+                "{name}.ftl has syntax errors"
+            );
+            let bundle = bundle(name);
+            for id in &ids {
+                assert!(bundle.has_message(id), "{name}.ftl is missing `{id}`");
+            }
+        }
+    }
+}
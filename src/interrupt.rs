@@ -0,0 +1,142 @@
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio::time;
+
+use crate::error::{AppError, AppResult};
+
+/// Exit status of a run cut short by Ctrl-C (128 + SIGINT, as shells report it).
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// How long a command gets to wind down after the first Ctrl-C before it is
+/// dropped anyway.
+pub const GRACE: Duration = Duration::from_secs(3);
+
+/// Cancellation shared by everything a command runs. The first Ctrl-C sets
+/// it: paged listings, id loops, and polling loops check it between steps,
+/// write out what they have, and return [`AppError::Interrupted`]. A second
+/// Ctrl-C (or [`GRACE`] passing) ends the command where it stands.
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    presses: AtomicUsize,
+    notify: Notify,
+}
+
+impl Interrupt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count Ctrl-C presses for the rest of the process.
+    pub fn listen(&self) {
+        let interrupt = self.clone();
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if !interrupt.is_set() {
+                    eprintln!("interrupted; finishing up (ctrl-c again to stop now)");
+                }
+                interrupt.trigger();
+            }
+        });
+    }
+
+    /// Record one Ctrl-C press.
+    pub fn trigger(&self) {
+        self.inner.presses.fetch_add(1, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.presses() > 0
+    }
+
+    /// `Err(Interrupted)` once Ctrl-C has been pressed.
+    pub fn check(&self) -> AppResult<()> {
+        if self.is_set() {
+            return Err(AppError::Interrupted);
+        }
+        Ok(())
+    }
+
+    /// Resolve once Ctrl-C has been pressed.
+    pub async fn cancelled(&self) {
+        self.presses_reach(1).await;
+    }
+
+    /// Sleep for `duration`, returning `Err(Interrupted)` early on Ctrl-C.
+    pub async fn sleep(&self, duration: Duration) -> AppResult<()> {
+        tokio::select! {
+            _ = time::sleep(duration) => Ok(()),
+            _ = self.cancelled() => Err(AppError::Interrupted),
+        }
+    }
+
+    /// Resolve when a command should stop waiting to wind down: a second
+    /// Ctrl-C, or `grace` after the first.
+    pub async fn forced(&self, grace: Duration) {
+        self.cancelled().await;
+        tokio::select! {
+            _ = time::sleep(grace) => {}
+            _ = self.presses_reach(2) => {}
+        }
+    }
+
+    fn presses(&self) -> usize {
+        self.inner.presses.load(Ordering::SeqCst)
+    }
+
+    async fn presses_reach(&self, count: usize) {
+        loop {
+            let mut notified = pin!(self.inner.notify.notified());
+            notified.as_mut().enable();
+            if self.presses() >= count {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sleep_ends_early_once_triggered() {
+        let interrupt = Interrupt::new();
+        assert!(interrupt.check().is_ok());
+
+        let waiter = interrupt.clone();
+        let sleeping = tokio::spawn(async move { waiter.sleep(Duration::from_secs(60)).await });
+        tokio::task::yield_now().await;
+        interrupt.trigger();
+
+        let result = time::timeout(Duration::from_secs(5), sleeping)
+            .await
+            .expect("sleep should end on interrupt")
+            .unwrap();
+        assert!(matches!(result, Err(AppError::Interrupted)));
+        assert!(interrupt.check().is_err());
+    }
+
+    #[tokio::test]
+    async fn second_press_forces_without_waiting_for_grace() {
+        let interrupt = Interrupt::new();
+        interrupt.trigger();
+        interrupt.trigger();
+        time::timeout(
+            Duration::from_secs(5),
+            interrupt.forced(Duration::from_secs(60)),
+        )
+        .await
+        .expect("a second press should force");
+    }
+}
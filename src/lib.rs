@@ -5,14 +5,23 @@ pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod context;
+pub mod daemon;
+pub mod desktop;
 pub mod error;
+pub mod i18n;
+pub mod interrupt;
+pub mod logging;
 pub mod mail;
+pub mod mcp;
 pub mod output;
+pub mod tui;
+
+use std::ffi::OsString;
 
 use cli::Cli;
 use error::AppResult;
 
-/// Entry point that runs the parsed CLI.
-pub async fn run(cli: Cli) -> AppResult<()> {
-    app::run(cli).await
+/// Entry point that runs the CLI parsed from `args`.
+pub async fn run(cli: Cli, args: Vec<OsString>) -> AppResult<()> {
+    app::run(cli, args).await
 }
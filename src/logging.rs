@@ -0,0 +1,98 @@
+use std::fs::{File, OpenOptions};
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::error::{AppError, AppResult};
+use crate::output::text::ColorChoice;
+
+/// Crate target whose events `-v` turns up; dependencies stay at `warn` until `-vvv`.
+const CRATE_TARGET: &str = env!("CARGO_CRATE_NAME");
+
+/// Map the `-v` count to the level logged for this crate.
+pub fn level_for(verbose: u8) -> LevelFilter {
+    match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Install the global tracing subscriber: human-readable events on stderr and,
+/// when `log_file` is set, the same events appended to that file without color.
+pub fn init(verbose: u8, log_file: Option<&Path>, color: ColorChoice) -> AppResult<()> {
+    let level = level_for(verbose);
+    let dependency_level = if verbose >= 3 {
+        LevelFilter::TRACE
+    } else {
+        LevelFilter::WARN
+    };
+    let filter = Targets::new()
+        .with_target(CRATE_TARGET, level)
+        .with_default(dependency_level);
+
+    let stderr_layer = fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(color.enabled(std::io::stderr().is_terminal()))
+        .with_target(verbose >= 2);
+
+    let file_layer = match log_file {
+        Some(path) => Some(
+            fmt::layer()
+                .with_writer(Mutex::new(open_log_file(path)?))
+                .with_ansi(false),
+        ),
+        None => None,
+    };
+
+    // A subscriber may already be installed (e.g. by an embedding test harness).
+    let _ = tracing_subscriber::registry()
+        .with(stderr_layer)
+        .with(file_layer)
+        .with(filter)
+        .try_init();
+
+    Ok(())
+}
+
+/// Open `path` for appending, creating it and its parent directory as needed.
+fn open_log_file(path: &Path) -> AppResult<File> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| {
+            AppError::Config(format!(
+                "failed to open log file `{}`: {err}",
+                path.display()
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_verbosity_to_levels() {
+        assert_eq!(level_for(0), LevelFilter::WARN);
+        assert_eq!(level_for(1), LevelFilter::INFO);
+        assert_eq!(level_for(2), LevelFilter::DEBUG);
+        assert_eq!(level_for(3), LevelFilter::TRACE);
+        assert_eq!(level_for(9), LevelFilter::TRACE);
+    }
+}
@@ -0,0 +1,93 @@
+/// Split an address-list header (`To`, `Cc`) into its entries, ignoring commas
+/// inside quoted display names and angle brackets.
+pub fn split_addresses(header: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut bracketed = false;
+
+    for ch in header.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            '<' if !quoted => bracketed = true,
+            '>' if !quoted => bracketed = false,
+            ',' if !quoted && !bracketed => {
+                entries.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    entries.push(current);
+
+    entries
+        .into_iter()
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+/// The bare, lowercased email of an entry like `Ada <ada@example.com>`.
+pub fn email_of(entry: &str) -> String {
+    let bare = match (entry.rfind('<'), entry.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &entry[start + 1..end],
+        _ => entry,
+    };
+    bare.trim().to_ascii_lowercase()
+}
+
+/// The display name of an entry like `"Doe, Jane" <jane@example.com>`, unquoted;
+/// `None` for a bare address.
+pub fn name_of(entry: &str) -> Option<String> {
+    let start = entry.rfind('<')?;
+    let name = entry[..start].trim().trim_matches('"').trim();
+    (!name.is_empty()).then(|| name.replace("\\\"", "\""))
+}
+
+/// Format `name` and `email` as an address-list entry, quoting names that
+/// contain specials such as commas.
+pub fn display(name: Option<&str>, email: &str) -> String {
+    let Some(name) = name.map(str::trim).filter(|name| !name.is_empty()) else {
+        return email.to_string();
+    };
+    if name.contains([',', ';', ':', '<', '>', '@', '"', '(', ')', '[', ']', '\\']) {
+        let escaped = name.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{escaped}\" <{email}>")
+    } else {
+        format!("{name} <{email}>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_top_level_commas_only() {
+        assert_eq!(
+            split_addresses(r#""Doe, Jane" <jane@example.com>, bob@example.com,  "#),
+            vec![
+                r#""Doe, Jane" <jane@example.com>"#.to_string(),
+                "bob@example.com".to_string()
+            ]
+        );
+        assert_eq!(email_of("Ada <Ada@Example.com>"), "ada@example.com");
+        assert_eq!(email_of(" bob@example.com "), "bob@example.com");
+        assert_eq!(
+            name_of(r#""Doe, Jane" <jane@example.com>"#).as_deref(),
+            Some("Doe, Jane")
+        );
+        assert_eq!(name_of("bob@example.com"), None);
+        assert_eq!(name_of("<bob@example.com>"), None);
+        assert_eq!(
+            display(Some("Doe, Jane"), "jane@example.com"),
+            r#""Doe, Jane" <jane@example.com>"#
+        );
+        assert_eq!(
+            display(Some("Ada"), "ada@example.com"),
+            "Ada <ada@example.com>"
+        );
+        assert_eq!(display(None, "bob@example.com"), "bob@example.com");
+    }
+}
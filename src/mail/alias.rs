@@ -0,0 +1,269 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult};
+use crate::mail::address;
+
+/// Nesting allowed when aliases refer to other aliases, as a guard against cycles.
+const MAX_DEPTH: usize = 8;
+
+/// One `alias <name> <address>[, <address>...]` definition from a mutt alias file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Alias {
+    pub name: String,
+    /// Address entries as written, e.g. `Ada Lovelace <ada@example.com>`, or
+    /// names of other aliases.
+    pub addresses: Vec<String>,
+}
+
+impl Alias {
+    /// The definition as one alias-file line.
+    pub fn line(&self) -> String {
+        format!("alias {} {}", self.name, self.addresses.join(", "))
+    }
+}
+
+/// Parse mutt alias syntax. Lines ending in `\` continue on the next line,
+/// `#` starts a comment line, `-group` options are skipped, and lines that
+/// aren't `alias` commands are ignored so a full muttrc can be shared. As in
+/// mutt, names match case-insensitively and a later definition replaces an
+/// earlier one.
+pub fn parse(text: &str) -> Vec<Alias> {
+    let mut aliases = Vec::<Alias>::new();
+    for entry in logical_lines(text) {
+        let Some(alias) = parse_line(&entry.text) else {
+            continue;
+        };
+        match aliases
+            .iter_mut()
+            .find(|existing| existing.name.eq_ignore_ascii_case(&alias.name))
+        {
+            Some(existing) => *existing = alias,
+            None => aliases.push(alias),
+        }
+    }
+    aliases
+}
+
+/// Replace every recipient that names an alias with the alias's addresses,
+/// following aliases of aliases. An entry without `@` that names no alias is
+/// an error rather than something to hand to Gmail.
+pub fn expand(aliases: &[Alias], recipients: Vec<String>) -> AppResult<Vec<String>> {
+    let mut expanded = Vec::new();
+    for recipient in recipients {
+        expand_into(aliases, &recipient, 0, &mut expanded)?;
+    }
+    Ok(expanded)
+}
+
+fn expand_into(
+    aliases: &[Alias],
+    recipient: &str,
+    depth: usize,
+    expanded: &mut Vec<String>,
+) -> AppResult<()> {
+    let recipient = recipient.trim();
+    if recipient.is_empty() {
+        return Ok(());
+    }
+    if recipient.contains('@') {
+        expanded.push(recipient.to_string());
+        return Ok(());
+    }
+
+    let alias = aliases
+        .iter()
+        .find(|alias| alias.name.eq_ignore_ascii_case(recipient))
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!(
+                "`{recipient}` is not an address or a known alias (see `gmail alias ls`)"
+            ))
+        })?;
+    if depth == MAX_DEPTH {
+        return Err(AppError::InvalidInput(format!(
+            "alias `{}` nests more than {MAX_DEPTH} levels deep; is it recursive?",
+            alias.name
+        )));
+    }
+    for address in &alias.addresses {
+        expand_into(aliases, address, depth + 1, expanded)?;
+    }
+    Ok(())
+}
+
+/// `text` with `alias` appended, and any earlier definition of its name removed.
+pub fn add(text: &str, alias: &Alias) -> String {
+    let (mut text, _) = remove(text, &alias.name);
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(&alias.line());
+    text.push('\n');
+    text
+}
+
+/// `text` without the definitions of `name`, keeping every other line
+/// (comments included) as written, and whether any were removed.
+pub fn remove(text: &str, name: &str) -> (String, bool) {
+    let mut kept = String::new();
+    let mut removed = false;
+    for entry in logical_lines(text) {
+        let matches =
+            parse_line(&entry.text).is_some_and(|alias| alias.name.eq_ignore_ascii_case(name));
+        if matches {
+            removed = true;
+        } else {
+            kept.push_str(entry.raw);
+        }
+    }
+    (kept, removed)
+}
+
+/// A command joined across `\` continuations, and the raw lines it came from.
+struct LogicalLine<'a> {
+    text: String,
+    raw: &'a str,
+}
+
+fn logical_lines(text: &str) -> Vec<LogicalLine<'_>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    let mut joined = String::new();
+    for line in text.split_inclusive('\n') {
+        end += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        if let Some(continued) = content.strip_suffix('\\') {
+            joined.push_str(continued);
+            continue;
+        }
+        joined.push_str(content);
+        lines.push(LogicalLine {
+            text: std::mem::take(&mut joined),
+            raw: &text[start..end],
+        });
+        start = end;
+    }
+    if start < text.len() {
+        lines.push(LogicalLine {
+            text: joined,
+            raw: &text[start..],
+        });
+    }
+    lines
+}
+
+fn parse_line(line: &str) -> Option<Alias> {
+    let mut rest = line.trim_start().strip_prefix("alias")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let name = loop {
+        let (word, tail) = next_word(rest)?;
+        rest = tail;
+        if word == "-group" {
+            rest = next_word(rest)?.1;
+            continue;
+        }
+        break word;
+    };
+    let addresses = address::split_addresses(rest);
+    (!addresses.is_empty()).then(|| Alias {
+        name: name.to_string(),
+        addresses,
+    })
+}
+
+fn next_word(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start();
+    if text.is_empty() {
+        return None;
+    }
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    Some((&text[..end], &text[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "# team lists\n\
+        alias ada Ada Lovelace <ada@example.com>\n\
+        alias -group work team ada, \\\n  bob@example.com\n\
+        set sort = threads\n\
+        alias everyone team, \"Doe, Jane\" <jane@example.com>\n";
+
+    #[test]
+    fn parses_mutt_alias_lines() {
+        let aliases = parse(FILE);
+        assert_eq!(
+            aliases,
+            [
+                Alias {
+                    name: "ada".to_string(),
+                    addresses: vec!["Ada Lovelace <ada@example.com>".to_string()],
+                },
+                Alias {
+                    name: "team".to_string(),
+                    addresses: vec!["ada".to_string(), "bob@example.com".to_string()],
+                },
+                Alias {
+                    name: "everyone".to_string(),
+                    addresses: vec![
+                        "team".to_string(),
+                        "\"Doe, Jane\" <jane@example.com>".to_string()
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_nested_aliases_and_rejects_unknown_names() {
+        let aliases = parse(FILE);
+        assert_eq!(
+            expand(
+                &aliases,
+                vec!["Everyone".to_string(), "x@example.com".to_string()]
+            )
+            .unwrap(),
+            [
+                "Ada Lovelace <ada@example.com>",
+                "bob@example.com",
+                "\"Doe, Jane\" <jane@example.com>",
+                "x@example.com",
+            ]
+        );
+        assert!(expand(&aliases, vec!["nobody".to_string()]).is_err());
+
+        let looped = parse("alias a b\nalias b a\n");
+        assert!(expand(&looped, vec!["a".to_string()]).is_err());
+    }
+
+    #[test]
+    fn adds_and_removes_definitions_in_place() {
+        let (text, removed) = remove(FILE, "TEAM");
+        assert!(removed);
+        assert_eq!(
+            text,
+            "# team lists\n\
+             alias ada Ada Lovelace <ada@example.com>\n\
+             set sort = threads\n\
+             alias everyone team, \"Doe, Jane\" <jane@example.com>\n"
+        );
+        assert!(!remove(&text, "team").1);
+
+        let alias = Alias {
+            name: "ada".to_string(),
+            addresses: vec!["ada@example.org".to_string()],
+        };
+        let text = add(
+            "alias ada old@example.com\nalias bob bob@example.com",
+            &alias,
+        );
+        assert_eq!(
+            text,
+            "alias bob bob@example.com\nalias ada ada@example.org\n"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult};
+use crate::mail::stats;
+
+/// A `cleanup.toml` file: rules run in order, each pairing a Gmail search
+/// with an action.
+///
+/// ```toml
+/// [[rule]]
+/// name = "old promotions"
+/// query = "category:promotions"
+/// older_than = "90d"
+/// action = "trash"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CleanupRules {
+    #[serde(rename = "rule", default)]
+    pub rules: Vec<CleanupRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CleanupRule {
+    /// Shown in the per-rule counts; defaults to the search.
+    pub name: Option<String>,
+    pub query: String,
+    /// Only messages older than this (`90d`, `6w`, `3m`, `1y`).
+    pub older_than: Option<String>,
+    pub action: CleanupAction,
+    /// The label `action = "label"` adds.
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum CleanupAction {
+    Trash,
+    Archive,
+    MarkRead,
+    Label,
+}
+
+impl CleanupRules {
+    /// Read and check a rules file.
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let raw = fs::read_to_string(path)?;
+        let rules: Self = toml::from_str(&raw)
+            .map_err(|err| AppError::Config(format!("invalid {}: {err}", path.display())))?;
+        if rules.rules.is_empty() {
+            return Err(AppError::Config(format!(
+                "{} has no [[rule]] entries",
+                path.display()
+            )));
+        }
+        for rule in &rules.rules {
+            rule.search()?;
+            rule.changes()?;
+        }
+        Ok(rules)
+    }
+}
+
+impl CleanupRule {
+    /// The name shown for this rule.
+    pub fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => self.search().unwrap_or_else(|_| self.query.clone()),
+        }
+    }
+
+    /// The full Gmail search: `query` plus the `older_than:` term.
+    pub fn search(&self) -> AppResult<String> {
+        let query = self.query.trim();
+        if query.is_empty() {
+            return Err(AppError::Config(
+                "a cleanup rule has an empty query; use `in:anywhere` to mean everything"
+                    .to_string(),
+            ));
+        }
+        let Some(age) = self.older_than.as_deref() else {
+            return Ok(query.to_string());
+        };
+        let term = stats::relative_term("older_than", age).ok_or_else(|| {
+            AppError::Config(format!(
+                "invalid older_than `{age}` in cleanup rule `{query}`; use a count and unit, e.g. 30d, 6w, 3m, 1y"
+            ))
+        })?;
+        Ok(format!("{query} {term}"))
+    }
+
+    /// Labels to add and remove, as names or system label ids.
+    pub fn changes(&self) -> AppResult<(Vec<String>, Vec<String>)> {
+        let labels = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        Ok(match self.action {
+            CleanupAction::Trash => (labels(&["TRASH"]), labels(&["INBOX"])),
+            CleanupAction::Archive => (Vec::new(), labels(&["INBOX"])),
+            CleanupAction::MarkRead => (Vec::new(), labels(&["UNREAD"])),
+            CleanupAction::Label => match self.label.as_deref().map(str::trim) {
+                Some(label) if !label.is_empty() => (vec![label.to_string()], Vec::new()),
+                _ => {
+                    return Err(AppError::Config(format!(
+                        "cleanup rule `{}` uses action = \"label\" without a label",
+                        self.query
+                    )));
+                }
+            },
+        })
+    }
+}
+
+impl CleanupAction {
+    /// The verb shown in counts: `trash`, `archive`, `mark read`, `label`.
+    pub fn verb(self) -> &'static str {
+        match self {
+            Self::Trash => "trash",
+            Self::Archive => "archive",
+            Self::MarkRead => "mark read",
+            Self::Label => "label",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> CleanupRules {
+        toml::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn reads_rules_with_age_thresholds() {
+        let rules = parse(
+            r#"
+[[rule]]
+name = "old promotions"
+query = "category:promotions"
+older_than = "12w"
+action = "trash"
+
+[[rule]]
+query = "from:ci@example.com"
+action = "label"
+label = "CI"
+"#,
+        );
+
+        let [promotions, ci] = rules.rules.as_slice() else {
+            panic!("expected two rules");
+        };
+        assert_eq!(promotions.name(), "old promotions");
+        assert_eq!(
+            promotions.search().unwrap(),
+            "category:promotions older_than:84d"
+        );
+        assert_eq!(
+            promotions.changes().unwrap(),
+            (vec!["TRASH".to_string()], vec!["INBOX".to_string()])
+        );
+        assert_eq!(ci.name(), "from:ci@example.com");
+        assert_eq!(ci.changes().unwrap().0, ["CI"]);
+    }
+
+    #[test]
+    fn rejects_incomplete_rules() {
+        let rules = parse(
+            r#"
+[[rule]]
+query = "label:newsletters"
+older_than = "soon"
+action = "archive"
+
+[[rule]]
+query = "is:unread"
+action = "label"
+"#,
+        );
+        assert!(rules.rules[0].search().is_err());
+        assert!(rules.rules[1].changes().is_err());
+        assert!(
+            toml::from_str::<CleanupRules>("[[rule]]\nquery = \"x\"\naction = \"shred\"").is_err()
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use chrono::{DateTime, Utc};
+
+use crate::error::{AppError, AppResult};
+use crate::mail::address;
+
+/// The attendee's answer in an iTIP REPLY (RFC 5546), as its `PARTSTAT` value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartStat {
+    Accepted,
+    Declined,
+    Tentative,
+}
+
+impl PartStat {
+    pub fn as_ical(self) -> &'static str {
+        match self {
+            Self::Accepted => "ACCEPTED",
+            Self::Declined => "DECLINED",
+            Self::Tentative => "TENTATIVE",
+        }
+    }
+
+    /// Subject prefix used by calendar clients, e.g. `Accepted: Standup`.
+    pub fn verb(self) -> &'static str {
+        match self {
+            Self::Accepted => "Accepted",
+            Self::Declined => "Declined",
+            Self::Tentative => "Tentative",
+        }
+    }
+}
+
+/// One content line split into name, parameters, and value, e.g.
+/// `ATTENDEE;CN=Ada:mailto:ada@example.com`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    /// Everything between the name and the value, including the leading `;`.
+    pub params: String,
+    pub value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        // The value starts at the first colon outside a quoted parameter.
+        let mut quoted = false;
+        let colon = line.char_indices().find_map(|(index, ch)| match ch {
+            '"' => {
+                quoted = !quoted;
+                None
+            }
+            ':' if !quoted => Some(index),
+            _ => None,
+        })?;
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let (name, params) = match head.find(';') {
+            Some(semi) => (&head[..semi], &head[semi..]),
+            None => (head, ""),
+        };
+        Some(Self {
+            name: name.to_ascii_uppercase(),
+            params: params.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    fn line(&self) -> String {
+        format!("{}{}:{}", self.name, self.params, self.value)
+    }
+
+    /// The address of a `mailto:` value, lowercased.
+    pub fn email(&self) -> Option<String> {
+        let value = self.value.trim();
+        let email = value
+            .get(..7)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+            .map(|_| &value[7..])?;
+        Some(email.trim().to_ascii_lowercase())
+    }
+
+    /// A parameter's value (unquoted), e.g. `CN`.
+    pub fn param(&self, name: &str) -> Option<String> {
+        self.params
+            .split(';')
+            .filter_map(|param| param.split_once('='))
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim_matches('"').to_string())
+    }
+}
+
+/// The parts of an invitation's first `VEVENT` a REPLY has to echo back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invite {
+    pub method: Option<String>,
+    pub uid: String,
+    pub summary: Option<String>,
+    pub organizer: Property,
+    pub attendees: Vec<Property>,
+    /// UID-adjacent properties copied verbatim: SEQUENCE, RECURRENCE-ID,
+    /// DTSTART, DTEND, DURATION.
+    pub echoed: Vec<Property>,
+}
+
+impl Invite {
+    pub fn organizer_email(&self) -> Option<String> {
+        self.organizer.email()
+    }
+
+    /// The ATTENDEE entry for `email`, if the invite lists it.
+    pub fn attendee(&self, email: &str) -> Option<&Property> {
+        let email = email.to_ascii_lowercase();
+        self.attendees
+            .iter()
+            .find(|attendee| attendee.email().as_deref() == Some(email.as_str()))
+    }
+}
+
+/// Parse an iCalendar invitation (usually `METHOD:REQUEST`).
+pub fn parse_invite(text: &str) -> AppResult<Invite> {
+    let mut method = None;
+    let mut in_event = false;
+    let mut depth = 0_usize;
+    let mut uid = None;
+    let mut summary = None;
+    let mut organizer = None;
+    let mut attendees = Vec::new();
+    let mut echoed = Vec::new();
+
+    for line in unfold(text) {
+        let Some(property) = Property::parse(&line) else {
+            continue;
+        };
+        match (property.name.as_str(), in_event) {
+            ("BEGIN", false) if property.value.eq_ignore_ascii_case("VEVENT") => in_event = true,
+            ("BEGIN", true) => depth += 1,
+            ("END", true) if depth > 0 => depth -= 1,
+            ("END", true) => break,
+            ("METHOD", false) => method = Some(property.value.trim().to_ascii_uppercase()),
+            (_, true) if depth > 0 => {}
+            ("UID", true) => uid = Some(property.value.clone()),
+            ("SUMMARY", true) => summary = Some(unescape(&property.value)),
+            ("ORGANIZER", true) => organizer = Some(property),
+            ("ATTENDEE", true) => attendees.push(property),
+            ("SEQUENCE" | "RECURRENCE-ID" | "DTSTART" | "DTEND" | "DURATION", true) => {
+                echoed.push(property)
+            }
+            _ => {}
+        }
+    }
+
+    let invalid = |what: &str| AppError::InvalidInput(format!("calendar invite has no {what}"));
+    Ok(Invite {
+        method,
+        uid: uid.ok_or_else(|| invalid("UID"))?,
+        summary,
+        organizer: organizer.ok_or_else(|| invalid("ORGANIZER"))?,
+        attendees,
+        echoed,
+    })
+}
+
+/// Build the `METHOD:REPLY` calendar answering `invite` for one attendee.
+/// `attendee` is the invite's own ATTENDEE line for us when present, so its
+/// parameters (CN, ROLE, ...) are kept with only PARTSTAT replaced.
+pub fn build_reply(
+    invite: &Invite,
+    email: &str,
+    attendee: Option<&Property>,
+    partstat: PartStat,
+    comment: Option<&str>,
+    now: DateTime<Utc>,
+) -> String {
+    let attendee = Property {
+        name: "ATTENDEE".to_string(),
+        params: attendee
+            .map(|attendee| {
+                attendee
+                    .params
+                    .split(';')
+                    .filter(|param| !param.is_empty())
+                    .filter(|param| {
+                        let key = param.split('=').next().unwrap_or_default();
+                        !key.eq_ignore_ascii_case("PARTSTAT") && !key.eq_ignore_ascii_case("RSVP")
+                    })
+                    .map(|param| format!(";{param}"))
+                    .collect::<String>()
+            })
+            .unwrap_or_default()
+            + &format!(";PARTSTAT={}", partstat.as_ical()),
+        value: format!("mailto:{email}"),
+    };
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        concat!("PRODID:-//gmail-cli//", env!("CARGO_PKG_VERSION"), "//EN").to_string(),
+        "VERSION:2.0".to_string(),
+        "METHOD:REPLY".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", invite.uid),
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+    ];
+    lines.extend(invite.echoed.iter().map(Property::line));
+    lines.push(invite.organizer.line());
+    lines.push(attendee.line());
+    if let Some(summary) = &invite.summary {
+        lines.push(format!("SUMMARY:{}", escape(summary)));
+    }
+    if let Some(comment) = comment.filter(|comment| !comment.trim().is_empty()) {
+        lines.push(format!("COMMENT:{}", escape(comment)));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// A new meeting to send as an iTIP REQUEST.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewEvent {
+    pub uid: String,
+    pub summary: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub location: Option<String>,
+    /// The sender, as an address-list entry (`Ada <ada@example.com>`).
+    pub organizer: String,
+    /// Address-list entries for everyone invited.
+    pub attendees: Vec<String>,
+}
+
+/// Build a minimal `METHOD:REQUEST` calendar for `event`, with every attendee
+/// asked to RSVP. Times are written in UTC so no VTIMEZONE is needed.
+pub fn build_request(event: &NewEvent, now: DateTime<Utc>) -> String {
+    const STAMP: &str = "%Y%m%dT%H%M%SZ";
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        concat!("PRODID:-//gmail-cli//", env!("CARGO_PKG_VERSION"), "//EN").to_string(),
+        "VERSION:2.0".to_string(),
+        "METHOD:REQUEST".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", event.uid),
+        format!("DTSTAMP:{}", now.format(STAMP)),
+        format!("DTSTART:{}", event.start.format(STAMP)),
+        format!("DTEND:{}", event.end.format(STAMP)),
+        "SEQUENCE:0".to_string(),
+        format!("SUMMARY:{}", escape(&event.summary)),
+    ];
+    if let Some(location) = event
+        .location
+        .as_deref()
+        .filter(|location| !location.trim().is_empty())
+    {
+        lines.push(format!("LOCATION:{}", escape(location)));
+    }
+    lines.push(person("ORGANIZER", &event.organizer, ""));
+    lines.extend(event.attendees.iter().map(|attendee| {
+        person(
+            "ATTENDEE",
+            attendee,
+            ";ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION;RSVP=TRUE",
+        )
+    }));
+    lines.push("STATUS:CONFIRMED".to_string());
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// An ORGANIZER/ATTENDEE line for an address-list `entry`, with its display
+/// name as `CN` (quoted, since names may hold `,` or `;`).
+fn person(name: &str, entry: &str, params: &str) -> String {
+    let cn = address::name_of(entry)
+        .map(|cn| format!(";CN=\"{}\"", cn.replace('"', "'")))
+        .unwrap_or_default();
+    format!("{name}{cn}{params}:mailto:{}", address::email_of(entry))
+}
+
+/// Join folded content lines (CRLF followed by a space or tab).
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+    for raw in text.lines() {
+        match raw.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// Fold a content line at 75 octets without splitting a UTF-8 character.
+/// vCard (RFC 6350) uses the same line folding.
+pub(crate) fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVITE: &str = "BEGIN:VCALENDAR\r\n\
+        METHOD:REQUEST\r\n\
+        BEGIN:VTIMEZONE\r\n\
+        TZID:Europe/Berlin\r\n\
+        END:VTIMEZONE\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART;TZID=Europe/Berlin:20240305T140000\r\n\
+        DTEND;TZID=Europe/Berlin:20240305T143000\r\n\
+        UID:abc123@google.com\r\n\
+        SEQUENCE:2\r\n\
+        ORGANIZER;CN=Boss:mailto:boss@example.com\r\n\
+        ATTENDEE;CUTYPE=INDIVIDUAL;ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION;RSVP=\r\n \
+        TRUE;CN=\"Me, Myself\":mailto:Me@example.com\r\n\
+        SUMMARY:Planning\\, Q2\r\n\
+        BEGIN:VALARM\r\n\
+        TRIGGER:-P0DT0H10M0S\r\n\
+        END:VALARM\r\n\
+        END:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+
+    #[test]
+    fn parses_the_event_and_unfolds_attendees() {
+        let invite = parse_invite(INVITE).unwrap();
+        assert_eq!(invite.method.as_deref(), Some("REQUEST"));
+        assert_eq!(invite.uid, "abc123@google.com");
+        assert_eq!(invite.summary.as_deref(), Some("Planning, Q2"));
+        assert_eq!(
+            invite.organizer_email().as_deref(),
+            Some("boss@example.com")
+        );
+        let me = invite.attendee("me@example.com").expect("attendee");
+        assert_eq!(me.param("CN").as_deref(), Some("Me, Myself"));
+        assert_eq!(invite.echoed.len(), 3);
+        assert!(parse_invite("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n").is_err());
+    }
+
+    #[test]
+    fn builds_a_reply_with_only_partstat_changed() {
+        let invite = parse_invite(INVITE).unwrap();
+        let now = DateTime::parse_from_rfc3339("2024-03-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let reply = build_reply(
+            &invite,
+            "me@example.com",
+            invite.attendee("me@example.com"),
+            PartStat::Declined,
+            Some("Out that week"),
+            now,
+        );
+        let unfolded = unfold(&reply).join("\n");
+
+        assert!(unfolded.contains("METHOD:REPLY"));
+        assert!(unfolded.contains("UID:abc123@google.com"));
+        assert!(unfolded.contains("SEQUENCE:2"));
+        assert!(unfolded.contains("DTSTAMP:20240301T090000Z"));
+        assert!(unfolded.contains(
+            "ATTENDEE;CUTYPE=INDIVIDUAL;ROLE=REQ-PARTICIPANT;CN=\"Me, Myself\";PARTSTAT=DECLINED:mailto:me@example.com"
+        ));
+        assert!(unfolded.contains("COMMENT:Out that week"));
+        assert!(!unfolded.contains("VALARM"));
+        assert!(reply.lines().all(|line| line.len() <= 76));
+    }
+
+    #[test]
+    fn builds_a_request_that_parses_back() {
+        let at = |text: &str| {
+            DateTime::parse_from_rfc3339(text)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let event = NewEvent {
+            uid: "u1@gmail-cli".to_string(),
+            summary: "Standup, daily".to_string(),
+            start: at("2025-03-10T08:30:00Z"),
+            end: at("2025-03-10T09:00:00Z"),
+            location: None,
+            organizer: "Me <me@example.com>".to_string(),
+            attendees: vec![
+                "\"Doe, Jane\" <jane@example.com>".to_string(),
+                "bob@example.com".to_string(),
+            ],
+        };
+        let text = build_request(&event, at("2025-03-05T12:00:00Z"));
+        assert!(text.contains("DTSTART:20250310T083000Z\r\nDTEND:20250310T090000Z\r\n"));
+        assert!(text.contains("SUMMARY:Standup\\, daily\r\n"));
+
+        let invite = parse_invite(&text).unwrap();
+        assert_eq!(invite.method.as_deref(), Some("REQUEST"));
+        assert_eq!(invite.uid, "u1@gmail-cli");
+        assert_eq!(invite.summary.as_deref(), Some("Standup, daily"));
+        assert_eq!(invite.organizer_email().as_deref(), Some("me@example.com"));
+        let jane = invite.attendee("jane@example.com").unwrap();
+        assert_eq!(jane.param("CN").as_deref(), Some("Doe, Jane"));
+        assert_eq!(jane.param("RSVP").as_deref(), Some("TRUE"));
+        assert!(invite.attendee("bob@example.com").is_some());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::mail::{address, rfc822};
+use crate::output::dates::parse_header;
+
+/// Whether `data` looks like an mbox file rather than a single message: it
+/// opens with a `From ` separator line, which no header line can.
+pub fn is_mbox(data: &[u8]) -> bool {
+    data.starts_with(b"From ")
+}
+
+/// Split an mbox file into its messages. A message starts at each `From `
+/// line that opens the file or follows a blank line; the separator itself is
+/// dropped, the blank line before it is not part of the previous message,
+/// and mboxrd quoting (`>From `, `>>From `, ...) loses one `>`.
+pub fn split(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+    let mut after_blank = true;
+    for line in data.split_inclusive(|byte| *byte == b'\n') {
+        if after_blank && line.starts_with(b"From ") {
+            if let Some(message) = current.take() {
+                messages.push(finish(message));
+            }
+            current = Some(Vec::new());
+            after_blank = false;
+            continue;
+        }
+        after_blank = matches!(line, b"\n" | b"\r\n");
+        let Some(message) = current.as_mut() else {
+            continue;
+        };
+        let quotes = line.iter().take_while(|byte| **byte == b'>').count();
+        if quotes > 0 && line[quotes..].starts_with(b"From ") {
+            message.extend_from_slice(&line[1..]);
+        } else {
+            message.extend_from_slice(line);
+        }
+    }
+    if let Some(message) = current {
+        messages.push(finish(message));
+    }
+    messages.retain(|message| !message.is_empty());
+    messages
+}
+
+/// One mbox entry for `message`: a `From <sender> <date>` separator taken
+/// from its From and Date headers, the message with mboxrd quoting of body
+/// lines that read like separators, and a closing blank line.
+pub fn entry(message: &[u8]) -> Vec<u8> {
+    let (headers, _) = rfc822::headers(message);
+    let sender = rfc822::values(&headers, "From")
+        .first()
+        .map(|from| address::email_of(from))
+        .filter(|email| !email.is_empty() && !email.contains(char::is_whitespace))
+        .unwrap_or_else(|| "MAILER-DAEMON".to_string());
+    let date = rfc822::values(&headers, "Date")
+        .first()
+        .and_then(|date| parse_header(date))
+        .map(|date| date.format("%a %b %e %H:%M:%S %Y").to_string())
+        .unwrap_or_else(|| "Thu Jan  1 00:00:00 1970".to_string());
+
+    let mut entry = format!("From {sender} {date}\n").into_bytes();
+    for line in message.split_inclusive(|byte| *byte == b'\n') {
+        let quotes = line.iter().take_while(|byte| **byte == b'>').count();
+        if line[quotes..].starts_with(b"From ") {
+            entry.push(b'>');
+        }
+        entry.extend_from_slice(line);
+    }
+    if !entry.ends_with(b"\n") {
+        entry.push(b'\n');
+    }
+    entry.push(b'\n');
+    entry
+}
+
+/// Drop the blank line that separates a message from the next `From ` line.
+fn finish(mut message: Vec<u8>) -> Vec<u8> {
+    if message.ends_with(b"\r\n\r\n") {
+        message.truncate(message.len() - 2);
+    } else if message.ends_with(b"\n\n") {
+        message.truncate(message.len() - 1);
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_messages_and_unquotes_from_lines() {
+        let mbox = b"From ada@example.com Mon Jun  3 09:00:00 2024\n\
+Subject: One\n\
+\n\
+>From the top.\n\
+From here on, still body.\n\
+\n\
+From bob@example.com Tue Jun  4 10:00:00 2024\n\
+Subject: Two\n\
+\n\
+Bye\n";
+        assert!(is_mbox(mbox));
+        let messages = split(mbox);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0],
+            b"Subject: One\n\nFrom the top.\nFrom here on, still body.\n"
+        );
+        assert_eq!(messages[1], b"Subject: Two\n\nBye\n");
+    }
+
+    #[test]
+    fn entries_split_back_into_the_same_messages() {
+        let first = b"From: Ada <ada@example.com>\nDate: Mon, 3 Jun 2024 09:00:00 +0000\n\nFrom the top.\n>From me.\n";
+        let second = b"Subject: No sender\n\nBye\n";
+        let mut mbox = entry(first);
+        assert!(mbox.starts_with(b"From ada@example.com Mon Jun  3 09:00:00 2024\n"));
+        mbox.extend(entry(second));
+        assert_eq!(split(&mbox), [first.to_vec(), second.to_vec()]);
+    }
+
+    #[test]
+    fn a_single_message_is_not_an_mbox() {
+        assert!(!is_mbox(b"Subject: Hi\r\n\r\nBody\r\n"));
+    }
+}
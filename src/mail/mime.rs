@@ -1,8 +1,12 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use pulldown_cmark::{Options, Parser, html};
-use rand::Rng;
+use sha2::{Digest, Sha256};
 
 use crate::api::models::SendRequest;
 
@@ -81,123 +85,246 @@ __BODY__
 
 /// Render markdown (tables, strikethrough, tasklists, footnotes) into the styled email HTML template.
 pub fn markdown_to_html(body_markdown: &str) -> String {
+    let mut body_html = markdown_fragment(body_markdown);
+    if body_html.trim().is_empty() {
+        body_html.push_str("<p></p>");
+    }
+
+    EMAIL_HTML_TEMPLATE.replacen("__BODY__", &body_html, 1)
+}
+
+/// Render markdown to bare HTML, without the email template (signatures).
+pub fn markdown_fragment(markdown: &str) -> String {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TASKLISTS);
     options.insert(Options::ENABLE_FOOTNOTES);
 
-    let parser = Parser::new_ext(body_markdown, options);
-    let mut body_html = String::new();
-    html::push_html(&mut body_html, parser);
+    let parser = Parser::new_ext(markdown, options);
+    let mut html_out = String::new();
+    html::push_html(&mut html_out, parser);
+    html_out
+}
 
-    if body_html.trim().is_empty() {
-        body_html.push_str("<p></p>");
+/// A browser preview of an outgoing message: a header summary above the
+/// body, with each `<img src>` naming an attachment (`cid:<filename>`) or a
+/// local file (relative to `base_dir`) inlined as a `data:` URI so the page
+/// renders the images. Other sources are left as they are.
+pub fn preview_html(request: &SendRequest, base_dir: &Path) -> String {
+    let mut summary = String::from("<table class=\"preview-headers\">\n");
+    let mut row = |name: &str, value: &str| {
+        if !value.is_empty() {
+            let _ = writeln!(
+                summary,
+                "<tr><th>{name}</th><td>{}</td></tr>",
+                html_escape::encode_text(value)
+            );
+        }
+    };
+    row("From", request.from.as_deref().unwrap_or_default());
+    row("To", &request.to.join(", "));
+    row("Cc", &request.cc.join(", "));
+    row("Bcc", &request.bcc.join(", "));
+    row("Subject", &request.subject);
+    let names = request
+        .attachments
+        .iter()
+        .map(|attachment| attachment.filename.as_str())
+        .collect::<Vec<_>>();
+    row("Attachments", &names.join(", "));
+    summary.push_str("</table>\n<hr>\n");
+
+    let body = inline_images(&request.body, |src| {
+        let (mime_type, data) = match src.strip_prefix("cid:") {
+            Some(name) => request
+                .attachments
+                .iter()
+                .find(|attachment| attachment.filename == name)
+                .map(|attachment| (attachment.mime_type.clone(), attachment.data.clone()))?,
+            None if src.contains("://") || src.starts_with("data:") => return None,
+            None => {
+                let path = base_dir.join(percent_decode(src));
+                let data = fs::read(&path).ok()?;
+                let mime_type = mime_guess::from_path(&path)
+                    .first_or_octet_stream()
+                    .to_string();
+                (mime_type, data)
+            }
+        };
+        Some(format!("data:{mime_type};base64,{}", STANDARD.encode(data)))
+    });
+    body.replacen(
+        "<div class=\"email-body\">",
+        &format!("{summary}<div class=\"email-body\">"),
+        1,
+    )
+}
+
+/// Rewrite every `<img src="...">` for which `resolve` returns a replacement.
+fn inline_images(html: &str, resolve: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("<img ") {
+        let (before, tag) = rest.split_at(start);
+        out.push_str(before);
+        let end = tag.find('>').map_or(tag.len(), |end| end + 1);
+        let (tag, after) = tag.split_at(end);
+        rest = after;
+
+        let replaced = tag.find("src=\"").and_then(|offset| {
+            let value_start = offset + "src=\"".len();
+            let value_len = tag[value_start..].find('"')?;
+            let src = html_escape::decode_html_entities(&tag[value_start..value_start + value_len]);
+            let inlined = resolve(&src)?;
+            Some(format!(
+                "{}{inlined}{}",
+                &tag[..value_start],
+                &tag[value_start + value_len..]
+            ))
+        });
+        out.push_str(replaced.as_deref().unwrap_or(tag));
     }
+    out.push_str(rest);
+    out
+}
 
-    EMAIL_HTML_TEMPLATE.replacen("__BODY__", &body_html, 1)
+/// Undo the `%XX` escapes Markdown rendering puts in image paths.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| value.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                index += 3;
+            }
+            None => {
+                out.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
 }
 
+/// Base64 lines are 76 characters, i.e. 57 input bytes each.
+const BASE64_LINE_BYTES: usize = 57;
+
 /// Build a base64url-encoded RFC 822 message, using multipart/mixed when attachments are present.
+///
+/// The message is written into one buffer sized up front, and attachments are
+/// encoded line by line straight into it, so large attachments are copied
+/// once rather than through several intermediate strings.
 pub fn build_raw_message(request: &SendRequest) -> String {
-    let mut headers = build_base_headers(request);
+    let mut message = String::with_capacity(estimated_len(request));
+    write_base_headers(&mut message, request);
 
-    let payload = if request.attachments.is_empty() {
-        headers.push("Content-Type: text/html; charset=utf-8".to_string());
-        format!("{}\r\n\r\n{}", headers.join("\r\n"), request.body)
+    if request.attachments.is_empty() {
+        message.push_str("Content-Type: text/html; charset=utf-8\r\n\r\n");
+        message.push_str(&request.body);
     } else {
-        let boundary = random_boundary();
-        headers.push(format!(
-            "Content-Type: multipart/mixed; boundary=\"{boundary}\""
-        ));
-        format!(
-            "{}\r\n\r\n{}",
-            headers.join("\r\n"),
-            multipart_body(request, &boundary)
-        )
-    };
+        let boundary = content_boundary(request);
+        let _ = write!(
+            message,
+            "Content-Type: multipart/mixed; boundary=\"{boundary}\"\r\n\r\n"
+        );
+        write_multipart_body(&mut message, request, &boundary);
+    }
 
-    URL_SAFE_NO_PAD.encode(payload.as_bytes())
+    let mut raw = String::with_capacity(base64::encoded_len(message.len(), false).unwrap_or(0));
+    URL_SAFE_NO_PAD.encode_string(message.as_bytes(), &mut raw);
+    raw
 }
 
-/// Assemble the common message headers (From, To, Cc, Bcc, Subject, threading) from the request.
-fn build_base_headers(request: &SendRequest) -> Vec<String> {
-    let mut headers = Vec::new();
+/// Upper bound on the unencoded message size: headers, body, and each
+/// attachment's folded base64 plus its part headers.
+fn estimated_len(request: &SendRequest) -> usize {
+    let attachments = request
+        .attachments
+        .iter()
+        .map(|attachment| {
+            let lines = attachment.data.len().div_ceil(BASE64_LINE_BYTES);
+            lines * 78 + attachment.filename.len() * 2 + attachment.mime_type.len() + 160
+        })
+        .sum::<usize>();
+    1024 + request.subject.len() * 2 + request.body.len() + attachments
+}
 
+/// Write the common message headers (From, To, Cc, Bcc, Subject, threading), each CRLF-terminated.
+fn write_base_headers(out: &mut String, request: &SendRequest) {
     if let Some(from) = &request.from {
-        headers.push(format!("From: {from}"));
+        let _ = write!(out, "From: {from}\r\n");
     }
 
-    headers.push(format!("To: {}", request.to.join(", ")));
+    let _ = write!(out, "To: {}\r\n", request.to.join(", "));
 
     if !request.cc.is_empty() {
-        headers.push(format!("Cc: {}", request.cc.join(", ")));
+        let _ = write!(out, "Cc: {}\r\n", request.cc.join(", "));
     }
 
     if !request.bcc.is_empty() {
-        headers.push(format!("Bcc: {}", request.bcc.join(", ")));
+        let _ = write!(out, "Bcc: {}\r\n", request.bcc.join(", "));
     }
 
-    headers.push(format!("Subject: {}", encode_header_text(&request.subject)));
-    headers.push("MIME-Version: 1.0".to_string());
+    let _ = write!(out, "Subject: {}\r\n", encode_header_text(&request.subject));
+    out.push_str("MIME-Version: 1.0\r\n");
     if let Some(in_reply_to) = &request.in_reply_to {
-        headers.push(format!("In-Reply-To: {in_reply_to}"));
+        let _ = write!(out, "In-Reply-To: {in_reply_to}\r\n");
     }
     if let Some(references) = &request.references {
-        headers.push(format!("References: {references}"));
+        let _ = write!(out, "References: {references}\r\n");
     }
-
-    headers
 }
 
-/// Build the multipart/mixed body: an HTML part followed by each base64-encoded attachment.
-fn multipart_body(request: &SendRequest, boundary: &str) -> String {
-    let mut out = String::new();
-    out.push_str(&format!("--{boundary}\r\n"));
+/// Write the multipart/mixed body: an HTML part followed by each base64-encoded attachment.
+fn write_multipart_body(out: &mut String, request: &SendRequest, boundary: &str) {
+    let _ = write!(out, "--{boundary}\r\n");
     out.push_str("Content-Type: text/html; charset=utf-8\r\n\r\n");
     out.push_str(&request.body);
     out.push_str("\r\n");
 
     for attachment in &request.attachments {
-        out.push_str(&format!("--{boundary}\r\n"));
-        out.push_str(&format!(
-            "Content-Type: {}; name=\"{}\"\r\n",
-            attachment.mime_type,
-            escape_header_value(&attachment.filename)
-        ));
-        out.push_str("Content-Transfer-Encoding: base64\r\n");
-        out.push_str(&format!(
-            "Content-Disposition: attachment; filename=\"{}\"\r\n\r\n",
-            escape_header_value(&attachment.filename)
-        ));
-
-        let encoded = STANDARD.encode(&attachment.data);
-        out.push_str(&fold_base64_lines(&encoded));
+        let filename = escape_header_value(&attachment.filename);
+        let _ = write!(
+            out,
+            "--{boundary}\r\n\
+             Content-Type: {}; name=\"{filename}\"\r\n\
+             Content-Transfer-Encoding: base64\r\n\
+             Content-Disposition: attachment; filename=\"{filename}\"\r\n\r\n",
+            attachment.mime_type
+        );
+        write_base64_lines(out, &attachment.data);
         out.push_str("\r\n");
     }
 
-    out.push_str(&format!("--{boundary}--\r\n"));
-    out
+    let _ = write!(out, "--{boundary}--\r\n");
 }
 
-/// Wrap a base64 string into 76-character CRLF-terminated lines per MIME conventions.
-fn fold_base64_lines(input: &str) -> String {
-    let mut out = String::with_capacity(input.len() + input.len() / 76 + 8);
-    let mut start = 0;
-    while start < input.len() {
-        let end = (start + 76).min(input.len());
-        out.push_str(&input[start..end]);
+/// Base64-encode `data` as 76-character CRLF-terminated lines per MIME conventions.
+fn write_base64_lines(out: &mut String, data: &[u8]) {
+    for line in data.chunks(BASE64_LINE_BYTES) {
+        STANDARD.encode_string(line, out);
         out.push_str("\r\n");
-        start = end;
     }
-    out
 }
 
-/// Generate a random MIME multipart boundary token.
-fn random_boundary() -> String {
-    let mut bytes = [0_u8; 12];
-    rand::thread_rng().fill(&mut bytes);
-    let token = STANDARD.encode(bytes);
+/// A MIME multipart boundary derived from the message content, so building
+/// the same message twice gives identical bytes (the outbox journal matches
+/// retries by content hash).
+fn content_boundary(request: &SendRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.body.as_bytes());
+    for attachment in &request.attachments {
+        hasher.update(attachment.filename.as_bytes());
+        hasher.update(&attachment.data);
+    }
+    let token = STANDARD.encode(&hasher.finalize()[..12]);
     format!("gmail-cli-{token}")
 }
 
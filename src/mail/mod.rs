@@ -1 +1,14 @@
+pub mod address;
+pub mod alias;
+pub mod cleanup;
+pub mod ics;
+pub mod mbox;
 pub mod mime;
+pub mod patch;
+pub mod rfc822;
+pub mod rules;
+pub mod stats;
+pub mod unsubscribe;
+pub mod usage;
+pub mod vcard;
+pub mod when;
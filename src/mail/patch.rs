@@ -0,0 +1,212 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+use crate::error::{AppError, AppResult};
+use crate::mail::address;
+
+/// Headers carried over from a `git format-patch` file besides From and Subject.
+const KEPT_HEADERS: [&str; 3] = ["MIME-Version", "Content-Type", "Content-Transfer-Encoding"];
+
+/// One `git format-patch` file: its author, subject, MIME headers, and the
+/// commit message plus diff as body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    pub author: Option<String>,
+    /// As written by git, already RFC 2047 encoded when non-ASCII.
+    pub subject: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// Addressing and threading for one message of a series.
+#[derive(Debug, Clone, Default)]
+pub struct PatchEnvelope {
+    pub from: Option<String>,
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+}
+
+/// Parse a patch in mbox form, skipping git's leading `From <sha> <date>` line.
+pub fn parse(text: &str) -> AppResult<Patch> {
+    let text = text.replace("\r\n", "\n");
+    let text = match text.strip_prefix("From ") {
+        Some(rest) => rest
+            .split_once('\n')
+            .map(|(_, rest)| rest)
+            .unwrap_or_default(),
+        None => text.as_str(),
+    };
+    let (head, body) = text.split_once("\n\n").unwrap_or((text, ""));
+
+    let mut headers = Vec::<(String, String)>::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push_str("\r\n");
+                value.push_str(line);
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let take = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+    };
+    let subject = take("Subject")
+        .filter(|subject| !subject.is_empty())
+        .ok_or_else(|| {
+            AppError::InvalidInput("not a git format-patch file: no Subject header".to_string())
+        })?;
+    let author = take("From");
+    let kept = headers
+        .iter()
+        .filter(|(key, _)| {
+            KEPT_HEADERS
+                .iter()
+                .any(|kept| key.eq_ignore_ascii_case(kept))
+        })
+        .cloned()
+        .collect();
+
+    Ok(Patch {
+        author,
+        subject,
+        headers: kept,
+        body: body.to_string(),
+    })
+}
+
+/// Build a base64url-encoded plain-text message for `patch`, as
+/// `git send-email` would: no HTML wrapper, and the commit author kept as a
+/// `From:` line at the top of the body when it differs from the sender.
+pub fn build_raw_patch(patch: &Patch, envelope: &PatchEnvelope) -> String {
+    let mut message = String::with_capacity(patch.body.len() + 1024);
+    let mut header = |name: &str, value: &str| {
+        message.push_str(name);
+        message.push_str(": ");
+        message.push_str(value);
+        message.push_str("\r\n");
+    };
+
+    if let Some(from) = &envelope.from {
+        header("From", from);
+    }
+    header("To", &envelope.to.join(", "));
+    if !envelope.cc.is_empty() {
+        header("Cc", &envelope.cc.join(", "));
+    }
+    header("Subject", &patch.subject);
+    if let Some(in_reply_to) = &envelope.in_reply_to {
+        header("In-Reply-To", in_reply_to);
+    }
+    if !envelope.references.is_empty() {
+        header("References", &envelope.references.join(" "));
+    }
+    for name in KEPT_HEADERS {
+        let value = patch
+            .headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str());
+        let default = match name {
+            "MIME-Version" => "1.0",
+            "Content-Type" => "text/plain; charset=UTF-8",
+            _ => "8bit",
+        };
+        header(name, value.unwrap_or(default));
+    }
+    message.push_str("\r\n");
+
+    let sender = envelope.from.as_deref().map(address::email_of);
+    if let Some(author) = &patch.author
+        && sender.is_some_and(|sender| sender != address::email_of(author))
+    {
+        message.push_str("From: ");
+        message.push_str(author);
+        message.push_str("\r\n\r\n");
+    }
+    for line in patch.body.lines() {
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+
+    URL_SAFE_NO_PAD.encode(message.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "From 3f2a1c Mon Sep 17 00:00:00 2001\n\
+        From: Ada Lovelace <ada@example.com>\n\
+        Date: Tue, 5 Mar 2024 14:03:00 +0000\n\
+        Subject: [PATCH 1/2] parser: handle empty\n input\n\
+        MIME-Version: 1.0\n\
+        Content-Type: text/plain; charset=UTF-8\n\
+        Content-Transfer-Encoding: 8bit\n\
+        \n\
+        Empty input used to panic.\n\
+        ---\n\
+        \x20src/parser.rs | 2 +-\n";
+
+    fn decode(raw: &str) -> String {
+        String::from_utf8(URL_SAFE_NO_PAD.decode(raw).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_mbox_headers_and_body() {
+        let patch = parse(PATCH).unwrap();
+        assert_eq!(
+            patch.author.as_deref(),
+            Some("Ada Lovelace <ada@example.com>")
+        );
+        assert_eq!(patch.subject, "[PATCH 1/2] parser: handle empty\r\n input");
+        assert_eq!(patch.headers.len(), 3);
+        assert!(patch.body.starts_with("Empty input used to panic.\n---\n"));
+        assert!(parse("just some text\n").is_err());
+    }
+
+    #[test]
+    fn builds_threaded_plain_text_with_foreign_author_line() {
+        let patch = parse(PATCH).unwrap();
+        let raw = build_raw_patch(
+            &patch,
+            &PatchEnvelope {
+                from: Some("Me <me@example.com>".to_string()),
+                to: vec!["list@example.org".to_string()],
+                cc: Vec::new(),
+                in_reply_to: Some("<cover@example.com>".to_string()),
+                references: vec!["<cover@example.com>".to_string()],
+            },
+        );
+        let message = decode(&raw);
+
+        assert!(message.contains("Subject: [PATCH 1/2] parser: handle empty\r\n input\r\n"));
+        assert!(message.contains("In-Reply-To: <cover@example.com>\r\n"));
+        assert!(message.contains("Content-Type: text/plain; charset=UTF-8\r\n"));
+        assert!(!message.contains("text/html"));
+        assert!(message.contains(
+            "\r\n\r\nFrom: Ada Lovelace <ada@example.com>\r\n\r\nEmpty input used to panic.\r\n"
+        ));
+    }
+
+    #[test]
+    fn omits_author_line_when_sender_wrote_the_patch() {
+        let patch = parse(PATCH).unwrap();
+        let raw = build_raw_patch(
+            &patch,
+            &PatchEnvelope {
+                from: Some("Ada <ADA@example.com>".to_string()),
+                to: vec!["list@example.org".to_string()],
+                ..PatchEnvelope::default()
+            },
+        );
+        assert!(!decode(&raw).contains("From: Ada Lovelace"));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::mail::address;
+
+/// Split a raw message at the blank line ending its header block, returning
+/// the headers (folded lines joined, names as written) and the byte offset
+/// where the header block ends. Works on bytes so 8-bit bodies pass through
+/// untouched.
+pub fn headers(message: &[u8]) -> (Vec<(String, String)>, usize) {
+    let end = header_end(message);
+    let text = String::from_utf8_lossy(&message[..end]);
+
+    let mut headers = Vec::<(String, String)>::new();
+    for line in text.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, end)
+}
+
+/// Values of every header named `name`, case-insensitively.
+pub fn values<'a>(headers: &'a [(String, String)], name: &str) -> Vec<&'a str> {
+    headers
+        .iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+        .collect()
+}
+
+/// Bare, lowercased addresses from the To, Cc, and Bcc headers, deduplicated.
+pub fn recipients(headers: &[(String, String)]) -> Vec<String> {
+    let mut out = Vec::new();
+    for name in ["To", "Cc", "Bcc"] {
+        for value in values(headers, name) {
+            for entry in address::split_addresses(value) {
+                let email = address::email_of(&entry);
+                if !email.is_empty() && !out.contains(&email) {
+                    out.push(email);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Offset of the blank line after the headers (or the end, if there is none).
+fn header_end(message: &[u8]) -> usize {
+    let mut start = 0;
+    for line in message.split_inclusive(|byte| *byte == b'\n') {
+        if line == b"\n" || line == b"\r\n" {
+            return start;
+        }
+        start += line.len();
+    }
+    message.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_folded_headers_and_recipients() {
+        let message = b"From: me@example.com\r\n\
+            To: Ada <ada@example.com>,\r\n \"Doe, Jane\" <jane@example.com>\r\n\
+            Bcc: ADA@example.com, audit@example.com\r\n\
+            Subject: hi\r\n\
+            \r\n\
+            To: not-a-header@example.com\r\n";
+        let (headers, end) = headers(message);
+
+        assert_eq!(&message[end..end + 2], b"\r\n");
+        assert_eq!(values(&headers, "subject"), ["hi"]);
+        assert_eq!(
+            recipients(&headers),
+            ["ada@example.com", "jane@example.com", "audit@example.com"]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+
+use crate::api::models::MessageView;
+use crate::error::{AppError, AppResult};
+
+/// Query whose new matches the rules see when the file doesn't set one.
+pub const DEFAULT_QUERY: &str = "in:inbox";
+
+/// A `rules.toml` file: local rules run against each newly arrived message,
+/// in order, for logic Gmail's server-side filters can't express.
+///
+/// ```toml
+/// query = "in:inbox"
+///
+/// [[rule]]
+/// name = "failed builds"
+/// from = "ci@example.com"
+/// regex = 'build #\d+ (failed|broke)'
+/// label = ["CI"]
+/// notify = true
+///
+/// [rule.header]
+/// List-Id = "builds\\.example\\.com"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesFile {
+    /// Gmail search selecting the messages the rules see.
+    #[serde(default = "default_query")]
+    pub query: String,
+    #[serde(rename = "rule", default)]
+    pub rules: Vec<RuleSpec>,
+}
+
+/// One rule as written: every condition set must hold, then every action runs.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSpec {
+    pub name: Option<String>,
+    /// Case-insensitive substring of the From header.
+    pub from: Option<String>,
+    /// Case-insensitive substring of the Subject.
+    pub subject: Option<String>,
+    /// Case-insensitive regex matched against the Subject and the body snippet.
+    pub regex: Option<String>,
+    /// Header name → case-insensitive regex over that header's value.
+    #[serde(default)]
+    pub header: BTreeMap<String, String>,
+    /// Labels (names or ids) to add.
+    #[serde(default)]
+    pub label: Vec<String>,
+    #[serde(default)]
+    pub archive: bool,
+    #[serde(default)]
+    pub mark_read: bool,
+    /// Raise a desktop notification.
+    #[serde(default)]
+    pub notify: bool,
+    /// Shell command run with the message JSON on stdin, as `on_message` hooks are.
+    pub run: Option<String>,
+    /// Skip the remaining rules once this one matches.
+    #[serde(default)]
+    pub stop: bool,
+}
+
+/// A checked rule with its patterns compiled.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub spec: RuleSpec,
+    regex: Option<Regex>,
+    header: Vec<(String, Regex)>,
+}
+
+/// The checked rules file.
+#[derive(Debug, Clone)]
+pub struct MailRules {
+    pub query: String,
+    pub rules: Vec<Rule>,
+}
+
+impl MailRules {
+    /// Read, check, and compile a rules file.
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let raw = fs::read_to_string(path)?;
+        let file: RulesFile = toml::from_str(&raw)
+            .map_err(|err| AppError::Config(format!("invalid {}: {err}", path.display())))?;
+        Self::compile(file)
+    }
+
+    /// Check and compile parsed rules.
+    pub fn compile(file: RulesFile) -> AppResult<Self> {
+        if file.rules.is_empty() {
+            return Err(AppError::Config(
+                "rules file has no [[rule]] entries".to_string(),
+            ));
+        }
+        let rules = file
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec)| Rule::compile(index, spec))
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Self {
+            query: file.query.trim().to_string(),
+            rules,
+        })
+    }
+
+    /// Every header some rule tests, to fetch once per message.
+    pub fn header_names(&self) -> Vec<&str> {
+        let mut names = self
+            .rules
+            .iter()
+            .flat_map(|rule| rule.header.iter().map(|(name, _)| name.as_str()))
+            .collect::<Vec<_>>();
+        names.sort_unstable_by_key(|name| name.to_ascii_lowercase());
+        names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        names
+    }
+
+    /// The rules a message triggers, in order, up to the first `stop`.
+    pub fn matching<'a>(
+        &'a self,
+        message: &MessageView,
+        headers: &[(String, String)],
+    ) -> Vec<&'a Rule> {
+        let mut matched = Vec::new();
+        for rule in &self.rules {
+            if rule.matches(message, headers) {
+                matched.push(rule);
+                if rule.spec.stop {
+                    break;
+                }
+            }
+        }
+        matched
+    }
+}
+
+impl Rule {
+    fn compile(index: usize, spec: RuleSpec) -> AppResult<Self> {
+        let name = spec
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("rule #{}", index + 1));
+        let pattern = |raw: &str| {
+            RegexBuilder::new(raw)
+                .case_insensitive(true)
+                .build()
+                .map_err(|err| AppError::Config(format!("{name}: invalid regex `{raw}`: {err}")))
+        };
+
+        let has_condition = spec.from.is_some()
+            || spec.subject.is_some()
+            || spec.regex.is_some()
+            || !spec.header.is_empty();
+        if !has_condition {
+            return Err(AppError::Config(format!(
+                "{name} needs a condition (from, subject, regex, or header)"
+            )));
+        }
+        let has_action = !spec.label.is_empty()
+            || spec.archive
+            || spec.mark_read
+            || spec.notify
+            || spec
+                .run
+                .as_deref()
+                .is_some_and(|run| !run.trim().is_empty());
+        if !has_action {
+            return Err(AppError::Config(format!(
+                "{name} needs an action (label, archive, mark_read, notify, or run)"
+            )));
+        }
+
+        let regex = spec.regex.as_deref().map(pattern).transpose()?;
+        let header = spec
+            .header
+            .iter()
+            .map(|(header, raw)| Ok((header.clone(), pattern(raw)?)))
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Self {
+            name,
+            spec,
+            regex,
+            header,
+        })
+    }
+
+    /// Whether every condition holds for `message`, with `headers` holding
+    /// the extra headers from [`MailRules::header_names`].
+    pub fn matches(&self, message: &MessageView, headers: &[(String, String)]) -> bool {
+        let contains = |value: Option<&str>, needle: &str| {
+            value
+                .unwrap_or_default()
+                .to_lowercase()
+                .contains(&needle.to_lowercase())
+        };
+        if let Some(from) = &self.spec.from
+            && !contains(message.from.as_deref(), from)
+        {
+            return false;
+        }
+        if let Some(subject) = &self.spec.subject
+            && !contains(message.subject.as_deref(), subject)
+        {
+            return false;
+        }
+        if let Some(regex) = &self.regex {
+            let subject = message.subject.as_deref().unwrap_or_default();
+            let snippet = message.snippet.as_deref().unwrap_or_default();
+            if !regex.is_match(subject) && !regex.is_match(snippet) {
+                return false;
+            }
+        }
+        self.header.iter().all(|(name, regex)| {
+            headers
+                .iter()
+                .any(|(header, value)| header.eq_ignore_ascii_case(name) && regex.is_match(value))
+        })
+    }
+
+    /// Labels to add and remove for this rule's label, archive, and mark-read actions.
+    pub fn label_changes(&self) -> (Vec<String>, Vec<String>) {
+        let mut remove = Vec::new();
+        if self.spec.archive {
+            remove.push("INBOX".to_string());
+        }
+        if self.spec.mark_read {
+            remove.push("UNREAD".to_string());
+        }
+        (self.spec.label.clone(), remove)
+    }
+}
+
+fn default_query() -> String {
+    DEFAULT_QUERY.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(raw: &str) -> AppResult<MailRules> {
+        MailRules::compile(toml::from_str(raw).unwrap())
+    }
+
+    fn message(from: &str, subject: &str, snippet: &str) -> MessageView {
+        MessageView {
+            id: "m1".to_string(),
+            from: Some(from.to_string()),
+            subject: Some(subject.to_string()),
+            snippet: Some(snippet.to_string()),
+            ..MessageView::default()
+        }
+    }
+
+    #[test]
+    fn matches_all_conditions_and_stops() {
+        let rules = rules(
+            r#"
+[[rule]]
+name = "failed builds"
+from = "CI@example.com"
+regex = 'build #\d+ failed'
+label = ["CI"]
+archive = true
+stop = true
+
+[rule.header]
+List-Id = 'builds\.example\.com'
+
+[[rule]]
+subject = "build"
+notify = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(rules.query, DEFAULT_QUERY);
+        assert_eq!(rules.header_names(), ["List-Id"]);
+
+        let failed = message("CI <ci@example.com>", "Build #42 failed", "");
+        let headers = [("list-id".to_string(), "<builds.example.com>".to_string())];
+        let names = |matched: Vec<&Rule>| {
+            matched
+                .iter()
+                .map(|rule| rule.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(rules.matching(&failed, &headers)), ["failed builds"]);
+        assert_eq!(names(rules.matching(&failed, &[])), ["rule #2"]);
+        assert_eq!(
+            rules.rules[0].label_changes(),
+            (vec!["CI".to_string()], vec!["INBOX".to_string()])
+        );
+
+        let passed = message("ci@example.com", "Deploy", "build #43 passed");
+        assert!(rules.matching(&passed, &headers).is_empty());
+    }
+
+    #[test]
+    fn rejects_rules_without_conditions_actions_or_valid_regex() {
+        assert!(rules("[[rule]]\nnotify = true").is_err());
+        assert!(rules("[[rule]]\nfrom = \"a\"").is_err());
+        assert!(rules("[[rule]]\nregex = \"(\"\nnotify = true").is_err());
+        assert!(rules("query = \"in:inbox\"").is_err());
+    }
+}
@@ -0,0 +1,510 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::{LabelView, MessageView};
+use crate::error::{AppError, AppResult};
+use crate::mail::address;
+use crate::output::dates;
+
+/// Mailbox analytics over the messages matching `query`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Report {
+    /// The Gmail search the messages were selected with.
+    pub query: String,
+    pub messages: usize,
+    pub received: usize,
+    pub sent: usize,
+    /// Most frequent senders of received mail.
+    pub top_senders: Vec<SenderCount>,
+    /// Most frequent sender domains of received mail.
+    pub top_domains: Vec<DomainCount>,
+    pub attachments: AttachmentVolume,
+    /// Every day from the oldest to the newest message, in local time.
+    pub per_day: Vec<DayVolume>,
+    pub response: ResponseLatency,
+    /// How many messages carry each label, most common first.
+    pub labels: Vec<LabelCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct SenderCount {
+    /// `Name <email>` as first seen.
+    pub sender: String,
+    pub email: String,
+    pub messages: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct DomainCount {
+    /// e.g. `example.com`
+    pub domain: String,
+    pub messages: usize,
+}
+
+/// Messages carrying attachments and how much room they take.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct AttachmentVolume {
+    pub messages: usize,
+    /// Gmail's size estimates of those messages, attachments included.
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct DayVolume {
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub received: usize,
+    pub sent: usize,
+}
+
+/// How long our replies took: each sent message is timed from the latest
+/// received message before it in the same thread.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ResponseLatency {
+    pub replies: usize,
+    pub average_minutes: Option<u64>,
+    pub median_minutes: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct LabelCount {
+    /// Label name, or its id when the label is unknown.
+    pub label: String,
+    pub messages: usize,
+}
+
+/// One `section,key,value` line of a report flattened for CSV and tables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct StatRow {
+    /// `summary`, `sender`, `domain`, `attachments`, `received`, `sent`,
+    /// `response`, or `label`.
+    pub section: String,
+    pub key: String,
+    pub value: u64,
+}
+
+/// Turn a `--since` window (`90d`, `6w`, `3m`, `1y`) into a Gmail
+/// `newer_than:` term.
+pub fn newer_than(since: &str) -> AppResult<String> {
+    relative_term("newer_than", since).ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "invalid --since `{}`; use a count and unit, e.g. 30d, 6w, 3m, 1y",
+            since.trim()
+        ))
+    })
+}
+
+/// `operator:window` for a relative age search (`newer_than`, `older_than`),
+/// or `None` if `window` isn't a count and unit. Weeks become days, which
+/// Gmail understands.
+pub(crate) fn relative_term(operator: &str, window: &str) -> Option<String> {
+    let window = window.trim();
+    let unit = window.chars().last()?;
+    let count = window[..window.len() - unit.len_utf8()]
+        .parse::<u32>()
+        .ok()
+        .filter(|count| *count > 0)?;
+    match unit.to_ascii_lowercase() {
+        'd' | 'm' | 'y' => Some(format!("{operator}:{count}{}", unit.to_ascii_lowercase())),
+        'w' => Some(format!("{operator}:{}d", count * 7)),
+        _ => None,
+    }
+}
+
+/// Aggregate `messages` (metadata is enough) into a [`Report`], naming labels
+/// from `labels`, counting the ids in `with_attachments` as carrying
+/// attachments, and bucketing days in `tz`. Drafts are left out; messages
+/// labelled `SENT` count as sent, everything else as received.
+pub fn build<Tz: TimeZone>(
+    query: &str,
+    messages: &[MessageView],
+    labels: &[LabelView],
+    with_attachments: &HashSet<String>,
+    top: usize,
+    tz: &Tz,
+) -> Report {
+    let messages = messages
+        .iter()
+        .filter(|message| !has_label(message, "DRAFT"))
+        .collect::<Vec<_>>();
+    let sent = messages
+        .iter()
+        .filter(|message| has_label(message, "SENT"))
+        .count();
+
+    Report {
+        query: query.to_string(),
+        messages: messages.len(),
+        received: messages.len() - sent,
+        sent,
+        top_senders: top_senders(&messages, top),
+        top_domains: top_domains(&messages, top),
+        attachments: attachment_volume(&messages, with_attachments),
+        per_day: per_day(&messages, tz),
+        response: response_latency(&messages),
+        labels: label_counts(&messages, labels),
+    }
+}
+
+impl Report {
+    /// The report as long-form rows, so every section fits one CSV or table.
+    pub fn rows(&self) -> Vec<StatRow> {
+        let row = |section: &str, key: &str, value: u64| StatRow {
+            section: section.to_string(),
+            key: key.to_string(),
+            value,
+        };
+
+        let mut rows = vec![
+            row("summary", "messages", self.messages as u64),
+            row("summary", "received", self.received as u64),
+            row("summary", "sent", self.sent as u64),
+        ];
+        rows.extend(
+            self.top_senders
+                .iter()
+                .map(|sender| row("sender", &sender.sender, sender.messages as u64)),
+        );
+        rows.extend(
+            self.top_domains
+                .iter()
+                .map(|domain| row("domain", &domain.domain, domain.messages as u64)),
+        );
+        rows.push(row(
+            "attachments",
+            "messages",
+            self.attachments.messages as u64,
+        ));
+        rows.push(row("attachments", "bytes", self.attachments.bytes));
+        rows.extend(
+            self.per_day
+                .iter()
+                .map(|day| row("received", &day.date, day.received as u64)),
+        );
+        rows.extend(
+            self.per_day
+                .iter()
+                .map(|day| row("sent", &day.date, day.sent as u64)),
+        );
+        rows.push(row("response", "replies", self.response.replies as u64));
+        if let Some(minutes) = self.response.average_minutes {
+            rows.push(row("response", "average_minutes", minutes));
+        }
+        if let Some(minutes) = self.response.median_minutes {
+            rows.push(row("response", "median_minutes", minutes));
+        }
+        rows.extend(
+            self.labels
+                .iter()
+                .map(|label| row("label", &label.label, label.messages as u64)),
+        );
+        rows
+    }
+}
+
+fn has_label(message: &MessageView, label: &str) -> bool {
+    message.label_ids.iter().any(|id| id == label)
+}
+
+fn sent_at(message: &MessageView) -> Option<DateTime<FixedOffset>> {
+    message.date.as_deref().and_then(dates::parse_header)
+}
+
+fn top_senders(messages: &[&MessageView], top: usize) -> Vec<SenderCount> {
+    let mut senders = Vec::<SenderCount>::new();
+    for message in messages
+        .iter()
+        .filter(|message| !has_label(message, "SENT"))
+    {
+        let Some(from) = message.from.as_deref() else {
+            continue;
+        };
+        let email = address::email_of(from);
+        match senders.iter_mut().find(|sender| sender.email == email) {
+            Some(sender) => sender.messages += 1,
+            None => senders.push(SenderCount {
+                sender: address::display(address::name_of(from).as_deref(), &email),
+                email,
+                messages: 1,
+            }),
+        }
+    }
+
+    senders.sort_by(|a, b| b.messages.cmp(&a.messages).then(a.email.cmp(&b.email)));
+    senders.truncate(top);
+    senders
+}
+
+fn top_domains(messages: &[&MessageView], top: usize) -> Vec<DomainCount> {
+    let mut counts = HashMap::<String, usize>::new();
+    for message in messages
+        .iter()
+        .filter(|message| !has_label(message, "SENT"))
+    {
+        let Some(email) = message.from.as_deref().map(address::email_of) else {
+            continue;
+        };
+        if let Some((_, domain)) = email.rsplit_once('@') {
+            *counts.entry(domain.to_string()).or_default() += 1;
+        }
+    }
+
+    let mut domains = counts
+        .into_iter()
+        .map(|(domain, messages)| DomainCount { domain, messages })
+        .collect::<Vec<_>>();
+    domains.sort_by(|a, b| b.messages.cmp(&a.messages).then(a.domain.cmp(&b.domain)));
+    domains.truncate(top);
+    domains
+}
+
+fn attachment_volume(
+    messages: &[&MessageView],
+    with_attachments: &HashSet<String>,
+) -> AttachmentVolume {
+    let messages = messages
+        .iter()
+        .filter(|message| with_attachments.contains(&message.id))
+        .collect::<Vec<_>>();
+    AttachmentVolume {
+        messages: messages.len(),
+        bytes: messages
+            .iter()
+            .filter_map(|message| message.size_estimate)
+            .sum(),
+    }
+}
+
+fn per_day<Tz: TimeZone>(messages: &[&MessageView], tz: &Tz) -> Vec<DayVolume> {
+    let mut days = BTreeMap::<NaiveDate, (usize, usize)>::new();
+    for message in messages {
+        let Some(when) = sent_at(message) else {
+            continue;
+        };
+        let day = days.entry(when.with_timezone(tz).date_naive()).or_default();
+        if has_label(message, "SENT") {
+            day.1 += 1;
+        } else {
+            day.0 += 1;
+        }
+    }
+
+    let (Some(first), Some(last)) = (
+        days.keys().next().copied(),
+        days.keys().next_back().copied(),
+    ) else {
+        return Vec::new();
+    };
+    first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| {
+            let (received, sent) = days.get(&date).copied().unwrap_or_default();
+            DayVolume {
+                date: date.format("%Y-%m-%d").to_string(),
+                received,
+                sent,
+            }
+        })
+        .collect()
+}
+
+fn response_latency(messages: &[&MessageView]) -> ResponseLatency {
+    let mut threads = HashMap::<&str, Vec<(DateTime<FixedOffset>, bool)>>::new();
+    for message in messages {
+        let (Some(thread_id), Some(when)) = (message.thread_id.as_deref(), sent_at(message)) else {
+            continue;
+        };
+        threads
+            .entry(thread_id)
+            .or_default()
+            .push((when, has_label(message, "SENT")));
+    }
+
+    let mut minutes = Vec::new();
+    for thread in threads.values_mut() {
+        thread.sort();
+        let mut waiting = None;
+        for (when, sent) in thread.iter() {
+            match (sent, waiting) {
+                (false, _) => waiting = Some(*when),
+                (true, Some(received)) => {
+                    minutes.push((*when - received).num_minutes().max(0) as u64);
+                    waiting = None;
+                }
+                (true, None) => {}
+            }
+        }
+    }
+
+    minutes.sort_unstable();
+    let replies = minutes.len();
+    ResponseLatency {
+        replies,
+        average_minutes: (replies > 0).then(|| minutes.iter().sum::<u64>() / replies as u64),
+        median_minutes: minutes.get(replies / 2).copied(),
+    }
+}
+
+fn label_counts(messages: &[&MessageView], labels: &[LabelView]) -> Vec<LabelCount> {
+    let mut counts = HashMap::<&str, usize>::new();
+    for id in messages.iter().flat_map(|message| &message.label_ids) {
+        *counts.entry(id.as_str()).or_default() += 1;
+    }
+
+    let mut counts = counts
+        .into_iter()
+        .map(|(id, messages)| LabelCount {
+            label: labels
+                .iter()
+                .find(|label| label.id == id)
+                .map_or(id, |label| label.name.as_str())
+                .to_string(),
+            messages,
+        })
+        .collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.messages.cmp(&a.messages).then(a.label.cmp(&b.label)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn message(id: &str, thread: &str, from: &str, date: &str, labels: &[&str]) -> MessageView {
+        MessageView {
+            id: id.to_string(),
+            thread_id: Some(thread.to_string()),
+            label_ids: labels.iter().map(|label| label.to_string()).collect(),
+            from: Some(from.to_string()),
+            date: Some(date.to_string()),
+            ..MessageView::default()
+        }
+    }
+
+    #[test]
+    fn reads_since_windows() {
+        assert_eq!(newer_than("90d").unwrap(), "newer_than:90d");
+        assert_eq!(newer_than("2w").unwrap(), "newer_than:14d");
+        assert_eq!(newer_than("1Y").unwrap(), "newer_than:1y");
+        assert!(newer_than("0d").is_err());
+        assert!(newer_than("d").is_err());
+        assert!(newer_than("3h").is_err());
+    }
+
+    #[test]
+    fn aggregates_senders_days_latency_and_labels() {
+        let messages = [
+            message(
+                "1",
+                "t1",
+                "Ada <ada@example.com>",
+                "Mon, 5 Oct 2026 09:00:00 +0000",
+                &["INBOX", "Label_1"],
+            ),
+            message(
+                "2",
+                "t1",
+                "Me <me@example.com>",
+                "Mon, 5 Oct 2026 10:30:00 +0000",
+                &["SENT"],
+            ),
+            MessageView {
+                size_estimate: Some(48_000),
+                ..message(
+                    "3",
+                    "t2",
+                    "ADA <Ada@Example.com>",
+                    "Wed, 7 Oct 2026 08:00:00 +0000",
+                    &["INBOX"],
+                )
+            },
+            message(
+                "4",
+                "t2",
+                "Me <me@example.com>",
+                "Wed, 7 Oct 2026 08:30:00 +0000",
+                &["SENT"],
+            ),
+            message(
+                "5",
+                "t3",
+                "Me <me@example.com>",
+                "Wed, 7 Oct 2026 12:00:00 +0000",
+                &["DRAFT"],
+            ),
+        ];
+        let labels = [LabelView {
+            id: "Label_1".to_string(),
+            name: "Work".to_string(),
+            kind: "user".to_string(),
+        }];
+
+        let with_attachments = HashSet::from(["3".to_string(), "9".to_string()]);
+        let report = build(
+            "newer_than:7d",
+            &messages,
+            &labels,
+            &with_attachments,
+            10,
+            &Utc,
+        );
+        assert_eq!((report.messages, report.received, report.sent), (4, 2, 2));
+        assert_eq!(
+            report.top_senders,
+            [SenderCount {
+                sender: "Ada <ada@example.com>".to_string(),
+                email: "ada@example.com".to_string(),
+                messages: 2,
+            }]
+        );
+        assert_eq!(
+            report.top_domains,
+            [DomainCount {
+                domain: "example.com".to_string(),
+                messages: 2,
+            }]
+        );
+        assert_eq!(
+            report.attachments,
+            AttachmentVolume {
+                messages: 1,
+                bytes: 48_000,
+            }
+        );
+        assert_eq!(
+            report
+                .per_day
+                .iter()
+                .map(|day| (day.date.as_str(), day.received, day.sent))
+                .collect::<Vec<_>>(),
+            [
+                ("2026-10-05", 1, 1),
+                ("2026-10-06", 0, 0),
+                ("2026-10-07", 1, 1)
+            ]
+        );
+        assert_eq!(
+            report.response,
+            ResponseLatency {
+                replies: 2,
+                average_minutes: Some(60),
+                median_minutes: Some(90),
+            }
+        );
+        assert_eq!(report.labels[0].label, "INBOX");
+        assert!(report.labels.iter().any(|label| label.label == "Work"));
+
+        let rows = report.rows();
+        assert_eq!(rows[0].section, "summary");
+        assert!(
+            rows.iter()
+                .any(|row| row.section == "sent" && row.key == "2026-10-07" && row.value == 1)
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use url::Url;
+
+/// The unsubscribe methods a message offers through `List-Unsubscribe`
+/// (RFC 2369) and `List-Unsubscribe-Post` (RFC 8058).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Unsubscribe {
+    /// The first `http(s)` URI.
+    pub link: Option<Url>,
+    /// Whether `link` accepts an RFC 8058 one-click POST: it is HTTPS and
+    /// the message carries `List-Unsubscribe-Post: List-Unsubscribe=One-Click`.
+    pub one_click: bool,
+    /// The first `mailto:` URI.
+    pub mailto: Option<Mailto>,
+}
+
+/// A `mailto:` URI split into the message it asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mailto {
+    pub to: Vec<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+impl Unsubscribe {
+    pub fn is_empty(&self) -> bool {
+        self.link.is_none() && self.mailto.is_none()
+    }
+}
+
+/// Read the `<uri>, <uri>` list in a `List-Unsubscribe` header, keeping the
+/// first web link and the first mailto; anything outside angle brackets is
+/// ignored, as the RFC asks.
+pub fn parse(list_unsubscribe: &str, list_unsubscribe_post: Option<&str>) -> Unsubscribe {
+    let mut found = Unsubscribe::default();
+    for uri in bracketed(list_unsubscribe) {
+        let Ok(url) = Url::parse(uri.trim()) else {
+            continue;
+        };
+        match url.scheme() {
+            "http" | "https" if found.link.is_none() => found.link = Some(url),
+            "mailto" if found.mailto.is_none() => found.mailto = parse_mailto(&url),
+            _ => {}
+        }
+    }
+
+    let post = list_unsubscribe_post.is_some_and(|value| {
+        value
+            .trim()
+            .eq_ignore_ascii_case("List-Unsubscribe=One-Click")
+    });
+    found.one_click = post
+        && found
+            .link
+            .as_ref()
+            .is_some_and(|link| link.scheme() == "https");
+    found
+}
+
+fn bracketed(header: &str) -> impl Iterator<Item = &str> {
+    header
+        .split('<')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('>').map(|(uri, _)| uri))
+}
+
+fn parse_mailto(url: &Url) -> Option<Mailto> {
+    let to = percent_decode(url.path())
+        .split(',')
+        .map(str::trim)
+        .filter(|address| address.contains('@'))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if to.is_empty() {
+        return None;
+    }
+
+    let mut mailto = Mailto {
+        to,
+        subject: None,
+        body: None,
+    };
+    for (key, value) in url.query_pairs() {
+        match key.to_ascii_lowercase().as_str() {
+            "subject" => mailto.subject = Some(value.into_owned()),
+            "body" => mailto.body = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+    Some(mailto)
+}
+
+/// Decode `%XX` escapes in a URI path (where, unlike in a query, `+` is literal).
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                index += 3;
+            }
+            None => {
+                out.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_one_click_https_and_keeps_the_mailto() {
+        let found = parse(
+            "<mailto:leave-123@lists.example.com?subject=unsubscribe%20me>, \
+             <https://example.com/u/123?t=abc>",
+            Some("List-Unsubscribe=One-Click"),
+        );
+        assert!(found.one_click);
+        assert_eq!(
+            found.link.unwrap().as_str(),
+            "https://example.com/u/123?t=abc"
+        );
+        assert_eq!(
+            found.mailto,
+            Some(Mailto {
+                to: vec!["leave-123@lists.example.com".to_string()],
+                subject: Some("unsubscribe me".to_string()),
+                body: None,
+            })
+        );
+    }
+
+    #[test]
+    fn one_click_needs_https_and_the_post_header() {
+        assert!(!parse("<https://example.com/u>", None).one_click);
+        assert!(!parse("<http://example.com/u>", Some("List-Unsubscribe=One-Click")).one_click);
+        assert!(parse("no brackets here", None).is_empty());
+
+        let found = parse("<mailto:a%2Bb@example.com>", None);
+        assert_eq!(found.mailto.unwrap().to, ["a+b@example.com"]);
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeZone};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::{LabelView, MessageView};
+use crate::mail::address;
+use crate::output::dates;
+
+/// Age buckets, newest first, as `(label, upper bound in days)`.
+const AGES: [(&str, i64); 5] = [
+    ("0-30d", 30),
+    ("30-90d", 90),
+    ("90d-1y", 365),
+    ("1-2y", 730),
+    ("2y+", i64::MAX),
+];
+
+/// Estimated storage taken by the messages matching `query`, from Gmail's
+/// per-message `sizeEstimate`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct UsageReport {
+    /// The Gmail search the messages were selected with, if any.
+    pub query: Option<String>,
+    pub messages: usize,
+    pub bytes: u64,
+    /// Largest labels first. A message counts toward each of its labels.
+    pub labels: Vec<UsageGroup>,
+    /// Largest senders first.
+    pub senders: Vec<UsageGroup>,
+    /// Every age bucket, newest first; `undated` holds messages without a
+    /// readable Date header.
+    pub ages: Vec<UsageGroup>,
+    /// The biggest single messages.
+    pub largest: Vec<LargeMessage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct UsageGroup {
+    pub key: String,
+    pub messages: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct LargeMessage {
+    pub id: String,
+    pub from: Option<String>,
+    pub subject: Option<String>,
+    pub date: Option<String>,
+    pub bytes: u64,
+}
+
+/// One `section,key,messages,bytes` line of a report flattened for CSV and tables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct UsageRow {
+    /// `summary`, `label`, `sender`, `age`, or `message` (keyed by id).
+    pub section: String,
+    pub key: String,
+    pub messages: usize,
+    pub bytes: u64,
+}
+
+/// Aggregate `messages` (metadata is enough) into a [`UsageReport`], naming
+/// labels from `labels`, keeping the `top` largest labels, senders, and
+/// messages, and aging messages relative to `now`.
+pub fn build<Tz: TimeZone>(
+    query: Option<&str>,
+    messages: &[MessageView],
+    labels: &[LabelView],
+    top: usize,
+    now: &DateTime<Tz>,
+) -> UsageReport {
+    let size = |message: &MessageView| message.size_estimate.unwrap_or_default();
+
+    let mut by_label = Groups::default();
+    let mut by_sender = Groups::default();
+    let mut by_age = Groups::default();
+    for message in messages {
+        let bytes = size(message);
+        for id in &message.label_ids {
+            let name = labels
+                .iter()
+                .find(|label| &label.id == id)
+                .map_or(id.as_str(), |label| label.name.as_str());
+            by_label.add(name, name, bytes);
+        }
+        if let Some(from) = message.from.as_deref() {
+            let email = address::email_of(from);
+            let display = address::display(address::name_of(from).as_deref(), &email);
+            by_sender.add(&email, &display, bytes);
+        }
+        let age = message
+            .date
+            .as_deref()
+            .and_then(dates::parse_header)
+            .map(|date| now.fixed_offset().signed_duration_since(date).num_days())
+            .map_or("undated", |days| {
+                AGES.iter()
+                    .find(|(_, limit)| days < *limit)
+                    .map_or("2y+", |(label, _)| label)
+            });
+        by_age.add(age, age, bytes);
+    }
+
+    let ages = AGES
+        .iter()
+        .map(|(label, _)| *label)
+        .chain(["undated"])
+        .filter_map(|label| by_age.take(label))
+        .collect::<Vec<_>>();
+
+    let mut largest = messages.iter().collect::<Vec<_>>();
+    largest.sort_by(|a, b| size(b).cmp(&size(a)).then(a.id.cmp(&b.id)));
+    largest.truncate(top);
+
+    UsageReport {
+        query: query.map(str::to_string),
+        messages: messages.len(),
+        bytes: messages.iter().map(size).sum(),
+        labels: by_label.largest(top),
+        senders: by_sender.largest(top),
+        ages,
+        largest: largest
+            .into_iter()
+            .map(|message| LargeMessage {
+                id: message.id.clone(),
+                from: message.from.clone(),
+                subject: message.subject.clone(),
+                date: message.date.clone(),
+                bytes: size(message),
+            })
+            .collect(),
+    }
+}
+
+impl UsageReport {
+    /// The report as long-form rows, so every section fits one CSV or table.
+    pub fn rows(&self) -> Vec<UsageRow> {
+        let row = |section: &str, group: &UsageGroup| UsageRow {
+            section: section.to_string(),
+            key: group.key.clone(),
+            messages: group.messages,
+            bytes: group.bytes,
+        };
+
+        let mut rows = vec![UsageRow {
+            section: "summary".to_string(),
+            key: "total".to_string(),
+            messages: self.messages,
+            bytes: self.bytes,
+        }];
+        rows.extend(self.labels.iter().map(|group| row("label", group)));
+        rows.extend(self.senders.iter().map(|group| row("sender", group)));
+        rows.extend(self.ages.iter().map(|group| row("age", group)));
+        rows.extend(self.largest.iter().map(|message| UsageRow {
+            section: "message".to_string(),
+            key: message.id.clone(),
+            messages: 1,
+            bytes: message.bytes,
+        }));
+        rows
+    }
+}
+
+/// `2048` → `2.0 KB`; decimal units, as Google reports storage.
+pub fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if value < 1000.0 {
+            break;
+        }
+        value /= 1000.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+/// Totals keyed by an identity, remembering how each key is shown.
+#[derive(Default)]
+struct Groups {
+    totals: HashMap<String, UsageGroup>,
+}
+
+impl Groups {
+    fn add(&mut self, identity: &str, shown: &str, bytes: u64) {
+        let group = self
+            .totals
+            .entry(identity.to_string())
+            .or_insert_with(|| UsageGroup {
+                key: shown.to_string(),
+                messages: 0,
+                bytes: 0,
+            });
+        group.messages += 1;
+        group.bytes += bytes;
+    }
+
+    fn take(&mut self, identity: &str) -> Option<UsageGroup> {
+        self.totals.remove(identity)
+    }
+
+    fn largest(self, top: usize) -> Vec<UsageGroup> {
+        let mut groups = self.totals.into_values().collect::<Vec<_>>();
+        groups.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.key.cmp(&b.key)));
+        groups.truncate(top);
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn message(id: &str, from: &str, date: &str, labels: &[&str], bytes: u64) -> MessageView {
+        MessageView {
+            id: id.to_string(),
+            from: Some(from.to_string()),
+            date: Some(date.to_string()),
+            label_ids: labels.iter().map(|label| label.to_string()).collect(),
+            size_estimate: Some(bytes),
+            ..MessageView::default()
+        }
+    }
+
+    #[test]
+    fn totals_by_label_sender_and_age() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+        let messages = [
+            message(
+                "a",
+                "Ada <ada@example.com>",
+                "Thu, 15 Oct 2026 09:00:00 +0000",
+                &["INBOX", "Label_1"],
+                5_000_000,
+            ),
+            message(
+                "b",
+                "ada@example.com",
+                "Mon, 1 Jun 2026 09:00:00 +0000",
+                &["Label_1"],
+                1_000,
+            ),
+            message(
+                "c",
+                "Bob <bob@example.com>",
+                "Sat, 1 Jan 2022 09:00:00 +0000",
+                &["INBOX"],
+                20_000,
+            ),
+        ];
+        let labels = [LabelView {
+            id: "Label_1".to_string(),
+            name: "Receipts".to_string(),
+            kind: "user".to_string(),
+        }];
+
+        let report = build(Some("in:anywhere"), &messages, &labels, 2, &now);
+        assert_eq!((report.messages, report.bytes), (3, 5_021_000));
+        assert_eq!(report.labels[0].key, "INBOX");
+        assert_eq!(report.labels[1].key, "Receipts");
+        assert_eq!(report.labels[1].bytes, 5_001_000);
+        assert_eq!(report.senders[0].key, "Ada <ada@example.com>");
+        assert_eq!(report.senders[0].messages, 2);
+        let ages = report
+            .ages
+            .iter()
+            .map(|group| group.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ages, ["0-30d", "90d-1y", "2y+"]);
+        assert_eq!(report.largest.len(), 2);
+        assert_eq!(report.largest[0].id, "a");
+        assert_eq!(report.rows()[0].bytes, 5_021_000);
+    }
+
+    #[test]
+    fn formats_sizes_in_decimal_units() {
+        assert_eq!(human_bytes(999), "999 B");
+        assert_eq!(human_bytes(2_048), "2.0 KB");
+        assert_eq!(human_bytes(5_021_000), "5.0 MB");
+        assert_eq!(human_bytes(3_200_000_000), "3.2 GB");
+    }
+}
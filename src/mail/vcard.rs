@@ -0,0 +1,152 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::models::MessageView;
+use crate::mail::{address, ics};
+
+/// One correspondent harvested from message headers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Contact {
+    pub email: String,
+    pub name: Option<String>,
+    /// How many of the scanned messages the address appeared on.
+    pub messages: usize,
+}
+
+/// Collect the unique From, To, and Cc addresses of `messages`, skipping
+/// `exclude` (our own addresses). A contact keeps the first display name seen
+/// that isn't just its address. Most frequent correspondents come first.
+pub fn harvest(messages: &[MessageView], exclude: &[String]) -> Vec<Contact> {
+    let mut contacts = Vec::<Contact>::new();
+    for message in messages {
+        let mut seen = Vec::<String>::new();
+        let headers = [&message.from, &message.to, &message.cc];
+        for entry in headers
+            .into_iter()
+            .flatten()
+            .flat_map(|header| address::split_addresses(header))
+        {
+            let email = address::email_of(&entry);
+            if !email.contains('@') || exclude.contains(&email) || seen.contains(&email) {
+                continue;
+            }
+            let name = address::name_of(&entry).filter(|name| !name.eq_ignore_ascii_case(&email));
+            match contacts.iter_mut().find(|contact| contact.email == email) {
+                Some(contact) => {
+                    contact.messages += 1;
+                    if contact.name.is_none() {
+                        contact.name = name;
+                    }
+                }
+                None => contacts.push(Contact {
+                    email: email.clone(),
+                    name,
+                    messages: 1,
+                }),
+            }
+            seen.push(email);
+        }
+    }
+
+    contacts.sort_by(|a, b| b.messages.cmp(&a.messages).then(a.email.cmp(&b.email)));
+    contacts
+}
+
+/// Render `contact` as a vCard 3.0 entry, the version most address books import.
+pub fn to_vcard(contact: &Contact) -> String {
+    let full_name = contact.name.as_deref().unwrap_or(&contact.email);
+    let (family, given) = match contact.name.as_deref() {
+        Some(name) => match name.split_once(',') {
+            // "Doe, Jane" is already family-name first.
+            Some((family, given)) => (family.trim(), given.trim()),
+            None => match name.rsplit_once(' ') {
+                Some((given, family)) => (family.trim(), given.trim()),
+                None => (name, ""),
+            },
+        },
+        None => ("", ""),
+    };
+
+    [
+        "BEGIN:VCARD".to_string(),
+        "VERSION:3.0".to_string(),
+        format!("FN:{}", escape(full_name)),
+        format!("N:{};{};;;", escape(family), escape(given)),
+        format!("EMAIL;TYPE=INTERNET:{}", contact.email),
+        "END:VCARD".to_string(),
+    ]
+    .iter()
+    .map(|line| ics::fold(line) + "\r\n")
+    .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(from: &str, to: &str, cc: Option<&str>) -> MessageView {
+        MessageView {
+            id: "m1".to_string(),
+            from: Some(from.to_string()),
+            to: Some(to.to_string()),
+            cc: cc.map(str::to_string),
+            ..MessageView::default()
+        }
+    }
+
+    #[test]
+    fn harvests_unique_addresses_without_our_own() {
+        let messages = [
+            message("ada@example.com", "Me <me@example.com>", None),
+            message(
+                "Me <me@example.com>",
+                "\"Lovelace, Ada\" <ADA@example.com>, Bob <bob@example.com>",
+                Some("bob@example.com"),
+            ),
+        ];
+        let contacts = harvest(&messages, &["me@example.com".to_string()]);
+
+        assert_eq!(
+            contacts,
+            [
+                Contact {
+                    email: "ada@example.com".to_string(),
+                    name: Some("Lovelace, Ada".to_string()),
+                    messages: 2,
+                },
+                Contact {
+                    email: "bob@example.com".to_string(),
+                    name: Some("Bob".to_string()),
+                    messages: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_vcard_names() {
+        let card = to_vcard(&Contact {
+            email: "ada@example.com".to_string(),
+            name: Some("Lovelace, Ada".to_string()),
+            messages: 1,
+        });
+        assert_eq!(
+            card,
+            "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Lovelace\\, Ada\r\nN:Lovelace;Ada;;;\r\n\
+             EMAIL;TYPE=INTERNET:ada@example.com\r\nEND:VCARD\r\n"
+        );
+
+        let card = to_vcard(&Contact {
+            email: "bob@example.com".to_string(),
+            name: None,
+            messages: 1,
+        });
+        assert!(card.contains("FN:bob@example.com\r\nN:;;;;\r\n"));
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::error::{AppError, AppResult};
+
+/// Parse a local date and time such as `mon 9:30am`, `tomorrow 14:00`,
+/// `2025-03-04 9am`, or `2025-03-04T09:30`, relative to `now`.
+///
+/// The day is `today`, `tomorrow`, a weekday (its next occurrence; today only
+/// if the time is still ahead), or an ISO date. Without a day the time is
+/// today's if still ahead, else tomorrow's. A time of day is required.
+pub fn parse_when(text: &str, now: NaiveDateTime) -> AppResult<NaiveDateTime> {
+    let invalid = |reason: &str| {
+        AppError::InvalidInput(format!(
+            "cannot read `{text}` as a date and time ({reason}); try `mon 9:30am` or `2025-03-04 14:00`"
+        ))
+    };
+
+    let lowered = text.trim().to_ascii_lowercase();
+    // `2025-03-04T09:30` is a day and a time.
+    let words = lowered
+        .split_whitespace()
+        .flat_map(|word| match word.split_once('t') {
+            Some((date, time)) if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() => {
+                vec![date, time]
+            }
+            _ => vec![word],
+        });
+
+    let mut day = None::<Day>;
+    let mut time = None::<NaiveTime>;
+    let mut tokens = words.peekable();
+    while let Some(token) = tokens.next() {
+        if let Some(parsed) = parse_day(token) {
+            if day.replace(parsed).is_some() {
+                return Err(invalid("more than one day"));
+            }
+            continue;
+        }
+        // Allow `9:30 am` as well as `9:30am`.
+        let token = match tokens.peek() {
+            Some(&suffix @ ("am" | "pm")) => {
+                tokens.next();
+                format!("{token}{suffix}")
+            }
+            _ => token.to_string(),
+        };
+        let parsed =
+            parse_time(&token).ok_or_else(|| invalid(&format!("unknown word `{token}`")))?;
+        if time.replace(parsed).is_some() {
+            return Err(invalid("more than one time"));
+        }
+    }
+
+    let time = time.ok_or_else(|| invalid("no time of day"))?;
+    let today = now.date();
+    let date = match day {
+        Some(Day::Date(date)) => date,
+        Some(Day::Offset(days)) => today + Duration::days(days),
+        Some(Day::Weekday(weekday)) => {
+            let ahead = (7 + weekday.num_days_from_monday() as i64
+                - today.weekday().num_days_from_monday() as i64)
+                % 7;
+            let ahead = if ahead == 0 && time <= now.time() {
+                7
+            } else {
+                ahead
+            };
+            today + Duration::days(ahead)
+        }
+        None if time > now.time() => today,
+        None => today + Duration::days(1),
+    };
+    Ok(date.and_time(time))
+}
+
+/// Parse a duration such as `30m`, `1h`, `1h30m`, or `90` (minutes).
+pub fn parse_duration(text: &str) -> AppResult<Duration> {
+    let invalid = || {
+        AppError::InvalidInput(format!(
+            "cannot read `{text}` as a duration; try `30m`, `1h`, or `1h30m`"
+        ))
+    };
+    let text = text.trim().to_ascii_lowercase();
+    if let Ok(minutes) = text.parse::<i64>() {
+        return (minutes > 0)
+            .then(|| Duration::minutes(minutes))
+            .ok_or_else(invalid);
+    }
+
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+    for ch in text.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let amount = digits.parse::<i64>().map_err(|_| invalid())?;
+        digits.clear();
+        total += match ch {
+            'd' => Duration::days(amount),
+            'h' => Duration::hours(amount),
+            'm' => Duration::minutes(amount),
+            's' => Duration::seconds(amount),
+            _ => return Err(invalid()),
+        };
+    }
+    if !digits.is_empty() || total <= Duration::zero() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+enum Day {
+    Date(NaiveDate),
+    Offset(i64),
+    Weekday(Weekday),
+}
+
+fn parse_day(token: &str) -> Option<Day> {
+    match token {
+        "today" => return Some(Day::Offset(0)),
+        "tomorrow" => return Some(Day::Offset(1)),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+        return Some(Day::Date(date));
+    }
+    // `mon`, `tues`, `thursday`, ... but not single letters.
+    const WEEKDAYS: [(&str, Weekday); 7] = [
+        ("monday", Weekday::Mon),
+        ("tuesday", Weekday::Tue),
+        ("wednesday", Weekday::Wed),
+        ("thursday", Weekday::Thu),
+        ("friday", Weekday::Fri),
+        ("saturday", Weekday::Sat),
+        ("sunday", Weekday::Sun),
+    ];
+    WEEKDAYS
+        .iter()
+        .find(|(name, _)| token.len() >= 3 && name.starts_with(token))
+        .map(|(_, weekday)| Day::Weekday(*weekday))
+}
+
+fn parse_time(token: &str) -> Option<NaiveTime> {
+    if token == "noon" {
+        return NaiveTime::from_hms_opt(12, 0, 0);
+    }
+    let (clock, meridiem) = match token.strip_suffix("am") {
+        Some(clock) => (clock, Some(false)),
+        None => match token.strip_suffix("pm") {
+            Some(clock) => (clock, Some(true)),
+            None => (token, None),
+        },
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => {
+            (hour.parse::<u32>().ok()?, minute.parse().ok()?)
+        }
+        Some(_) => return None,
+        // A bare number is only a time with am/pm (`9am`), not `9`.
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn parses_days_and_times_relative_to_now() {
+        // A Wednesday afternoon.
+        let now = at("2025-03-05 15:00");
+        assert_eq!(
+            parse_when("mon 9:30am", now).unwrap(),
+            at("2025-03-10 09:30")
+        );
+        assert_eq!(parse_when("Wed 4 pm", now).unwrap(), at("2025-03-05 16:00"));
+        assert_eq!(
+            parse_when("wednesday 9am", now).unwrap(),
+            at("2025-03-12 09:00")
+        );
+        assert_eq!(
+            parse_when("tomorrow noon", now).unwrap(),
+            at("2025-03-06 12:00")
+        );
+        assert_eq!(parse_when("14:00", now).unwrap(), at("2025-03-06 14:00"));
+        assert_eq!(
+            parse_when("12am today", now).unwrap(),
+            at("2025-03-05 00:00")
+        );
+        assert_eq!(
+            parse_when("2025-04-01T09:05", now).unwrap(),
+            at("2025-04-01 09:05")
+        );
+        assert_eq!(
+            parse_when("2025-04-01 2pm", now).unwrap(),
+            at("2025-04-01 14:00")
+        );
+
+        assert!(parse_when("monday", now).is_err());
+        assert!(parse_when("mon 9", now).is_err());
+        assert!(parse_when("13pm", now).is_err());
+        assert!(parse_when("mon tue 9am", now).is_err());
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("45").unwrap(), Duration::minutes(45));
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("soon").is_err());
+    }
+}
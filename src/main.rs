@@ -1,11 +1,17 @@
 use clap::Parser;
+use gmail::output::OutputMode;
 
 #[tokio::main]
 async fn main() {
-    let cli = gmail::cli::Cli::parse();
+    let args = std::env::args_os().collect::<Vec<_>>();
+    let cli = gmail::cli::Cli::parse_from(&args);
+    let json_errors = cli.json || matches!(cli.output, Some(OutputMode::Json | OutputMode::Ndjson));
 
-    if let Err(err) = gmail::run(cli).await {
-        eprintln!("error: {err}");
-        std::process::exit(1);
+    if let Err(err) = gmail::run(cli, args).await {
+        match serde_json::to_string(&serde_json::json!({ "error": err.report() })) {
+            Ok(line) if json_errors => eprintln!("{line}"),
+            _ => eprintln!("{}: {err}", gmail::output::text::error_prefix()),
+        }
+        std::process::exit(err.exit_code());
     }
 }
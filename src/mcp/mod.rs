@@ -0,0 +1,176 @@
+pub mod tools;
+
+use serde_json::{Value, json};
+use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::context::AppContext;
+use crate::error::AppResult;
+
+use self::tools::Tool;
+
+/// Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// A Model Context Protocol server over stdio: newline-delimited JSON-RPC
+/// requests on stdin, responses on stdout, diagnostics on stderr.
+#[derive(Debug)]
+pub struct Server {
+    tools: Vec<Tool>,
+}
+
+impl Server {
+    /// Serve the given tools; anything else is reported as unknown.
+    pub fn new(tools: Vec<Tool>) -> Self {
+        Self { tools }
+    }
+
+    /// Answer requests until stdin closes. Requests are handled one at a time.
+    pub async fn serve(&self, ctx: &AppContext) -> AppResult<()> {
+        let mut lines = BufReader::new(io::stdin()).lines();
+        let mut stdout = io::stdout();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some(response) = self.handle(ctx, &line).await else {
+                continue;
+            };
+            let mut bytes = serde_json::to_vec(&response)?;
+            bytes.push(b'\n');
+            stdout.write_all(&bytes).await?;
+            stdout.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// The response to one JSON-RPC message, or `None` for a notification.
+    async fn handle(&self, ctx: &AppContext, line: &str) -> Option<Value> {
+        let message = match serde_json::from_str::<Value>(line) {
+            Ok(message) => message,
+            Err(err) => {
+                return Some(error(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("parse error: {err}"),
+                ));
+            }
+        };
+        let method = message.get("method").and_then(Value::as_str);
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        // Notifications (no id) such as `notifications/initialized` get no reply.
+        let id = message.get("id").cloned()?;
+
+        let result = match method {
+            Some("tools/call") => self.call(ctx, params).await,
+            Some(method) => self.respond(method, &params),
+            None => Err((INVALID_REQUEST, "request has no method".to_string())),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error(id, code, message),
+        })
+    }
+
+    /// Methods answered without touching the mailbox.
+    fn respond(&self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(initialize(params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({
+                "tools": self.tools.iter().map(|tool| tool.definition()).collect::<Vec<_>>(),
+            })),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method `{method}`"))),
+        }
+    }
+
+    /// Run a `tools/call`. Failures inside the tool come back as an `isError`
+    /// result the agent can read, not as a protocol error.
+    async fn call(&self, ctx: &AppContext, params: Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "tools/call needs a tool name".to_string()))?;
+        let tool = Tool::from_name(name)
+            .filter(|tool| self.tools.contains(tool))
+            .ok_or_else(|| {
+                (
+                    INVALID_PARAMS,
+                    format!("unknown or disallowed tool `{name}`"),
+                )
+            })?;
+        let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+
+        tracing::debug!(tool = name, "mcp tool call");
+        Ok(match tools::call(ctx, tool, arguments).await {
+            Ok(value) => json!({
+                "content": [{
+                    "type": "text",
+                    "text": serde_json::to_string_pretty(&value).unwrap_or_default(),
+                }],
+                "isError": false,
+            }),
+            Err(err) => json!({
+                "content": [{ "type": "text", "text": err.to_string() }],
+                "isError": true,
+            }),
+        })
+    }
+}
+
+/// The `initialize` result: the client's protocol version when we speak it,
+/// else our newest, and a tools-only capability set.
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|version| PROTOCOL_VERSIONS.contains(version))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": {
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn error(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_protocol_version() {
+        let result = initialize(&json!({ "protocolVersion": "2024-11-05" }));
+        assert_eq!(result["protocolVersion"], "2024-11-05");
+        assert_eq!(result["capabilities"], json!({ "tools": {} }));
+
+        let result = initialize(&json!({ "protocolVersion": "1999-01-01" }));
+        assert_eq!(result["protocolVersion"], PROTOCOL_VERSIONS[0]);
+    }
+
+    #[test]
+    fn lists_only_allowed_tools() {
+        let server = Server::new(vec![Tool::GetMessage]);
+        let listed = server.respond("tools/list", &Value::Null).unwrap();
+        assert_eq!(listed["tools"].as_array().unwrap().len(), 1);
+        assert_eq!(listed["tools"][0]["name"], "get_message");
+
+        let (code, _) = server.respond("resources/list", &Value::Null).unwrap_err();
+        assert_eq!(code, METHOD_NOT_FOUND);
+    }
+}
@@ -0,0 +1,341 @@
+use schemars::{JsonSchema, Schema, schema_for};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::cli::SendArgs;
+use crate::commands;
+use crate::config::Settings;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+
+/// Messages returned by the list tools when `limit` is not given.
+const DEFAULT_LIMIT: u32 = 20;
+
+/// Groups allowed when the profile has no `mcp.allow` setting.
+const DEFAULT_ALLOW: &[&str] = &["read"];
+
+/// An operation exposed to agents as an MCP tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    ListMessages,
+    SearchMessages,
+    GetMessage,
+    ListLabels,
+    AddLabels,
+    RemoveLabels,
+    SendMessage,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 7] = [
+        Tool::ListMessages,
+        Tool::SearchMessages,
+        Tool::GetMessage,
+        Tool::ListLabels,
+        Tool::AddLabels,
+        Tool::RemoveLabels,
+        Tool::SendMessage,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ListMessages => "list_messages",
+            Self::SearchMessages => "search_messages",
+            Self::GetMessage => "get_message",
+            Self::ListLabels => "list_labels",
+            Self::AddLabels => "add_labels",
+            Self::RemoveLabels => "remove_labels",
+            Self::SendMessage => "send_message",
+        }
+    }
+
+    /// The `mcp.allow` group that enables this tool.
+    pub fn group(self) -> &'static str {
+        match self {
+            Self::ListMessages | Self::SearchMessages | Self::GetMessage | Self::ListLabels => {
+                "read"
+            }
+            Self::AddLabels | Self::RemoveLabels => "label",
+            Self::SendMessage => "send",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::ListMessages => {
+                "List recent messages (newest first) with their headers and snippet, optionally narrowed to the inbox or a Gmail search query."
+            }
+            Self::SearchMessages => {
+                "Search messages with Gmail search syntax (e.g. `from:ada is:unread newer_than:7d`)."
+            }
+            Self::GetMessage => {
+                "Fetch one message by id, including its decoded plain-text body and attachment names."
+            }
+            Self::ListLabels => "List the mailbox's system and user labels.",
+            Self::AddLabels => "Add labels (names or ids) to a message.",
+            Self::RemoveLabels => {
+                "Remove labels (names or ids) from a message; removing INBOX archives it."
+            }
+            Self::SendMessage => {
+                "Send an email with a markdown body, or reply to a message by id (recipients and subject are then derived from it)."
+            }
+        }
+    }
+
+    fn input_schema(self) -> Schema {
+        match self {
+            Self::ListMessages => schema_for!(ListParams),
+            Self::SearchMessages => schema_for!(SearchParams),
+            Self::GetMessage => schema_for!(GetParams),
+            Self::ListLabels => schema_for!(NoParams),
+            Self::AddLabels | Self::RemoveLabels => schema_for!(LabelParams),
+            Self::SendMessage => schema_for!(SendParams),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tool| tool.name() == name)
+    }
+
+    /// The entry for this tool in a `tools/list` response.
+    pub fn definition(self) -> Value {
+        json!({
+            "name": self.name(),
+            "description": self.description(),
+            "inputSchema": self.input_schema(),
+            "annotations": {
+                "readOnlyHint": self.group() == "read",
+                "destructiveHint": false,
+                "openWorldHint": self == Self::SendMessage,
+            },
+        })
+    }
+}
+
+/// The tools the profile's `mcp.allow` setting permits, in `Tool::ALL` order.
+/// Entries are group names (`read`, `label`, `send`), tool names, or `all`.
+pub fn allowed(settings: &Settings) -> AppResult<Vec<Tool>> {
+    let configured = settings
+        .mcp
+        .as_ref()
+        .map(|mcp| mcp.allow.clone())
+        .filter(|allow| !allow.is_empty())
+        .unwrap_or_else(|| {
+            DEFAULT_ALLOW
+                .iter()
+                .map(|entry| entry.to_string())
+                .collect()
+        });
+
+    for entry in &configured {
+        let known = entry == "all"
+            || Tool::ALL
+                .iter()
+                .any(|tool| tool.group() == entry || tool.name() == entry);
+        if !known {
+            return Err(AppError::Config(format!(
+                "unknown mcp.allow entry `{entry}`; use read, label, send, all, or a tool name"
+            )));
+        }
+    }
+
+    Ok(Tool::ALL
+        .into_iter()
+        .filter(|tool| {
+            configured
+                .iter()
+                .any(|entry| entry == "all" || entry == tool.group() || entry == tool.name())
+        })
+        .collect())
+}
+
+/// Run `tool` with the call's `arguments`, returning the JSON the equivalent
+/// command would print with `--json`.
+pub async fn call(ctx: &AppContext, tool: Tool, arguments: Value) -> AppResult<Value> {
+    let access_token = ctx.access_token().await?;
+    let client = &ctx.gmail_client;
+    let value = match tool {
+        Tool::ListMessages => {
+            let params = parse::<ListParams>(arguments)?;
+            let query = match (params.inbox, params.query.as_deref()) {
+                (true, Some(query)) => Some(format!("in:inbox {query}")),
+                (true, None) => Some("in:inbox".to_string()),
+                (false, query) => query.map(str::to_string),
+            };
+            let limit = limit(params.limit)?;
+            serde_json::to_value(client.list(&access_token, limit, query.as_deref()).await?)?
+        }
+        Tool::SearchMessages => {
+            let params = parse::<SearchParams>(arguments)?;
+            let limit = limit(params.limit)?;
+            serde_json::to_value(
+                client
+                    .list(&access_token, limit, Some(&params.query))
+                    .await?,
+            )?
+        }
+        Tool::GetMessage => {
+            let params = parse::<GetParams>(arguments)?;
+            serde_json::to_value(client.get_msg_full(&params.id, &access_token).await?)?
+        }
+        Tool::ListLabels => serde_json::to_value(client.list_labels(&access_token).await?)?,
+        Tool::AddLabels => {
+            let params = parse::<LabelParams>(arguments)?;
+            let result = client
+                .add_labels(&params.id, &params.labels, &access_token)
+                .await?;
+            serde_json::to_value(result)?
+        }
+        Tool::RemoveLabels => {
+            let params = parse::<LabelParams>(arguments)?;
+            let result = client
+                .rm_labels(&params.id, &params.labels, &access_token)
+                .await?;
+            serde_json::to_value(result)?
+        }
+        Tool::SendMessage => {
+            let params = parse::<SendParams>(arguments)?;
+            let args = SendArgs {
+                to: params.to,
+                cc: params.cc,
+                bcc: params.bcc,
+                subject: params.subject,
+                body: Some(params.body),
+                reply: params.reply_to,
+                ..SendArgs::default()
+            };
+            serde_json::to_value(commands::send::submit(ctx, args).await?)?
+        }
+    };
+    Ok(value)
+}
+
+fn parse<T: DeserializeOwned>(arguments: Value) -> AppResult<T> {
+    let arguments = match arguments {
+        Value::Null => json!({}),
+        other => other,
+    };
+    serde_json::from_value(arguments)
+        .map_err(|err| AppError::InvalidInput(format!("invalid tool arguments: {err}")))
+}
+
+fn limit(limit: Option<u32>) -> AppResult<u32> {
+    match limit.unwrap_or(DEFAULT_LIMIT) {
+        0 => Err(AppError::InvalidInput(
+            "limit must be greater than 0".to_string(),
+        )),
+        limit => Ok(limit),
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct NoParams {}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct ListParams {
+    /// Only messages in the inbox.
+    #[serde(default)]
+    inbox: bool,
+    /// Optional Gmail search query to narrow the list.
+    query: Option<String>,
+    /// Maximum messages to return (default 20).
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct SearchParams {
+    /// Gmail search query, e.g. `from:ada has:attachment`.
+    query: String,
+    /// Maximum messages to return (default 20).
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct GetParams {
+    /// Gmail message id.
+    id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct LabelParams {
+    /// Gmail message id.
+    id: String,
+    /// Label names or ids.
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct SendParams {
+    /// Recipient addresses; optional when replying.
+    #[serde(default)]
+    to: Vec<String>,
+    #[serde(default)]
+    cc: Vec<String>,
+    #[serde(default)]
+    bcc: Vec<String>,
+    /// Subject; optional when replying.
+    subject: Option<String>,
+    /// Message body in markdown.
+    body: String,
+    /// Id of the message to reply to, threading the reply under it.
+    reply_to: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::McpSettings;
+
+    fn settings(allow: &[&str]) -> Settings {
+        Settings {
+            mcp: Some(McpSettings {
+                allow: allow.iter().map(|entry| entry.to_string()).collect(),
+            }),
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn allows_read_only_tools_by_default() {
+        let tools = allowed(&Settings::default()).unwrap();
+        assert!(tools.iter().all(|tool| tool.group() == "read"));
+        assert!(tools.contains(&Tool::SearchMessages));
+        assert!(!tools.contains(&Tool::SendMessage));
+    }
+
+    #[test]
+    fn mixes_groups_and_tool_names() {
+        let tools = allowed(&settings(&["label", "get_message"])).unwrap();
+        assert_eq!(
+            tools,
+            [Tool::GetMessage, Tool::AddLabels, Tool::RemoveLabels]
+        );
+        assert_eq!(allowed(&settings(&["all"])).unwrap(), Tool::ALL);
+        assert!(allowed(&settings(&["delete"])).is_err());
+    }
+
+    #[test]
+    fn input_schemas_are_objects() {
+        for tool in Tool::ALL {
+            let definition = tool.definition();
+            assert_eq!(
+                definition["inputSchema"]["type"],
+                "object",
+                "{}",
+                tool.name()
+            );
+            assert_eq!(Tool::from_name(tool.name()), Some(tool));
+        }
+        assert_eq!(
+            Tool::SearchMessages.definition()["inputSchema"]["required"],
+            json!(["query"])
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, Datelike, FixedOffset, Local, TimeZone};
+
+use crate::error::{AppError, AppResult};
+
+/// How message dates are shown in text output, from the `date_format` setting.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DateFormat {
+    /// "just now", "5m ago", "2h ago", "yesterday 14:03", "Mon 09:12",
+    /// "Mar 5", then "2023-03-05" for older years (the default)
+    #[default]
+    Relative,
+    /// `2024-03-05 14:03` in local time
+    Iso,
+    /// The Date header exactly as sent
+    Raw,
+    /// A strftime pattern rendered in local time, e.g. `%d.%m.%Y %H:%M`
+    Pattern(String),
+}
+
+impl DateFormat {
+    /// Parse a `date_format` setting: `relative`, `iso`, `raw`, or a strftime pattern.
+    pub fn parse(setting: Option<&str>) -> AppResult<Self> {
+        let Some(setting) = setting.map(str::trim).filter(|value| !value.is_empty()) else {
+            return Ok(Self::Relative);
+        };
+
+        match setting {
+            "relative" => Ok(Self::Relative),
+            "iso" => Ok(Self::Iso),
+            "raw" => Ok(Self::Raw),
+            pattern => {
+                StrftimeItems::new(pattern).parse().map_err(|_| {
+                    AppError::Config(format!(
+                        "invalid date_format `{pattern}`; use relative, iso, raw, or a strftime pattern"
+                    ))
+                })?;
+                Ok(Self::Pattern(pattern.to_string()))
+            }
+        }
+    }
+
+    /// Render a Date header value; unparseable headers are shown unchanged.
+    pub fn format(&self, raw: &str) -> String {
+        let when = match (self, parse_header(raw)) {
+            (Self::Raw, _) | (_, None) => return raw.to_string(),
+            (_, Some(when)) => when.with_timezone(&Local),
+        };
+
+        match self {
+            Self::Relative => relative(&when, &Local::now()),
+            Self::Pattern(pattern) => when.format(pattern).to_string(),
+            Self::Iso | Self::Raw => when.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+}
+
+/// Parse an RFC 2822 Date header, tolerating a trailing comment such as `(UTC)`.
+/// RFC 3339 (a message's `internal_date`) is accepted too.
+pub fn parse_header(raw: &str) -> Option<DateTime<FixedOffset>> {
+    let trimmed = raw.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc2822(trimmed) {
+        return Some(parsed);
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(trimmed) {
+        return Some(parsed);
+    }
+
+    let without_comment = match trimmed.rfind('(') {
+        Some(start) if trimmed.ends_with(')') => trimmed[..start].trim_end(),
+        _ => trimmed,
+    };
+    DateTime::parse_from_rfc2822(without_comment).ok()
+}
+
+/// Describe `when` relative to `now`, both in the same (display) time zone.
+pub fn relative<Tz: TimeZone>(when: &DateTime<Tz>, now: &DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let elapsed = now.clone().signed_duration_since(when.clone());
+    let days_apart = (now.date_naive() - when.date_naive()).num_days();
+
+    if elapsed.num_seconds() < 0 {
+        // Clock skew or a future-dated header: show the absolute time.
+        return when.format("%Y-%m-%d %H:%M").to_string();
+    }
+    if elapsed.num_minutes() < 1 {
+        return "just now".to_string();
+    }
+    if elapsed.num_hours() < 1 {
+        return format!("{}m ago", elapsed.num_minutes());
+    }
+    if days_apart == 0 {
+        return format!("{}h ago", elapsed.num_hours());
+    }
+    if days_apart == 1 {
+        return when.format("yesterday %H:%M").to_string();
+    }
+    if days_apart < 7 {
+        return when.format("%a %H:%M").to_string();
+    }
+    if when.year() == now.year() {
+        return when.format("%b %-d").to_string();
+    }
+    when.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn describes_recent_and_older_dates() {
+        let now = at("2024-03-08T15:30:00Z");
+        assert_eq!(relative(&at("2024-03-08T15:29:40Z"), &now), "just now");
+        assert_eq!(relative(&at("2024-03-08T15:05:00Z"), &now), "25m ago");
+        assert_eq!(relative(&at("2024-03-08T13:10:00Z"), &now), "2h ago");
+        assert_eq!(
+            relative(&at("2024-03-07T14:03:00Z"), &now),
+            "yesterday 14:03"
+        );
+        assert_eq!(relative(&at("2024-03-04T09:12:00Z"), &now), "Mon 09:12");
+        assert_eq!(relative(&at("2024-01-05T09:12:00Z"), &now), "Jan 5");
+        assert_eq!(relative(&at("2023-03-05T09:12:00Z"), &now), "2023-03-05");
+        assert_eq!(
+            relative(&at("2024-03-09T09:00:00Z"), &now),
+            "2024-03-09 09:00"
+        );
+    }
+
+    #[test]
+    fn parses_headers_with_trailing_comments() {
+        let parsed = parse_header("Tue, 5 Mar 2024 14:03:00 +0000 (UTC)").unwrap();
+        assert_eq!(parsed.to_rfc3339(), "2024-03-05T14:03:00+00:00");
+        assert_eq!(parse_header("2024-03-05T14:03:00Z"), Some(parsed));
+        assert!(parse_header("not a date").is_none());
+    }
+
+    #[test]
+    fn parses_settings() {
+        assert_eq!(DateFormat::parse(None).unwrap(), DateFormat::Relative);
+        assert_eq!(DateFormat::parse(Some("iso")).unwrap(), DateFormat::Iso);
+        assert_eq!(
+            DateFormat::parse(Some("%d.%m.%Y")).unwrap(),
+            DateFormat::Pattern("%d.%m.%Y".to_string())
+        );
+        assert!(DateFormat::parse(Some("%Q")).is_err());
+        assert_eq!(DateFormat::Raw.format("whenever"), "whenever");
+        assert_eq!(DateFormat::Iso.format("garbage"), "garbage");
+    }
+}
@@ -0,0 +1,51 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use crate::error::{AppError, AppResult};
+
+/// Replace `path` with `bytes` atomically: write a temporary file beside it,
+/// sync it, then rename over the target so readers never see a partial file.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> AppResult<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        AppError::InvalidInput(format!("--out `{}` is not a file path", path.display()))
+    })?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".tmp-{}", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+    if let Err(err) = written.and_then(|()| fs::rename(&temp_path, path)) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(AppError::Io(err));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_file_contents_without_leaving_temp_files() {
+        let dir = std::env::temp_dir().join(format!("gmail-out-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("result.json");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_paths_without_a_file_name() {
+        assert!(write_atomic(Path::new("/"), b"x").is_err());
+    }
+}
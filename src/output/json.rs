@@ -4,7 +4,23 @@ use crate::error::AppResult;
 
 /// Serialize a value as pretty JSON and print it to stdout.
 pub fn print<T: Serialize>(value: &T) -> AppResult<()> {
-    let payload = serde_json::to_string_pretty(value)?;
-    println!("{payload}");
+    print!("{}", render(value)?);
+    Ok(())
+}
+
+/// Serialize a value as pretty JSON with a trailing newline.
+pub fn render<T: Serialize>(value: &T) -> AppResult<String> {
+    Ok(format!("{}\n", serde_json::to_string_pretty(value)?))
+}
+
+/// Serialize a value as a single line of compact JSON (one NDJSON record) and
+/// flush stdout so downstream consumers see it immediately.
+pub fn print_line<T: Serialize>(value: &T) -> AppResult<()> {
+    use std::io::Write;
+
+    let payload = serde_json::to_string(value)?;
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{payload}")?;
+    stdout.flush()?;
     Ok(())
 }
@@ -0,0 +1,165 @@
+use std::io::IsTerminal;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Ellipsis appended to text cut short to fit a column.
+pub const ELLIPSIS: char = '…';
+
+/// Width of stdout in columns when it is a terminal, else `None` so piped
+/// output keeps a stable, width-independent shape.
+pub fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    let (terminal_size::Width(width), _) = terminal_size::terminal_size()?;
+    Some(usize::from(width)).filter(|width| *width > 0)
+}
+
+/// Display width of `text` in terminal cells (CJK and emoji count as two).
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+/// Cells taken by one grapheme cluster. Clusters such as ZWJ emoji sequences
+/// render as a single glyph, so they are capped at two cells.
+fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width().min(2)
+}
+
+/// Cut `text` to at most `width` cells, ending in an ellipsis when shortened.
+/// Cuts fall between grapheme clusters, so emoji and combining marks stay whole.
+pub fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let (head, _) = split_at_width(text, width - 1);
+    format!("{head}{ELLIPSIS}")
+}
+
+/// Split `text` after the last whole grapheme that fits in `width` cells.
+fn split_at_width(text: &str, width: usize) -> (&str, &str) {
+    let mut used = 0;
+    for (offset, grapheme) in text.grapheme_indices(true) {
+        used += grapheme_width(grapheme);
+        if used > width {
+            return text.split_at(offset);
+        }
+    }
+    (text, "")
+}
+
+/// Word-wrap `text` into lines of at most `width` cells, breaking words
+/// longer than a line. Whitespace runs collapse to single spaces.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word = word;
+        loop {
+            let line_width = display_width(&line);
+            let needed = if line.is_empty() { 0 } else { 1 } + display_width(word);
+            if line_width + needed <= width {
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                break;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                continue;
+            }
+            // The word alone is wider than a line: hard-break it, keeping at
+            // least one grapheme per line so wide glyphs can't stall the loop.
+            let (mut head, mut rest) = split_at_width(word, width);
+            if head.is_empty() {
+                let first = word.graphemes(true).next().unwrap_or(word);
+                (head, rest) = word.split_at(first.len());
+            }
+            lines.push(head.to_string());
+            word = rest;
+            if word.is_empty() {
+                break;
+            }
+        }
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Wrap `text` to `width` and keep at most `max_lines`, ellipsizing the last
+/// kept line when anything was dropped.
+pub fn wrap_lines(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines = wrap(text, width);
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            let kept = truncate(last, width.saturating_sub(1));
+            *last = format!("{}{ELLIPSIS}", kept.trim_end_matches(ELLIPSIS));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_with_ellipsis() {
+        assert_eq!(truncate("hello", 10), "hello");
+        assert_eq!(truncate("hello world", 6), "hello…");
+        assert_eq!(truncate("héllo wörld", 3), "hé…");
+        assert_eq!(truncate("abc", 0), "");
+    }
+
+    #[test]
+    fn measures_wide_and_clustered_glyphs() {
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("👩‍👩‍👧"), 2);
+        assert_eq!(display_width("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn never_splits_grapheme_clusters() {
+        assert_eq!(truncate("日本語テキスト", 7), "日本語…");
+        assert_eq!(truncate("hi 👩‍👩‍👧 family", 6), "hi 👩‍👩‍👧…");
+        assert_eq!(truncate("hi 👩‍👩‍👧 family", 5), "hi …");
+        assert_eq!(truncate("cafe\u{301} noir", 5), "cafe\u{301}…");
+        assert!(display_width(&truncate("日本語テキスト", 6)) <= 6);
+    }
+
+    #[test]
+    fn hard_breaks_wide_words_by_cells() {
+        assert_eq!(wrap("日本語テキスト", 4), ["日本", "語テ", "キス", "ト"]);
+        assert_eq!(wrap("日本", 1), ["日", "本"]);
+    }
+
+    #[test]
+    fn wraps_on_word_boundaries() {
+        assert_eq!(
+            wrap("the quick  brown fox jumps", 10),
+            ["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert!(wrap("   ", 10).is_empty());
+    }
+
+    #[test]
+    fn limits_wrapped_lines() {
+        let lines = wrap_lines("one two three four five six", 9, 2);
+        assert_eq!(lines, ["one two", "three…"]);
+        assert!(lines.iter().all(|line| display_width(line) <= 9));
+    }
+}
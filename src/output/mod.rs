@@ -1,30 +1,109 @@
+pub mod dates;
+pub mod file;
 pub mod json;
+pub mod layout;
+pub mod pager;
+pub mod tabular;
 pub mod text;
+pub mod yaml;
+
+use clap::ValueEnum;
+use std::io::Write;
+use std::path::PathBuf;
 
 use serde::Serialize;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+
+use self::dates::DateFormat;
+use self::pager::PagedWriter;
+use self::text::{Role, Theme};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// How command results are rendered, selected with `--output` (or `--json`).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, ValueEnum)]
 pub enum OutputMode {
+    /// Human-readable lines (the default)
+    #[default]
     Text,
+    /// Pretty-printed JSON document
     Json,
+    /// YAML document
+    Yaml,
+    /// Comma-separated values with a header row
+    Csv,
+    /// One compact JSON object per line
+    Ndjson,
+    /// Aligned columns with a header row
+    Table,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Output {
     mode: OutputMode,
+    theme: Theme,
+    pager: Option<String>,
+    width: Option<usize>,
+    out: Option<PathBuf>,
+    dates: DateFormat,
 }
 
 impl Output {
-    /// Create an output handle in JSON or text mode.
-    pub fn new(json: bool) -> Self {
-        let mode = if json {
-            OutputMode::Json
-        } else {
-            OutputMode::Text
-        };
-        Self { mode }
+    /// Create an output handle rendering in `mode`, styling text with `theme`.
+    pub fn new(mode: OutputMode, theme: Theme) -> Self {
+        Self {
+            mode,
+            theme,
+            pager: None,
+            width: None,
+            out: None,
+            dates: DateFormat::default(),
+        }
+    }
+
+    /// Show message dates in text output using `dates`.
+    pub fn with_dates(mut self, dates: DateFormat) -> Self {
+        self.dates = dates;
+        self
+    }
+
+    /// Render a Date header for text output per the `date_format` setting.
+    pub fn date(&self, raw: &str) -> String {
+        self.dates.format(raw)
+    }
+
+    /// Send primary output to `path` instead of stdout. Output bound for a
+    /// file is never colored, paged, or fitted to the terminal.
+    pub fn with_out(mut self, path: Option<PathBuf>) -> Self {
+        if path.is_some() {
+            self.theme = self.theme.without_color();
+            self.pager = None;
+            self.width = None;
+        }
+        self.out = path;
+        self
+    }
+
+    /// Lay text and table output out for a terminal `width` columns wide
+    /// (see [`layout::terminal_width`]); `None` leaves lines unbounded.
+    pub fn with_width(mut self, width: Option<usize>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// The terminal width text output should fit, when writing to a terminal.
+    pub fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    /// Page long text output through `pager` (see [`pager::resolve`]).
+    pub fn with_pager(mut self, pager: Option<String>) -> Self {
+        self.pager = pager;
+        self
+    }
+
+    /// A buffer for long text output; call [`PagedWriter::finish`] to show it.
+    pub fn paged(&self) -> PagedWriter {
+        PagedWriter::new(self.pager.clone(), self.out.clone())
     }
 
     /// The current output mode.
@@ -32,11 +111,77 @@ impl Output {
         self.mode
     }
 
-    /// Print `text_line` in text mode or `json_value` in JSON mode.
+    /// Style a fragment of text output for `role` (a no-op when color is off).
+    pub fn paint(&self, role: Role, text: &str) -> String {
+        self.theme.paint(role, text)
+    }
+
+    /// Refuse `--out` for `command`, which emits until interrupted: the file
+    /// is replaced on every emit, so it would only ever hold the last one.
+    pub fn require_stdout(&self, command: &str) -> AppResult<()> {
+        if self.out.is_some() {
+            return Err(AppError::InvalidInput(format!(
+                "`gmail {command}` does not support --out; redirect stdout to capture the feed"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Print `text_line` in text mode, or render `json_value` in the selected
+    /// structured format. For NDJSON, arrays emit one line per element.
     pub fn emit<T: Serialize>(&self, text_line: &str, json_value: &T) -> AppResult<()> {
-        match self.mode {
-            OutputMode::Text => text::print_line(text_line),
-            OutputMode::Json => json::print(json_value),
+        let rendered = match self.mode {
+            OutputMode::Text => format!("{text_line}\n"),
+            OutputMode::Json => json::render(json_value)?,
+            OutputMode::Yaml => yaml::render(json_value)?,
+            OutputMode::Ndjson => {
+                let records = match serde_json::to_value(json_value)? {
+                    serde_json::Value::Array(items) => items,
+                    value => vec![value],
+                };
+                let mut out = String::new();
+                for record in &records {
+                    out.push_str(&serde_json::to_string(record)?);
+                    out.push('\n');
+                }
+                out
+            }
+            OutputMode::Csv => tabular::render_csv(&serde_json::to_value(json_value)?),
+            OutputMode::Table => {
+                tabular::render_table(&serde_json::to_value(json_value)?, self.width)
+            }
+        };
+        self.write(rendered.as_bytes())
+    }
+
+    /// Write a command's primary output: atomically to the `--out` file when
+    /// set, otherwise to stdout.
+    pub fn write(&self, bytes: &[u8]) -> AppResult<()> {
+        if let Some(path) = &self.out {
+            return file::write_atomic(path, bytes);
         }
+
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(bytes)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feeds_refuse_an_out_file() {
+        let output = Output::new(OutputMode::Json, Theme::default());
+        assert!(output.require_stdout("notify").is_ok());
+
+        let output = output.with_out(Some(PathBuf::from("feed.json")));
+        let err = output.require_stdout("notify").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("`gmail notify` does not support --out")
+        );
     }
 }
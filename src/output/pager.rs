@@ -0,0 +1,176 @@
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::config::PagerSetting;
+use crate::error::AppResult;
+
+use super::{file, layout};
+
+/// Environment variable naming a pager just for this tool, checked before `PAGER`.
+pub const GMAIL_PAGER_ENV: &str = "GMAIL_PAGER";
+
+/// Pager used when neither the settings nor the environment name one.
+const DEFAULT_PAGER: &str = "less";
+
+/// Pick the pager command for this run, or `None` when paging is off.
+///
+/// Paging needs stdout to be a terminal and is disabled by `--no-pager`,
+/// `"pager": false`, or an empty / `cat` pager. The command comes from the
+/// `pager` setting, then `GMAIL_PAGER`, then `PAGER`, then `less`.
+pub fn resolve(no_pager: bool, setting: Option<&PagerSetting>) -> Option<String> {
+    if no_pager || !io::stdout().is_terminal() {
+        return None;
+    }
+
+    let command = match setting {
+        Some(PagerSetting::Enabled(false)) => return None,
+        Some(PagerSetting::Command(command)) => command.clone(),
+        Some(PagerSetting::Enabled(true)) | None => std::env::var(GMAIL_PAGER_ENV)
+            .or_else(|_| std::env::var("PAGER"))
+            .unwrap_or_else(|_| DEFAULT_PAGER.to_string()),
+    };
+
+    let command = command.trim();
+    if command.is_empty() || command == "cat" {
+        return None;
+    }
+    Some(command.to_string())
+}
+
+/// Buffers text output, then either prints it or pipes it through the pager
+/// when it would not fit on one screen (or writes it to the `--out` file).
+#[derive(Debug)]
+pub struct PagedWriter {
+    buffer: Vec<u8>,
+    command: Option<String>,
+    out: Option<PathBuf>,
+}
+
+impl PagedWriter {
+    pub(crate) fn new(command: Option<String>, out: Option<PathBuf>) -> Self {
+        Self {
+            buffer: Vec::new(),
+            command,
+            out,
+        }
+    }
+
+    /// Flush the buffered text to the terminal, paging it if it overflows.
+    pub fn finish(self) -> AppResult<()> {
+        if let Some(path) = &self.out {
+            return file::write_atomic(path, &self.buffer);
+        }
+        if let Some(command) = &self.command
+            && let Some((width, height)) = terminal_dimensions()
+            && screen_lines(&String::from_utf8_lossy(&self.buffer), width) >= height
+            && spawn_pager(command, &self.buffer).is_ok()
+        {
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&self.buffer)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+impl Write for PagedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Number of terminal rows `text` occupies at `width` columns, counting wraps.
+pub fn screen_lines(text: &str, width: usize) -> usize {
+    let width = width.max(1);
+    text.lines()
+        .map(|line| {
+            let columns = layout::display_width(&strip_escapes(line));
+            columns.div_ceil(width).max(1)
+        })
+        .sum()
+}
+
+/// `line` without ANSI SGR escape sequences.
+fn strip_escapes(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            for next in chars.by_ref() {
+                if next == 'm' {
+                    break;
+                }
+            }
+            continue;
+        }
+        out.push(ch);
+    }
+    out
+}
+
+fn terminal_dimensions() -> Option<(usize, usize)> {
+    let (terminal_size::Width(width), terminal_size::Height(height)) =
+        terminal_size::terminal_size()?;
+    Some((usize::from(width), usize::from(height)))
+}
+
+/// Run `command` through the shell with `text` on stdin and wait for it to exit.
+fn spawn_pager(command: &str, text: &[u8]) -> io::Result<()> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.args(["/C", command]);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.args(["-c", command]);
+        process
+    };
+    // Like git: quit if one screen, keep colors, don't clear the screen.
+    if std::env::var_os("LESS").is_none() {
+        process.env("LESS", "FRX");
+    }
+
+    let mut child = process.stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        match stdin.write_all(text) {
+            // The user quit the pager before reading everything.
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {}
+            result => result?,
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_wrapped_screen_lines() {
+        assert_eq!(screen_lines("a\nb\n", 80), 2);
+        assert_eq!(screen_lines("", 80), 0);
+        assert_eq!(screen_lines("\n\n", 80), 2);
+        assert_eq!(screen_lines(&"x".repeat(25), 10), 3);
+        assert_eq!(screen_lines("日本語テキスト", 10), 2);
+    }
+
+    #[test]
+    fn ignores_color_codes_when_measuring() {
+        assert_eq!(screen_lines("\x1b[1;36mabcde\x1b[0m", 5), 1);
+    }
+
+    #[test]
+    fn pager_setting_false_disables_paging() {
+        assert_eq!(resolve(false, Some(&PagerSetting::Enabled(false))), None);
+        assert_eq!(resolve(true, None), None);
+    }
+}
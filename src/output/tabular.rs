@@ -0,0 +1,149 @@
+use serde_json::{Map, Value};
+
+use super::layout;
+
+/// Narrowest a column is squeezed to when fitting a table to the terminal.
+const MIN_COLUMN_WIDTH: usize = 6;
+
+/// Flatten a JSON value into a header row plus data rows: an array of objects
+/// becomes one row per element (columns in first-seen key order), a single
+/// object becomes one row, and scalars become a one-column `value` table.
+/// Nested arrays/objects are rendered as compact JSON in their cell.
+pub fn rows(value: &Value) -> (Vec<String>, Vec<Vec<String>>) {
+    let records: Vec<&Value> = match value {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+
+    if !records.iter().all(|record| record.is_object()) {
+        let rows = records.iter().map(|record| vec![cell(record)]).collect();
+        return (vec!["value".to_string()], rows);
+    }
+
+    let mut columns: Vec<String> = Vec::new();
+    for record in &records {
+        for key in record.as_object().map(Map::keys).into_iter().flatten() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+
+    let rows = records
+        .iter()
+        .map(|record| {
+            columns
+                .iter()
+                .map(|column| record.get(column).map(cell).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    (columns, rows)
+}
+
+/// Render rows as RFC 4180 CSV with a header line.
+pub fn render_csv(value: &Value) -> String {
+    let (columns, rows) = rows(value);
+    let mut out = String::new();
+    for line in std::iter::once(&columns).chain(rows.iter()) {
+        let fields = line
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Render rows as a left-aligned, space-padded table with a header and rule.
+/// With `max_width`, the widest columns are narrowed (cells ellipsized) until
+/// the table fits, down to a minimum column width.
+pub fn render_table(value: &Value, max_width: Option<usize>) -> String {
+    let (columns, rows) = rows(value);
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|field| field.replace(['\r', '\n'], " "))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = columns
+        .iter()
+        .map(|column| layout::display_width(column))
+        .collect::<Vec<_>>();
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(layout::display_width(field));
+        }
+    }
+    if let Some(max_width) = max_width {
+        fit_widths(&mut widths, max_width);
+    }
+
+    let format_line = |fields: &[String]| {
+        fields
+            .iter()
+            .zip(&widths)
+            .map(|(field, width)| {
+                let field = layout::truncate(field, *width);
+                let padding = width.saturating_sub(layout::display_width(&field));
+                format!("{field}{}", " ".repeat(padding))
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = format_line(&columns);
+    out.push('\n');
+    let rule = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>();
+    out.push_str(&rule.join("  "));
+    out.push('\n');
+    for row in &rows {
+        out.push_str(&format_line(row));
+        out.push('\n');
+    }
+    out
+}
+
+/// Shrink the widest columns one step at a time until the row (with two-space
+/// gutters) fits in `max_width` or every column is at the minimum.
+fn fit_widths(widths: &mut [usize], max_width: usize) {
+    let gutters = widths.len().saturating_sub(1) * 2;
+    while widths.iter().sum::<usize>() + gutters > max_width {
+        let Some(widest) = widths
+            .iter_mut()
+            .filter(|width| **width > MIN_COLUMN_WIDTH)
+            .max_by_key(|width| **width)
+        else {
+            break;
+        };
+        *widest -= 1;
+    }
+}
+
+/// Render a JSON value as a single table/CSV cell.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote, or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
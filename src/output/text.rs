@@ -1,7 +1,294 @@
-use crate::error::AppResult;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+
+use crate::config::ThemeSettings;
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+
+/// Environment variable that disables color in `auto` mode (https://no-color.org).
+pub const NO_COLOR_ENV: &str = "NO_COLOR";
 
 /// Print a single line to stdout.
 pub fn print_line(line: &str) -> AppResult<()> {
     println!("{line}");
     Ok(())
 }
+
+/// When to color text output, selected with `--color`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    /// Always emit color codes
+    Always,
+    /// Never emit color codes
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether color should be used for a stream, given whether it is a terminal.
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                is_terminal
+                    && std::env::var_os(NO_COLOR_ENV).is_none_or(|value| value.is_empty())
+                    && std::env::var("TERM").map_or(true, |term| term != "dumb")
+            }
+        }
+    }
+}
+
+/// The kinds of text a theme assigns styles to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Role {
+    Sender,
+    Date,
+    Label,
+    Error,
+}
+
+/// An ANSI text style: an optional 256-color foreground plus attributes.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Style {
+    pub fg: Option<u8>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    /// Parse a space-separated style spec such as `bold cyan`, `dim`,
+    /// `bright-red`, or a 256-color index like `208`.
+    pub fn parse(spec: &str) -> AppResult<Self> {
+        let mut style = Self::default();
+        for token in spec.split_whitespace() {
+            match token.to_ascii_lowercase().as_str() {
+                "bold" => style.bold = true,
+                "dim" => style.dim = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                "none" | "plain" => {}
+                color => {
+                    style.fg = Some(parse_color(color).ok_or_else(|| {
+                        AppError::Config(format!(
+                            "unknown theme color `{token}`; use a name like `cyan`, `bright-red`, or a 0-255 index"
+                        ))
+                    })?);
+                }
+            }
+        }
+        Ok(style)
+    }
+
+    /// Wrap `text` in this style's SGR escape codes.
+    pub fn paint(&self, text: &str) -> String {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if self.dim {
+            codes.push("2".to_string());
+        }
+        if self.italic {
+            codes.push("3".to_string());
+        }
+        if self.underline {
+            codes.push("4".to_string());
+        }
+        match self.fg {
+            Some(color @ 0..=7) => codes.push(format!("{}", 30 + color)),
+            Some(color @ 8..=15) => codes.push(format!("{}", 90 + color - 8)),
+            Some(color) => codes.push(format!("38;5;{color}")),
+            None => {}
+        }
+
+        if codes.is_empty() {
+            return text.to_string();
+        }
+        format!("\x1b[{}m{text}\x1b[0m", codes.join(";"))
+    }
+}
+
+/// Per-role styles for text output, and whether color is enabled at all.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Theme {
+    enabled: bool,
+    sender: Style,
+    date: Style,
+    label: Style,
+    error: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sender: Style {
+                fg: Some(6),
+                bold: true,
+                ..Style::default()
+            },
+            date: Style {
+                dim: true,
+                ..Style::default()
+            },
+            label: Style {
+                fg: Some(3),
+                ..Style::default()
+            },
+            error: Style {
+                fg: Some(1),
+                bold: true,
+                ..Style::default()
+            },
+        }
+    }
+}
+
+impl Theme {
+    /// Build a theme from the profile's `theme` settings over the defaults,
+    /// enabled according to `choice` and whether stdout is a terminal.
+    pub fn resolve(choice: ColorChoice, settings: Option<&ThemeSettings>) -> AppResult<Self> {
+        let mut theme = Self {
+            enabled: choice.enabled(std::io::stdout().is_terminal()),
+            ..Self::default()
+        };
+
+        if let Some(settings) = settings {
+            for (slot, spec) in [
+                (&mut theme.sender, &settings.sender),
+                (&mut theme.date, &settings.date),
+                (&mut theme.label, &settings.label),
+                (&mut theme.error, &settings.error),
+            ] {
+                if let Some(spec) = spec {
+                    *slot = Style::parse(spec)?;
+                }
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// The same styles with color turned off.
+    pub fn without_color(self) -> Self {
+        Self {
+            enabled: false,
+            ..self
+        }
+    }
+
+    /// Whether this theme emits color codes.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Style `text` for `role`, or return it unchanged when color is disabled.
+    pub fn paint(&self, role: Role, text: &str) -> String {
+        if !self.enabled {
+            return text.to_string();
+        }
+
+        let style = match role {
+            Role::Sender => self.sender,
+            Role::Date => self.date,
+            Role::Label => self.label,
+            Role::Error => self.error,
+        };
+        style.paint(text)
+    }
+}
+
+static STDERR_ERROR_STYLE: OnceLock<Option<Style>> = OnceLock::new();
+
+/// Record how `error:` prefixes on stderr should be styled for this process.
+pub fn install_error_style(choice: ColorChoice, theme: &Theme) {
+    let enabled = choice.enabled(std::io::stderr().is_terminal());
+    let _ = STDERR_ERROR_STYLE.set(enabled.then_some(theme.error));
+}
+
+/// The `error` prefix for messages printed to stderr, translated and styled
+/// when enabled.
+pub fn error_prefix() -> String {
+    let prefix = i18n::text("error-prefix");
+    match STDERR_ERROR_STYLE.get().copied().flatten() {
+        Some(style) => style.paint(&prefix),
+        None => prefix,
+    }
+}
+
+/// Map a color name (optionally `bright-` prefixed) or 0-255 index to a palette index.
+fn parse_color(name: &str) -> Option<u8> {
+    if let Ok(index) = name.parse::<u8>() {
+        return Some(index);
+    }
+
+    let (base, offset) = match name.strip_prefix("bright-") {
+        Some(base) => (base, 8),
+        None => (name, 0),
+    };
+    let index = match base {
+        "black" => 0,
+        "red" => 1,
+        "green" => 2,
+        "yellow" => 3,
+        "blue" => 4,
+        "magenta" => 5,
+        "cyan" => 6,
+        "white" => 7,
+        "gray" | "grey" if offset == 0 => return Some(8),
+        _ => return None,
+    };
+    Some(index + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_style_specs() {
+        let style = Style::parse("bold bright-red").unwrap();
+        assert_eq!(
+            style,
+            Style {
+                fg: Some(9),
+                bold: true,
+                ..Style::default()
+            }
+        );
+        assert_eq!(Style::parse("208").unwrap().fg, Some(208));
+        assert!(Style::parse("chartreuse").is_err());
+    }
+
+    #[test]
+    fn paints_sgr_codes() {
+        let style = Style::parse("bold cyan").unwrap();
+        assert_eq!(style.paint("hi"), "\x1b[1;36mhi\x1b[0m");
+        assert_eq!(
+            Style::parse("bright-blue").unwrap().paint("x"),
+            "\x1b[94mx\x1b[0m"
+        );
+        assert_eq!(
+            Style::parse("208").unwrap().paint("x"),
+            "\x1b[38;5;208mx\x1b[0m"
+        );
+        assert_eq!(Style::default().paint("x"), "x");
+    }
+
+    #[test]
+    fn disabled_theme_leaves_text_plain() {
+        let theme = Theme::resolve(ColorChoice::Never, None).unwrap();
+        assert!(!theme.enabled());
+        assert_eq!(theme.paint(Role::Sender, "a@b.com"), "a@b.com");
+
+        let theme = Theme::resolve(ColorChoice::Always, None).unwrap();
+        assert!(theme.paint(Role::Sender, "a@b.com").starts_with("\x1b["));
+    }
+}
@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult};
+
+/// Serialize a value as a YAML document and print it to stdout.
+pub fn print<T: Serialize>(value: &T) -> AppResult<()> {
+    print!("{}", render(value)?);
+    Ok(())
+}
+
+/// Serialize a value as a YAML document.
+pub fn render<T: Serialize>(value: &T) -> AppResult<String> {
+    serde_yaml::to_string(value)
+        .map_err(|err| AppError::InvalidInput(format!("failed to render yaml output: {err}")))
+}
@@ -0,0 +1,217 @@
+mod state;
+mod ui;
+
+use std::time::Duration;
+
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::api::models::MessageView;
+use crate::cli::SendArgs;
+use crate::commands;
+use crate::context::AppContext;
+use crate::error::{AppError, AppResult};
+
+pub use state::{Action, InboxState, Prompt, PromptKind};
+
+/// How long to wait for a key press before redrawing.
+const TICK: Duration = Duration::from_millis(250);
+
+/// Run the inbox browser until the user quits, restoring the terminal on exit.
+pub async fn run(ctx: &AppContext, query: Option<String>, limit: u32) -> AppResult<()> {
+    let mut state = InboxState::new(query);
+    reload(ctx, &mut state, limit).await?;
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(ctx, &mut terminal, &mut state, limit).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    ctx: &AppContext,
+    terminal: &mut DefaultTerminal,
+    state: &mut InboxState,
+    limit: u32,
+) -> AppResult<()> {
+    loop {
+        terminal.draw(|frame| ui::draw(frame, state))?;
+
+        // Fetch the selected message's full body before waiting on input so
+        // the preview fills in as soon as the selection settles.
+        if let Some(id) = state.pending_preview() {
+            match load_preview(ctx, &id).await {
+                Ok(message) => state.store_preview(message),
+                Err(err) => {
+                    state.status = Some(format!("failed to load {id}: {err}"));
+                    state.preview_failed(&id, &err.to_string());
+                }
+            }
+            continue;
+        }
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let had_prompt = state.prompt.is_some();
+        let Some(action) = state.handle_key(key) else {
+            if !had_prompt {
+                state.status = None;
+            }
+            continue;
+        };
+
+        let outcome = match action {
+            Action::Quit => return Ok(()),
+            Action::Refresh => reload(ctx, state, limit)
+                .await
+                .map(|()| format!("{} messages", state.messages.len())),
+            Action::Search(query) => {
+                state.query = Some(query).filter(|query| !query.is_empty());
+                state.selected = 0;
+                reload(ctx, state, limit)
+                    .await
+                    .map(|()| format!("{} messages", state.messages.len()))
+            }
+            Action::Archive(id) => archive(ctx, state, &id).await,
+            Action::Label { id, labels } => label(ctx, &id, &labels).await,
+            Action::Reply(id) => {
+                ratatui::restore();
+                let outcome = reply(ctx, &id).await;
+                *terminal = ratatui::init();
+                outcome
+            }
+        };
+
+        state.status = Some(match outcome {
+            Ok(message) => message,
+            Err(err) => format!("error: {err}"),
+        });
+    }
+}
+
+/// Re-run the current query and replace the message list.
+async fn reload(ctx: &AppContext, state: &mut InboxState, limit: u32) -> AppResult<()> {
+    let access_token = ctx.access_token().await?;
+    let messages = ctx
+        .gmail_client
+        .list(&access_token, limit, state.query.as_deref())
+        .await?;
+    state.set_messages(messages);
+    Ok(())
+}
+
+/// Fetch the full message shown in the preview pane.
+async fn load_preview(ctx: &AppContext, id: &str) -> AppResult<MessageView> {
+    let access_token = ctx.access_token().await?;
+    ctx.gmail_client.get_msg_full(id, &access_token).await
+}
+
+/// Add labels (names or ids) to a message.
+async fn label(ctx: &AppContext, id: &str, labels: &[String]) -> AppResult<String> {
+    let access_token = ctx.access_token().await?;
+    ctx.gmail_client
+        .add_labels(id, labels, &access_token)
+        .await?;
+    Ok(format!("labeled {id}: {}", labels.join(", ")))
+}
+
+/// Archive by removing the INBOX label, then drop the message from the list.
+async fn archive(ctx: &AppContext, state: &mut InboxState, id: &str) -> AppResult<String> {
+    let access_token = ctx.access_token().await?;
+    ctx.gmail_client
+        .rm_labels(id, &["INBOX".to_string()], &access_token)
+        .await?;
+    state.remove(id);
+    Ok(format!("archived {id}"))
+}
+
+/// Compose a reply in the editor (quoting the original) and send it.
+async fn reply(ctx: &AppContext, id: &str) -> AppResult<String> {
+    let access_token = ctx.access_token().await?;
+    let parent = ctx.gmail_client.get_msg_full(id, &access_token).await?;
+
+    let quoted = parent
+        .body
+        .as_deref()
+        .or(parent.snippet.as_deref())
+        .unwrap_or("")
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let from = parent.from.as_deref().unwrap_or("(unknown sender)");
+    let template = format!("\n\n{from} wrote:\n{quoted}\n");
+
+    let path = std::env::temp_dir().join(format!("gmail-reply-{}.md", std::process::id()));
+    std::fs::write(&path, template)?;
+    let edited = edit_file(ctx.settings.editor.as_deref(), &path);
+    let body = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+    edited?;
+    let body = body?;
+
+    let is_quote = |line: &&str| line.starts_with('>') || line.ends_with(" wrote:");
+    let written = body
+        .lines()
+        .filter(|line| !is_quote(line))
+        .any(|line| !line.trim().is_empty());
+    if !written {
+        return Ok("reply aborted (empty message)".to_string());
+    }
+    // `send` adds its own quote block when `reply_quote_default` is on
+    // (these args leave `--quote` unset).
+    let body = if ctx.settings.reply_quote_default.unwrap_or(false) {
+        body.lines()
+            .filter(|line| !is_quote(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        body
+    };
+
+    let result = commands::send::submit(
+        ctx,
+        SendArgs {
+            reply: Some(id.to_string()),
+            body: Some(body),
+            ..SendArgs::default()
+        },
+    )
+    .await?;
+    Ok(format!("sent reply {}", result.id))
+}
+
+/// Open `path` in the configured editor (else `$VISUAL`, `$EDITOR`, `vi`) and
+/// wait for it to exit.
+fn edit_file(configured: Option<&str>, path: &std::path::Path) -> AppResult<()> {
+    let editor = configured
+        .map(str::to_string)
+        .or_else(|| std::env::var("VISUAL").ok())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .unwrap_or_else(|| "vi".to_string());
+
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", &editor]);
+        command
+    } else {
+        let mut command = std::process::Command::new("sh");
+        command.args(["-c", &format!("{editor} \"$1\""), "sh"]);
+        command
+    };
+    let status = command.arg(path).status()?;
+    if !status.success() {
+        return Err(AppError::InvalidInput(format!(
+            "editor `{editor}` exited with {status}"
+        )));
+    }
+    Ok(())
+}
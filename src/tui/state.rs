@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::api::models::MessageView;
+
+/// Something the event loop must do in response to a key press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Refresh,
+    Search(String),
+    Archive(String),
+    Reply(String),
+    Label { id: String, labels: Vec<String> },
+}
+
+/// What a line-editing prompt at the bottom of the screen is collecting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    Search,
+    Label,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub buffer: String,
+}
+
+/// Everything the inbox browser draws: the message list, the selection,
+/// previews fetched so far, and the status/prompt line.
+#[derive(Debug, Default)]
+pub struct InboxState {
+    pub query: Option<String>,
+    pub messages: Vec<MessageView>,
+    pub selected: usize,
+    pub preview_scroll: u16,
+    pub prompt: Option<Prompt>,
+    pub status: Option<String>,
+    previews: HashMap<String, MessageView>,
+}
+
+impl InboxState {
+    pub fn new(query: Option<String>) -> Self {
+        Self {
+            query,
+            ..Self::default()
+        }
+    }
+
+    /// Replace the message list, keeping the selection in range.
+    pub fn set_messages(&mut self, messages: Vec<MessageView>) {
+        self.messages = messages;
+        self.selected = self.selected.min(self.messages.len().saturating_sub(1));
+        self.preview_scroll = 0;
+    }
+
+    pub fn selected_message(&self) -> Option<&MessageView> {
+        self.messages.get(self.selected)
+    }
+
+    /// The fully fetched selected message, once its preview has loaded.
+    pub fn preview(&self) -> Option<&MessageView> {
+        self.selected_message()
+            .and_then(|message| self.previews.get(&message.id))
+    }
+
+    /// Id of the selected message when its preview still needs fetching.
+    pub fn pending_preview(&self) -> Option<String> {
+        self.selected_message()
+            .filter(|message| !self.previews.contains_key(&message.id))
+            .map(|message| message.id.clone())
+    }
+
+    pub fn store_preview(&mut self, message: MessageView) {
+        self.previews.insert(message.id.clone(), message);
+    }
+
+    /// Record a preview that failed to load so it is not retried on every redraw.
+    pub fn preview_failed(&mut self, id: &str, error: &str) {
+        if let Some(message) = self.messages.iter().find(|message| message.id == id) {
+            let mut placeholder = message.clone();
+            placeholder.body = Some(format!("(failed to load message: {error})"));
+            self.store_preview(placeholder);
+        }
+    }
+
+    /// Drop a message from the list (after archiving it).
+    pub fn remove(&mut self, id: &str) {
+        self.messages.retain(|message| message.id != id);
+        self.previews.remove(id);
+        let messages = std::mem::take(&mut self.messages);
+        self.set_messages(messages);
+    }
+
+    /// Apply a key press, returning the action the event loop should run.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if self.prompt.is_some() {
+            return self.handle_prompt_key(key);
+        }
+
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Action::Quit);
+        }
+
+        let selected_id = self.selected_message().map(|message| message.id.clone());
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Char('j') | KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_selection(-1),
+            KeyCode::Char('g') | KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::Char('G') | KeyCode::End => self.move_selection(isize::MAX),
+            KeyCode::Char('J') | KeyCode::PageDown => {
+                self.preview_scroll = self.preview_scroll.saturating_add(10);
+            }
+            KeyCode::Char('K') | KeyCode::PageUp => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(10);
+            }
+            KeyCode::Char('R') => return Some(Action::Refresh),
+            KeyCode::Char('/') => self.open_prompt(PromptKind::Search),
+            KeyCode::Char('l') if selected_id.is_some() => self.open_prompt(PromptKind::Label),
+            KeyCode::Char('a') => return selected_id.map(Action::Archive),
+            KeyCode::Char('r') => return selected_id.map(Action::Reply),
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let prompt = self.prompt.as_mut()?;
+        match key.code {
+            KeyCode::Esc => self.prompt = None,
+            KeyCode::Backspace => {
+                prompt.buffer.pop();
+            }
+            KeyCode::Char(ch) => prompt.buffer.push(ch),
+            KeyCode::Enter => {
+                let prompt = self.prompt.take()?;
+                let input = prompt.buffer.trim().to_string();
+                return match prompt.kind {
+                    PromptKind::Search => Some(Action::Search(input)),
+                    PromptKind::Label => {
+                        let id = self.selected_message()?.id.clone();
+                        let labels = input
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|label| !label.is_empty())
+                            .map(str::to_string)
+                            .collect::<Vec<_>>();
+                        (!labels.is_empty()).then_some(Action::Label { id, labels })
+                    }
+                };
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn open_prompt(&mut self, kind: PromptKind) {
+        let buffer = match kind {
+            PromptKind::Search => self.query.clone().unwrap_or_default(),
+            PromptKind::Label => String::new(),
+        };
+        self.prompt = Some(Prompt { kind, buffer });
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.messages.len().saturating_sub(1);
+        let next = self.selected.saturating_add_signed(delta).min(last);
+        if next != self.selected {
+            self.selected = next;
+            self.preview_scroll = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str) -> MessageView {
+        MessageView {
+            id: id.to_string(),
+            ..MessageView::default()
+        }
+    }
+
+    fn press(state: &mut InboxState, code: KeyCode) -> Option<Action> {
+        state.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn navigates_and_clamps_selection() {
+        let mut state = InboxState::new(None);
+        state.set_messages(vec![message("a"), message("b"), message("c")]);
+
+        press(&mut state, KeyCode::Char('k'));
+        assert_eq!(state.selected, 0);
+        press(&mut state, KeyCode::Char('G'));
+        assert_eq!(state.selected, 2);
+        press(&mut state, KeyCode::Char('j'));
+        assert_eq!(state.selected, 2);
+        assert_eq!(
+            press(&mut state, KeyCode::Char('a')),
+            Some(Action::Archive("c".to_string()))
+        );
+
+        state.remove("c");
+        assert_eq!(state.selected, 1);
+        assert_eq!(state.pending_preview().as_deref(), Some("b"));
+        state.store_preview(message("b"));
+        assert_eq!(state.pending_preview(), None);
+    }
+
+    #[test]
+    fn label_prompt_collects_comma_separated_names() {
+        let mut state = InboxState::new(None);
+        state.set_messages(vec![message("a")]);
+
+        press(&mut state, KeyCode::Char('l'));
+        for ch in "Work, Later".chars() {
+            press(&mut state, KeyCode::Char(ch));
+        }
+        assert_eq!(
+            press(&mut state, KeyCode::Enter),
+            Some(Action::Label {
+                id: "a".to_string(),
+                labels: vec!["Work".to_string(), "Later".to_string()],
+            })
+        );
+        assert!(state.prompt.is_none());
+    }
+
+    #[test]
+    fn search_prompt_starts_from_current_query() {
+        let mut state = InboxState::new(Some("in:inbox".to_string()));
+        press(&mut state, KeyCode::Char('/'));
+        press(&mut state, KeyCode::Char('!'));
+        press(&mut state, KeyCode::Backspace);
+        assert_eq!(
+            press(&mut state, KeyCode::Enter),
+            Some(Action::Search("in:inbox".to_string()))
+        );
+
+        press(&mut state, KeyCode::Char('/'));
+        press(&mut state, KeyCode::Esc);
+        assert!(state.prompt.is_none());
+        assert_eq!(press(&mut state, KeyCode::Char('q')), Some(Action::Quit));
+    }
+}
@@ -0,0 +1,124 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+
+use super::state::{InboxState, PromptKind};
+
+const KEY_HELP: &str =
+    "j/k move  J/K scroll  a archive  r reply  l label  / search  R refresh  q quit";
+
+/// Draw the message list, the preview pane, and the status/prompt line.
+pub fn draw(frame: &mut Frame, state: &InboxState) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(frame.area());
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(rows[0]);
+
+    draw_list(frame, state, panes[0]);
+    draw_preview(frame, state, panes[1]);
+    draw_status(frame, state, rows[1]);
+}
+
+fn draw_list(frame: &mut Frame, state: &InboxState, area: ratatui::layout::Rect) {
+    let items = state
+        .messages
+        .iter()
+        .map(|message| {
+            let from = message.from.as_deref().unwrap_or("(unknown sender)");
+            let subject = message.subject.as_deref().unwrap_or("(no subject)");
+            ListItem::new(vec![
+                Line::from(Span::styled(
+                    from.to_string(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(subject.to_string()),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let title = match &state.query {
+        Some(query) => format!(" {} ({}) ", query, state.messages.len()),
+        None => format!(" all mail ({}) ", state.messages.len()),
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().bg(Color::DarkGray).fg(Color::White));
+
+    let mut list_state = ListState::default();
+    if !state.messages.is_empty() {
+        list_state.select(Some(state.selected));
+    }
+    frame.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn draw_preview(frame: &mut Frame, state: &InboxState, area: ratatui::layout::Rect) {
+    let block = Block::default().borders(Borders::ALL).title(" preview ");
+
+    let lines = match (state.selected_message(), state.preview()) {
+        (None, _) => vec![Line::from("no messages")],
+        (Some(_), None) => vec![Line::from("loading…")],
+        (Some(_), Some(message)) => {
+            let header = |name: &str, value: Option<&str>| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{name}: "),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ),
+                    Span::raw(value.unwrap_or("").to_string()),
+                ])
+            };
+            let mut lines = vec![
+                header("From", message.from.as_deref()),
+                header("Date", message.date.as_deref()),
+                header("Subject", message.subject.as_deref()),
+            ];
+            if !message.attachments.is_empty() {
+                let names = message
+                    .attachments
+                    .iter()
+                    .map(|attachment| attachment.filename.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                lines.push(header("Attachments", Some(&names)));
+            }
+            lines.push(Line::from(""));
+            let body = message
+                .body
+                .as_deref()
+                .or(message.snippet.as_deref())
+                .unwrap_or("(no body)");
+            lines.extend(body.lines().map(|line| Line::from(line.to_string())));
+            lines
+        }
+    };
+
+    let preview = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((state.preview_scroll, 0));
+    frame.render_widget(preview, area);
+}
+
+fn draw_status(frame: &mut Frame, state: &InboxState, area: ratatui::layout::Rect) {
+    let line = match (&state.prompt, &state.status) {
+        (Some(prompt), _) => {
+            let label = match prompt.kind {
+                PromptKind::Search => "search",
+                PromptKind::Label => "add labels (comma-separated)",
+            };
+            Line::from(format!("{label}: {}▏", prompt.buffer))
+        }
+        (None, Some(status)) => Line::from(status.as_str()),
+        (None, None) => Line::from(Span::styled(
+            KEY_HELP,
+            Style::default().add_modifier(Modifier::DIM),
+        )),
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
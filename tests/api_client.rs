@@ -1,11 +1,35 @@
+mod audit {
+    pub use gmail::api::audit::*;
+}
+
+mod drafts {
+    pub use gmail::api::drafts::*;
+}
+
 mod error {
     pub use gmail::error::*;
 }
 
+mod interrupt {
+    pub use gmail::interrupt::*;
+}
+
+mod forwarding {
+    pub use gmail::api::forwarding::*;
+}
+
+mod history {
+    pub use gmail::api::history::*;
+}
+
 mod labels {
     pub use gmail::api::labels::*;
 }
 
+mod legacy_access {
+    pub use gmail::api::legacy_access::*;
+}
+
 mod messages {
     pub use gmail::api::messages::*;
 }
@@ -14,10 +38,30 @@ mod models {
     pub use gmail::api::models::*;
 }
 
+mod people {
+    pub use gmail::api::people::*;
+}
+
+mod mail {
+    pub use gmail::mail::*;
+}
+
 mod send_as {
     pub use gmail::api::send_as::*;
 }
 
+mod threads {
+    pub use gmail::api::threads::*;
+}
+
+mod timings {
+    pub use gmail::api::timings::*;
+}
+
+mod watch {
+    pub use gmail::api::watch::*;
+}
+
 mod client_under_test {
     #![allow(dead_code)]
 
@@ -28,8 +72,10 @@ mod client_under_test {
         let resource = GmailMessageResource {
             id: "msg-123".to_string(),
             thread_id: Some("thread-456".to_string()),
+            label_ids: Some(vec!["INBOX".to_string()]),
             snippet: Some("hello world".to_string()),
             payload: Some(GmailMessagePayload {
+                part_id: None,
                 headers: Some(vec![
                     GmailMessageHeader {
                         name: "Subject".to_string(),
@@ -39,6 +85,10 @@ mod client_under_test {
                         name: "From".to_string(),
                         value: "dev@example.com".to_string(),
                     },
+                    GmailMessageHeader {
+                        name: "To".to_string(),
+                        value: r#""Lovelace, Ada" <Ada@example.com>, bob@example.com"#.to_string(),
+                    },
                     GmailMessageHeader {
                         name: "Date".to_string(),
                         value: "Mon, 16 Feb 2026 10:00:00 +0000".to_string(),
@@ -53,26 +103,35 @@ mod client_under_test {
                 body: None,
                 parts: None,
             }),
+            size_estimate: Some(2048),
+            internal_date: Some("1771236000000".to_string()),
         };
 
         let view = resource.into_view();
         assert_eq!(view.id, "msg-123");
         assert_eq!(view.thread_id.as_deref(), Some("thread-456"));
+        assert_eq!(view.label_ids, ["INBOX"]);
         assert_eq!(view.subject.as_deref(), Some("hello"));
         assert_eq!(view.from.as_deref(), Some("dev@example.com"));
+        assert_eq!(view.to_addresses, ["ada@example.com", "bob@example.com"]);
+        assert!(view.cc_addresses.is_empty());
+        assert_eq!(view.internal_date.as_deref(), Some("2026-02-16T10:00:00Z"));
         assert_eq!(view.message_id.as_deref(), Some("<abc@example.com>"));
         assert!(view.attachments.is_empty());
+        assert_eq!(view.size_estimate, Some(2048));
     }
 
     #[test]
     fn collects_attachments_from_nested_parts() {
         let payload = GmailMessagePayload {
+            part_id: None,
             headers: None,
             mime_type: Some("multipart/mixed".to_string()),
             filename: None,
             body: None,
             parts: vec![
                 GmailMessagePayload {
+                    part_id: None,
                     headers: None,
                     mime_type: Some("text/plain".to_string()),
                     filename: Some(String::new()),
@@ -84,11 +143,13 @@ mod client_under_test {
                     parts: None,
                 },
                 GmailMessagePayload {
+                    part_id: None,
                     headers: None,
                     mime_type: Some("multipart/related".to_string()),
                     filename: None,
                     body: None,
                     parts: vec![GmailMessagePayload {
+                        part_id: Some("1.0".to_string()),
                         headers: None,
                         mime_type: Some("application/pdf".to_string()),
                         filename: Some("resume.pdf".to_string()),
@@ -113,6 +174,7 @@ mod client_under_test {
         assert_eq!(out[0].attachment_id, "att-1");
         assert_eq!(out[0].mime_type, "application/pdf");
         assert_eq!(out[0].size, Some(2048));
+        assert_eq!(out[0].part_id.as_deref(), Some("1.0"));
     }
 
     #[test]
@@ -126,12 +188,14 @@ mod client_under_test {
     fn extract_body_prefers_plain_text_over_html() {
         // "hello" base64url; html part would decode to "hi" but plain wins.
         let payload = GmailMessagePayload {
+            part_id: None,
             headers: None,
             mime_type: Some("multipart/alternative".to_string()),
             filename: None,
             body: None,
             parts: vec![
                 GmailMessagePayload {
+                    part_id: None,
                     headers: None,
                     mime_type: Some("text/html".to_string()),
                     filename: None,
@@ -144,6 +208,7 @@ mod client_under_test {
                     parts: None,
                 },
                 GmailMessagePayload {
+                    part_id: None,
                     headers: None,
                     mime_type: Some("text/plain".to_string()),
                     filename: None,
@@ -164,6 +229,7 @@ mod client_under_test {
     #[test]
     fn extract_body_falls_back_to_stripped_html() {
         let payload = GmailMessagePayload {
+            part_id: None,
             headers: None,
             mime_type: Some("text/html".to_string()),
             filename: None,
@@ -192,6 +258,29 @@ mod client_under_test {
         );
     }
 
+    #[test]
+    fn matches_label_names_and_ids_once_each() {
+        let known = vec![
+            LabelView {
+                id: "INBOX".to_string(),
+                name: "INBOX".to_string(),
+                kind: "system".to_string(),
+            },
+            LabelView {
+                id: "Label_7".to_string(),
+                name: "Work".to_string(),
+                kind: "user".to_string(),
+            },
+        ];
+        let requested = ["work", " Label_7 ", "", "inbox"].map(str::to_string);
+
+        assert_eq!(
+            match_label_ids(&known, &requested).unwrap(),
+            ["Label_7", "INBOX"]
+        );
+        assert!(match_label_ids(&known, &["Later".to_string()]).is_err());
+    }
+
     #[test]
     fn maps_send_as_resource_to_view() {
         let response: GmailSendAsListResponse = serde_json::from_str(
@@ -201,6 +290,8 @@ mod client_under_test {
                         "sendAsEmail": "hello@digimata.dev",
                         "displayName": "Andrew Jones",
                         "verificationStatus": "accepted",
+                        "signature": "<p>Andrew</p>",
+                        "replyToAddress": "",
                         "treatAsAlias": true
                     },
                     {
@@ -232,6 +323,8 @@ mod client_under_test {
         assert_eq!(verified.display_name.as_deref(), Some("Andrew Jones"));
         assert!(!verified.is_primary);
         assert!(verified.is_sendable());
+        assert_eq!(verified.signature.as_deref(), Some("<p>Andrew</p>"));
+        assert_eq!(verified.reply_to, None);
 
         let primary = &views[1];
         assert_eq!(primary.email, "andjones100@gmail.com");
@@ -245,6 +338,93 @@ mod client_under_test {
         assert!(!pending.is_sendable());
     }
 
+    #[test]
+    fn maps_forwarding_resources_to_views() {
+        let response: GmailForwardingListResponse = serde_json::from_str(
+            r#"{"forwardingAddresses": [
+                {"forwardingEmail": "me@example.org", "verificationStatus": "accepted"}
+            ]}"#,
+        )
+        .unwrap();
+        let address = response
+            .forwarding_addresses
+            .into_iter()
+            .map(GmailForwardingAddressResource::into_view)
+            .next()
+            .unwrap();
+        assert_eq!(address.email, "me@example.org");
+        assert_eq!(address.verification_status.as_deref(), Some("accepted"));
+
+        let off: GmailAutoForwardingResource = serde_json::from_str(
+            r#"{"enabled": false, "emailAddress": "", "disposition": "dispositionUnspecified"}"#,
+        )
+        .unwrap();
+        let off = off.into_view();
+        assert!(!off.enabled);
+        assert_eq!((off.email, off.disposition), (None, None));
+    }
+
+    #[test]
+    fn maps_imap_and_pop_resources_to_views() {
+        let imap: GmailImapResource = serde_json::from_str(
+            r#"{"enabled": true, "autoExpunge": true, "expungeBehavior": "archive"}"#,
+        )
+        .unwrap();
+        let imap = imap.into_view();
+        assert!(imap.enabled && imap.auto_expunge);
+        assert_eq!(imap.expunge_behavior.as_deref(), Some("archive"));
+
+        let pop: GmailPopResource =
+            serde_json::from_str(r#"{"accessWindow": "disabled"}"#).unwrap();
+        assert!(!pop.into_view().enabled);
+        let pop: GmailPopResource =
+            serde_json::from_str(r#"{"accessWindow": "fromNowOn", "disposition": "archive"}"#)
+                .unwrap();
+        let pop = pop.into_view();
+        assert!(pop.enabled);
+        assert_eq!(pop.access_window, "fromNowOn");
+    }
+
+    #[test]
+    fn maps_people_search_results_to_matches() {
+        let response: PeopleSearchResponse = serde_json::from_str(
+            r#"{
+                "results": [{
+                    "person": {
+                        "resourceName": "people/c1",
+                        "names": [{ "displayName": "Chen, Sarah" }],
+                        "emailAddresses": [
+                            { "value": "sarah@acme.example" },
+                            { "value": "sarah.chen@gmail.com" }
+                        ],
+                        "organizations": [
+                            { "name": "Acme", "department": "Accounting", "title": "Controller" }
+                        ]
+                    }
+                }]
+            }"#,
+        )
+        .expect("people payload should deserialize");
+
+        let matches = response
+            .people()
+            .into_iter()
+            .flat_map(|person| person.into_matches(PersonSource::Contacts))
+            .collect::<Vec<_>>();
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].address, r#""Chen, Sarah" <sarah@acme.example>"#);
+        assert_eq!(
+            matches[0].organization.as_deref(),
+            Some("Accounting, Controller, Acme")
+        );
+        assert_eq!(matches[1].email, "sarah.chen@gmail.com");
+        assert_eq!(matches[1].source, PersonSource::Contacts);
+
+        let empty: PeopleSearchResponse = serde_json::from_str("{}").unwrap();
+        assert!(empty.people().is_empty());
+    }
+
     #[test]
     fn maps_unauthorized_as_auth_error() {
         let error = map_api_error(
@@ -253,13 +433,32 @@ mod client_under_test {
         );
 
         match error {
-            AppError::Auth(message) => {
-                assert!(message.contains("invalid authentication credentials"));
+            AppError::Auth(err) => {
+                assert!(err.message.contains("invalid authentication credentials"));
+                assert_eq!(err.status, Some(401));
             }
             other => panic!("expected auth error, got {other:?}"),
         }
     }
 
+    #[test]
+    fn maps_rate_limited_forbidden_as_retryable_api_error() {
+        let error = map_api_error(
+            StatusCode::FORBIDDEN,
+            r#"{"error":{"code":403,"message":"User-rate limit exceeded.","errors":[{"reason":"userRateLimitExceeded"}]}}"#,
+        )
+        .at_endpoint("GET", "/gmail/v1/users/me/messages");
+
+        assert!(matches!(error, AppError::Api(_)), "{error:?}");
+        assert_eq!(error.status(), Some(403));
+        assert_eq!(error.reason(), Some("userRateLimitExceeded"));
+        assert!(error.retryable());
+        assert_eq!(
+            error.report().endpoint.as_deref(),
+            Some("GET /gmail/v1/users/me/messages")
+        );
+    }
+
     #[test]
     fn maps_not_found_as_api_error() {
         let error = map_api_error(
@@ -268,10 +467,568 @@ mod client_under_test {
         );
 
         match error {
-            AppError::Api(message) => {
-                assert!(message.contains("Requested entity was not found"));
+            AppError::Api(err) => {
+                assert!(err.message.contains("Requested entity was not found"));
+                assert_eq!(err.reason, None);
+                assert!(!err.retryable());
             }
             other => panic!("expected api error, got {other:?}"),
         }
     }
+
+    #[test]
+    fn flattens_history_records_into_events() {
+        let response: GmailHistoryListResponse = serde_json::from_str(
+            r#"{
+                "history": [
+                    {
+                        "id": "101",
+                        "messagesAdded": [
+                            { "message": { "id": "m1", "threadId": "t1", "labelIds": ["INBOX", "UNREAD"] } }
+                        ]
+                    },
+                    {
+                        "id": "102",
+                        "labelsRemoved": [
+                            { "message": { "id": "m1", "threadId": "t1" }, "labelIds": ["UNREAD"] }
+                        ],
+                        "messagesDeleted": [
+                            { "message": { "id": "m0", "threadId": "t0" } }
+                        ]
+                    }
+                ],
+                "historyId": "105"
+            }"#,
+        )
+        .expect("history payload should deserialize");
+
+        assert_eq!(response.history_id.as_deref(), Some("105"));
+        let mut events = Vec::new();
+        for record in response.history.expect("history records") {
+            record.into_events(&mut events);
+        }
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, MailboxEventKind::MessageAdded);
+        assert_eq!(events[0].history_id, "101");
+        assert_eq!(events[0].label_ids, ["INBOX", "UNREAD"]);
+        assert_eq!(events[1].kind, MailboxEventKind::MessageDeleted);
+        assert_eq!(events[1].message_id, "m0");
+        assert_eq!(events[2].kind, MailboxEventKind::LabelsRemoved);
+        assert_eq!(events[2].label_ids, ["UNREAD"]);
+    }
+
+    #[test]
+    fn batch_modify_request_uses_api_field_names() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let body = GmailBatchModifyRequest {
+            ids: &ids,
+            add_label_ids: &["TRASH".to_string()],
+            remove_label_ids: &["INBOX".to_string()],
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "ids": ["a", "b"],
+                "addLabelIds": ["TRASH"],
+                "removeLabelIds": ["INBOX"],
+            })
+        );
+    }
+
+    #[test]
+    fn reads_thread_pages_and_summarizes_conversations() {
+        let page: GmailMessageListResource = serde_json::from_value(serde_json::json!({
+            "threads": [{"id": "t1", "snippet": "hi", "historyId": "7"}],
+            "nextPageToken": "p2",
+        }))
+        .unwrap();
+        assert_eq!(page.messages.unwrap()[0].id, "t1");
+        assert_eq!(page.next_page_token.as_deref(), Some("p2"));
+
+        let resource: GmailThreadResource = serde_json::from_value(serde_json::json!({
+            "id": "t1",
+            "historyId": "7",
+            "messages": [
+                {
+                    "id": "m1",
+                    "snippet": "first",
+                    "payload": {"headers": [
+                        {"name": "Subject", "value": "Plans"},
+                        {"name": "From", "value": "Ada <ada@example.com>"},
+                    ]},
+                },
+                {
+                    "id": "m2",
+                    "labelIds": ["UNREAD"],
+                    "snippet": "second",
+                    "payload": {"headers": [
+                        {"name": "Subject", "value": "Re: Plans"},
+                        {"name": "From", "value": "grace@example.com"},
+                        {"name": "Date", "value": "Sat, 17 Oct 2026 09:00:00 +0000"},
+                    ]},
+                },
+                {
+                    "id": "m3",
+                    "snippet": "third",
+                    "payload": {"headers": [
+                        {"name": "From", "value": "\"Ada L.\" <ADA@example.com>"},
+                    ]},
+                },
+            ],
+        }))
+        .unwrap();
+        let thread = ThreadView {
+            id: resource.id,
+            history_id: resource.history_id,
+            messages: resource
+                .messages
+                .unwrap_or_default()
+                .into_iter()
+                .map(GmailMessageResource::into_view)
+                .collect(),
+        };
+        let summary = thread.summary();
+        assert_eq!(summary.subject.as_deref(), Some("Plans"));
+        assert_eq!(summary.participants, ["Ada", "grace@example.com"]);
+        assert_eq!(summary.message_count, 3);
+        assert_eq!(summary.snippet.as_deref(), Some("third"));
+        assert_eq!(
+            summary.date.as_deref(),
+            Some("Sat, 17 Oct 2026 09:00:00 +0000")
+        );
+        assert!(summary.unread);
+    }
+
+    #[tokio::test]
+    async fn builder_targets_base_url_with_headers() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/emulator/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = r#"{"emailAddress":"ada@example.com","historyId":"42"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .user_agent("emulator-test/1.0")
+            .header("X-Goog-User-Project", "demo")
+            .build()
+            .unwrap();
+        let profile = client.get_profile("token").await.unwrap();
+        assert_eq!(profile.email, "ada@example.com");
+
+        let request = server.await.unwrap();
+        let request_line = request.lines().next().unwrap();
+        assert_eq!(
+            request_line,
+            "GET /emulator/gmail/v1/users/me/profile HTTP/1.1"
+        );
+        let request = request.to_ascii_lowercase();
+        assert!(
+            request.contains("user-agent: emulator-test/1.0"),
+            "{request}"
+        );
+        assert!(request.contains("x-goog-user-project: demo"), "{request}");
+        assert!(request.contains("authorization: bearer token"), "{request}");
+    }
+
+    #[tokio::test]
+    async fn audits_writes_and_refuses_them_on_dry_runs() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let response = "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n";
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let trail = AuditTrail::new();
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .build()
+            .unwrap()
+            .with_audit(trail.clone());
+        let dry_run = client.clone().with_dry_run(true);
+        assert!(
+            dry_run
+                .delete_forwarding_address("old@example.com", "token")
+                .await
+                .is_err()
+        );
+        assert!(trail.drain().is_empty());
+
+        client
+            .delete_forwarding_address("old@example.com", "token")
+            .await
+            .unwrap();
+        let request = server.await.unwrap();
+        assert!(request.starts_with("DELETE "), "{request}");
+        let writes = trail.drain();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].operation, "settings.forwardingAddresses.delete");
+        assert_eq!(writes[0].ids, ["old@example.com"]);
+    }
+
+    #[tokio::test]
+    async fn trashes_messages_and_audits_them() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = r#"{"id":"m1","labelIds":["TRASH"]}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let trail = AuditTrail::new();
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .build()
+            .unwrap()
+            .with_audit(trail.clone());
+        client.trash("m1", "token").await.unwrap();
+
+        let request = server.await.unwrap();
+        assert!(
+            request.starts_with("POST /gmail/v1/users/me/messages/m1/trash "),
+            "{request}"
+        );
+        let writes = trail.drain();
+        assert_eq!(writes[0].operation, "messages.trash");
+        assert_eq!(writes[0].ids, ["m1"]);
+    }
+
+    #[tokio::test]
+    async fn audits_a_write_gmail_refuses_as_failed() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = r#"{"error":{"code":404,"message":"Requested entity was not found."}}"#;
+            let response = format!(
+                "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let trail = AuditTrail::new();
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .build()
+            .unwrap()
+            .with_audit(trail.clone());
+        assert!(client.trash("m1", "token").await.is_err());
+        server.await.unwrap();
+
+        let writes = trail.drain();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].operation, "messages.trash");
+        assert!(writes[0].failed);
+    }
+
+    #[tokio::test]
+    async fn trashes_threads_and_audits_them() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = r#"{"id":"t1"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let trail = AuditTrail::new();
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .build()
+            .unwrap()
+            .with_audit(trail.clone());
+        client.trash_thread("t1", "token").await.unwrap();
+
+        let request = server.await.unwrap();
+        assert!(
+            request.starts_with("POST /gmail/v1/users/me/threads/t1/trash "),
+            "{request}"
+        );
+        let writes = trail.drain();
+        assert_eq!(writes[0].operation, "threads.trash");
+        assert_eq!(writes[0].ids, ["t1"]);
+    }
+
+    #[tokio::test]
+    async fn updates_drafts_in_place_and_audits_them() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = r#"{"id":"r1","message":{"id":"m2","threadId":"t1"}}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let trail = AuditTrail::new();
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .build()
+            .unwrap()
+            .with_audit(trail.clone());
+        let draft = client
+            .update_draft("r1", "cmF3", Some("t1"), "token")
+            .await
+            .unwrap();
+        assert_eq!((draft.id.as_str(), draft.message_id.as_str()), ("r1", "m2"));
+
+        let request = server.await.unwrap();
+        assert!(
+            request.starts_with("PUT /gmail/v1/users/me/drafts/r1 "),
+            "{request}"
+        );
+        let writes = trail.drain();
+        assert_eq!(writes[0].operation, "drafts.update");
+        assert_eq!(writes[0].ids, ["r1"]);
+    }
+
+    #[tokio::test]
+    async fn imports_messages_dated_by_their_header_with_labels() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"}") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = r#"{"id":"m9","threadId":"t9"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let trail = AuditTrail::new();
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .build()
+            .unwrap()
+            .with_audit(trail.clone());
+        let labels = vec!["INBOX".to_string()];
+        let result = client
+            .import_message("cmF3", &labels, false, "token")
+            .await
+            .unwrap();
+        assert_eq!(result.id, "m9");
+
+        let request = server.await.unwrap();
+        assert!(
+            request.starts_with(
+                "POST /gmail/v1/users/me/messages/import?internalDateSource=dateHeader "
+            ),
+            "{request}"
+        );
+        assert!(request.contains(r#""labelIds":["INBOX"]"#), "{request}");
+        let writes = trail.drain();
+        assert_eq!(writes[0].operation, "messages.import");
+        assert_eq!(writes[0].added_labels, ["INBOX"]);
+    }
+
+    #[tokio::test]
+    async fn creates_labels_and_audits_them() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"}") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = r#"{"id":"Label_7","name":"Work/Clients","type":"user"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let trail = AuditTrail::new();
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .build()
+            .unwrap()
+            .with_audit(trail.clone());
+        let label = client
+            .create_label("Work/Clients", Some("labelShowIfUnread"), None, "token")
+            .await
+            .unwrap();
+        assert_eq!(label.id, "Label_7");
+
+        let request = server.await.unwrap();
+        assert!(
+            request.starts_with("POST /gmail/v1/users/me/labels "),
+            "{request}"
+        );
+        assert!(
+            request
+                .ends_with(r#"{"name":"Work/Clients","labelListVisibility":"labelShowIfUnread"}"#),
+            "{request}"
+        );
+        let writes = trail.drain();
+        assert_eq!(writes[0].operation, "labels.create");
+        assert_eq!(writes[0].ids, ["Label_7"]);
+    }
+
+    #[tokio::test]
+    async fn batch_deletes_ids_and_audits_them() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"}") {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let trail = AuditTrail::new();
+        let client = GmailClient::builder()
+            .base_url(&base_url)
+            .build()
+            .unwrap()
+            .with_audit(trail.clone());
+        let ids = vec!["m1".to_string(), "m2".to_string()];
+        assert_eq!(client.batch_delete(&ids, "token").await.unwrap(), 2);
+
+        let request = server.await.unwrap();
+        assert!(
+            request.starts_with("POST /gmail/v1/users/me/messages/batchDelete "),
+            "{request}"
+        );
+        assert!(request.ends_with(r#"{"ids":["m1","m2"]}"#), "{request}");
+        let writes = trail.drain();
+        assert_eq!(writes[0].operation, "messages.batchDelete");
+        assert_eq!(writes[0].ids, ["m1", "m2"]);
+    }
+
+    #[test]
+    fn builder_rejects_invalid_settings() {
+        assert!(
+            GmailClient::builder()
+                .base_url("not a url")
+                .build()
+                .is_err()
+        );
+        assert!(
+            GmailClient::builder()
+                .base_url("mailto:ada@example.com")
+                .build()
+                .is_err()
+        );
+        assert!(
+            GmailClient::builder()
+                .header("bad header", "x")
+                .build()
+                .is_err()
+        );
+        assert_eq!(
+            GmailClient::builder().build().unwrap().base_url(),
+            "https://gmail.googleapis.com"
+        );
+    }
 }
@@ -2,6 +2,10 @@ mod config {
     pub use gmail::config::*;
 }
 
+mod desktop {
+    pub use gmail::desktop::*;
+}
+
 mod error {
     pub use gmail::error::*;
 }
@@ -1,5 +1,15 @@
+use std::path::PathBuf;
+
 use clap::Parser;
-use gmail::cli::{AliasesCommand, AuthCommand, Cli, Command};
+use gmail::cli::{
+    AliasCommand, AliasesCommand, AttachmentsCommand, AuthCommand, Cli, Command, ContactsCommand,
+    DraftCommand, ForwardAction, ForwardingCommand, HistoryType, LabelCommand, LabelListVisibility,
+    LegacyAccessCommand, MessageListVisibility, MuteCommand, ProfileCommand, QueueCommand,
+    RsvpResponse, RulesCommand, SearchCommand, SendasCommand, SnoozeCommand, ThreadCommand, Toggle,
+    WatchCommand,
+};
+use gmail::output::OutputMode;
+use gmail::output::text::ColorChoice;
 
 #[test]
 fn parses_auth_login() {
@@ -14,7 +24,7 @@ fn parses_auth_login() {
 fn parses_get() {
     let cli = Cli::try_parse_from(["gmail", "get", "abc123"]).expect("cli parse should work");
     match cli.command {
-        Command::Get(get) => assert_eq!(get.id, "abc123"),
+        Command::Get(get) => assert_eq!(get.ids, ["abc123"]),
         _ => panic!("expected get command"),
     }
 }
@@ -77,3 +87,1251 @@ fn parses_list() {
         _ => panic!("expected list command"),
     }
 }
+
+#[test]
+fn parses_watch_start() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "watch",
+        "start",
+        "--topic",
+        "projects/p/topics/t",
+        "--label",
+        "INBOX",
+        "--label",
+        "Work",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Watch(watch) => match watch.command {
+            WatchCommand::Start(start) => {
+                assert_eq!(start.topic.as_deref(), Some("projects/p/topics/t"));
+                assert_eq!(start.labels, ["INBOX", "Work"]);
+            }
+            _ => panic!("expected watch start"),
+        },
+        _ => panic!("expected watch command"),
+    }
+}
+
+#[test]
+fn parses_notify() {
+    let cli = Cli::try_parse_from(["gmail", "notify", "--q", "is:important", "--interval", "30"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Notify(notify) => {
+            assert_eq!(notify.q.as_deref(), Some("is:important"));
+            assert_eq!(notify.interval, 30);
+        }
+        _ => panic!("expected notify command"),
+    }
+}
+
+#[test]
+fn parses_output_format_and_json_alias() {
+    let cli = Cli::try_parse_from(["gmail", "--output", "csv", "label", "ls"])
+        .expect("cli parse should work");
+    assert_eq!(cli.output, Some(OutputMode::Csv));
+
+    let cli =
+        Cli::try_parse_from(["gmail", "label", "ls", "--json"]).expect("cli parse should work");
+    assert!(cli.json);
+
+    assert!(Cli::try_parse_from(["gmail", "--json", "--output", "yaml", "label", "ls"]).is_err());
+}
+
+#[test]
+fn parses_color_choice() {
+    let cli = Cli::try_parse_from(["gmail", "label", "ls"]).expect("cli parse should work");
+    assert_eq!(cli.color, None);
+
+    let cli =
+        Cli::try_parse_from(["gmail", "list", "--color", "never"]).expect("cli parse should work");
+    assert_eq!(cli.color, Some(ColorChoice::Never));
+
+    assert!(Cli::try_parse_from(["gmail", "--color", "sometimes", "list"]).is_err());
+}
+
+#[test]
+fn parses_tui_defaults() {
+    let cli = Cli::try_parse_from(["gmail", "tui"]).expect("cli parse should work");
+    let Command::Tui(args) = cli.command else {
+        panic!("expected tui command");
+    };
+    assert_eq!(args.q, "in:inbox");
+    assert_eq!(args.limit, 50);
+}
+
+#[test]
+fn parses_global_out_after_subcommand() {
+    let cli = Cli::try_parse_from(["gmail", "list", "--json", "--out", "inbox.json"])
+        .expect("cli parse should work");
+    assert_eq!(cli.out.as_deref(), Some(std::path::Path::new("inbox.json")));
+
+    let cli = Cli::try_parse_from(["gmail", "attachments", "get", "abc", "--out", "files"])
+        .expect("cli parse should work");
+    assert_eq!(cli.out.as_deref(), Some(std::path::Path::new("files")));
+}
+
+#[test]
+fn parses_attachment_search_and_bulk_get() {
+    let cli = Cli::try_parse_from(["gmail", "attachments", "ls", "--q", "from:finance"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Attachments(args) => match args.command {
+            AttachmentsCommand::Ls(args) => {
+                assert_eq!(args.id, None);
+                assert_eq!(args.q.as_deref(), Some("from:finance"));
+            }
+            _ => panic!("expected attachments ls"),
+        },
+        _ => panic!("expected attachments command"),
+    }
+
+    let cli = Cli::try_parse_from(["gmail", "attachments", "get", "--all", "--dir", "./out"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Attachments(args) => match args.command {
+            AttachmentsCommand::Get(args) => {
+                assert!(args.all);
+                assert_eq!(args.dir.as_deref(), Some(std::path::Path::new("./out")));
+            }
+            _ => panic!("expected attachments get"),
+        },
+        _ => panic!("expected attachments command"),
+    }
+
+    assert!(Cli::try_parse_from(["gmail", "attachments", "get"]).is_err());
+    assert!(Cli::try_parse_from(["gmail", "attachments", "get", "abc", "--q", "x"]).is_err());
+    assert!(Cli::try_parse_from(["gmail", "attachments", "ls", "abc", "--q", "x"]).is_err());
+}
+
+#[test]
+fn parses_no_pager() {
+    let cli =
+        Cli::try_parse_from(["gmail", "get", "abc", "--no-pager"]).expect("cli parse should work");
+    assert!(cli.no_pager);
+}
+
+#[test]
+fn parses_verbosity_and_log_file() {
+    let cli = Cli::try_parse_from(["gmail", "-vv", "list", "--log-file", "/tmp/gmail.log"])
+        .expect("cli parse should work");
+    assert_eq!(cli.verbose, 2);
+    assert_eq!(
+        cli.log_file.as_deref(),
+        Some(std::path::Path::new("/tmp/gmail.log"))
+    );
+}
+
+#[test]
+fn parses_config_and_data_dir_overrides() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "list",
+        "--config-dir",
+        "/tmp/gmail-config",
+        "--data-dir",
+        "/tmp/gmail-data",
+    ])
+    .expect("cli parse should work");
+    assert_eq!(cli.config_dir, Some(PathBuf::from("/tmp/gmail-config")));
+    assert_eq!(cli.data_dir, Some(PathBuf::from("/tmp/gmail-data")));
+    assert!(!cli.timings);
+}
+
+#[test]
+fn parses_global_timings_flag_after_subcommand() {
+    let cli = Cli::try_parse_from(["gmail", "list", "--timings"]).expect("cli parse should work");
+    assert!(cli.timings);
+}
+
+#[test]
+fn parses_global_dry_run_flag() {
+    let cli = Cli::try_parse_from(["gmail", "label", "add", "m1", "Receipts", "--dry-run"])
+        .expect("cli parse should work");
+    assert!(cli.dry_run);
+}
+
+#[test]
+fn parses_reply_scope_flags() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "send",
+        "--reply",
+        "m1",
+        "--reply-all",
+        "--keep-cc=false",
+        "--body",
+        "ok",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Send(args) => {
+            assert_eq!(args.reply_all, Some(true));
+            assert_eq!(args.keep_cc, Some(false));
+        }
+        _ => panic!("expected send command"),
+    }
+
+    assert!(Cli::try_parse_from(["gmail", "send", "--reply-all", "--body", "x"]).is_err());
+}
+
+#[test]
+fn parses_send_invite_and_requires_when() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "send",
+        "--to",
+        "team@example.com",
+        "--invite",
+        "Standup",
+        "--when",
+        "mon 9:30am",
+        "--duration",
+        "30m",
+        "--body",
+        "see you there",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Send(args) => {
+            assert_eq!(args.invite.as_deref(), Some("Standup"));
+            assert_eq!(args.when.as_deref(), Some("mon 9:30am"));
+            assert_eq!(args.duration.as_deref(), Some("30m"));
+        }
+        _ => panic!("expected send command"),
+    }
+
+    assert!(Cli::try_parse_from(["gmail", "send", "--invite", "Standup", "--body", "x"]).is_err());
+}
+
+#[test]
+fn parses_send_patch_series() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "send-patch",
+        "0001-a.patch",
+        "0002-b.patch",
+        "--to",
+        "list@example.org",
+        "--chain-reply-to",
+    ])
+    .expect("cli parse should work");
+
+    match cli.command {
+        Command::SendPatch(args) => {
+            assert_eq!(
+                args.patches,
+                [PathBuf::from("0001-a.patch"), PathBuf::from("0002-b.patch")]
+            );
+            assert_eq!(args.to, ["list@example.org"]);
+            assert!(args.chain_reply_to);
+        }
+        _ => panic!("expected send-patch command"),
+    }
+
+    assert!(Cli::try_parse_from(["gmail", "send-patch", "0001-a.patch"]).is_err());
+}
+
+#[test]
+fn parses_sendmail_style_flags() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "sendmail",
+        "-FCronDaemon",
+        "-i",
+        "-B8BITMIME",
+        "-oem",
+        "-t",
+        "--",
+        "root@example.com",
+    ])
+    .expect("cli parse should work");
+
+    match cli.command {
+        Command::Sendmail(args) => {
+            assert!(args.read_recipients && args.ignore_dots);
+            assert_eq!(args.full_name.as_deref(), Some("CronDaemon"));
+            assert_eq!(args.options, ["em"]);
+            assert_eq!(args.recipients, ["root@example.com"]);
+        }
+        _ => panic!("expected sendmail command"),
+    }
+}
+
+#[test]
+fn parses_rsvp_response_and_comment() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "rsvp",
+        "m1",
+        "tentative",
+        "--comment",
+        "maybe late",
+    ])
+    .expect("cli parse should work");
+
+    match cli.command {
+        Command::Rsvp(args) => {
+            assert_eq!(args.id, "m1");
+            assert_eq!(args.response, RsvpResponse::Tentative);
+            assert_eq!(args.comment.as_deref(), Some("maybe late"));
+        }
+        _ => panic!("expected rsvp command"),
+    }
+
+    assert!(Cli::try_parse_from(["gmail", "rsvp", "m1", "maybe"]).is_err());
+}
+
+#[test]
+fn parses_contacts_export() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "contacts",
+        "export",
+        "--q",
+        "label:clients",
+        "--vcf",
+        "contacts.vcf",
+    ])
+    .expect("cli parse should work");
+
+    match cli.command {
+        Command::Contacts(args) => match args.command {
+            ContactsCommand::Export(args) => {
+                assert_eq!(args.q.as_deref(), Some("label:clients"));
+                assert_eq!(args.limit, 100);
+                assert_eq!(args.vcf, Some(PathBuf::from("contacts.vcf")));
+            }
+            _ => panic!("expected contacts export"),
+        },
+        _ => panic!("expected contacts command"),
+    }
+}
+
+#[test]
+fn parses_contacts_search_words() {
+    let cli = Cli::try_parse_from(["gmail", "contacts", "search", "sarah", "accounting"])
+        .expect("cli parse should work");
+
+    match cli.command {
+        Command::Contacts(args) => match args.command {
+            ContactsCommand::Search(args) => {
+                assert_eq!(args.query, ["sarah", "accounting"]);
+                assert_eq!(args.limit, 10);
+            }
+            _ => panic!("expected contacts search"),
+        },
+        _ => panic!("expected contacts command"),
+    }
+}
+
+#[test]
+fn parses_alias_add() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "alias",
+        "add",
+        "team",
+        "Ada <ada@example.com>",
+        "bob@example.com",
+    ])
+    .expect("cli parse should work");
+
+    match cli.command {
+        Command::Alias(args) => match args.command {
+            AliasCommand::Add(args) => {
+                assert_eq!(args.name, "team");
+                assert_eq!(args.addresses, ["Ada <ada@example.com>", "bob@example.com"]);
+            }
+            _ => panic!("expected alias add"),
+        },
+        _ => panic!("expected alias command"),
+    }
+}
+
+#[test]
+fn parses_stats_defaults() {
+    let cli =
+        Cli::try_parse_from(["gmail", "stats", "--q", "from:ada"]).expect("cli parse should work");
+    match cli.command {
+        Command::Stats(args) => {
+            assert_eq!(args.since, "90d");
+            assert_eq!(args.q.as_deref(), Some("from:ada"));
+            assert_eq!((args.limit, args.top), (2000, 10));
+        }
+        _ => panic!("expected stats command"),
+    }
+}
+
+#[test]
+fn parses_unsubscribe() {
+    let cli = Cli::try_parse_from(["gmail", "unsubscribe", "-", "--send"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Unsubscribe(args) => {
+            assert_eq!(args.id, "-");
+            assert!(args.send);
+        }
+        _ => panic!("expected unsubscribe command"),
+    }
+}
+
+#[test]
+fn parses_schema_command_path() {
+    let cli =
+        Cli::try_parse_from(["gmail", "schema", "label", "ls"]).expect("cli parse should work");
+    match cli.command {
+        Command::Schema(args) => assert_eq!(args.command, ["label", "ls"]),
+        _ => panic!("expected schema command"),
+    }
+}
+
+#[test]
+fn parses_mcp() {
+    let cli =
+        Cli::try_parse_from(["gmail", "--profile", "work", "mcp"]).expect("cli parse should work");
+    assert!(matches!(cli.command, Command::Mcp));
+    assert_eq!(cli.profile.as_deref(), Some("work"));
+}
+
+#[test]
+fn parses_sendas_signature_from_text_or_file() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "sendas",
+        "set-signature",
+        "me@example.com",
+        "**Me**",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Sendas(args) => match args.command {
+            SendasCommand::SetSignature(args) => {
+                assert_eq!(args.email, "me@example.com");
+                assert_eq!(args.text.as_deref(), Some("**Me**"));
+            }
+            _ => panic!("expected sendas set-signature"),
+        },
+        _ => panic!("expected sendas command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "sendas", "set-signature", "me@example.com"]).is_err());
+}
+
+#[test]
+fn auto_forwarding_on_requires_an_address() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "forwarding",
+        "auto",
+        "on",
+        "--to",
+        "me@example.org",
+        "--action",
+        "archive",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Forwarding(args) => match args.command {
+            ForwardingCommand::Auto(args) => {
+                assert_eq!(args.state, Toggle::On);
+                assert_eq!(args.to.as_deref(), Some("me@example.org"));
+                assert_eq!(args.action, ForwardAction::Archive);
+            }
+            _ => panic!("expected forwarding auto"),
+        },
+        _ => panic!("expected forwarding command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "forwarding", "auto", "on"]).is_err());
+    assert!(Cli::try_parse_from(["gmail", "forwarding", "auto", "off"]).is_ok());
+}
+
+#[test]
+fn parses_legacy_access_toggles() {
+    let cli = Cli::try_parse_from(["gmail", "legacy-access", "pop", "on", "--all-mail"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::LegacyAccess(args) => match args.command {
+            LegacyAccessCommand::Pop(args) => {
+                assert_eq!(args.state, Toggle::On);
+                assert!(args.all_mail);
+            }
+            _ => panic!("expected legacy-access pop"),
+        },
+        _ => panic!("expected legacy-access command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "legacy-access", "imap", "maybe"]).is_err());
+}
+
+#[test]
+fn parses_quota_days() {
+    let cli =
+        Cli::try_parse_from(["gmail", "quota", "--days", "3"]).expect("cli parse should work");
+    match cli.command {
+        Command::Quota(args) => assert_eq!(args.days, 3),
+        _ => panic!("expected quota command"),
+    }
+}
+
+#[test]
+fn parses_usage_defaults_and_top() {
+    let cli = Cli::try_parse_from(["gmail", "usage", "--q", "larger:5M", "--top", "5"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Usage(args) => {
+            assert_eq!(args.q.as_deref(), Some("larger:5M"));
+            assert_eq!((args.limit, args.top), (2000, 5));
+        }
+        _ => panic!("expected usage command"),
+    }
+}
+
+#[test]
+fn parses_log_filters() {
+    let cli = Cli::try_parse_from(["gmail", "log", "--since", "7d", "--all-profiles"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Log(args) => {
+            assert_eq!(args.since.as_deref(), Some("7d"));
+            assert!(args.all_profiles);
+        }
+        _ => panic!("expected log command"),
+    }
+}
+
+#[test]
+fn parses_cleanup_rules_and_dry_run() {
+    let cli = Cli::try_parse_from(["gmail", "cleanup", "--rules", "cleanup.toml", "--dry-run"])
+        .expect("cli parse should work");
+    assert!(cli.dry_run);
+    match cli.command {
+        Command::Cleanup(args) => {
+            assert_eq!(args.rules, std::path::PathBuf::from("cleanup.toml"));
+            assert_eq!(args.limit, 5000);
+        }
+        _ => panic!("expected cleanup command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "cleanup"]).is_err());
+}
+
+#[test]
+fn parses_daemon_rules_with_optional_path() {
+    let cli = Cli::try_parse_from(["gmail", "daemon", "--rules"]).expect("cli parse should work");
+    match cli.command {
+        Command::Daemon(args) => assert_eq!(args.rules.as_deref(), Some("")),
+        _ => panic!("expected daemon command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "rules", "test", "18c", "--file", "r.toml"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Rules(args) => {
+            let RulesCommand::Test { id, file } = args.command;
+            assert_eq!(id, "18c");
+            assert_eq!(file, Some(std::path::PathBuf::from("r.toml")));
+        }
+        _ => panic!("expected rules command"),
+    }
+}
+
+#[test]
+fn parses_comma_separated_profiles() {
+    let cli = Cli::try_parse_from(["gmail", "list", "--profiles", "work,home", "--inbox"])
+        .expect("cli parse should work");
+    assert_eq!(cli.profiles, ["work", "home"]);
+    assert!(matches!(cli.command, Command::List(_)));
+    assert!(
+        Cli::try_parse_from(["gmail", "--profile", "work", "--profiles", "a,b", "list"]).is_err()
+    );
+}
+
+#[test]
+fn parses_ids_only_and_stdin_id() {
+    let cli = Cli::try_parse_from(["gmail", "list", "--ids-only", "--q", "is:unread"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::List(args) => assert!(args.ids_only),
+        _ => panic!("expected list command"),
+    }
+
+    let cli = Cli::try_parse_from(["gmail", "get", "-"]).expect("cli parse should work");
+    match cli.command {
+        Command::Get(args) => assert_eq!(args.ids, ["-"]),
+        _ => panic!("expected get command"),
+    }
+}
+
+#[test]
+fn parses_get_raw() {
+    let cli = Cli::try_parse_from(["gmail", "get", "abc123", "--raw", "--out", "msg.eml"])
+        .expect("cli parse should work");
+    assert_eq!(cli.out, Some(PathBuf::from("msg.eml")));
+    match cli.command {
+        Command::Get(args) => {
+            assert_eq!(args.ids, ["abc123"]);
+            assert!(args.raw);
+        }
+        _ => panic!("expected get command"),
+    }
+}
+
+#[test]
+fn parses_get_with_several_ids() {
+    let cli =
+        Cli::try_parse_from(["gmail", "get", "m1", "m2", "m3"]).expect("cli parse should work");
+    match cli.command {
+        Command::Get(args) => assert_eq!(args.ids, ["m1", "m2", "m3"]),
+        _ => panic!("expected get command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "get"]).is_err());
+}
+
+#[test]
+fn parses_get_thread() {
+    let cli =
+        Cli::try_parse_from(["gmail", "get", "m1", "--thread"]).expect("cli parse should work");
+    match cli.command {
+        Command::Get(args) => {
+            assert!(args.thread);
+            assert_eq!(args.ids, ["m1"]);
+        }
+        _ => panic!("expected get command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "get", "m1", "--thread", "--raw"]).is_err());
+}
+
+#[test]
+fn parses_get_headers() {
+    let cli =
+        Cli::try_parse_from(["gmail", "get", "m1", "--headers"]).expect("cli parse should work");
+    match cli.command {
+        Command::Get(args) => assert!(args.headers),
+        _ => panic!("expected get command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "get", "m1", "--headers", "--raw"]).is_err());
+}
+
+#[test]
+fn parses_get_save_attachments() {
+    let cli = Cli::try_parse_from(["gmail", "get", "m1", "--save-attachments", "./files"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Get(args) => {
+            assert_eq!(args.save_attachments, Some(PathBuf::from("./files")));
+        }
+        _ => panic!("expected get command"),
+    }
+    assert!(
+        Cli::try_parse_from(["gmail", "get", "m1", "--raw", "--save-attachments", "x"]).is_err()
+    );
+}
+
+#[test]
+fn parses_thread_subcommands() {
+    let cli = Cli::try_parse_from(["gmail", "thread", "get", "t1"]).expect("cli parse should work");
+    match cli.command {
+        Command::Thread(args) => match args.command {
+            ThreadCommand::Get(get) => assert_eq!(get.id, "t1"),
+            _ => panic!("expected thread get"),
+        },
+        _ => panic!("expected thread command"),
+    }
+
+    let cli = Cli::try_parse_from(["gmail", "thread", "list", "--inbox", "--limit", "5"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Thread(args) => match args.command {
+            ThreadCommand::Ls(ls) => {
+                assert!(ls.inbox);
+                assert_eq!(ls.limit, 5);
+                assert_eq!(ls.q, None);
+            }
+            _ => panic!("expected thread ls"),
+        },
+        _ => panic!("expected thread command"),
+    }
+}
+
+#[test]
+fn parses_list_threads() {
+    let cli = Cli::try_parse_from(["gmail", "list", "--threads", "--inbox"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::List(args) => {
+            assert!(args.threads);
+            assert!(args.inbox);
+            assert!(!args.ids_only);
+        }
+        _ => panic!("expected list command"),
+    }
+}
+
+#[test]
+fn parses_attachment_part() {
+    let cli = Cli::try_parse_from(["gmail", "attachment", "get", "m1", "0.2"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Attachments(args) => match args.command {
+            AttachmentsCommand::Get(get) => {
+                assert_eq!(get.id.as_deref(), Some("m1"));
+                assert_eq!(get.part.as_deref(), Some("0.2"));
+            }
+            _ => panic!("expected attachments get"),
+        },
+        _ => panic!("expected attachments command"),
+    }
+    assert!(
+        Cli::try_parse_from(["gmail", "attachments", "get", "m1", "1", "--index", "1"]).is_err()
+    );
+}
+
+#[test]
+fn parses_archive_ids() {
+    let cli = Cli::try_parse_from(["gmail", "archive", "m1", "m2"]).expect("cli parse should work");
+    match cli.command {
+        Command::Archive(args) => assert_eq!(args.ids, ["m1", "m2"]),
+        _ => panic!("expected archive command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "archive"]).is_err());
+
+    let cli = Cli::try_parse_from(["gmail", "read", "m1"]).expect("cli parse should work");
+    assert!(matches!(cli.command, Command::Read(args) if args.ids == ["m1"]));
+
+    let cli = Cli::try_parse_from(["gmail", "not-spam", "m1"]).expect("cli parse should work");
+    assert!(matches!(cli.command, Command::NotSpam(args) if args.ids == ["m1"]));
+
+    let cli = Cli::try_parse_from(["gmail", "untrash", "-"]).expect("cli parse should work");
+    assert!(matches!(cli.command, Command::Untrash(args) if args.ids == ["-"]));
+}
+
+#[test]
+fn parses_delete_confirmation_flag() {
+    let cli = Cli::try_parse_from(["gmail", "delete", "m1", "-y"]).expect("cli parse should work");
+    match cli.command {
+        Command::Delete(args) => {
+            assert_eq!(args.ids, ["m1"]);
+            assert!(args.yes);
+        }
+        _ => panic!("expected delete command"),
+    }
+}
+
+#[test]
+fn parses_star_ids_or_query() {
+    let cli = Cli::try_parse_from(["gmail", "star", "--q", "from:boss", "--limit", "50"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Star(args) => {
+            assert!(args.ids.is_empty());
+            assert_eq!(args.q.as_deref(), Some("from:boss"));
+            assert_eq!(args.limit, 50);
+        }
+        _ => panic!("expected star command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "unstar", "m1", "--q", "is:starred"]).is_err());
+    assert!(Cli::try_parse_from(["gmail", "unstar"]).is_err());
+}
+
+#[test]
+fn parses_label_add_with_several_ids() {
+    let cli = Cli::try_parse_from(["gmail", "label", "add", "m1,m2", "Receipts", "Travel"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Label(label) => match label.command {
+            LabelCommand::Add(args) => {
+                assert_eq!(args.ids, ["m1", "m2"]);
+                assert_eq!(args.labels, ["Receipts", "Travel"]);
+            }
+            _ => panic!("expected label add"),
+        },
+        _ => panic!("expected label command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "label", "rm", "m1"]).is_err());
+}
+
+#[test]
+fn parses_query_driven_bulk_commands() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "archive",
+        "--q",
+        "from:newsletter older_than:30d",
+        "--yes",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Archive(args) => {
+            assert!(args.ids.is_empty());
+            assert_eq!(args.q.as_deref(), Some("from:newsletter older_than:30d"));
+            assert!(args.yes);
+        }
+        _ => panic!("expected archive command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "trash", "m1", "--q", "in:inbox"]).is_err());
+
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "read",
+        "--q",
+        "label:newsletters is:unread",
+        "--all",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Read(args) => assert!(args.all),
+        _ => panic!("expected read command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "read", "m1", "--all"]).is_err());
+    assert!(
+        Cli::try_parse_from(["gmail", "read", "--q", "is:unread", "--all", "--limit", "5"])
+            .is_err()
+    );
+
+    let cli = Cli::try_parse_from(["gmail", "label", "rm", "--q", "label:Old", "Old"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Label(label) => match label.command {
+            LabelCommand::Rm(args) => {
+                assert_eq!(args.ids, ["Old"]);
+                assert!(args.labels.is_empty());
+                assert_eq!(args.q.as_deref(), Some("label:Old"));
+            }
+            _ => panic!("expected label rm"),
+        },
+        _ => panic!("expected label command"),
+    }
+}
+
+#[test]
+fn parses_draft_subcommands() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "draft",
+        "create",
+        "--to",
+        "ada@example.com",
+        "--subject",
+        "Hi",
+        "--body",
+        "x",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Draft(draft) => match draft.command {
+            DraftCommand::Create(args) => {
+                assert_eq!(args.to, ["ada@example.com"]);
+                assert_eq!(args.subject.as_deref(), Some("Hi"));
+            }
+            _ => panic!("expected draft create"),
+        },
+        _ => panic!("expected draft command"),
+    }
+
+    let cli = Cli::try_parse_from(["gmail", "draft", "edit", "r1", "--subject", "Later"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Draft(draft) => match draft.command {
+            DraftCommand::Edit(args) => {
+                assert_eq!(args.id, "r1");
+                assert_eq!(args.message.subject.as_deref(), Some("Later"));
+                assert!(args.message.to.is_empty());
+            }
+            _ => panic!("expected draft edit"),
+        },
+        _ => panic!("expected draft command"),
+    }
+
+    assert!(Cli::try_parse_from(["gmail", "draft", "send", "r1"]).is_ok());
+    assert!(Cli::try_parse_from(["gmail", "draft", "rm"]).is_err());
+}
+
+#[test]
+fn parses_import() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "import",
+        "a.eml",
+        "old.mbox",
+        "--label",
+        "INBOX",
+        "--label",
+        "Archive/2019",
+        "--insert",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Import(args) => {
+            assert_eq!(
+                args.files,
+                [PathBuf::from("a.eml"), PathBuf::from("old.mbox")]
+            );
+            assert_eq!(args.labels, ["INBOX", "Archive/2019"]);
+            assert!(args.insert);
+        }
+        _ => panic!("expected import command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "import"]).is_err());
+}
+
+#[test]
+fn parses_export() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "export",
+        "--q",
+        "label:projects",
+        "--mbox",
+        "projects.mbox",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Export(args) => {
+            assert_eq!(args.q.as_deref(), Some("label:projects"));
+            assert_eq!(args.mbox, Some(PathBuf::from("projects.mbox")));
+            assert_eq!(args.dir, None);
+            assert_eq!(args.limit, None);
+        }
+        _ => panic!("expected export command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "export"]).is_err());
+    assert!(
+        Cli::try_parse_from(["gmail", "export", "--dir", "out", "--mbox", "all.mbox"]).is_err()
+    );
+}
+
+#[test]
+fn parses_history() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "history",
+        "--since",
+        "12345",
+        "--label",
+        "INBOX",
+        "--type",
+        "message-added,label-removed",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::History(args) => {
+            assert_eq!(args.since.as_deref(), Some("12345"));
+            assert_eq!(args.label.as_deref(), Some("INBOX"));
+            assert_eq!(
+                args.types,
+                [HistoryType::MessageAdded, HistoryType::LabelRemoved]
+            );
+        }
+        _ => panic!("expected history command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "history"]).is_ok());
+    assert!(Cli::try_parse_from(["gmail", "history", "--label", "INBOX"]).is_err());
+}
+
+#[test]
+fn parses_profile_info() {
+    let cli = Cli::try_parse_from(["gmail", "profile", "info"]).expect("cli parse should work");
+    match cli.command {
+        Command::Profile(profile) => assert!(matches!(profile.command, ProfileCommand::Info)),
+        _ => panic!("expected profile command"),
+    }
+}
+
+#[test]
+fn parses_search_filters_with_list_options() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "search",
+        "--from",
+        "ada@example.com",
+        "--from",
+        "bob",
+        "--limit",
+        "5",
+        "--threads",
+        "invoice",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Search(args) => {
+            assert_eq!(args.from, ["ada@example.com", "bob"]);
+            assert_eq!(args.words, ["invoice"]);
+            assert_eq!(args.list.limit, 5);
+            assert!(args.list.threads);
+        }
+        _ => panic!("expected search command"),
+    }
+}
+
+#[test]
+fn parses_search_save_and_list_saved() {
+    let cli = Cli::try_parse_from(["gmail", "search", "save", "work", "label:work", "is:unread"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Search(args) => match args.command {
+            Some(SearchCommand::Save { name, query }) => {
+                assert_eq!(name, "work");
+                assert_eq!(query, ["label:work", "is:unread"]);
+            }
+            _ => panic!("expected search save"),
+        },
+        _ => panic!("expected search command"),
+    }
+
+    let cli = Cli::try_parse_from(["gmail", "list", "--saved", "work", "--q", "from:ada"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::List(args) => {
+            assert_eq!(args.saved.as_deref(), Some("work"));
+            assert_eq!(args.q.as_deref(), Some("from:ada"));
+        }
+        _ => panic!("expected list command"),
+    }
+}
+
+#[test]
+fn parses_snooze_and_its_subcommands() {
+    let cli = Cli::try_parse_from(["gmail", "snooze", "abc", "def", "--until", "tomorrow"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Snooze(args) => {
+            assert!(args.command.is_none());
+            assert_eq!(args.ids, ["abc", "def"]);
+            assert_eq!(args.until.as_deref(), Some("tomorrow"));
+        }
+        _ => panic!("expected snooze command"),
+    }
+
+    let cli =
+        Cli::try_parse_from(["gmail", "snooze", "wake", "abc"]).expect("cli parse should work");
+    match cli.command {
+        Command::Snooze(args) => match args.command {
+            Some(SnoozeCommand::Wake { ids }) => assert_eq!(ids, ["abc"]),
+            _ => panic!("expected snooze wake"),
+        },
+        _ => panic!("expected snooze command"),
+    }
+
+    assert!(Cli::try_parse_from(["gmail", "snooze", "abc"]).is_err());
+    assert!(Cli::try_parse_from(["gmail", "snooze", "run"]).is_ok());
+}
+
+#[test]
+fn parses_empty_trash_and_spam() {
+    let cli =
+        Cli::try_parse_from(["gmail", "empty-trash", "--yes"]).expect("cli parse should work");
+    match cli.command {
+        Command::EmptyTrash(args) => assert!(args.yes),
+        _ => panic!("expected empty-trash command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "empty-spam"]).expect("cli parse should work");
+    match cli.command {
+        Command::EmptySpam(args) => assert!(!args.yes),
+        _ => panic!("expected empty-spam command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "empty-trash", "-y"]).is_err());
+}
+
+#[test]
+fn parses_mute_and_unmute() {
+    let cli = Cli::try_parse_from(["gmail", "mute", "t1", "t2"]).expect("cli parse should work");
+    match cli.command {
+        Command::Mute(args) => {
+            assert!(args.command.is_none());
+            assert_eq!(args.ids, ["t1", "t2"]);
+        }
+        _ => panic!("expected mute command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "mute", "run"]).expect("cli parse should work");
+    match cli.command {
+        Command::Mute(args) => assert!(matches!(args.command, Some(MuteCommand::Run))),
+        _ => panic!("expected mute command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "unmute", "t1"]).expect("cli parse should work");
+    match cli.command {
+        Command::Unmute(args) => assert_eq!(args.ids, ["t1"]),
+        _ => panic!("expected unmute command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "mute"]).is_err());
+}
+
+#[test]
+fn parses_thread_flag_on_triage_and_label_commands() {
+    let cli =
+        Cli::try_parse_from(["gmail", "archive", "--thread", "t1"]).expect("cli parse should work");
+    match cli.command {
+        Command::Archive(args) => {
+            assert!(args.thread);
+            assert_eq!(args.ids, ["t1"]);
+        }
+        _ => panic!("expected archive command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "trash", "--thread", "--q", "from:ada", "--yes"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Trash(args) => assert!(args.thread && args.yes),
+        _ => panic!("expected trash command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "label", "add", "t1", "Work", "--thread"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Label(label) => match label.command {
+            LabelCommand::Add(args) => {
+                assert!(args.thread);
+                assert_eq!(args.labels, ["Work"]);
+            }
+            _ => panic!("expected label add command"),
+        },
+        _ => panic!("expected label command"),
+    }
+}
+
+#[test]
+fn parses_label_create() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "label",
+        "create",
+        "Work/Clients",
+        "Receipts",
+        "--list-visibility",
+        "show-if-unread",
+        "--message-visibility",
+        "hide",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Label(label) => match label.command {
+            LabelCommand::Create(args) => {
+                assert_eq!(args.names, ["Work/Clients", "Receipts"]);
+                assert_eq!(
+                    args.list_visibility,
+                    Some(LabelListVisibility::ShowIfUnread)
+                );
+                assert_eq!(args.message_visibility, Some(MessageListVisibility::Hide));
+            }
+            _ => panic!("expected label create command"),
+        },
+        _ => panic!("expected label command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "label", "create"]).is_err());
+}
+
+#[test]
+fn parses_open() {
+    let cli = Cli::try_parse_from(["gmail", "open", "18c2f0a1b2c3d4e5", "--print"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Open(args) => {
+            assert_eq!(args.id, "18c2f0a1b2c3d4e5");
+            assert!(args.print);
+        }
+        _ => panic!("expected open command"),
+    }
+}
+
+#[test]
+fn parses_send_save_draft() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "send",
+        "--to",
+        "ada@example.com",
+        "--subject",
+        "Hi",
+        "--body",
+        "x",
+        "--save-draft",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Send(args) => assert!(args.save_draft),
+        _ => panic!("expected send command"),
+    }
+    assert!(
+        Cli::try_parse_from(["gmail", "send", "--body", "x", "--save-draft", "--preview"]).is_err()
+    );
+}
+
+#[test]
+fn parses_scheduled_send_and_queue() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "send",
+        "--to",
+        "ada@example.com",
+        "--body",
+        "x",
+        "--at",
+        "2024-06-01 09:00",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Send(args) => assert_eq!(args.at.as_deref(), Some("2024-06-01 09:00")),
+        _ => panic!("expected send command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "send", "--body", "x", "--in", "2h"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Send(args) => assert_eq!(args.in_.as_deref(), Some("2h")),
+        _ => panic!("expected send command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "send", "--at", "9am", "--in", "2h"]).is_err());
+    assert!(Cli::try_parse_from(["gmail", "send", "--in", "2h", "--save-draft"]).is_err());
+
+    let cli =
+        Cli::try_parse_from(["gmail", "queue", "rm", "a1", "b2"]).expect("cli parse should work");
+    match cli.command {
+        Command::Queue(queue) => match queue.command {
+            QueueCommand::Rm(args) => assert_eq!(args.ids, ["a1", "b2"]),
+            _ => panic!("expected queue rm"),
+        },
+        _ => panic!("expected queue command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "queue", "run"]).expect("cli parse should work");
+    assert!(matches!(
+        cli.command,
+        Command::Queue(queue) if matches!(queue.command, QueueCommand::Run)
+    ));
+}
+
+#[test]
+fn parses_forward() {
+    let cli = Cli::try_parse_from([
+        "gmail",
+        "forward",
+        "m1",
+        "--to",
+        "ada@example.com,bob@example.com",
+        "--body",
+        "FYI",
+        "--no-attachments",
+    ])
+    .expect("cli parse should work");
+    match cli.command {
+        Command::Forward(args) => {
+            assert_eq!(args.id, "m1");
+            assert_eq!(args.to, ["ada@example.com", "bob@example.com"]);
+            assert_eq!(args.body.as_deref(), Some("FYI"));
+            assert!(args.no_attachments);
+        }
+        _ => panic!("expected forward command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "forward", "m1"]).is_err());
+}
+
+#[test]
+fn parses_reply_quote() {
+    let cli = Cli::try_parse_from(["gmail", "send", "--reply", "m1", "--quote", "--body", "ok"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Send(args) => assert_eq!(args.quote, Some(true)),
+        _ => panic!("expected send command"),
+    }
+    let cli = Cli::try_parse_from(["gmail", "send", "--reply", "m1", "--quote=false"])
+        .expect("cli parse should work");
+    match cli.command {
+        Command::Send(args) => assert_eq!(args.quote, Some(false)),
+        _ => panic!("expected send command"),
+    }
+    assert!(Cli::try_parse_from(["gmail", "send", "--quote", "--body", "ok"]).is_err());
+}
@@ -1,7 +1,15 @@
+mod api {
+    pub use gmail::api::*;
+}
+
 mod cli {
     pub use gmail::cli::*;
 }
 
+mod commands {
+    pub use gmail::commands::*;
+}
+
 mod context {
     pub use gmail::context::*;
 }
@@ -10,6 +18,10 @@ mod error {
     pub use gmail::error::*;
 }
 
+mod i18n {
+    pub use gmail::i18n::*;
+}
+
 mod output {
     pub use gmail::output::*;
 }
@@ -37,14 +49,52 @@ mod list_under_test {
         let input = Some(
             "this is a very long preview string that should be truncated at one hundred and twenty characters to keep list output compact and readable",
         );
-        let preview = format_preview(input);
-        assert!(preview.ends_with("..."));
-        assert!(preview.len() <= 123);
+        let preview = format_preview(input, None);
+        assert_eq!(preview.len(), 1);
+        assert!(preview[0].ends_with('…'));
+        assert_eq!(preview[0].chars().count(), 120);
+    }
+
+    #[test]
+    fn wraps_preview_to_terminal_width() {
+        let input = Some(
+            "this is a very long preview string that should be wrapped to the terminal width instead of being cut at a fixed length, and it keeps going well past three lines",
+        );
+        let preview = format_preview(input, Some(43));
+        assert_eq!(preview.len(), 3);
+        assert!(preview.iter().all(|line| line.chars().count() <= 40));
+        assert!(preview[2].ends_with('…'));
+    }
+
+    #[test]
+    fn truncates_preview_on_display_cells() {
+        let input = "會議記錄👩‍💻".repeat(20);
+        let preview = format_preview(Some(&input), None);
+        let width = gmail::output::layout::display_width(&preview[0]);
+        assert!(width <= 120, "preview is {width} cells wide");
+        assert!(preview[0].ends_with('…'));
+        assert!(!preview[0].contains('\u{200d}') || preview[0].contains("👩‍💻"));
+    }
+
+    #[test]
+    fn fits_header_values_to_width() {
+        assert_eq!(fit("short", Some(80), "   from: "), "short");
+        assert_eq!(
+            fit(
+                "someone with a very long display name <a@b.c>",
+                Some(30),
+                "   from: "
+            )
+            .chars()
+            .count(),
+            21
+        );
+        assert_eq!(fit("unbounded", None, "   from: "), "unbounded");
     }
 
     #[test]
     fn decodes_common_html_entities_in_preview() {
-        let preview = format_preview(Some("I&#39;ve &amp; you&#x27;ve &lt;done&gt; this"));
-        assert_eq!(preview, "I've & you've <done> this");
+        let preview = format_preview(Some("I&#39;ve &amp; you&#x27;ve &lt;done&gt; this"), None);
+        assert_eq!(preview, ["I've & you've <done> this"]);
     }
 }
@@ -0,0 +1,25 @@
+use gmail::commands::man::write_pages;
+
+#[test]
+fn writes_a_page_per_subcommand() {
+    let out_dir = std::env::temp_dir().join(format!("gmail-man-test-{}", std::process::id()));
+    let pages = write_pages(&out_dir).expect("pages should render");
+
+    for name in [
+        "gmail.1",
+        "gmail-auth.1",
+        "gmail-auth-login.1",
+        "gmail-watch-start.1",
+    ] {
+        assert!(
+            pages.iter().any(|page| page.ends_with(name)),
+            "missing {name}"
+        );
+    }
+    assert!(!pages.iter().any(|page| page.ends_with("gmail-help.1")));
+
+    let root = std::fs::read_to_string(out_dir.join("gmail.1")).expect("root page exists");
+    assert!(root.contains(".TH gmail 1"));
+
+    std::fs::remove_dir_all(&out_dir).expect("cleanup");
+}
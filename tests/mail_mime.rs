@@ -2,7 +2,7 @@ use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 
 use gmail::api::models::{Attachment, SendRequest};
-use gmail::mail::mime::{build_raw_message, markdown_to_html};
+use gmail::mail::mime::{build_raw_message, markdown_to_html, preview_html};
 
 #[test]
 fn renders_markdown_body_inside_html_template() {
@@ -159,3 +159,65 @@ fn long_non_ascii_subject_folds_into_multiple_encoded_words() {
     }
     assert_eq!(decode_subject_words(&payload), subject);
 }
+
+#[test]
+fn folds_large_attachments_into_76_char_lines() {
+    use base64::engine::general_purpose::STANDARD;
+
+    let data = (0..10_000)
+        .map(|index| (index % 251) as u8)
+        .collect::<Vec<_>>();
+    let mut request = send_request_with_subject("Big");
+    request.attachments.push(Attachment {
+        filename: "blob.bin".to_string(),
+        mime_type: "application/octet-stream".to_string(),
+        data: data.clone(),
+    });
+
+    let payload = decoded_payload(&request);
+    let start = payload
+        .find("filename=\"blob.bin\"\r\n\r\n")
+        .expect("attachment part")
+        + "filename=\"blob.bin\"\r\n\r\n".len();
+    let end = start + payload[start..].find("\r\n\r\n").expect("end of part");
+    let lines = payload[start..end].split("\r\n").collect::<Vec<_>>();
+
+    assert!(lines.iter().all(|line| line.len() <= 76));
+    assert!(lines[..lines.len() - 1].iter().all(|line| line.len() == 76));
+    assert_eq!(STANDARD.decode(lines.concat()).unwrap(), data);
+}
+
+#[test]
+fn preview_inlines_attachment_and_local_images() {
+    let dir = std::env::temp_dir().join(format!("gmail-preview-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("chart one.png"), b"png").unwrap();
+
+    let request = SendRequest {
+        from: None,
+        to: vec!["dev@example.com".to_string()],
+        cc: vec![],
+        bcc: vec![],
+        subject: "Q3 <draft>".to_string(),
+        body: markdown_to_html(
+            "![logo](cid:logo.gif) ![chart](chart%20one.png) ![remote](https://example.com/a.png)",
+        ),
+        in_reply_to: None,
+        references: None,
+        thread_id: None,
+        attachments: vec![Attachment {
+            filename: "logo.gif".to_string(),
+            mime_type: "image/gif".to_string(),
+            data: b"gif".to_vec(),
+        }],
+    };
+
+    let html = preview_html(&request, &dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(html.contains("<th>Subject</th><td>Q3 &lt;draft&gt;</td>"));
+    assert!(html.contains("<th>Attachments</th><td>logo.gif</td>"));
+    assert!(html.contains("src=\"data:image/gif;base64,Z2lm\""));
+    assert!(html.contains("src=\"data:image/png;base64,cG5n\""));
+    assert!(html.contains("src=\"https://example.com/a.png\""));
+}
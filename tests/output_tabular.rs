@@ -0,0 +1,55 @@
+use serde_json::json;
+
+use gmail::output::tabular::{render_csv, render_table, rows};
+
+#[test]
+fn array_of_objects_uses_first_seen_key_order() {
+    let (columns, rows) = rows(&json!([
+        { "id": "a", "subject": "hi" },
+        { "id": "b", "from": "x@example.com" }
+    ]));
+
+    assert_eq!(columns, ["id", "subject", "from"]);
+    assert_eq!(rows[0], ["a", "hi", ""]);
+    assert_eq!(rows[1], ["b", "", "x@example.com"]);
+}
+
+#[test]
+fn csv_quotes_fields_with_delimiters() {
+    let csv = render_csv(&json!([{ "id": "a", "subject": "hello, \"world\"" }]));
+    assert_eq!(csv, "id,subject\r\na,\"hello, \"\"world\"\"\"\r\n");
+}
+
+#[test]
+fn nested_values_render_as_compact_json() {
+    let csv = render_csv(&json!({ "id": "a", "labels": ["INBOX", "UNREAD"] }));
+    assert_eq!(csv, "id,labels\r\na,\"[\"\"INBOX\"\",\"\"UNREAD\"\"]\"\r\n");
+}
+
+#[test]
+fn table_pads_columns_to_widest_cell() {
+    let table = render_table(
+        &json!([
+            { "id": "a", "name": "Inbox" },
+            { "id": "long-id", "name": "X" }
+        ]),
+        None,
+    );
+    assert_eq!(
+        table,
+        "id       name\n-------  -----\na        Inbox\nlong-id  X\n"
+    );
+}
+
+#[test]
+fn table_narrows_widest_column_to_fit_width() {
+    let table = render_table(
+        &json!([{ "id": "a", "subject": "a rather long subject line here" }]),
+        Some(20),
+    );
+    assert_eq!(
+        table,
+        "id  subject\n--  ----------------\na   a rather long s…\n"
+    );
+    assert!(table.lines().all(|line| line.chars().count() <= 20));
+}